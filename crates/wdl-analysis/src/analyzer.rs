@@ -16,11 +16,13 @@ use anyhow::Result;
 use anyhow::anyhow;
 use anyhow::bail;
 use ignore::WalkBuilder;
+use ignore::overrides::OverrideBuilder;
 use indexmap::IndexSet;
 use line_index::LineCol;
 use line_index::LineIndex;
 use line_index::WideEncoding;
 use line_index::WideLineCol;
+use lsp_types::CodeAction;
 use lsp_types::CompletionResponse;
 use lsp_types::DocumentSymbolResponse;
 use lsp_types::GotoDefinitionResponse;
@@ -43,6 +45,7 @@ use crate::graph::ParseState;
 use crate::queue::AddRequest;
 use crate::queue::AnalysisQueue;
 use crate::queue::AnalyzeRequest;
+use crate::queue::CodeActionRequest;
 use crate::queue::CompletionRequest;
 use crate::queue::DocumentSymbolRequest;
 use crate::queue::FindAllReferencesRequest;
@@ -82,6 +85,82 @@ pub fn path_to_uri(path: impl AsRef<Path>) -> Option<Url> {
     Url::from_file_path(absolute(path).ok()?.clean()).ok()
 }
 
+/// Finds the WDL documents in the given directory.
+///
+/// The directory is walked according to the document discovery settings of
+/// the given configuration (globs, ignore files, and symbolic links).
+pub fn find_documents(path: &Path, config: &Config) -> Result<IndexSet<Url>> {
+    let mut documents = IndexSet::new();
+
+    let metadata = path.metadata().with_context(|| {
+        format!(
+            "failed to read metadata for `{path}`",
+            path = path.display()
+        )
+    })?;
+
+    if metadata.is_file() {
+        bail!("`{path}` is a file, not a directory", path = path.display());
+    }
+
+    let mut overrides = OverrideBuilder::new(path);
+    for glob in config.include_globs() {
+        overrides
+            .add(glob)
+            .with_context(|| format!("invalid include glob `{glob}`"))?;
+    }
+    for glob in config.exclude_globs() {
+        overrides
+            .add(&format!("!{glob}"))
+            .with_context(|| format!("invalid exclude glob `{glob}`"))?;
+    }
+
+    let mut walker = WalkBuilder::new(path);
+    if let Some(ignore_filename) = config.ignore_filename() {
+        walker.add_custom_ignore_filename(ignore_filename);
+    }
+    let walker = walker
+        .standard_filters(false)
+        .git_ignore(config.respect_gitignore())
+        .git_exclude(config.respect_gitignore())
+        .require_git(false)
+        .parents(true)
+        .follow_links(config.follow_symlinks())
+        .overrides(
+            overrides
+                .build()
+                .context("failed to build document globs")?,
+        )
+        .build();
+
+    for result in walker {
+        let entry = result
+            .with_context(|| format!("failed to read directory `{path}`", path = path.display()))?;
+
+        // Skip entries without a file type
+        let Some(file_type) = entry.file_type() else {
+            continue;
+        };
+        // Skip non-files
+        if !file_type.is_file() {
+            continue;
+        }
+        // Skip files without a `.wdl` extension
+        if entry.path().extension() != Some(OsStr::new("wdl")) {
+            continue;
+        }
+
+        documents.insert(path_to_uri(entry.path()).with_context(|| {
+            format!(
+                "failed to convert path `{path}` to a URI",
+                path = entry.path().display()
+            )
+        })?);
+    }
+
+    Ok(documents)
+}
+
 /// Represents the result of an analysis.
 ///
 /// Analysis results are cheap to clone.
@@ -408,59 +487,7 @@ where
         let path = path.as_ref().to_path_buf();
         let config = self.config.clone();
         // Start by searching for documents
-        let documents = RayonHandle::spawn(move || -> Result<IndexSet<Url>> {
-            let mut documents = IndexSet::new();
-
-            let metadata = path.metadata().with_context(|| {
-                format!(
-                    "failed to read metadata for `{path}`",
-                    path = path.display()
-                )
-            })?;
-
-            if metadata.is_file() {
-                bail!("`{path}` is a file, not a directory", path = path.display());
-            }
-
-            let mut walker = WalkBuilder::new(&path);
-            if let Some(ignore_filename) = config.ignore_filename() {
-                walker.add_custom_ignore_filename(ignore_filename);
-            }
-            let walker = walker
-                .standard_filters(false)
-                .parents(true)
-                .follow_links(true)
-                .build();
-
-            for result in walker {
-                let entry = result.with_context(|| {
-                    format!("failed to read directory `{path}`", path = path.display())
-                })?;
-
-                // Skip entries without a file type
-                let Some(file_type) = entry.file_type() else {
-                    continue;
-                };
-                // Skip non-files
-                if !file_type.is_file() {
-                    continue;
-                }
-                // Skip files without a `.wdl` extension
-                if entry.path().extension() != Some(OsStr::new("wdl")) {
-                    continue;
-                }
-
-                documents.insert(path_to_uri(entry.path()).with_context(|| {
-                    format!(
-                        "failed to convert path `{path}` to a URI",
-                        path = entry.path().display()
-                    )
-                })?);
-            }
-
-            Ok(documents)
-        })
-        .await?;
+        let documents = RayonHandle::spawn(move || find_documents(&path, &config)).await?;
 
         if documents.is_empty() {
             return Ok(());
@@ -868,6 +895,38 @@ where
             )
         })
     }
+
+    /// Gets the code actions for a range of a document.
+    pub async fn code_action(
+        &self,
+        document: Url,
+        start: SourcePosition,
+        end: SourcePosition,
+        encoding: SourcePositionEncoding,
+    ) -> Result<Option<Vec<CodeAction>>> {
+        let (tx, rx) = oneshot::channel();
+        self.sender
+            .send(Request::CodeAction(CodeActionRequest {
+                document,
+                start,
+                end,
+                encoding,
+                completed: tx,
+            }))
+            .map_err(|_| {
+                anyhow!(
+                    "failed to send code action request to analysis queue because the channel has \
+                     closed"
+                )
+            })?;
+
+        rx.await.map_err(|_| {
+            anyhow!(
+                "failed to receive code action response from analysis queue because the channel \
+                 has closed"
+            )
+        })
+    }
 }
 
 impl Default for Analyzer<()> {
@@ -1173,4 +1232,53 @@ workflow test {
         let results = analyzer.analyze(()).await.unwrap();
         assert!(results.is_empty());
     }
+
+    #[tokio::test]
+    async fn it_orders_diagnostics_for_stable_output() {
+        let dir = TempDir::new().expect("failed to create temporary directory");
+        for name in ["c.wdl", "a.wdl", "b.wdl"] {
+            fs::write(
+                dir.path().join(name),
+                r#"version 1.1
+
+task test {
+    command <<<>>>
+}
+
+workflow test {
+    Int x = "a"
+    Int y =
+}
+"#,
+            )
+            .expect("failed to create test file");
+        }
+
+        let analyzer = Analyzer::new(
+            Config::default().with_stable_output(true),
+            |_, _, _, _| async {},
+        );
+        analyzer
+            .add_directory(dir.path())
+            .await
+            .expect("should add documents");
+
+        let results = analyzer.analyze(()).await.unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(
+            results
+                .windows(2)
+                .all(|w| w[0].document.uri() < w[1].document.uri())
+        );
+
+        for result in &results {
+            let diagnostics = result.document.diagnostics().collect::<Vec<_>>();
+            assert!(diagnostics.len() > 1);
+            assert!(
+                diagnostics
+                    .windows(2)
+                    .all(|w| w[0].cmp_by_location(w[1]).is_le())
+            );
+        }
+    }
 }
@@ -7,13 +7,16 @@ use wdl_ast::Severity;
 use wdl_ast::SupportedVersion;
 use wdl_ast::SyntaxNode;
 
+use crate::CONSTANT_CONDITION_RULE_ID;
 use crate::Rule;
 use crate::SyntaxNodeExt as _;
+use crate::UNCALLED_TASK_RULE_ID;
 use crate::UNNECESSARY_FUNCTION_CALL;
 use crate::UNUSED_CALL_RULE_ID;
 use crate::UNUSED_DECL_RULE_ID;
 use crate::UNUSED_IMPORT_RULE_ID;
 use crate::UNUSED_INPUT_RULE_ID;
+use crate::UNUSED_STRUCT_RULE_ID;
 use crate::USING_FALLBACK_VERSION;
 use crate::rules;
 
@@ -46,7 +49,12 @@ impl Default for Config {
                 diagnostics: Default::default(),
                 fallback_version: None,
                 ignore_filename: None,
+                respect_gitignore: false,
+                follow_symlinks: true,
+                include_globs: Default::default(),
+                exclude_globs: Default::default(),
                 all_rules: Default::default(),
+                stable_output: false,
                 feature_flags: FeatureFlags::default(),
             }),
         }
@@ -70,11 +78,38 @@ impl Config {
         self.inner.ignore_filename.as_deref()
     }
 
+    /// Gets whether `.gitignore` files are respected when searching
+    /// directories for documents.
+    pub fn respect_gitignore(&self) -> bool {
+        self.inner.respect_gitignore
+    }
+
+    /// Gets whether symbolic links are followed when searching directories for
+    /// documents.
+    pub fn follow_symlinks(&self) -> bool {
+        self.inner.follow_symlinks
+    }
+
+    /// Gets the globs of documents to include when searching directories.
+    pub fn include_globs(&self) -> &[String] {
+        &self.inner.include_globs
+    }
+
+    /// Gets the globs of documents to exclude when searching directories.
+    pub fn exclude_globs(&self) -> &[String] {
+        &self.inner.exclude_globs
+    }
+
     /// Gets the list of all known rule identifiers.
     pub fn all_rules(&self) -> &[String] {
         &self.inner.all_rules
     }
 
+    /// Gets whether diagnostics are reported in a stable order.
+    pub fn stable_output(&self) -> bool {
+        self.inner.stable_output
+    }
+
     /// Gets the feature flags.
     pub fn feature_flags(&self) -> &FeatureFlags {
         &self.inner.feature_flags
@@ -146,6 +181,62 @@ impl Config {
         }
     }
 
+    /// Return a new configuration with the previous `.gitignore` option
+    /// replaced by the argument.
+    ///
+    /// When `true`, `.gitignore` files (as well as `.git/info/exclude` within
+    /// a git repository) are respected when searching directories for
+    /// documents, even if the directories are not within a git repository.
+    /// The default is `false`.
+    pub fn with_respect_gitignore(&self, respect: bool) -> Self {
+        let mut inner = (*self.inner).clone();
+        inner.respect_gitignore = respect;
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Return a new configuration with the previous symbolic link option
+    /// replaced by the argument.
+    ///
+    /// When `true`, symbolic links are followed when searching directories for
+    /// documents. The default is `true`.
+    pub fn with_follow_symlinks(&self, follow: bool) -> Self {
+        let mut inner = (*self.inner).clone();
+        inner.follow_symlinks = follow;
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Return a new configuration with the previous include globs replaced by
+    /// the argument.
+    ///
+    /// When searching directories, only documents matching at least one of the
+    /// globs are added. Globs are matched against paths relative to the
+    /// directory being searched. The default is to include all documents.
+    pub fn with_include_globs(&self, globs: Vec<String>) -> Self {
+        let mut inner = (*self.inner).clone();
+        inner.include_globs = globs;
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
+    /// Return a new configuration with the previous exclude globs replaced by
+    /// the argument.
+    ///
+    /// When searching directories, documents and directories matching any of
+    /// the globs are skipped. Globs are matched against paths relative to the
+    /// directory being searched. Exclusions take precedence over inclusions.
+    pub fn with_exclude_globs(&self, globs: Vec<String>) -> Self {
+        let mut inner = (*self.inner).clone();
+        inner.exclude_globs = globs;
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
     /// Returns a new configuration with the list of all known rule identifiers
     /// replaced by the argument.
     ///
@@ -158,6 +249,24 @@ impl Config {
         }
     }
 
+    /// Return a new configuration with the previous stable output option
+    /// replaced by the argument.
+    ///
+    /// Analysis results are always ordered by document URI. When `true`, the
+    /// parse and analysis diagnostics of each document are additionally
+    /// reported together ordered by span and then by rule (see
+    /// [`Diagnostic::cmp_by_location`](wdl_ast::Diagnostic::cmp_by_location)),
+    /// making output suitable for snapshot tests and diffing. The default is
+    /// `false`, where parse diagnostics are reported before analysis
+    /// diagnostics.
+    pub fn with_stable_output(&self, stable: bool) -> Self {
+        let mut inner = (*self.inner).clone();
+        inner.stable_output = stable;
+        Self {
+            inner: Arc::new(inner),
+        }
+    }
+
     /// Return a new configuration with the previous [`FeatureFlags`]
     /// replaced by the argument.
     pub fn with_feature_flags(&self, feature_flags: FeatureFlags) -> Self {
@@ -180,14 +289,34 @@ struct ConfigInner {
     fallback_version: Option<SupportedVersion>,
     /// See [`Config::with_ignore_filename()`]
     ignore_filename: Option<String>,
+    /// See [`Config::with_respect_gitignore()`]
+    #[serde(default)]
+    respect_gitignore: bool,
+    /// See [`Config::with_follow_symlinks()`]
+    #[serde(default = "default_follow_symlinks")]
+    follow_symlinks: bool,
+    /// See [`Config::with_include_globs()`]
+    #[serde(default)]
+    include_globs: Vec<String>,
+    /// See [`Config::with_exclude_globs()`]
+    #[serde(default)]
+    exclude_globs: Vec<String>,
     /// A list of all known rule identifiers.
     #[serde(default)]
     all_rules: Vec<String>,
+    /// See [`Config::with_stable_output()`]
+    #[serde(default)]
+    stable_output: bool,
     /// The set of feature flags that can be enabled or disabled.
     #[serde(default)]
     feature_flags: FeatureFlags,
 }
 
+/// Gets the default value of [`ConfigInner::follow_symlinks`].
+fn default_follow_symlinks() -> bool {
+    true
+}
+
 /// A set of feature flags that can be enabled.
 #[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 pub struct FeatureFlags {
@@ -243,6 +372,20 @@ pub struct DiagnosticsConfig {
     /// configured with [`Config::with_fallback_version()`], this diagnostic
     /// will not be emitted.
     pub using_fallback_version: Option<Severity>,
+    /// The severity for the constant condition diagnostic.
+    ///
+    /// A value of `None` disables the diagnostic.
+    pub constant_condition: Option<Severity>,
+    /// The severity for the unused struct diagnostic.
+    ///
+    /// A value of `None` disables the diagnostic. This diagnostic is only
+    /// emitted by [`unused_definitions()`](crate::unused_definitions).
+    pub unused_struct: Option<Severity>,
+    /// The severity for the uncalled task diagnostic.
+    ///
+    /// A value of `None` disables the diagnostic. This diagnostic is only
+    /// emitted by [`unused_definitions()`](crate::unused_definitions).
+    pub uncalled_task: Option<Severity>,
 }
 
 impl Default for DiagnosticsConfig {
@@ -260,6 +403,9 @@ impl DiagnosticsConfig {
         let mut unused_call = None;
         let mut unnecessary_function_call = None;
         let mut using_fallback_version = None;
+        let mut constant_condition = None;
+        let mut unused_struct = None;
+        let mut uncalled_task = None;
 
         for rule in rules {
             let rule = rule.as_ref();
@@ -270,6 +416,9 @@ impl DiagnosticsConfig {
                 UNUSED_CALL_RULE_ID => unused_call = Some(rule.severity()),
                 UNNECESSARY_FUNCTION_CALL => unnecessary_function_call = Some(rule.severity()),
                 USING_FALLBACK_VERSION => using_fallback_version = Some(rule.severity()),
+                CONSTANT_CONDITION_RULE_ID => constant_condition = Some(rule.severity()),
+                UNUSED_STRUCT_RULE_ID => unused_struct = Some(rule.severity()),
+                UNCALLED_TASK_RULE_ID => uncalled_task = Some(rule.severity()),
                 unrecognized => {
                     warn!(unrecognized, "unrecognized rule");
                     if cfg!(test) {
@@ -286,6 +435,9 @@ impl DiagnosticsConfig {
             unused_call,
             unnecessary_function_call,
             using_fallback_version,
+            constant_condition,
+            unused_struct,
+            uncalled_task,
         }
     }
 
@@ -318,6 +470,18 @@ impl DiagnosticsConfig {
             self.using_fallback_version = None;
         }
 
+        if exceptions.contains(CONSTANT_CONDITION_RULE_ID) {
+            self.constant_condition = None;
+        }
+
+        if exceptions.contains(UNUSED_STRUCT_RULE_ID) {
+            self.unused_struct = None;
+        }
+
+        if exceptions.contains(UNCALLED_TASK_RULE_ID) {
+            self.uncalled_task = None;
+        }
+
         self
     }
 
@@ -330,6 +494,9 @@ impl DiagnosticsConfig {
             unused_call: None,
             unnecessary_function_call: None,
             using_fallback_version: None,
+            constant_condition: None,
+            unused_struct: None,
+            uncalled_task: None,
         }
     }
 }
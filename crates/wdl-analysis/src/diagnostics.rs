@@ -12,11 +12,14 @@ use wdl_ast::TreeToken;
 use wdl_ast::Version;
 use wdl_ast::v1::PlaceholderOption;
 
+use crate::CONSTANT_CONDITION_RULE_ID;
+use crate::UNCALLED_TASK_RULE_ID;
 use crate::UNNECESSARY_FUNCTION_CALL;
 use crate::UNUSED_CALL_RULE_ID;
 use crate::UNUSED_DECL_RULE_ID;
 use crate::UNUSED_IMPORT_RULE_ID;
 use crate::UNUSED_INPUT_RULE_ID;
+use crate::UNUSED_STRUCT_RULE_ID;
 use crate::types::CallKind;
 use crate::types::CallType;
 use crate::types::Type;
@@ -789,6 +792,22 @@ pub fn unused_call(name: &str, span: Span) -> Diagnostic {
         .with_highlight(span)
 }
 
+/// Creates an "unused struct" diagnostic.
+pub fn unused_struct(name: &str, span: Span) -> Diagnostic {
+    Diagnostic::warning(format!("unused struct `{name}`"))
+        .with_rule(UNUSED_STRUCT_RULE_ID)
+        .with_highlight(span)
+        .with_fix("remove the struct if it is not used outside of the analyzed documents")
+}
+
+/// Creates an "uncalled task" diagnostic.
+pub fn uncalled_task(name: &str, span: Span) -> Diagnostic {
+    Diagnostic::warning(format!("task `{name}` is not called by any workflow"))
+        .with_rule(UNCALLED_TASK_RULE_ID)
+        .with_highlight(span)
+        .with_fix("remove the task if it is not called or run outside of the analyzed documents")
+}
+
 /// Creates an "unnecessary function call" diagnostic.
 pub fn unnecessary_function_call(
     name: &str,
@@ -802,6 +821,25 @@ pub fn unnecessary_function_call(
         .with_label(label.to_string(), label_span)
 }
 
+/// Creates a "constant condition" diagnostic.
+///
+/// The given clause is the kind of conditional clause with the condition (e.g.
+/// `if` or `else if`).
+pub fn constant_condition(clause: &str, value: bool, span: Span) -> Diagnostic {
+    let fix = if value {
+        "remove the condition so that the declarations and call outputs within it are not optional"
+    } else {
+        "remove the clause as it is never evaluated"
+    };
+
+    Diagnostic::warning(format!(
+        "the condition of the `{clause}` clause is always `{value}`"
+    ))
+    .with_rule(CONSTANT_CONDITION_RULE_ID)
+    .with_label(format!("this always evaluates to `{value}`"), span)
+    .with_fix(fix)
+}
+
 /// Generates a diagnostic error message when a placeholder option has a type
 /// mismatch.
 pub fn invalid_placeholder_option<N: TreeNode>(
@@ -839,3 +877,13 @@ pub fn invalid_regex_pattern(
     ))
     .with_label("invalid regular expression", span)
 }
+
+/// Creates an invalid return codes diagnostic.
+pub fn invalid_return_codes(name: &str, value: &str, span: Span) -> Diagnostic {
+    Diagnostic::error(format!(
+        "invalid value `{value}` for `{name}`: only `*` is accepted when the return codes are \
+         specified as a string"
+    ))
+    .with_highlight(span)
+    .with_fix("use `*` to accept any return code or specify the accepted return codes as integers")
+}
@@ -25,6 +25,7 @@ use crate::diagnostics::no_common_type;
 use crate::diagnostics::unused_import;
 use crate::graph::DocumentGraph;
 use crate::graph::ParseState;
+use crate::model::SemanticModel;
 use crate::types::CallType;
 use crate::types::Optional;
 use crate::types::Type;
@@ -788,6 +789,33 @@ impl Document {
         self.data.workflow.as_ref()
     }
 
+    /// Gets the qualified names of the callables with the given name that may
+    /// be suggested as the target of a call that could not be resolved.
+    ///
+    /// The namespace specified by the call, if any, is excluded.
+    pub(crate) fn call_target_suggestions(
+        &self,
+        namespace: Option<&str>,
+        name: &str,
+    ) -> Vec<String> {
+        v1::call_target_suggestions(&self.data, namespace, name)
+    }
+
+    /// Gets the semantic model of the document.
+    ///
+    /// The semantic model describes the workflow, tasks, and structs of the
+    /// document and may be serialized for use by other tools.
+    pub fn semantic_model(&self) -> SemanticModel {
+        let ast = self.data.root.clone().and_then(|root| {
+            wdl_ast::Document::cast(SyntaxNode::new_root(root))
+                .expect("should cast")
+                .ast_with_version_fallback(self.data.config.fallback_version())
+                .into_v1()
+        });
+
+        SemanticModel::new(self, ast)
+    }
+
     /// Gets the structs in the document.
     pub fn structs(&self) -> impl Iterator<Item = (&str, &Struct)> {
         self.data.structs.iter().map(|(n, s)| (n.as_str(), s))
@@ -809,23 +837,44 @@ impl Document {
     }
 
     /// Gets all diagnostics for the document (both from parsing and analysis).
+    ///
+    /// If the document was analyzed with
+    /// [`Config::with_stable_output`](crate::Config::with_stable_output), the
+    /// diagnostics are ordered by span and then by rule; otherwise, the sorted
+    /// parse diagnostics are returned before the sorted analysis diagnostics.
     pub fn diagnostics(&self) -> impl Iterator<Item = &Diagnostic> {
-        self.data
-            .parse_diagnostics
-            .iter()
-            .chain(self.data.analysis_diagnostics.iter())
+        // The diagnostics were sorted when stored, so stable output only needs
+        // to merge the two lists
+        let stable = self.data.config.stable_output();
+        let mut parse = self.data.parse_diagnostics.iter().peekable();
+        let mut analysis = self.data.analysis_diagnostics.iter().peekable();
+        std::iter::from_fn(move || match (parse.peek(), analysis.peek()) {
+            (Some(p), Some(a)) if stable && a.cmp_by_location(p).is_lt() => analysis.next(),
+            (Some(_), _) => parse.next(),
+            (None, _) => analysis.next(),
+        })
     }
 
     /// Sorts the diagnostics for the document.
     ///
+    /// With stable output, the diagnostics are sorted by span and then by
+    /// rule.
+    ///
     /// # Panics
     ///
     /// Panics if there is more than one reference to the document.
     pub fn sort_diagnostics(&mut self) -> Self {
         let data = &mut self.data;
         let inner = Arc::get_mut(data).expect("should only have one reference");
-        inner.parse_diagnostics.sort();
-        inner.analysis_diagnostics.sort();
+        if inner.config.stable_output() {
+            inner.parse_diagnostics.sort_by(|a, b| a.cmp_by_location(b));
+            inner
+                .analysis_diagnostics
+                .sort_by(|a, b| a.cmp_by_location(b));
+        } else {
+            inner.parse_diagnostics.sort();
+            inner.analysis_diagnostics.sort();
+        }
         Self { data: data.clone() }
     }
 
@@ -1,6 +1,7 @@
 //! Conversion of a V1 AST to an analyzed document.
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::hash::RandomState;
 use std::sync::Arc;
 
@@ -29,6 +30,7 @@ use wdl_ast::v1::Decl;
 use wdl_ast::v1::DocumentItem;
 use wdl_ast::v1::Expr;
 use wdl_ast::v1::ImportStatement;
+use wdl_ast::v1::LiteralExpr;
 use wdl_ast::v1::ScatterStatement;
 use wdl_ast::v1::StructDefinition;
 use wdl_ast::v1::TaskDefinition;
@@ -50,7 +52,9 @@ use super::Struct;
 use super::TASK_VAR_NAME;
 use super::Task;
 use super::Workflow;
+use crate::CONSTANT_CONDITION_RULE_ID;
 use crate::SyntaxNodeExt;
+use crate::UNNECESSARY_FUNCTION_CALL;
 use crate::UNUSED_CALL_RULE_ID;
 use crate::UNUSED_DECL_RULE_ID;
 use crate::UNUSED_IMPORT_RULE_ID;
@@ -61,6 +65,7 @@ use crate::diagnostics::Context;
 use crate::diagnostics::Io;
 use crate::diagnostics::NameContext;
 use crate::diagnostics::call_input_type_mismatch;
+use crate::diagnostics::constant_condition as constant_condition_diagnostic;
 use crate::diagnostics::duplicate_workflow;
 use crate::diagnostics::else_if_not_supported;
 use crate::diagnostics::else_not_supported;
@@ -1225,7 +1230,127 @@ fn add_conditional_statement(
             document
                 .analysis_diagnostics
                 .push(if_conditional_mismatch(&ty, expr.span()));
+            continue;
+        }
+
+        // Check for a condition that always evaluates to the same value
+        if let Some(severity) = config.diagnostics_config().constant_condition
+            && !statement
+                .inner()
+                .is_rule_excepted(CONSTANT_CONDITION_RULE_ID)
+            && let Some(value) =
+                constant_condition(config, &expr, &ScopeRef::new(scopes, scope_index))
+        {
+            let clause = match clause.kind() {
+                ConditionalStatementClauseKind::ElseIf => "else if",
+                _ => "if",
+            };
+
+            document.analysis_diagnostics.push(
+                constant_condition_diagnostic(clause, value, expr.span()).with_severity(severity),
+            );
+        }
+    }
+}
+
+/// Determines if the given condition always evaluates to the same value.
+///
+/// Calls to `defined` with a non-optional argument are not considered when
+/// the `UnnecessaryFunctionCall` rule reports them.
+///
+/// Returns `None` if the value of the condition cannot be determined
+/// statically.
+fn constant_condition(config: &Config, expr: &Expr, scope: &ScopeRef<'_>) -> Option<bool> {
+    match expr {
+        Expr::Literal(LiteralExpr::Boolean(b)) => Some(b.value()),
+        Expr::Parenthesized(e) => constant_condition(config, &e.expr(), scope),
+        Expr::LogicalNot(e) => constant_condition(config, &e.operand(), scope).map(|v| !v),
+        Expr::LogicalAnd(e) => {
+            let (lhs, rhs) = e.operands();
+            match (
+                constant_condition(config, &lhs, scope),
+                constant_condition(config, &rhs, scope),
+            ) {
+                (Some(false), _) | (_, Some(false)) => Some(false),
+                (Some(true), Some(true)) => Some(true),
+                _ => None,
+            }
+        }
+        Expr::LogicalOr(e) => {
+            let (lhs, rhs) = e.operands();
+            match (
+                constant_condition(config, &lhs, scope),
+                constant_condition(config, &rhs, scope),
+            ) {
+                (Some(true), _) | (_, Some(true)) => Some(true),
+                (Some(false), Some(false)) => Some(false),
+                _ => None,
+            }
+        }
+        Expr::Equality(e) => {
+            let (lhs, rhs) = e.operands();
+            constant_equality(&lhs, &rhs)
+        }
+        Expr::Inequality(e) => {
+            let (lhs, rhs) = e.operands();
+            constant_equality(&lhs, &rhs).map(|v| !v)
+        }
+        Expr::Call(e) if e.target().text() == "defined" => match e.arguments().next()? {
+            Expr::Literal(LiteralExpr::None(_)) => Some(false),
+            Expr::NameRef(_)
+                if config
+                    .diagnostics_config()
+                    .unnecessary_function_call
+                    .is_some()
+                    && !e.inner().is_rule_excepted(UNNECESSARY_FUNCTION_CALL) =>
+            {
+                None
+            }
+            Expr::NameRef(r) => {
+                let ty = scope.lookup(r.name().text())?.ty();
+                (!ty.is_optional() && !ty.is_union()).then_some(true)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Determines if the given operands of an equality comparison are always equal
+/// or always unequal.
+///
+/// Returns `None` if the result of the comparison cannot be determined
+/// statically.
+fn constant_equality(lhs: &Expr, rhs: &Expr) -> Option<bool> {
+    match (lhs, rhs) {
+        (Expr::Literal(LiteralExpr::Boolean(a)), Expr::Literal(LiteralExpr::Boolean(b))) => {
+            Some(a.value() == b.value())
+        }
+        (Expr::Literal(LiteralExpr::Integer(a)), Expr::Literal(LiteralExpr::Integer(b))) => {
+            Some(a.value()? == b.value()?)
+        }
+        // A name or a literal always equals itself
+        (Expr::NameRef(a), Expr::NameRef(b)) => {
+            (a.name().text() == b.name().text()).then_some(true)
         }
+        (Expr::Literal(a), Expr::Literal(b)) if is_simple_literal(a) => {
+            (a.inner().text() == b.inner().text()).then_some(true)
+        }
+        _ => None,
+    }
+}
+
+/// Determines if the given literal is a primitive literal or `None`.
+///
+/// String literals with placeholders are not simple literals.
+fn is_simple_literal(literal: &LiteralExpr) -> bool {
+    match literal {
+        LiteralExpr::Boolean(_)
+        | LiteralExpr::Integer(_)
+        | LiteralExpr::Float(_)
+        | LiteralExpr::None(_) => true,
+        LiteralExpr::String(s) => s.text().is_some(),
+        _ => false,
     }
 }
 
@@ -1437,9 +1562,16 @@ fn resolve_call_type(
                 namespace = Some(&document.namespaces[target.text()])
             }
             None => {
-                document
-                    .analysis_diagnostics
-                    .push(unknown_namespace(&target));
+                let callee = statement.target().names().last().expect("should have name");
+                let suggestions =
+                    call_target_suggestions(document, Some(target.text()), callee.text());
+                let indirect = indirect_call_targets(document, callee.text());
+                document.analysis_diagnostics.push(with_call_target_fix(
+                    unknown_namespace(&target),
+                    callee.text(),
+                    &suggestions,
+                    &indirect,
+                ));
                 return None;
             }
         }
@@ -1465,10 +1597,24 @@ fn resolve_call_type(
                 workflow.outputs.clone(),
             ),
             _ => {
-                document.analysis_diagnostics.push(unknown_task_or_workflow(
-                    namespace.map(|ns| ns.span),
+                let diagnostic =
+                    unknown_task_or_workflow(namespace.map(|ns| ns.span), name.text(), name.span());
+                let ns_name = statement
+                    .target()
+                    .names()
+                    .next()
+                    .filter(|_| namespace.is_some());
+                let suggestions = call_target_suggestions(
+                    document,
+                    ns_name.as_ref().map(|n| n.text()),
+                    name.text(),
+                );
+                let indirect = indirect_call_targets(document, name.text());
+                document.analysis_diagnostics.push(with_call_target_fix(
+                    diagnostic,
                     name.text(),
-                    name.span(),
+                    &suggestions,
+                    &indirect,
                 ));
                 return None;
             }
@@ -1496,6 +1642,112 @@ fn resolve_call_type(
     }
 }
 
+/// Gets the qualified names of the callables with the given name that are
+/// visible to a document, for suggesting a call target.
+///
+/// Tasks of the document itself are suggested only when the call specified a
+/// namespace; the given namespace is never suggested.
+///
+/// Only the document's direct imports are searched, as a call may have at most
+/// one namespace qualifier; see [`indirect_call_targets`] for callables that
+/// are imported transitively.
+pub(crate) fn call_target_suggestions(
+    document: &DocumentData,
+    namespace: Option<&str>,
+    name: &str,
+) -> Vec<String> {
+    let mut suggestions = Vec::new();
+    if namespace.is_some() && document.tasks.contains_key(name) {
+        suggestions.push(name.to_string());
+    }
+
+    for (ns_name, ns) in &document.namespaces {
+        if Some(ns_name.as_str()) == namespace {
+            continue;
+        }
+
+        let data = &ns.document.data;
+        if data.tasks.contains_key(name) || data.workflow.as_ref().is_some_and(|w| w.name == name) {
+            suggestions.push(format!("{ns_name}.{name}"));
+        }
+    }
+
+    suggestions
+}
+
+/// Gets the namespace paths of the documents imported transitively (through
+/// another import) that have a callable with the given name.
+///
+/// Such callables cannot be the target of a call until their document is
+/// imported directly, so they are never suggested as a replacement.
+fn indirect_call_targets(document: &DocumentData, name: &str) -> Vec<String> {
+    let mut visited: HashSet<&Url> = document
+        .namespaces
+        .values()
+        .map(|ns| ns.source.as_ref())
+        .collect();
+    let mut queue: VecDeque<_> = document
+        .namespaces
+        .iter()
+        .map(|(ns_name, ns)| (ns_name.clone(), ns.document.data.as_ref()))
+        .collect();
+
+    let mut paths = Vec::new();
+    while let Some((path, data)) = queue.pop_front() {
+        for (ns_name, ns) in &data.namespaces {
+            if !visited.insert(ns.source.as_ref()) {
+                continue;
+            }
+
+            let path = format!("{path}.{ns_name}");
+            let data = ns.document.data.as_ref();
+            if data.tasks.contains_key(name)
+                || data.workflow.as_ref().is_some_and(|w| w.name == name)
+            {
+                paths.push(path.clone());
+            }
+
+            queue.push_back((path, data));
+        }
+    }
+
+    paths
+}
+
+/// Adds a fix to a call target diagnostic that suggests the given qualified
+/// names.
+///
+/// If there are no suggestions, the fix instead points out the namespaces
+/// through which a callable with the given name is imported indirectly.
+fn with_call_target_fix(
+    diagnostic: Diagnostic,
+    name: &str,
+    suggestions: &[String],
+    indirect: &[String],
+) -> Diagnostic {
+    match suggestions {
+        [] if indirect.is_empty() => diagnostic,
+        [] => diagnostic.with_fix(format!(
+            "`{name}` is only imported indirectly through {paths}; import its document directly \
+             to call it",
+            paths = indirect
+                .iter()
+                .map(|p| format!("`{p}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        [suggestion] => diagnostic.with_fix(format!("did you mean `{suggestion}`?")),
+        _ => diagnostic.with_fix(format!(
+            "did you mean one of {names}?",
+            names = suggestions
+                .iter()
+                .map(|s| format!("`{s}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
 /// Resolves an import to its document.
 fn resolve_import(
     graph: &DocumentGraph,
@@ -8,6 +8,7 @@ use crate::diagnostics;
 use crate::document::ScopeRef;
 use crate::types::v1::EvaluationContext;
 
+mod code_action;
 mod common;
 mod completions;
 mod document_symbol;
@@ -20,6 +21,7 @@ mod signature_help;
 pub(crate) mod snippets;
 mod workspace_symbol;
 
+pub use code_action::*;
 pub use completions::*;
 pub use document_symbol::*;
 pub use find_all_references::*;
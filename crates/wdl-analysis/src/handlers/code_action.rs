@@ -0,0 +1,193 @@
+//! Handlers for code action requests.
+//!
+//! This module implements the LSP `textDocument/codeAction` functionality for
+//! WDL files. It provides quick fixes that rewrite call targets which could not
+//! be resolved to a qualified name found in the document's imports and that
+//! insert a version statement into documents missing one.
+//!
+//! See: [LSP Specification](https://microsoft.github.io/language-server-protocol/specifications/lsp/3.17/specification/#textDocument_codeAction)
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use anyhow::bail;
+use line_index::LineIndex;
+use lsp_types::CodeAction;
+use lsp_types::CodeActionKind;
+use lsp_types::TextEdit;
+use lsp_types::WorkspaceEdit;
+use url::Url;
+use wdl_ast::AstNode;
+use wdl_ast::AstToken;
+use wdl_ast::Span;
+use wdl_ast::SyntaxKind;
+use wdl_ast::SyntaxNode;
+use wdl_ast::v1::CallStatement;
+use wdl_grammar::lexer::v1::find_version_statement;
+
+use crate::Document;
+use crate::SourcePosition;
+use crate::SourcePositionEncoding;
+use crate::graph::DocumentGraph;
+use crate::graph::ParseState;
+use crate::handlers::common::location_from_span;
+use crate::handlers::common::position_to_offset;
+
+/// The WDL version inserted into documents missing a version statement.
+const DEFAULT_VERSION: &str = "1.2";
+
+/// Handles a code action request.
+///
+/// Returns quick fixes for the unresolved call targets between the given
+/// start and end positions, along with a quick fix for inserting a version
+/// statement if the document is missing one.
+pub fn code_action(
+    graph: &DocumentGraph,
+    uri: &Url,
+    start: SourcePosition,
+    end: SourcePosition,
+    encoding: SourcePositionEncoding,
+) -> Result<Option<Vec<CodeAction>>> {
+    let Some(index) = graph.get_index(uri) else {
+        bail!("document `{uri}` not found in graph")
+    };
+    let node = graph.get(index);
+    let (root, lines) = match node.parse_state() {
+        ParseState::Parsed { root, lines, .. } => {
+            (SyntaxNode::new_root(root.clone()), lines.clone())
+        }
+        _ => bail!("document `{uri}` has not been parsed"),
+    };
+    let Some(document) = node.document() else {
+        bail!("document `{uri}` has not been analyzed")
+    };
+
+    let start = u32::from(position_to_offset(&lines, start, encoding)?) as usize;
+    let end = u32::from(position_to_offset(&lines, end, encoding)?) as usize;
+    let range = Span::new(start, end.saturating_sub(start));
+
+    let mut actions = Vec::new();
+    if let Some(action) = insert_version_statement(uri, &root, &lines)? {
+        actions.push(action);
+    }
+
+    for statement in root.descendants().filter_map(CallStatement::cast) {
+        let target = statement.target();
+        let span = target.span();
+        if span.end() < range.start() || span.start() > range.end() {
+            continue;
+        }
+
+        let names: Vec<_> = target.names().collect();
+        let (namespace, name) = match names.as_slice() {
+            [name] => (None, name.text()),
+            [namespace, name] => (Some(namespace.text()), name.text()),
+            _ => continue,
+        };
+
+        if is_resolved(document, namespace, name) {
+            continue;
+        }
+
+        let suggestions = document.call_target_suggestions(namespace, name);
+        let preferred = suggestions.len() == 1;
+        let location = location_from_span(uri, span, &lines)?;
+        for suggestion in suggestions {
+            actions.push(CodeAction {
+                title: format!("Replace with `{suggestion}`"),
+                kind: Some(CodeActionKind::QUICKFIX),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(
+                        uri.clone(),
+                        vec![TextEdit {
+                            range: location.range,
+                            new_text: suggestion,
+                        }],
+                    )])),
+                    document_changes: None,
+                    change_annotations: None,
+                }),
+                is_preferred: Some(preferred),
+                ..Default::default()
+            });
+        }
+    }
+
+    if actions.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(actions))
+}
+
+/// Creates a quick fix that inserts a version statement into a document
+/// missing one.
+///
+/// The version statement is inserted after any leading comments so that they
+/// remain in the document's preamble.
+///
+/// Returns `Ok(None)` if the document already has a version statement,
+/// including one that does not come first in the document.
+fn insert_version_statement(
+    uri: &Url,
+    root: &SyntaxNode,
+    lines: &Arc<LineIndex>,
+) -> Result<Option<CodeAction>> {
+    if root
+        .children()
+        .any(|c| c.kind() == SyntaxKind::VersionStatementNode)
+        || find_version_statement(&root.text().to_string()).is_some()
+    {
+        return Ok(None);
+    }
+
+    let mut last_comment = None;
+    for element in root.children_with_tokens() {
+        match element.kind() {
+            SyntaxKind::Comment => last_comment = Some(element.text_range().end()),
+            SyntaxKind::Whitespace => continue,
+            _ => break,
+        }
+    }
+
+    let (offset, new_text) = match last_comment {
+        Some(end) => (
+            u32::from(end) as usize,
+            format!("\n\nversion {DEFAULT_VERSION}"),
+        ),
+        None => (0, format!("version {DEFAULT_VERSION}\n\n")),
+    };
+
+    let location = location_from_span(uri, Span::new(offset, 0), lines)?;
+    Ok(Some(CodeAction {
+        title: format!("Insert `version {DEFAULT_VERSION}`"),
+        kind: Some(CodeActionKind::QUICKFIX),
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(
+                uri.clone(),
+                vec![TextEdit {
+                    range: location.range,
+                    new_text,
+                }],
+            )])),
+            document_changes: None,
+            change_annotations: None,
+        }),
+        is_preferred: Some(true),
+        ..Default::default()
+    }))
+}
+
+/// Determines if a call target resolves to a task or workflow.
+fn is_resolved(document: &Document, namespace: Option<&str>, name: &str) -> bool {
+    let document = match namespace {
+        Some(namespace) => match document.namespace(namespace) {
+            Some(ns) => ns.document(),
+            None => return false,
+        },
+        None => document,
+    };
+
+    document.task_by_name(name).is_some() || document.workflow().is_some_and(|w| w.name() == name)
+}
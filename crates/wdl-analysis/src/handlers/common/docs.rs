@@ -4,6 +4,9 @@ use lsp_types::Documentation;
 use lsp_types::MarkupContent;
 use rowan::TextSize;
 use wdl_ast::AstNode;
+use wdl_ast::AstToken;
+use wdl_ast::Span;
+use wdl_ast::v1::Decl;
 use wdl_ast::v1::StructDefinition;
 use wdl_ast::v1::TaskDefinition;
 use wdl_ast::v1::WorkflowDefinition;
@@ -81,3 +84,18 @@ pub fn provide_struct_documentation(
         Err(_) => None,
     }
 }
+
+/// Provides the documentation comment of the declaration with the given name
+/// span.
+///
+/// Returns `None` if the span is not the name of a declaration or if the
+/// declaration has no documentation comment.
+pub fn provide_decl_documentation(name_span: Span, root: &wdl_ast::Document) -> Option<String> {
+    let offset = TextSize::try_from(name_span.start()).ok()?;
+    root.inner()
+        .token_at_offset(offset)
+        .right_biased()
+        .and_then(|t| t.parent_ancestors().find_map(Decl::cast))
+        .filter(|d| d.name().span() == name_span)?
+        .doc_comment()
+}
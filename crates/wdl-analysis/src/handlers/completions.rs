@@ -73,6 +73,7 @@ use crate::handlers::TypeEvalContext;
 use crate::handlers::common::make_md_docs;
 use crate::handlers::common::position;
 use crate::handlers::common::position_to_offset;
+use crate::handlers::common::provide_decl_documentation;
 use crate::handlers::common::provide_struct_documentation;
 use crate::handlers::common::provide_task_documentation;
 use crate::handlers::common::provide_workflow_documentation;
@@ -191,7 +192,7 @@ pub fn completion(
                 SyntaxKind::WorkflowDefinitionNode => {
                     add_keyword_completions(&WORKFLOW_ITEM_EXPECTED_SET, &mut items);
                     if let Some(scope) = document.find_scope_by_position(offset.into()) {
-                        add_scope_completions(scope, &mut items);
+                        add_scope_completions(document, scope, &mut items);
                     }
                     add_stdlib_completions(&mut items);
                     add_struct_completions(document, &mut items);
@@ -202,7 +203,7 @@ pub fn completion(
                 SyntaxKind::ScatterStatementNode | SyntaxKind::ConditionalStatementNode => {
                     add_keyword_completions(&NESTED_WORKFLOW_STATEMENT_KEYWORDS, &mut items);
                     if let Some(scope) = document.find_scope_by_position(offset.into()) {
-                        add_scope_completions(scope, &mut items);
+                        add_scope_completions(document, scope, &mut items);
                     }
                     add_stdlib_completions(&mut items);
                     add_struct_completions(document, &mut items);
@@ -214,7 +215,7 @@ pub fn completion(
                 SyntaxKind::TaskDefinitionNode => {
                     add_keyword_completions(&TASK_ITEM_EXPECTED_SET, &mut items);
                     if let Some(scope) = document.find_scope_by_position(offset.into()) {
-                        add_scope_completions(scope, &mut items);
+                        add_scope_completions(document, scope, &mut items);
                     }
                     add_stdlib_completions(&mut items);
                     add_struct_completions(document, &mut items);
@@ -728,7 +729,15 @@ fn add_callable_completions(document: &Document, items: &mut Vec<CompletionItem>
 
 /// Adds completions for variables and declarations visible in the current
 /// scope.
-fn add_scope_completions(scope: ScopeRef<'_>, items: &mut Vec<CompletionItem>) {
+///
+/// Declarations with documentation comments include the comment as the
+/// completion's documentation.
+fn add_scope_completions(
+    document: &Document,
+    scope: ScopeRef<'_>,
+    items: &mut Vec<CompletionItem>,
+) {
+    let root = document.root();
     let mut current_scope = Some(scope);
     while let Some(s) = current_scope {
         for (name, name_info) in s.names() {
@@ -748,6 +757,8 @@ fn add_scope_completions(scope: ScopeRef<'_>, items: &mut Vec<CompletionItem>) {
                     label: name.to_string(),
                     kind,
                     detail,
+                    documentation: provide_decl_documentation(name_info.span(), &root)
+                        .and_then(make_md_docs),
                     ..Default::default()
                 });
             }
@@ -37,6 +37,7 @@ use crate::handlers::TypeEvalContext;
 use crate::handlers::common::find_identifier_token_at_offset;
 use crate::handlers::common::location_from_span;
 use crate::handlers::common::position_to_offset;
+use crate::handlers::common::provide_decl_documentation;
 use crate::handlers::common::provide_struct_documentation;
 use crate::handlers::common::provide_task_documentation;
 use crate::handlers::common::provide_workflow_documentation;
@@ -119,7 +120,8 @@ fn resolve_hover_content(
         let (kind, documentation) = match name.ty() {
             Type::Call(_) => ("call", None),
             _ => {
-                let doc = find_parameter_meta_documentation(token);
+                let doc = provide_decl_documentation(name.span(), &document.root())
+                    .or_else(|| find_parameter_meta_documentation(token));
                 ("variable", doc)
             }
         };
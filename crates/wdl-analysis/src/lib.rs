@@ -45,11 +45,13 @@ pub mod document;
 pub mod eval;
 mod graph;
 pub mod handlers;
+pub mod model;
 mod queue;
 mod rayon;
 mod rules;
 pub mod stdlib;
 pub mod types;
+mod unused;
 mod validation;
 mod visitor;
 
@@ -59,6 +61,7 @@ pub use config::DiagnosticsConfig;
 pub use config::FeatureFlags;
 pub use document::Document;
 pub use rules::*;
+pub use unused::*;
 pub use validation::*;
 pub use visitor::*;
 
@@ -0,0 +1,537 @@
+//! Representation of the semantic model of analyzed WDL documents.
+//!
+//! The semantic model describes the workflow, tasks, and structs of a document
+//! (their typed inputs and outputs, defaults, and metadata) in a form that can
+//! be serialized with [`serde`]. It allows tools, such as registry user
+//! interfaces and submission portals, to introspect a document without
+//! traversing its AST.
+//!
+//! The model is a stable API: fields may be added to it, but existing fields
+//! will not be renamed or removed without a breaking release.
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde::Serialize;
+use wdl_ast::AstNode;
+use wdl_ast::AstToken;
+use wdl_ast::v1;
+
+use crate::document::Document;
+use crate::document::Input;
+use crate::document::Output;
+
+/// Represents a metadata value of a task, workflow, or parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MetadataValue {
+    /// The value is `null`.
+    Null,
+    /// The value is a boolean.
+    Boolean(bool),
+    /// The value is an integer.
+    Integer(i64),
+    /// The value is a float.
+    Float(f64),
+    /// The value is a string.
+    String(String),
+    /// The value is an array.
+    Array(Vec<MetadataValue>),
+    /// The value is an object.
+    Object(IndexMap<String, MetadataValue>),
+}
+
+impl MetadataValue {
+    /// Constructs a metadata value from a V1 AST metadata value.
+    ///
+    /// Numbers that are out of range are represented as `null`.
+    fn from_v1(value: &v1::MetadataValue) -> Self {
+        match value {
+            v1::MetadataValue::Boolean(v) => Self::Boolean(v.value()),
+            v1::MetadataValue::Integer(v) => v.value().map_or(Self::Null, Self::Integer),
+            v1::MetadataValue::Float(v) => v.value().map_or(Self::Null, Self::Float),
+            v1::MetadataValue::String(v) => {
+                Self::String(v.text().map(|t| t.text().to_string()).unwrap_or_default())
+            }
+            v1::MetadataValue::Null(_) => Self::Null,
+            v1::MetadataValue::Object(o) => Self::Object(metadata_items(o.items())),
+            v1::MetadataValue::Array(a) => {
+                Self::Array(a.elements().map(|v| Self::from_v1(&v)).collect())
+            }
+        }
+    }
+}
+
+/// Collects metadata items into a map of metadata values.
+fn metadata_items(
+    items: impl Iterator<Item = v1::MetadataObjectItem>,
+) -> IndexMap<String, MetadataValue> {
+    items
+        .map(|item| {
+            (
+                item.name().text().to_string(),
+                MetadataValue::from_v1(&item.value()),
+            )
+        })
+        .collect()
+}
+
+/// Represents an input of a task or workflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputModel {
+    /// The name of the input.
+    name: String,
+    /// The type of the input.
+    #[serde(rename = "type")]
+    ty: String,
+    /// Whether or not the input is required.
+    required: bool,
+    /// The source of the input's default expression.
+    default: Option<String>,
+    /// The parameter metadata of the input.
+    meta: Option<MetadataValue>,
+}
+
+impl InputModel {
+    /// Gets the name of the input.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the type of the input.
+    pub fn ty(&self) -> &str {
+        &self.ty
+    }
+
+    /// Whether or not the input is required.
+    ///
+    /// A required input is one that has a non-optional type and no default
+    /// expression.
+    pub fn required(&self) -> bool {
+        self.required
+    }
+
+    /// Gets the source of the input's default expression.
+    ///
+    /// Returns `None` if the input has no default.
+    pub fn default(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    /// Gets the parameter metadata of the input.
+    ///
+    /// Returns `None` if the input has no entry in the `parameter_meta`
+    /// section.
+    pub fn meta(&self) -> Option<&MetadataValue> {
+        self.meta.as_ref()
+    }
+}
+
+/// Represents an output of a task or workflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutputModel {
+    /// The name of the output.
+    name: String,
+    /// The type of the output.
+    #[serde(rename = "type")]
+    ty: String,
+    /// The source of the output's expression.
+    expression: Option<String>,
+    /// The parameter metadata of the output.
+    meta: Option<MetadataValue>,
+}
+
+impl OutputModel {
+    /// Gets the name of the output.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the type of the output.
+    pub fn ty(&self) -> &str {
+        &self.ty
+    }
+
+    /// Gets the source of the output's expression.
+    ///
+    /// Returns `None` if the document could not be parsed.
+    pub fn expression(&self) -> Option<&str> {
+        self.expression.as_deref()
+    }
+
+    /// Gets the parameter metadata of the output.
+    ///
+    /// Returns `None` if the output has no entry in the `parameter_meta`
+    /// section.
+    pub fn meta(&self) -> Option<&MetadataValue> {
+        self.meta.as_ref()
+    }
+}
+
+/// Represents the sections of a task or workflow definition used by the
+/// semantic model.
+#[derive(Default)]
+struct Sections {
+    /// The input section of the definition.
+    input: Option<v1::InputSection>,
+    /// The output section of the definition.
+    output: Option<v1::OutputSection>,
+    /// The metadata section of the definition.
+    metadata: Option<v1::MetadataSection>,
+    /// The parameter metadata section of the definition.
+    parameter_metadata: Option<v1::ParameterMetadataSection>,
+}
+
+/// Represents a task or workflow.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CallableModel {
+    /// The name of the task or workflow.
+    name: String,
+    /// The inputs of the task or workflow.
+    inputs: Vec<InputModel>,
+    /// The outputs of the task or workflow.
+    outputs: Vec<OutputModel>,
+    /// The metadata of the task or workflow.
+    meta: IndexMap<String, MetadataValue>,
+}
+
+impl CallableModel {
+    /// Constructs a new model of a task or workflow.
+    fn new(
+        name: &str,
+        inputs: &IndexMap<String, Input>,
+        outputs: &IndexMap<String, Output>,
+        sections: Sections,
+    ) -> Self {
+        let mut parameter_meta = sections
+            .parameter_metadata
+            .map(|s| metadata_items(s.items()))
+            .unwrap_or_default();
+
+        let inputs = inputs
+            .iter()
+            .map(|(n, input)| InputModel {
+                name: n.clone(),
+                ty: input.ty().to_string(),
+                required: input.required(),
+                default: sections.input.as_ref().and_then(|s| {
+                    s.declarations()
+                        .find(|d| d.name().text() == n)
+                        .and_then(|d| d.expr())
+                        .map(|e| e.inner().text().to_string())
+                }),
+                meta: parameter_meta.shift_remove(n),
+            })
+            .collect();
+
+        let outputs = outputs
+            .iter()
+            .map(|(n, output)| OutputModel {
+                name: n.clone(),
+                ty: output.ty().to_string(),
+                expression: sections.output.as_ref().and_then(|s| {
+                    s.declarations()
+                        .find(|d| d.name().text() == n)
+                        .map(|d| d.expr().inner().text().to_string())
+                }),
+                meta: parameter_meta.shift_remove(n),
+            })
+            .collect();
+
+        Self {
+            name: name.to_string(),
+            inputs,
+            outputs,
+            meta: sections
+                .metadata
+                .map(|s| metadata_items(s.items()))
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Gets the name of the task or workflow.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the inputs of the task or workflow, in declaration order.
+    pub fn inputs(&self) -> &[InputModel] {
+        &self.inputs
+    }
+
+    /// Gets the outputs of the task or workflow, in declaration order.
+    pub fn outputs(&self) -> &[OutputModel] {
+        &self.outputs
+    }
+
+    /// Gets the metadata of the task or workflow.
+    pub fn meta(&self) -> &IndexMap<String, MetadataValue> {
+        &self.meta
+    }
+}
+
+/// Represents a struct.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructModel {
+    /// The name of the struct.
+    name: String,
+    /// The types of the struct's members.
+    members: IndexMap<String, String>,
+}
+
+impl StructModel {
+    /// Gets the name of the struct.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the types of the struct's members, in declaration order.
+    pub fn members(&self) -> &IndexMap<String, String> {
+        &self.members
+    }
+}
+
+/// Represents the semantic model of an analyzed document.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SemanticModel {
+    /// The WDL version of the document.
+    version: Option<String>,
+    /// The workflow of the document.
+    workflow: Option<CallableModel>,
+    /// The tasks of the document.
+    tasks: Vec<CallableModel>,
+    /// The structs defined in the document.
+    structs: Vec<StructModel>,
+}
+
+impl SemanticModel {
+    /// Constructs the semantic model of the given document.
+    ///
+    /// The AST of the document provides the defaults and metadata of the
+    /// model; it is `None` if the document could not be parsed.
+    pub(crate) fn new(document: &Document, ast: Option<v1::Ast>) -> Self {
+        let workflow = document.workflow().map(|workflow| {
+            let sections = ast
+                .as_ref()
+                .and_then(|ast| ast.workflows().find(|w| w.name().text() == workflow.name()))
+                .map(|w| Sections {
+                    input: w.input(),
+                    output: w.output(),
+                    metadata: w.metadata(),
+                    parameter_metadata: w.parameter_metadata(),
+                })
+                .unwrap_or_default();
+
+            CallableModel::new(
+                workflow.name(),
+                workflow.inputs(),
+                workflow.outputs(),
+                sections,
+            )
+        });
+
+        let tasks = document
+            .tasks()
+            .map(|task| {
+                let sections = ast
+                    .as_ref()
+                    .and_then(|ast| ast.tasks().find(|t| t.name().text() == task.name()))
+                    .map(|t| Sections {
+                        input: t.input(),
+                        output: t.output(),
+                        metadata: t.metadata(),
+                        parameter_metadata: t.parameter_metadata(),
+                    })
+                    .unwrap_or_default();
+
+                CallableModel::new(task.name(), task.inputs(), task.outputs(), sections)
+            })
+            .collect();
+
+        // Only the structs defined in the document are included; imported structs are
+        // included in the models of the documents that define them
+        let structs = document
+            .structs()
+            .filter(|(_, s)| s.namespace().is_none())
+            .map(|(name, s)| StructModel {
+                name: name.to_string(),
+                members: s
+                    .ty()
+                    .and_then(|ty| ty.as_struct())
+                    .map(|ty| {
+                        ty.members()
+                            .iter()
+                            .map(|(n, ty)| (n.clone(), ty.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            })
+            .collect();
+
+        Self {
+            version: document.version().map(|v| v.to_string()),
+            workflow,
+            tasks,
+            structs,
+        }
+    }
+
+    /// Gets the WDL version of the document.
+    ///
+    /// Returns `None` if the document could not be parsed or contains an
+    /// unsupported version.
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
+    }
+
+    /// Gets the workflow of the document.
+    ///
+    /// Returns `None` if the document does not contain a workflow.
+    pub fn workflow(&self) -> Option<&CallableModel> {
+        self.workflow.as_ref()
+    }
+
+    /// Gets the tasks of the document, in declaration order.
+    pub fn tasks(&self) -> &[CallableModel] {
+        &self.tasks
+    }
+
+    /// Gets the structs defined in the document, in declaration order.
+    pub fn structs(&self) -> &[StructModel] {
+        &self.structs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::Analyzer;
+    use crate::path_to_uri;
+
+    #[tokio::test]
+    async fn semantic_model() {
+        let dir = TempDir::new().expect("failed to create temporary directory");
+        let path = dir.path().join("foo.wdl");
+        fs::write(
+            &path,
+            r#"version 1.2
+
+struct Sample {
+    String name
+    Array[File] reads
+}
+
+task greet {
+    meta {
+        description: "Greets someone"
+        tags: ["example", 1, true, null]
+    }
+
+    parameter_meta {
+        name: { help: "The name to greet" }
+        greeting: "The greeting"
+    }
+
+    input {
+        String name
+        Int times = 1 + 1
+        String? suffix
+    }
+
+    command <<<>>>
+
+    output {
+        String greeting = "hello ~{name}"
+    }
+}
+
+workflow main {
+    input {
+        Sample sample
+    }
+
+    call greet { name = sample.name }
+
+    output {
+        String greeting = greet.greeting
+    }
+}
+"#,
+        )
+        .expect("failed to create test file");
+
+        let analyzer = Analyzer::default();
+        analyzer
+            .add_document(path_to_uri(&path).expect("should convert to URI"))
+            .await
+            .expect("should add document");
+
+        let results = analyzer.analyze(()).await.unwrap();
+        assert_eq!(results.len(), 1);
+        let model = results[0].document.semantic_model();
+        assert_eq!(model.version(), Some("1.2"));
+
+        assert_eq!(model.structs().len(), 1);
+        assert_eq!(model.structs()[0].name(), "Sample");
+        assert_eq!(
+            model.structs()[0]
+                .members()
+                .iter()
+                .map(|(n, ty)| (n.as_str(), ty.as_str()))
+                .collect::<Vec<_>>(),
+            [("name", "String"), ("reads", "Array[File]")]
+        );
+
+        let task = &model.tasks()[0];
+        assert_eq!(task.name(), "greet");
+        assert_eq!(
+            task.meta().get("description"),
+            Some(&MetadataValue::String("Greets someone".to_string()))
+        );
+        assert_eq!(
+            task.meta().get("tags"),
+            Some(&MetadataValue::Array(vec![
+                MetadataValue::String("example".to_string()),
+                MetadataValue::Integer(1),
+                MetadataValue::Boolean(true),
+                MetadataValue::Null,
+            ]))
+        );
+
+        let inputs = task
+            .inputs()
+            .iter()
+            .map(|i| (i.name(), i.ty(), i.required(), i.default()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            inputs,
+            [
+                ("name", "String", true, None),
+                ("times", "Int", false, Some("1 + 1")),
+                ("suffix", "String?", false, None),
+            ]
+        );
+        assert_eq!(
+            task.inputs()[0].meta(),
+            Some(&MetadataValue::Object(IndexMap::from([(
+                "help".to_string(),
+                MetadataValue::String("The name to greet".to_string())
+            )])))
+        );
+        assert_eq!(task.outputs()[0].name(), "greeting");
+        assert_eq!(task.outputs()[0].expression(), Some("\"hello ~{name}\""));
+        assert_eq!(
+            task.outputs()[0].meta(),
+            Some(&MetadataValue::String("The greeting".to_string()))
+        );
+
+        let workflow = model.workflow().expect("should have a workflow");
+        assert_eq!(workflow.name(), "main");
+        assert_eq!(workflow.inputs()[0].ty(), "Sample");
+        assert_eq!(workflow.outputs()[0].expression(), Some("greet.greeting"));
+    }
+}
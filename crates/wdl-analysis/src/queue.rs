@@ -15,6 +15,7 @@ use futures::Future;
 use futures::StreamExt;
 use futures::stream::FuturesUnordered;
 use indexmap::IndexSet;
+use lsp_types::CodeAction;
 use lsp_types::CompletionResponse;
 use lsp_types::DocumentSymbolResponse;
 use lsp_types::GotoDefinitionResponse;
@@ -89,6 +90,8 @@ pub enum Request<Context> {
     WorkspaceSymbol(WorkspaceSymbolRequest),
     /// A request to get signature help.
     SignatureHelp(SignatureHelpRequest),
+    /// A request to get code actions.
+    CodeAction(CodeActionRequest),
 }
 
 /// Represents a request to add documents to the graph.
@@ -251,6 +254,20 @@ pub struct SignatureHelpRequest {
     pub completed: oneshot::Sender<Option<SignatureHelp>>,
 }
 
+/// Represents a request for code actions.
+pub struct CodeActionRequest {
+    /// The document where the request was initiated.
+    pub document: Url,
+    /// The start of the range to get code actions for.
+    pub start: SourcePosition,
+    /// The end of the range to get code actions for.
+    pub end: SourcePosition,
+    /// The encoding used for the positions.
+    pub encoding: SourcePositionEncoding,
+    /// The sender for completing the request.
+    pub completed: oneshot::Sender<Option<Vec<CodeAction>>>,
+}
+
 /// A simple enumeration to signal a cancellation to the caller.
 enum Cancelable<T> {
     /// The operation completed and yielded a value.
@@ -714,6 +731,35 @@ where
                         }
                     }
                 }
+                Request::CodeAction(CodeActionRequest {
+                    document,
+                    start,
+                    end,
+                    encoding,
+                    completed,
+                }) => {
+                    let start_time = Instant::now();
+                    debug!(
+                        "received request for code actions at {document}: {line}:{char}",
+                        line = start.line,
+                        char = start.character
+                    );
+
+                    let graph = self.graph.read();
+                    match handlers::code_action(&graph, &document, start, end, encoding) {
+                        Ok(result) => {
+                            debug!(
+                                "code action request completed in {elapsed:?}",
+                                elapsed = start_time.elapsed()
+                            );
+                            completed.send(result).ok();
+                        }
+                        Err(err) => {
+                            debug!("error occurred while completing code action request: {err:?}");
+                            completed.send(None).ok();
+                        }
+                    }
+                }
             }
         }
 
@@ -20,6 +20,15 @@ pub const UNNECESSARY_FUNCTION_CALL: &str = "UnnecessaryFunctionCall";
 /// The rule identifier for unsupported version fallback warnings.
 pub const USING_FALLBACK_VERSION: &str = "UsingFallbackVersion";
 
+/// The rule identifier for constant conditional statement warnings.
+pub const CONSTANT_CONDITION_RULE_ID: &str = "ConstantCondition";
+
+/// The rule identifier for unused struct warnings.
+pub const UNUSED_STRUCT_RULE_ID: &str = "UnusedStruct";
+
+/// The rule identifier for uncalled task warnings.
+pub const UNCALLED_TASK_RULE_ID: &str = "UncalledTask";
+
 /// A trait implemented by analysis rules.
 pub trait Rule: Send + Sync {
     /// The unique identifier for the rule.
@@ -52,6 +61,9 @@ pub fn rules() -> Vec<Box<dyn Rule>> {
         Box::<UnusedCallRule>::default(),
         Box::<UnnecessaryFunctionCall>::default(),
         Box::<UsingFallbackVersion>::default(),
+        Box::<ConstantConditionRule>::default(),
+        Box::<UnusedStructRule>::default(),
+        Box::<UncalledTaskRule>::default(),
     ];
 
     // Ensure all the rule ids are unique and pascal case
@@ -312,3 +324,128 @@ impl Rule for UsingFallbackVersion {
         self.0
     }
 }
+
+/// Represents the constant condition rule.
+#[derive(Debug, Clone, Copy)]
+pub struct ConstantConditionRule(Severity);
+
+impl ConstantConditionRule {
+    /// Creates a new constant condition rule.
+    pub fn new() -> Self {
+        Self(Severity::Warning)
+    }
+}
+
+impl Default for ConstantConditionRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rule for ConstantConditionRule {
+    fn id(&self) -> &'static str {
+        CONSTANT_CONDITION_RULE_ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures that the conditions of conditional statements are not always true or always false."
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A conditional statement with a condition that is always true needlessly makes the \
+         declarations and call outputs within it optional to the rest of the workflow; a \
+         conditional statement with a condition that is always false is never evaluated."
+    }
+
+    fn deny(&mut self) {
+        self.0 = Severity::Error;
+    }
+
+    fn severity(&self) -> Severity {
+        self.0
+    }
+}
+
+/// Represents the unused struct rule.
+#[derive(Debug, Clone, Copy)]
+pub struct UnusedStructRule(Severity);
+
+impl UnusedStructRule {
+    /// Creates a new unused struct rule.
+    pub fn new() -> Self {
+        Self(Severity::Warning)
+    }
+}
+
+impl Default for UnusedStructRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rule for UnusedStructRule {
+    fn id(&self) -> &'static str {
+        UNUSED_STRUCT_RULE_ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures that structs are used by a document in the analyzed set of documents."
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A struct that is never used as a type or in a struct literal by any of the analyzed \
+         documents is a candidate for deletion. As libraries may intentionally define structs for \
+         use by other documents, this rule is only checked across a set of documents (e.g. with \
+         `sprocket check --report-unused`)."
+    }
+
+    fn deny(&mut self) {
+        self.0 = Severity::Error;
+    }
+
+    fn severity(&self) -> Severity {
+        self.0
+    }
+}
+
+/// Represents the uncalled task rule.
+#[derive(Debug, Clone, Copy)]
+pub struct UncalledTaskRule(Severity);
+
+impl UncalledTaskRule {
+    /// Creates a new uncalled task rule.
+    pub fn new() -> Self {
+        Self(Severity::Warning)
+    }
+}
+
+impl Default for UncalledTaskRule {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Rule for UncalledTaskRule {
+    fn id(&self) -> &'static str {
+        UNCALLED_TASK_RULE_ID
+    }
+
+    fn description(&self) -> &'static str {
+        "Ensures that tasks are called by a workflow in the analyzed set of documents."
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A task that is never called by any workflow of the analyzed documents is a candidate for \
+         deletion. As libraries may intentionally define tasks for use by other documents or to be \
+         run directly, this rule is only checked across a set of documents (e.g. with `sprocket \
+         check --report-unused`)."
+    }
+
+    fn deny(&mut self) {
+        self.0 = Severity::Error;
+    }
+
+    fn severity(&self) -> Severity {
+        self.0
+    }
+}
@@ -60,15 +60,20 @@ use wdl_ast::v1::TASK_HINT_GPU;
 use wdl_ast::v1::TASK_HINT_INPUTS;
 use wdl_ast::v1::TASK_HINT_LOCALIZATION_OPTIONAL;
 use wdl_ast::v1::TASK_HINT_LOCALIZATION_OPTIONAL_ALIAS;
+use wdl_ast::v1::TASK_HINT_LOG_HEAD;
+use wdl_ast::v1::TASK_HINT_LOG_TAIL;
 use wdl_ast::v1::TASK_HINT_MAX_CPU;
 use wdl_ast::v1::TASK_HINT_MAX_CPU_ALIAS;
 use wdl_ast::v1::TASK_HINT_MAX_MEMORY;
 use wdl_ast::v1::TASK_HINT_MAX_MEMORY_ALIAS;
+use wdl_ast::v1::TASK_HINT_MAX_TIME;
 use wdl_ast::v1::TASK_HINT_OUTPUTS;
 use wdl_ast::v1::TASK_HINT_SHORT_TASK;
 use wdl_ast::v1::TASK_HINT_SHORT_TASK_ALIAS;
+use wdl_ast::v1::TASK_HINT_TIME_MINUTES;
 use wdl_ast::v1::TASK_REQUIREMENT_CONTAINER;
 use wdl_ast::v1::TASK_REQUIREMENT_CONTAINER_ALIAS;
+use wdl_ast::v1::TASK_REQUIREMENT_CONTINUE_ON_RETURN_CODE;
 use wdl_ast::v1::TASK_REQUIREMENT_CPU;
 use wdl_ast::v1::TASK_REQUIREMENT_DISKS;
 use wdl_ast::v1::TASK_REQUIREMENT_FPGA;
@@ -104,6 +109,7 @@ use crate::diagnostics::if_conditional_mismatch;
 use crate::diagnostics::index_type_mismatch;
 use crate::diagnostics::invalid_placeholder_option;
 use crate::diagnostics::invalid_regex_pattern;
+use crate::diagnostics::invalid_return_codes;
 use crate::diagnostics::logical_and_mismatch;
 use crate::diagnostics::logical_not_mismatch;
 use crate::diagnostics::logical_or_mismatch;
@@ -235,6 +241,14 @@ pub fn task_requirement_types(version: SupportedVersion, name: &str) -> Option<&
             STDLIB.array_int_type().clone(),
         ])
     });
+    /// The types for the legacy `continueOnReturnCode` runtime attribute.
+    static CONTINUE_ON_RETURN_CODE_TYPES: LazyLock<Box<[Type]>> = LazyLock::new(|| {
+        Box::new([
+            PrimitiveType::Boolean.into(),
+            PrimitiveType::Integer.into(),
+            STDLIB.array_int_type().clone(),
+        ])
+    });
 
     match name {
         TASK_REQUIREMENT_CONTAINER | TASK_REQUIREMENT_CONTAINER_ALIAS => Some(&CONTAINER_TYPES),
@@ -251,6 +265,7 @@ pub fn task_requirement_types(version: SupportedVersion, name: &str) -> Option<&
             Some(&RETURN_CODES_TYPES)
         }
         TASK_REQUIREMENT_RETURN_CODES_ALIAS => Some(&RETURN_CODES_TYPES),
+        TASK_REQUIREMENT_CONTINUE_ON_RETURN_CODE => Some(&CONTINUE_ON_RETURN_CODE_TYPES),
         _ => None,
     }
 }
@@ -304,6 +319,18 @@ pub fn task_hint_types(
     const SHORT_TASK_TYPES: &[Type] = &[Type::Primitive(PrimitiveType::Boolean, false)];
     /// The types for the `cacheable` hint
     const CACHEABLE_TYPES: &[Type] = &[Type::Primitive(PrimitiveType::Boolean, false)];
+    /// The types for the `max_time` hint.
+    const MAX_TIME_TYPES: &[Type] = &[
+        Type::Primitive(PrimitiveType::Integer, false),
+        Type::Primitive(PrimitiveType::String, false),
+    ];
+    /// The types for the `time_minutes` hint.
+    const TIME_MINUTES_TYPES: &[Type] = &[Type::Primitive(PrimitiveType::Integer, false)];
+    /// The types for the `log_head` and `log_tail` hints.
+    const LOG_LIMIT_TYPES: &[Type] = &[
+        Type::Primitive(PrimitiveType::Integer, false),
+        Type::Primitive(PrimitiveType::String, false),
+    ];
 
     match name {
         TASK_HINT_DISKS => Some(&DISKS_TYPES),
@@ -328,6 +355,9 @@ pub fn task_hint_types(
         TASK_HINT_SHORT_TASK if version >= SupportedVersion::V1(V1::Two) => Some(SHORT_TASK_TYPES),
         TASK_HINT_SHORT_TASK_ALIAS => Some(SHORT_TASK_TYPES),
         TASK_HINT_CACHEABLE => Some(CACHEABLE_TYPES),
+        TASK_HINT_MAX_TIME => Some(MAX_TIME_TYPES),
+        TASK_HINT_TIME_MINUTES => Some(TIME_MINUTES_TYPES),
+        TASK_HINT_LOG_HEAD | TASK_HINT_LOG_TAIL => Some(LOG_LIMIT_TYPES),
         _ => None,
     }
 }
@@ -1022,6 +1052,19 @@ impl<'a, C: EvaluationContext> ExprTypeEvaluator<'a, C> {
                     expr_ty,
                     expr.span(),
                 ));
+            } else if matches!(
+                name.text(),
+                TASK_REQUIREMENT_RETURN_CODES | TASK_REQUIREMENT_RETURN_CODES_ALIAS
+            ) && let Expr::Literal(LiteralExpr::String(s)) = expr
+                && let Some(text) = s.text()
+                && text.text() != "*"
+            {
+                // Only `*` is accepted for a string return codes value
+                self.context.add_diagnostic(invalid_return_codes(
+                    name.text(),
+                    text.text(),
+                    expr.span(),
+                ));
             }
 
             return true;
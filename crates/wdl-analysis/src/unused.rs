@@ -0,0 +1,237 @@
+//! Detection of unused structs and uncalled tasks across a set of documents.
+//!
+//! Unlike the other analysis rules, these rules cannot be checked for a
+//! document in isolation: a struct or task is only unused if no document in
+//! the analyzed set uses it. As libraries may intentionally define structs and
+//! tasks for use by other documents, detection must be explicitly requested.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use serde::Serialize;
+use url::Url;
+use wdl_ast::AstNode;
+use wdl_ast::AstToken;
+use wdl_ast::Diagnostic;
+use wdl_ast::Severity;
+use wdl_ast::Span;
+use wdl_ast::v1;
+
+use crate::SyntaxNodeExt;
+use crate::UNCALLED_TASK_RULE_ID;
+use crate::UNUSED_STRUCT_RULE_ID;
+use crate::diagnostics::uncalled_task;
+use crate::diagnostics::unused_struct;
+use crate::document::Document;
+use crate::document::Struct;
+use crate::types::CallKind;
+
+/// Represents the kind of an unused definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionKind {
+    /// The definition is a struct that is not used.
+    Struct,
+    /// The definition is a task that is not called.
+    Task,
+}
+
+/// Represents a struct or task that is not used by any of a set of documents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnusedDefinition {
+    /// The kind of the definition.
+    kind: DefinitionKind,
+    /// The name of the definition.
+    name: String,
+    /// The path of the document containing the definition.
+    path: String,
+    /// The one-based line of the definition's name.
+    line: usize,
+    /// The URI of the document containing the definition.
+    #[serde(skip)]
+    uri: Arc<Url>,
+    /// The span of the definition's name.
+    #[serde(skip)]
+    span: Span,
+    /// The severity of the diagnostic for the definition.
+    #[serde(skip)]
+    severity: Severity,
+}
+
+impl UnusedDefinition {
+    /// Gets the kind of the definition.
+    pub fn kind(&self) -> DefinitionKind {
+        self.kind
+    }
+
+    /// Gets the name of the definition.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the path of the document containing the definition.
+    ///
+    /// See [`Document::path`].
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Gets the one-based line of the definition's name.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Gets the URI of the document containing the definition.
+    pub fn uri(&self) -> &Arc<Url> {
+        &self.uri
+    }
+
+    /// Gets the span of the definition's name.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Converts the unused definition to a diagnostic.
+    pub fn to_diagnostic(&self) -> Diagnostic {
+        match self.kind {
+            DefinitionKind::Struct => unused_struct(&self.name, self.span),
+            DefinitionKind::Task => uncalled_task(&self.name, self.span),
+        }
+        .with_severity(self.severity)
+    }
+}
+
+/// Resolves an imported struct to the document and name of its definition.
+///
+/// Imported structs may be aliased, so the definition is found by comparing
+/// struct nodes.
+fn struct_definition<'a>(
+    mut document: &'a Document,
+    mut s: &'a Struct,
+) -> Option<(String, String)> {
+    while let Some(ns) = s.namespace() {
+        let target = document.namespace(ns)?.document();
+        s = target
+            .structs()
+            .map(|(_, t)| t)
+            .find(|t| t.node() == s.node())?;
+        document = target;
+    }
+
+    Some((document.uri().to_string(), s.name().to_string()))
+}
+
+/// Finds the structs that are not used and the tasks that are not called by
+/// any of the given documents.
+///
+/// A struct is used if it is referenced as a type or by a struct literal; a
+/// task is used if it is called by a workflow.
+///
+/// Definitions are not reported if their rule is disabled in the
+/// configuration of the containing document or is excepted with an `#@
+/// except` comment.
+pub fn unused_definitions<'a>(
+    documents: impl IntoIterator<Item = &'a Document>,
+) -> Vec<UnusedDefinition> {
+    let documents = documents
+        .into_iter()
+        .filter(|d| d.version().is_some())
+        .collect::<Vec<_>>();
+
+    // Collect the kind, document URI, and name of every used struct and called task
+    let mut used = HashSet::new();
+    for document in &documents {
+        let root = document.root();
+        for node in root.inner().descendants() {
+            let name = if let Some(ty) = v1::TypeRef::cast(node.clone()) {
+                ty.name()
+            } else if let Some(literal) = v1::LiteralStruct::cast(node) {
+                literal.name()
+            } else {
+                continue;
+            };
+
+            if let Some((uri, name)) = document
+                .struct_by_name(name.text())
+                .and_then(|s| struct_definition(document, s))
+            {
+                used.insert((DefinitionKind::Struct, uri, name));
+            }
+        }
+
+        if let Some(workflow) = document.workflow() {
+            for call in workflow.calls().values() {
+                if call.kind() != CallKind::Task {
+                    continue;
+                }
+
+                let uri = match call.namespace() {
+                    Some(ns) => match document.namespace(ns) {
+                        Some(ns) => ns.document().uri().to_string(),
+                        None => continue,
+                    },
+                    None => document.uri().to_string(),
+                };
+
+                used.insert((DefinitionKind::Task, uri, call.name().to_string()));
+            }
+        }
+    }
+
+    let mut unused = Vec::new();
+    for document in &documents {
+        let root = document.root();
+        let Some(ast) = root
+            .ast_with_version_fallback(document.config().fallback_version())
+            .into_v1()
+        else {
+            continue;
+        };
+
+        let config = document.config().diagnostics_config();
+        let source = root.inner().text().to_string();
+        let structs = ast.structs().map(|s| {
+            (
+                DefinitionKind::Struct,
+                UNUSED_STRUCT_RULE_ID,
+                config.unused_struct,
+                s.inner().clone(),
+                s.name(),
+            )
+        });
+        let tasks = ast.tasks().map(|t| {
+            (
+                DefinitionKind::Task,
+                UNCALLED_TASK_RULE_ID,
+                config.uncalled_task,
+                t.inner().clone(),
+                t.name(),
+            )
+        });
+
+        for (kind, rule, severity, node, name) in structs.chain(tasks) {
+            let Some(severity) = severity else {
+                continue;
+            };
+
+            if node.is_rule_excepted(rule)
+                || used.contains(&(kind, document.uri().to_string(), name.text().to_string()))
+            {
+                continue;
+            }
+
+            let span = name.span();
+            unused.push(UnusedDefinition {
+                kind,
+                name: name.text().to_string(),
+                path: document.path().into_owned(),
+                line: source[..span.start()].matches('\n').count() + 1,
+                uri: document.uri().clone(),
+                span,
+                severity,
+            });
+        }
+    }
+
+    unused
+}
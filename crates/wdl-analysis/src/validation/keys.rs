@@ -24,6 +24,7 @@ use wdl_ast::v1::TASK_HINT_MAX_MEMORY;
 use wdl_ast::v1::TASK_HINT_MAX_MEMORY_ALIAS;
 use wdl_ast::v1::TASK_REQUIREMENT_CONTAINER;
 use wdl_ast::v1::TASK_REQUIREMENT_CONTAINER_ALIAS;
+use wdl_ast::v1::TASK_REQUIREMENT_CONTINUE_ON_RETURN_CODE;
 use wdl_ast::v1::TASK_REQUIREMENT_MAX_RETRIES;
 use wdl_ast::v1::TASK_REQUIREMENT_MAX_RETRIES_ALIAS;
 use wdl_ast::v1::TASK_REQUIREMENT_RETURN_CODES;
@@ -243,7 +244,13 @@ impl Visitor for UniqueKeysVisitor {
 
         check_duplicate_keys(
             &mut self.0,
-            &[(TASK_REQUIREMENT_CONTAINER, TASK_REQUIREMENT_CONTAINER_ALIAS)],
+            &[
+                (TASK_REQUIREMENT_CONTAINER, TASK_REQUIREMENT_CONTAINER_ALIAS),
+                (
+                    TASK_REQUIREMENT_RETURN_CODES_ALIAS,
+                    TASK_REQUIREMENT_CONTINUE_ON_RETURN_CODE,
+                ),
+            ],
             section.items().map(|i| i.name()),
             Context::RuntimeSection,
             diagnostics,
@@ -59,6 +59,12 @@ mod element;
 
 pub use element::*;
 
+/// The prefix of documentation comments.
+///
+/// See [`AstNode::doc_comment`] for how documentation comments are attached to
+/// nodes.
+pub const DOC_COMMENT_PREFIX: &str = "##";
+
 /// A trait that abstracts the underlying representation of a syntax tree node.
 ///
 /// The default node type is `SyntaxNode` for all AST nodes.
@@ -259,6 +265,66 @@ pub trait AstNode<N: TreeNode>: Sized {
     {
         self.inner().descendants().filter_map(|d| D::cast(d))
     }
+
+    /// Gets the documentation comment of the node.
+    ///
+    /// A documentation comment is a contiguous block of full-line comments
+    /// beginning with [`DOC_COMMENT_PREFIX`] (followed by a space or the end of
+    /// the line) that immediately precedes the node; lint directive comments
+    /// (`#@`) within the block are ignored.
+    ///
+    /// The prefix (and a single following space) is removed from each line and
+    /// the lines are joined with newlines.
+    ///
+    /// Returns `None` if the node has no documentation comment.
+    fn doc_comment(&self) -> Option<String> {
+        let parent = self.inner().parent()?;
+        let mut lines: Vec<String> = Vec::new();
+        let mut line_start = true;
+        for child in parent.children_with_tokens() {
+            let token = match child {
+                NodeOrToken::Node(n) if n == *self.inner() => break,
+                NodeOrToken::Node(_) => {
+                    lines.clear();
+                    line_start = false;
+                    continue;
+                }
+                NodeOrToken::Token(t) => t,
+            };
+
+            match token.kind() {
+                SyntaxKind::Whitespace => {
+                    let newlines = token.text().matches('\n').count();
+                    if newlines > 1 {
+                        lines.clear();
+                    }
+
+                    line_start = line_start || newlines > 0;
+                    continue;
+                }
+                SyntaxKind::Comment if line_start => {
+                    let text = token.text();
+                    if let Some(line) = text
+                        .strip_prefix(DOC_COMMENT_PREFIX)
+                        .filter(|l| l.is_empty() || l.starts_with(' '))
+                    {
+                        lines.push(line.strip_prefix(' ').unwrap_or(line).to_string());
+                    } else if !text.starts_with("#@") {
+                        lines.clear();
+                    }
+                }
+                _ => lines.clear(),
+            }
+
+            line_start = false;
+        }
+
+        if lines.is_empty() {
+            return None;
+        }
+
+        Some(lines.join("\n"))
+    }
 }
 
 /// A trait implemented by AST tokens.
@@ -710,3 +776,72 @@ impl<T: TreeToken> std::borrow::Borrow<str> for TokenText<T> {
         self.0.text()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn doc_comments() {
+        let (document, diagnostics) = Document::parse(
+            r#"## The preamble.
+
+version 1.1
+
+## Says hello.
+##
+## Greets the given name.
+task hello {
+    input {
+        ## The name to greet.
+        #@ except: SnakeCase
+        String name
+        # Not a doc comment.
+        Int count
+        ## Detached from `verbose`.
+
+        Boolean verbose
+        Int threads  ## Not a doc comment.
+        Int memory
+    }
+
+    command <<<>>>
+}
+
+## Detached from `main`.
+# Breaks the block.
+workflow main {}
+"#,
+        );
+
+        assert!(diagnostics.is_empty());
+        let ast = document.ast();
+        let ast = ast.as_v1().expect("should be a V1 AST");
+
+        let task = ast.tasks().next().expect("should have a task");
+        assert_eq!(
+            task.doc_comment().as_deref(),
+            Some("Says hello.\n\nGreets the given name.")
+        );
+
+        let docs: Vec<_> = task
+            .input()
+            .expect("should have an input section")
+            .declarations()
+            .map(|d| d.doc_comment())
+            .collect();
+        assert_eq!(
+            docs,
+            [
+                Some("The name to greet.".to_string()),
+                None,
+                None,
+                None,
+                None
+            ]
+        );
+
+        let workflow = ast.workflows().next().expect("should have a workflow");
+        assert!(workflow.doc_comment().is_none());
+    }
+}
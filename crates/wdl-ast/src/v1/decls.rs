@@ -878,6 +878,16 @@ impl<N: TreeNode> Decl<N> {
         }
     }
 
+    /// Gets the documentation comment of the declaration.
+    ///
+    /// See [`AstNode::doc_comment`] for more information.
+    pub fn doc_comment(&self) -> Option<String> {
+        match self {
+            Self::Bound(d) => d.doc_comment(),
+            Self::Unbound(d) => d.doc_comment(),
+        }
+    }
+
     /// Attempts to get a reference to the inner [`BoundDecl`].
     ///
     /// * If `self` is a [`Decl::Bound`], then a reference to the inner
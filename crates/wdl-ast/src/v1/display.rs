@@ -67,6 +67,15 @@ fn write_meta_array<N: TreeNode>(
     Ok(())
 }
 
+/// Formats a documentation comment as a list item.
+///
+/// The lines of the comment are joined into a single paragraph.
+fn write_doc_comment(f: &mut impl fmt::Write, doc: &str, indent: usize) -> fmt::Result {
+    let prefix = " ".repeat(indent);
+    let text = doc.lines().map(str::trim).collect::<Vec<_>>().join(" ");
+    writeln!(f, "{prefix}- {text}", text = text.trim())
+}
+
 /// Gets the entire metadata value for a given parameter name.
 pub fn get_param_meta<N: TreeNode>(
     name: &str,
@@ -101,6 +110,10 @@ pub fn write_input_section<N: TreeNode>(
                 writeln!(f)?;
                 format_meta_value(f, &meta_val, 2)?;
                 writeln!(f)?;
+            } else if let Some(doc) = decl.doc_comment() {
+                writeln!(f)?;
+                write_doc_comment(f, &doc, 2)?;
+                writeln!(f)?;
             } else {
                 writeln!(f)?;
             }
@@ -126,6 +139,10 @@ pub fn write_output_section<N: TreeNode>(
                 writeln!(f)?;
                 format_meta_value(f, &meta_val, 2)?;
                 writeln!(f)?;
+            } else if let Some(doc) = decl.doc_comment() {
+                writeln!(f)?;
+                write_doc_comment(f, &doc, 2)?;
+                writeln!(f)?;
             } else {
                 writeln!(f)?;
             }
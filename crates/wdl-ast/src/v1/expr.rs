@@ -2044,21 +2044,20 @@ impl<N: TreeNode> LiteralString<N> {
             text.replace_range(..len, "");
         }
 
-        // Trim the last line
+        // Trim the last line; all whitespace preceding the closing delimiter is
+        // removed, including at most one newline, so that whitespace following a
+        // trailing placeholder is also removed
         if let Some(StrippedStringPart::Text(text)) = result.last_mut() {
-            if let Some(index) = text.rfind(|c| !matches!(c, ' ' | '\t')) {
-                text.truncate(index + 1);
-            } else {
-                text.clear();
-            }
-
-            if text.ends_with('\n') {
-                text.pop();
-            }
-
-            if text.ends_with('\r') {
-                text.pop();
-            }
+            let trimmed = text.trim_end_matches([' ', '\t']);
+            let len = match trimmed.strip_suffix('\n') {
+                Some(line) => line
+                    .strip_suffix('\r')
+                    .unwrap_or(line)
+                    .trim_end_matches([' ', '\t'])
+                    .len(),
+                None => trimmed.len(),
+            };
+            text.truncate(len);
         }
 
         // Now that the string has been unescaped and the first and last lines trimmed,
@@ -6178,6 +6177,60 @@ task test {
         }
     }
 
+    #[test]
+    fn strip_whitespace_after_trailing_placeholder() {
+        let (document, diagnostics) = Document::parse(
+            "version 1.2\n\ntask test {\n    String a = <<<\n        hello ~{\"world\"}   \n    \
+             >>>\n    String b = <<<\n        hello   \n    >>>\n    String c = <<<\n        \
+             hello\n\n    >>>\n}",
+        );
+
+        assert!(diagnostics.is_empty());
+        let ast = document.ast();
+        let ast = ast.as_v1().expect("should be a V1 AST");
+
+        let tasks: Vec<_> = ast.tasks().collect();
+        assert_eq!(tasks.len(), 1);
+
+        let decls: Vec<_> = tasks[0].declarations().collect();
+        assert_eq!(decls.len(), 3);
+
+        // The whitespace following the trailing placeholder is removed
+        let expr = decls[0].expr().unwrap_literal().unwrap_string();
+        let stripped = expr.strip_whitespace().unwrap();
+        assert_eq!(stripped.len(), 3);
+        match &stripped[0] {
+            StrippedStringPart::Text(text) => assert_eq!(text.as_str(), "hello "),
+            _ => panic!("expected text part"),
+        }
+        match &stripped[1] {
+            StrippedStringPart::Placeholder(_) => {}
+            _ => panic!("expected interpolated part"),
+        }
+        match &stripped[2] {
+            StrippedStringPart::Text(text) => assert_eq!(text.as_str(), ""),
+            _ => panic!("expected text part"),
+        }
+
+        // Trailing whitespace on the last line is removed
+        let expr = decls[1].expr().unwrap_literal().unwrap_string();
+        let stripped = expr.strip_whitespace().unwrap();
+        assert_eq!(stripped.len(), 1);
+        match &stripped[0] {
+            StrippedStringPart::Text(text) => assert_eq!(text.as_str(), "hello"),
+            _ => panic!("expected text part"),
+        }
+
+        // Only one newline preceding the closing delimiter is removed
+        let expr = decls[2].expr().unwrap_literal().unwrap_string();
+        let stripped = expr.strip_whitespace().unwrap();
+        assert_eq!(stripped.len(), 1);
+        match &stripped[0] {
+            StrippedStringPart::Text(text) => assert_eq!(text.as_str(), "hello\n"),
+            _ => panic!("expected text part"),
+        }
+    }
+
     #[test]
     fn remove_multiple_line_continuations() {
         let (document, diagnostics) = Document::parse(
@@ -66,6 +66,10 @@ impl<N: TreeNode> StructDefinition<N> {
         }
         writeln!(f, "}}\n```\n---")?;
 
+        if let Some(doc) = self.doc_comment() {
+            writeln!(f, "{doc}\n")?;
+        }
+
         if let Some(meta) = self.metadata().next()
             && let Some(desc) = meta.items().find(|i| i.name().text() == "description")
             && let MetadataValue::String(s) = desc.value()
@@ -286,6 +286,11 @@ pub const TASK_REQUIREMENT_MEMORY: &str = "memory";
 pub const TASK_REQUIREMENT_RETURN_CODES: &str = "return_codes";
 /// The alias of the `return_codes` task requirement (i.e. `returnCodes`).
 pub const TASK_REQUIREMENT_RETURN_CODES_ALIAS: &str = "returnCodes";
+/// The name of the legacy `continueOnReturnCode` runtime attribute.
+///
+/// This attribute predates `returnCodes` and additionally accepts a `Boolean`
+/// value, where `true` accepts any return code.
+pub const TASK_REQUIREMENT_CONTINUE_ON_RETURN_CODE: &str = "continueOnReturnCode";
 
 /// The name of the `disks` task hint.
 pub const TASK_HINT_DISKS: &str = "disks";
@@ -316,6 +321,31 @@ pub const TASK_HINT_SHORT_TASK: &str = "short_task";
 pub const TASK_HINT_SHORT_TASK_ALIAS: &str = "shortTask";
 /// The name of the `cacheable` task hint.
 pub const TASK_HINT_CACHEABLE: &str = "cacheable";
+/// The name of the `max_time` task hint.
+///
+/// This hint is not part of the WDL specification; its value is the maximum
+/// amount of time the task may execute for, either as an `Int` number of
+/// seconds or as a `String` with a unit suffix (e.g. `"90m"`).
+pub const TASK_HINT_MAX_TIME: &str = "max_time";
+/// The name of the `time_minutes` task hint.
+///
+/// This hint is not part of the WDL specification; its value is the maximum
+/// number of minutes the task may execute for.
+pub const TASK_HINT_TIME_MINUTES: &str = "time_minutes";
+/// The name of the `log_head` task hint.
+///
+/// This hint is not part of the WDL specification; its value is the number of
+/// bytes at the start of the task's standard output and standard error to
+/// retain when they are downsampled, either as an `Int` number of bytes or as
+/// a `String` with a unit (e.g. `"5 MiB"`).
+pub const TASK_HINT_LOG_HEAD: &str = "log_head";
+/// The name of the `log_tail` task hint.
+///
+/// This hint is not part of the WDL specification; its value is the number of
+/// bytes at the end of the task's standard output and standard error to
+/// retain when they are downsampled, either as an `Int` number of bytes or as
+/// a `String` with a unit (e.g. `"5 MiB"`).
+pub const TASK_HINT_LOG_TAIL: &str = "log_tail";
 
 /// Unescapes command text.
 fn unescape_command_text(s: &str, heredoc: bool, buffer: &mut String) {
@@ -407,6 +437,10 @@ impl<N: TreeNode> TaskDefinition<N> {
     pub fn markdown_description(&self, f: &mut impl fmt::Write) -> fmt::Result {
         writeln!(f, "```wdl\ntask {}\n```\n---", self.name().text())?;
 
+        if let Some(doc) = self.doc_comment() {
+            writeln!(f, "{doc}\n")?;
+        }
+
         if let Some(meta) = self.metadata()
             && let Some(desc) = meta.items().find(|i| i.name().text() == "description")
             && let MetadataValue::String(s) = desc.value()
@@ -147,6 +147,10 @@ impl<N: TreeNode> WorkflowDefinition<N> {
     pub fn markdown_description(&self, f: &mut impl fmt::Write) -> fmt::Result {
         writeln!(f, "```wdl\nworkflow {}\n```\n---", self.name().text())?;
 
+        if let Some(doc) = self.doc_comment() {
+            writeln!(f, "{doc}\n")?;
+        }
+
         if let Some(meta) = self.metadata()
             && let Some(desc) = meta.items().find(|i| i.name().text() == "description")
             && let MetadataValue::String(s) = desc.value()
@@ -39,6 +39,12 @@ pub(crate) trait MetaMapExt {
     /// This will always return some text; in the absence of a `description`
     /// key, it will return a default message ("No description provided").
     fn render_description(&self, summarize: bool) -> Markup;
+    /// Returns the rendered [`Markup`] of the `description` key, optionally
+    /// summarizing it.
+    ///
+    /// In the absence of a `description` key, the given fallback text (such
+    /// as a documentation comment) is rendered instead, if provided.
+    fn render_description_or(&self, fallback: Option<&str>, summarize: bool) -> Markup;
     /// Returns the rendered [`Markup`] of the remaining metadata keys,
     /// excluding the keys specified in `filter_keys`.
     fn render_remaining(&self, filter_keys: &[&str], assets: &Path) -> Option<Markup>;
@@ -46,6 +52,10 @@ pub(crate) trait MetaMapExt {
 
 impl MetaMapExt for MetaMap {
     fn render_description(&self, summarize: bool) -> Markup {
+        self.render_description_or(None, summarize)
+    }
+
+    fn render_description_or(&self, fallback: Option<&str>, summarize: bool) -> Markup {
         let desc = self
             .get(DESCRIPTION_KEY)
             .map(|v| match v {
@@ -55,6 +65,7 @@ impl MetaMapExt for MetaMap {
                 }
                 _ => "ERROR: description not of type String".to_string(),
             })
+            .or_else(|| fallback.map(str::to_string))
             .unwrap_or_else(|| "No description provided".to_string());
 
         if !summarize {
@@ -212,8 +212,12 @@ impl Parameter {
     }
 
     /// Render the description of the parameter.
+    ///
+    /// If the parameter has no `description` meta entry, its documentation
+    /// comment is used instead.
     pub fn description(&self, summarize: bool) -> Markup {
-        self.meta().render_description(summarize)
+        self.meta()
+            .render_description_or(self.decl.doc_comment().as_deref(), summarize)
     }
 
     /// Render any remaining metadata as HTML.
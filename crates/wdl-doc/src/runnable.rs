@@ -35,6 +35,9 @@ pub(crate) trait Runnable {
     /// Get the [`MetaMap`] of the runnable.
     fn meta(&self) -> &MetaMap;
 
+    /// Get the documentation comment of the runnable.
+    fn doc_comment(&self) -> Option<&str>;
+
     /// Get the inputs of the runnable.
     fn inputs(&self) -> &[Parameter];
 
@@ -101,9 +104,11 @@ pub(crate) trait Runnable {
     /// Render the description of the runnable as HTML.
     ///
     /// This will always return some text; in the absence of a `description`
-    /// key, it will return a default message ("No description provided").
+    /// key, it will return the documentation comment of the runnable or a
+    /// default message ("No description provided").
     fn render_description(&self, summarize: bool) -> Markup {
-        self.meta().render_description(summarize)
+        self.meta()
+            .render_description_or(self.doc_comment(), summarize)
     }
 
     /// Render the "run with" component of the runnable.
@@ -25,6 +25,8 @@ pub struct Task {
     name: String,
     /// The [`VersionBadge`] which displays the WDL version of the task.
     version: VersionBadge,
+    /// The documentation comment of the task.
+    doc_comment: Option<String>,
     /// The meta of the task.
     meta: MetaMap,
     /// The input parameters of the task.
@@ -73,6 +75,7 @@ impl Task {
         Self {
             name,
             version: VersionBadge::new(version),
+            doc_comment: definition.doc_comment(),
             meta,
             inputs,
             outputs,
@@ -200,6 +203,10 @@ impl Runnable for Task {
         &self.version
     }
 
+    fn doc_comment(&self) -> Option<&str> {
+        self.doc_comment.as_deref()
+    }
+
     fn meta(&self) -> &MetaMap {
         &self.meta
     }
@@ -272,4 +279,42 @@ mod tests {
         assert_eq!(task.inputs().len(), 1);
         assert_eq!(task.outputs().len(), 1);
     }
+
+    #[test]
+    fn test_task_doc_comment() {
+        let (doc, _) = Document::parse(
+            r#"
+            version 1.0
+
+            ## Greets someone.
+            task my_task {
+                input {
+                    ## The name to greet.
+                    String name
+                }
+                command <<<>>>
+            }
+            "#,
+        );
+
+        let doc_item = doc.ast().into_v1().unwrap().items().next().unwrap();
+        let ast_task = doc_item.into_task_definition().unwrap();
+
+        let task = Task::new(
+            ast_task.name().text().to_owned(),
+            SupportedVersion::V1(V1::Zero),
+            ast_task,
+            None,
+        );
+
+        assert_eq!(task.doc_comment(), Some("Greets someone."));
+        assert_eq!(
+            task.render_description(false).into_string(),
+            "Greets someone."
+        );
+        assert_eq!(
+            task.inputs()[0].description(false).into_string(),
+            "The name to greet."
+        );
+    }
 }
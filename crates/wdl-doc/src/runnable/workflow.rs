@@ -3,6 +3,7 @@
 use std::path::PathBuf;
 
 use maud::Markup;
+use wdl_ast::AstNode;
 use wdl_ast::SupportedVersion;
 use wdl_ast::v1::MetadataValue;
 use wdl_ast::v1::WorkflowDefinition;
@@ -25,6 +26,8 @@ pub(crate) struct Workflow {
     name: String,
     /// The [`VersionBadge`] which displays the WDL version of the workflow.
     version: VersionBadge,
+    /// The documentation comment of the workflow.
+    doc_comment: Option<String>,
     /// The meta of the workflow.
     meta: MetaMap,
     /// The inputs of the workflow.
@@ -63,6 +66,7 @@ impl Workflow {
         Self {
             name,
             version: VersionBadge::new(version),
+            doc_comment: definition.doc_comment(),
             meta,
             inputs,
             outputs,
@@ -227,6 +231,10 @@ impl Runnable for Workflow {
         &self.version
     }
 
+    fn doc_comment(&self) -> Option<&str> {
+        self.doc_comment.as_deref()
+    }
+
     fn meta(&self) -> &MetaMap {
         &self.meta
     }
@@ -8,6 +8,8 @@ use wdl_ast::AstToken;
 use wdl_ast::SupportedVersion;
 use wdl_ast::v1::StructDefinition;
 
+use crate::Markdown;
+use crate::Render;
 use crate::VersionBadge;
 use crate::docs_tree::PageSections;
 
@@ -37,6 +39,11 @@ impl Struct {
             div class="main__container" {
                 p class="text-brand-pink-400" { "Struct" }
                 h1 id="title" class="main__title" { code { (name) } }
+                @if let Some(doc) = self.definition.doc_comment() {
+                    div class="markdown-body mb-4" {
+                        (Markdown(doc).render())
+                    }
+                }
                 div class="main__badge-container" {
                     (self.version.render())
                 }
@@ -10,6 +10,7 @@ use std::ops::Sub;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use anyhow::Result;
 use anyhow::anyhow;
@@ -25,14 +26,18 @@ use tracing::debug;
 
 use crate::Input;
 use crate::Value;
+use crate::config::TaskStubConfig;
 use crate::http::Transferer;
+use crate::logs::LogLimit;
 use crate::path::EvaluationPath;
 
 mod apptainer;
 mod docker;
 mod local;
 mod lsf_apptainer;
+mod rerun;
 mod slurm_apptainer;
+mod stub;
 mod tes;
 
 pub use apptainer::*;
@@ -40,6 +45,7 @@ pub use docker::*;
 pub use local::*;
 pub use lsf_apptainer::*;
 pub use slurm_apptainer::*;
+pub use stub::*;
 pub use tes::*;
 
 /// The default work directory name.
@@ -161,6 +167,10 @@ pub struct TaskSpawnRequest {
     task_eval_root: PathBuf,
     /// The temp directory for the evaluation.
     temp_dir: PathBuf,
+    /// The sender for notifying that the task's execution has started.
+    started: Mutex<Option<oneshot::Sender<()>>>,
+    /// The limits on the size of the task's logs.
+    log_limit: Option<LogLimit>,
 }
 
 impl TaskSpawnRequest {
@@ -180,6 +190,41 @@ impl TaskSpawnRequest {
             attempt_dir,
             task_eval_root,
             temp_dir,
+            started: Mutex::new(None),
+            log_limit: None,
+        }
+    }
+
+    /// Sets the limits on the size of the task's logs.
+    ///
+    /// The limits are only applied by backends that limit logs as tasks
+    /// execute.
+    pub(crate) fn with_log_limit(mut self, log_limit: Option<LogLimit>) -> Self {
+        self.log_limit = log_limit;
+        self
+    }
+
+    /// Gets the limits on the size of the task's logs.
+    pub(crate) fn log_limit(&self) -> Option<LogLimit> {
+        self.log_limit
+    }
+
+    /// Sets the sender for notifying that the task's execution has started.
+    ///
+    /// The sender is notified once the task is no longer waiting for
+    /// resources.
+    pub fn with_started(self, started: oneshot::Sender<()>) -> Self {
+        *self.started.lock().expect("failed to lock sender") = Some(started);
+        self
+    }
+
+    /// Notifies that the task's execution has started.
+    ///
+    /// Backends call this when the task begins executing; subsequent calls do
+    /// nothing.
+    pub fn notify_started(&self) {
+        if let Some(started) = self.started.lock().expect("failed to lock sender").take() {
+            let _ = started.send(());
         }
     }
 
@@ -275,6 +320,17 @@ pub struct TaskExecutionResult {
     pub stdout: Value,
     /// The value of the task's stderr file.
     pub stderr: Value,
+    /// Whether or not the backend observed the task running out of memory.
+    ///
+    /// This is `None` if the backend has no signal for out of memory
+    /// conditions.
+    pub out_of_memory: Option<bool>,
+    /// The digest of the container image the task was executed in, as
+    /// resolved by the backend.
+    ///
+    /// This is `None` if the digest is unknown or if the task was not executed
+    /// in a container.
+    pub digest: Option<String>,
 }
 
 /// Represents a task execution backend.
@@ -305,6 +361,32 @@ pub trait TaskExecutionBackend: Send + Sync {
     /// `true`.
     fn needs_local_inputs(&self) -> bool;
 
+    /// Determines if the engine should enforce a task's maximum execution time.
+    ///
+    /// Backends that submit tasks to a scheduler return `false` and instead
+    /// pass the task's maximum execution time to the scheduler, as time spent
+    /// waiting in the scheduler's queue should not count towards it.
+    fn enforces_max_time(&self) -> bool {
+        true
+    }
+
+    /// Determines if the backend limits the size of a task's logs as the task
+    /// executes.
+    ///
+    /// The logs of tasks executed by backends that do not limit them are
+    /// downsampled by the engine once the task exits.
+    fn limits_logs(&self) -> bool {
+        false
+    }
+
+    /// Determines if the backend writes a task's logs to the local attempt
+    /// directory as the task executes.
+    ///
+    /// Task logs cannot be forwarded to log sinks for backends that do not.
+    fn writes_local_logs(&self) -> bool {
+        true
+    }
+
     /// Spawns a task with the execution backend.
     ///
     /// Returns a oneshot receiver for awaiting the completion of the task.
@@ -314,6 +396,18 @@ pub trait TaskExecutionBackend: Send + Sync {
         token: CancellationToken,
     ) -> Result<Receiver<Result<TaskExecutionResult>>>;
 
+    /// Gets the stub for the given task.
+    ///
+    /// Returns `None` if the backend does not stub task execution.
+    ///
+    /// When a stub is returned, the stub's script is spawned in place of the
+    /// task's command and the stub's literal outputs are used in place of
+    /// evaluating the corresponding output expressions.
+    fn task_stub(&self, task: &str) -> Option<&TaskStubConfig> {
+        let _ = task;
+        None
+    }
+
     /// Performs cleanup operations after task execution completes.
     ///
     /// Returns `None` if no cleanup is required.
@@ -388,6 +482,14 @@ struct TaskManager<Req> {
     tx: mpsc::UnboundedSender<(Req, oneshot::Sender<Result<TaskExecutionResult>>)>,
 }
 
+impl<Req> Clone for TaskManager<Req> {
+    fn clone(&self) -> Self {
+        Self {
+            tx: self.tx.clone(),
+        }
+    }
+}
+
 impl<Req> TaskManager<Req>
 where
     Req: TaskManagerRequest,
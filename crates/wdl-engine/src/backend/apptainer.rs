@@ -268,14 +268,14 @@ mod tests {
             env.into(),
             Arc::new(TestEnv::default()) as Arc<dyn Transferer>,
         );
-        let spawn_request = TaskSpawnRequest {
-            id: "example_task".to_string(),
+        let spawn_request = TaskSpawnRequest::new(
+            "example_task".to_string(),
             info,
-            attempt: 0,
-            attempt_dir: tmp.path().join("0"),
-            task_eval_root: tmp.path().to_path_buf(),
-            temp_dir: tmp.path().join("tmp"),
-        };
+            0,
+            tmp.path().join("0"),
+            tmp.path().to_path_buf(),
+            tmp.path().join("tmp"),
+        );
         (tmp, state, spawn_request)
     }
 
@@ -1,5 +1,6 @@
 //! Implementation of the Docker backend.
 
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs;
 use std::sync::Arc;
@@ -8,7 +9,10 @@ use std::sync::Mutex;
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::bail;
+use bollard::query_parameters::CreateImageOptionsBuilder;
+use bollard::query_parameters::EventsOptionsBuilder;
 use crankshaft::config::backend;
+use crankshaft::docker::Docker;
 use crankshaft::engine::Task;
 use crankshaft::engine::service::name::GeneratorIterator;
 use crankshaft::engine::service::name::UniqueAlphanumeric;
@@ -22,10 +26,13 @@ use crankshaft::engine::task::input::Contents;
 use crankshaft::engine::task::input::Type as InputType;
 use crankshaft::engine::task::output::Type as OutputType;
 use crankshaft::events::Event;
+use futures::StreamExt as _;
 use nonempty::NonEmpty;
+use tokio::sync::OnceCell;
 use tokio::sync::broadcast;
 use tokio::sync::oneshot;
 use tokio::sync::oneshot::Receiver;
+use tokio::task::JoinHandle;
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 use tracing::warn;
@@ -37,6 +44,9 @@ use super::TaskExecutionResult;
 use super::TaskManager;
 use super::TaskManagerRequest;
 use super::TaskSpawnRequest;
+use super::rerun::DockerInvocation;
+use super::rerun::Mount;
+use super::rerun::write_rerun_script;
 use crate::COMMAND_FILE_NAME;
 use crate::ONE_GIBIBYTE;
 use crate::PrimitiveValue;
@@ -45,10 +55,12 @@ use crate::STDOUT_FILE_NAME;
 use crate::Value;
 use crate::WORK_DIR_NAME;
 use crate::backend::INITIAL_EXPECTED_NAMES;
+use crate::config::Architecture;
 use crate::config::Config;
 use crate::config::DEFAULT_TASK_SHELL;
 use crate::config::DockerBackendConfig;
 use crate::config::TaskResourceLimitBehavior;
+use crate::config::matches_platform;
 use crate::path::EvaluationPath;
 use crate::v1::container;
 use crate::v1::cpu;
@@ -72,6 +84,302 @@ const GUEST_STDOUT_PATH: &str = "/mnt/task/stdout";
 /// The path to the container's stderr.
 const GUEST_STDERR_PATH: &str = "/mnt/task/stderr";
 
+/// Verifies that container images are available for the architecture of the
+/// hosts that execute tasks.
+///
+/// This surfaces an architecture mismatch before a task is scheduled rather
+/// than as an `exec format error` from within the container.
+struct PlatformVerifier {
+    /// The architecture of the hosts that execute tasks.
+    architecture: Architecture,
+    /// The Docker client used to query image registries.
+    docker: Docker,
+    /// The result of verifying each image.
+    ///
+    /// An error message is stored for images that are not available for the
+    /// architecture.
+    ///
+    /// The lock is only held to get the cell of an image so that images are
+    /// verified concurrently.
+    verified: Mutex<HashMap<String, Arc<OnceCell<Option<String>>>>>,
+}
+
+impl std::fmt::Debug for PlatformVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PlatformVerifier")
+            .field("architecture", &self.architecture)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PlatformVerifier {
+    /// Verifies that the given image is available for the architecture.
+    ///
+    /// The registry of each image is only queried once.
+    async fn verify(&self, image: &str) -> Result<()> {
+        let cell = self
+            .verified
+            .lock()
+            .expect("failed to lock verified images")
+            .entry(image.to_string())
+            .or_default()
+            .clone();
+
+        match cell.get_or_init(|| self.check(image)).await {
+            Some(e) => bail!("{e}"),
+            None => Ok(()),
+        }
+    }
+
+    /// Queries the registry of the given image for the platforms it is
+    /// available for and pulls the image for the architecture.
+    ///
+    /// The image is pulled so that the task's container is created from the
+    /// image for the architecture rather than from the image for the platform
+    /// of the Docker daemon.
+    ///
+    /// Returns an error message if the image is not available for the
+    /// architecture.
+    async fn check(&self, image: &str) -> Option<String> {
+        let inspection = match self
+            .docker
+            .inner()
+            .inspect_registry_image(image, None)
+            .await
+        {
+            Ok(inspection) => inspection,
+            Err(e) => {
+                // The image may only exist locally, so let the task run
+                warn!("failed to verify the platforms of container image `{image}`: {e}");
+                return None;
+            }
+        };
+
+        let target = self.architecture.platform();
+        let platforms = inspection
+            .platforms
+            .iter()
+            .map(|p| {
+                let mut platform = format!(
+                    "{os}/{arch}",
+                    os = p.os.as_deref().unwrap_or("unknown"),
+                    arch = p.architecture.as_deref().unwrap_or("unknown")
+                );
+                if let Some(variant) = &p.variant {
+                    platform.push('/');
+                    platform.push_str(variant);
+                }
+
+                platform
+            })
+            .collect::<Vec<_>>();
+
+        if !platforms.is_empty() && !platforms.iter().any(|p| matches_platform(p, &target)) {
+            return Some(format!(
+                "container image `{image}` is not available for the `{architecture}` architecture \
+                 of the Docker backend (the image is available for {available})",
+                architecture = self.architecture,
+                available = platforms
+                    .iter()
+                    .map(|p| format!("`{p}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        let options = CreateImageOptionsBuilder::new()
+            .from_image(image)
+            .platform(&target)
+            .build();
+        let mut pull = self.docker.inner().create_image(Some(options), None, None);
+        while let Some(result) = pull.next().await {
+            if let Err(e) = result {
+                return Some(format!(
+                    "failed to pull container image `{image}` for platform `{target}`: {e}"
+                ));
+            }
+        }
+
+        None
+    }
+}
+
+/// A client of the Docker daemon used for queries that are not made through
+/// Crankshaft.
+struct Daemon {
+    /// The Docker client.
+    docker: Docker,
+    /// Whether or not the daemon reports the events of task containers.
+    ///
+    /// This is `false` if tasks are run as services, as the events of other
+    /// nodes are not reported by the daemon.
+    events: bool,
+    /// The resolved digest of each container image.
+    ///
+    /// The lock is only held to get the cell of an image so that digests are
+    /// resolved concurrently.
+    digests: Mutex<HashMap<String, Arc<OnceCell<Option<String>>>>>,
+}
+
+impl std::fmt::Debug for Daemon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Daemon")
+            .field("events", &self.events)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Daemon {
+    /// Starts watching for the containers of the given task running out of
+    /// memory.
+    ///
+    /// The containers of a task are named after the task.
+    ///
+    /// Returns `None` if the daemon does not report the events of task
+    /// containers.
+    fn watch_out_of_memory(&self, name: &str) -> Option<OomWatcher> {
+        self.events.then(|| OomWatcher::start(&self.docker, name))
+    }
+
+    /// Gets the digest of the given container image.
+    ///
+    /// The digest of each image is only resolved once so that the attempts of
+    /// every task using an image report the same digest.
+    ///
+    /// Returns `None` if the digest could not be resolved.
+    async fn digest(&self, image: &str) -> Option<String> {
+        if let Some((_, digest)) = image.split_once('@') {
+            return Some(digest.to_string());
+        }
+
+        let cell = self
+            .digests
+            .lock()
+            .expect("failed to lock image digests")
+            .entry(image.to_string())
+            .or_default()
+            .clone();
+
+        cell.get_or_init(|| self.resolve_digest(image))
+            .await
+            .clone()
+    }
+
+    /// Resolves the digest of the given container image.
+    ///
+    /// The digest of the local image is preferred as that is the image a
+    /// task's container was created from once the image has been pulled;
+    /// otherwise, the image's registry is queried.
+    ///
+    /// Returns `None` if the digest could not be resolved.
+    async fn resolve_digest(&self, image: &str) -> Option<String> {
+        let local = self
+            .docker
+            .inner()
+            .inspect_image(image)
+            .await
+            .ok()
+            .and_then(|i| i.repo_digests)
+            .and_then(|d| {
+                d.iter()
+                    .find_map(|d| d.split_once('@').map(|(_, digest)| digest.to_string()))
+            });
+
+        if local.is_some() {
+            return local;
+        }
+
+        match self
+            .docker
+            .inner()
+            .inspect_registry_image(image, None)
+            .await
+        {
+            Ok(inspection) => inspection.descriptor.digest,
+            Err(e) => {
+                warn!("failed to resolve the digest of container image `{image}`: {e}");
+                None
+            }
+        }
+    }
+}
+
+/// Determines if the given container name is the name of a container of the
+/// task with the given name.
+///
+/// The container of each execution of a task is named after the task with the
+/// index of the execution (e.g. `<task>-0`).
+fn is_task_container(container: &str, task: &str) -> bool {
+    container == task
+        || container
+            .strip_prefix(task)
+            .and_then(|s| s.strip_prefix('-'))
+            .is_some_and(|i| !i.is_empty() && i.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Watches the Docker daemon for a task's container running out of memory.
+///
+/// The daemon emits an `oom` event when the kernel kills a process of a
+/// container that has exceeded its memory limit.
+struct OomWatcher {
+    /// The token for stopping the watcher.
+    stop: CancellationToken,
+    /// The handle of the task receiving the daemon's events.
+    ///
+    /// The task returns `None` if the events could not be received.
+    handle: JoinHandle<Option<bool>>,
+}
+
+impl OomWatcher {
+    /// Starts watching for the containers of the given task.
+    fn start(docker: &Docker, name: &str) -> Self {
+        let filters = HashMap::from([("type", vec!["container"]), ("event", vec!["oom"])]);
+        let mut events = docker
+            .inner()
+            .events(Some(EventsOptionsBuilder::new().filters(&filters).build()));
+        let stop = CancellationToken::new();
+        let stopped = stop.clone();
+        let name = name.to_string();
+        let handle = tokio::spawn(async move {
+            loop {
+                let event = tokio::select! {
+                    _ = stopped.cancelled() => return Some(false),
+                    event = events.next() => event,
+                };
+
+                match event {
+                    Some(Ok(event)) => {
+                        let container = event
+                            .actor
+                            .as_ref()
+                            .and_then(|a| a.attributes.as_ref())
+                            .and_then(|a| a.get("name"));
+                        if container.is_some_and(|c| is_task_container(c, &name)) {
+                            return Some(true);
+                        }
+                    }
+                    Some(Err(e)) => {
+                        warn!("failed to receive events from the Docker daemon: {e}");
+                        return None;
+                    }
+                    None => return None,
+                }
+            }
+        });
+
+        Self { stop, handle }
+    }
+
+    /// Stops the watcher and returns whether a container of the task ran out
+    /// of memory.
+    ///
+    /// Returns `None` if the daemon's events could not be received.
+    async fn finish(self) -> Option<bool> {
+        self.stop.cancel();
+        self.handle.await.ok().flatten()
+    }
+}
+
 /// This request contains the requested cpu and memory reservations for the task
 /// as well as the result receiver channel.
 #[derive(Debug)]
@@ -86,6 +394,8 @@ struct DockerTaskRequest {
     name: String,
     /// The requested container for the task.
     container: String,
+    /// The architecture of the hosts that execute tasks, if configured.
+    architecture: Option<Architecture>,
     /// The requested CPU reservation for the task.
     cpu: f64,
     /// The requested memory reservation for the task, in bytes.
@@ -96,6 +406,10 @@ struct DockerTaskRequest {
     max_memory: Option<u64>,
     /// The requested GPU count for the task.
     gpu: Option<u64>,
+    /// The client of the Docker daemon.
+    ///
+    /// This is `None` if the client could not connect to the daemon.
+    daemon: Option<Arc<Daemon>>,
     /// The cancellation token for the request.
     token: CancellationToken,
 }
@@ -148,6 +462,7 @@ impl TaskManagerRequest for DockerTaskRequest {
         // Allocate the inputs, which will always be, at most, the number of inputs plus
         // the working directory and command
         let mut inputs = Vec::with_capacity(self.inner.inputs().len() + 2);
+        let mut mounts = Vec::with_capacity(inputs.capacity());
         for input in self.inner.inputs().iter() {
             let guest_path = input.guest_path().expect("input should have guest path");
             let local_path = input.local_path().expect("input should be localized");
@@ -168,6 +483,11 @@ impl TaskManagerRequest for DockerTaskRequest {
                     .read_only(true)
                     .build(),
             );
+            mounts.push(Mount {
+                host: local_path.into(),
+                guest: guest_path.as_str(),
+                read_only: true,
+            });
         }
 
         // Add an input for the work directory
@@ -190,6 +510,22 @@ impl TaskManagerRequest for DockerTaskRequest {
                 .build(),
         );
 
+        let shell = self
+            .config
+            .task
+            .shell
+            .as_deref()
+            .unwrap_or(DEFAULT_TASK_SHELL);
+        mounts.push(Mount {
+            host: work_dir.as_path().into(),
+            guest: GUEST_WORK_DIR,
+            read_only: false,
+        });
+        mounts.push(Mount {
+            host: command_path.as_path().into(),
+            guest: GUEST_COMMAND_PATH,
+            read_only: true,
+        });
         let stdout_path = self.inner.attempt_dir().join(STDOUT_FILE_NAME);
         let stderr_path = self.inner.attempt_dir().join(STDERR_FILE_NAME);
 
@@ -206,18 +542,18 @@ impl TaskManagerRequest for DockerTaskRequest {
                 .build(),
         ];
 
+        // Watch for the container running out of memory before it is created
+        let watcher = self
+            .daemon
+            .as_ref()
+            .and_then(|d| d.watch_out_of_memory(&self.name));
+
         let task = Task::builder()
-            .name(self.name)
+            .name(self.name.clone())
             .executions(NonEmpty::new(
                 Execution::builder()
-                    .image(self.container)
-                    .program(
-                        self.config
-                            .task
-                            .shell
-                            .as_deref()
-                            .unwrap_or(DEFAULT_TASK_SHELL),
-                    )
+                    .image(self.container.clone())
+                    .program(shell)
                     .args([GUEST_COMMAND_PATH.to_string()])
                     .work_dir(GUEST_WORK_DIR)
                     .env(self.inner.env().clone())
@@ -238,7 +574,45 @@ impl TaskManagerRequest for DockerTaskRequest {
             )
             .build();
 
-        let statuses = self.backend.run(task, self.token.clone())?.await?;
+        // The task's execution starts once its container is created
+        self.inner.notify_started();
+        let statuses = self.backend.run(task, self.token.clone())?.await;
+        let out_of_memory = match watcher {
+            Some(watcher) => watcher.finish().await,
+            None => None,
+        };
+
+        // The digest is resolved once the image has been pulled for the task's
+        // container so that it is the digest of the image the container was created
+        // from
+        let digest = match &self.daemon {
+            Some(daemon) => daemon.digest(&self.container).await,
+            None => None,
+        };
+
+        // Write a script for rerunning the task outside of the engine, pinning its
+        // image to the digest
+        let container: Cow<'_, str> = match &digest {
+            Some(digest) if !self.container.contains('@') => {
+                format!("{image}@{digest}", image = self.container).into()
+            }
+            _ => self.container.as_str().into(),
+        };
+        let script = DockerInvocation {
+            container: &container,
+            shell,
+            work_dir: GUEST_WORK_DIR,
+            command: GUEST_COMMAND_PATH,
+            mounts,
+            cpu: self.max_cpu.unwrap_or(self.cpu),
+            memory: self.max_memory.unwrap_or(self.memory),
+            gpu: self.gpu,
+            platform: self.architecture.map(|a| a.platform()),
+        }
+        .script(&self.name, &self.inner);
+        write_rerun_script(self.inner.attempt_dir(), &script)?;
+
+        let statuses = statuses?;
 
         assert_eq!(statuses.len(), 1, "there should only be one exit status");
         let status = statuses.first();
@@ -260,6 +634,8 @@ impl TaskManagerRequest for DockerTaskRequest {
                     .expect("path should be UTF-8"),
             )
             .into(),
+            out_of_memory,
+            digest,
         })
     }
 }
@@ -280,6 +656,14 @@ pub struct DockerBackend {
     manager: TaskManager<DockerTaskRequest>,
     /// The name generator for tasks.
     names: Arc<Mutex<GeneratorIterator<UniqueAlphanumeric>>>,
+    /// The verifier of container platforms.
+    ///
+    /// This is `None` if no architecture was configured for the backend.
+    platforms: Option<Arc<PlatformVerifier>>,
+    /// The client of the Docker daemon.
+    ///
+    /// This is `None` if the client could not connect to the daemon.
+    daemon: Option<Arc<Daemon>>,
 }
 
 impl DockerBackend {
@@ -324,6 +708,27 @@ impl DockerBackend {
             TaskManager::new(cpu, max_cpu, memory, max_memory)
         };
 
+        let daemon = match Docker::with_defaults() {
+            Ok(docker) => Some(Arc::new(Daemon {
+                docker,
+                events: !resources.use_service(),
+                digests: Default::default(),
+            })),
+            Err(e) => {
+                warn!("failed to connect to Docker daemon: {e}");
+                None
+            }
+        };
+
+        let platforms = match backend_config.architecture {
+            Some(architecture) => Some(Arc::new(PlatformVerifier {
+                architecture,
+                docker: Docker::with_defaults().context("failed to connect to Docker daemon")?,
+                verified: Default::default(),
+            })),
+            None => None,
+        };
+
         Ok(Self {
             config,
             inner: Arc::new(backend),
@@ -332,6 +737,8 @@ impl DockerBackend {
             max_memory,
             manager,
             names,
+            platforms,
+            daemon,
         })
     }
 }
@@ -466,22 +873,39 @@ impl TaskExecutionBackend for DockerBackend {
                 .next()
                 .expect("generator should never be exhausted")
         );
-        self.manager.send(
-            DockerTaskRequest {
-                config: self.config.clone(),
-                inner: request,
-                backend: self.inner.clone(),
-                name,
-                container,
-                cpu,
-                memory,
-                max_cpu,
-                max_memory,
-                gpu,
-                token,
-            },
-            completed_tx,
-        );
+        let request = DockerTaskRequest {
+            config: self.config.clone(),
+            inner: request,
+            backend: self.inner.clone(),
+            name,
+            container,
+            architecture: self.platforms.as_ref().map(|p| p.architecture),
+            cpu,
+            memory,
+            max_cpu,
+            max_memory,
+            gpu,
+            daemon: self.daemon.clone(),
+            token,
+        };
+
+        match &self.platforms {
+            Some(platforms) => {
+                // Verify the container is available for the architecture before the task is
+                // queued so that resources are not reserved for a task that cannot run
+                let platforms = platforms.clone();
+                let manager = self.manager.clone();
+                tokio::spawn(async move {
+                    match platforms.verify(&request.container).await {
+                        Ok(()) => manager.send(request, completed_tx),
+                        Err(e) => {
+                            completed_tx.send(Err(e)).ok();
+                        }
+                    }
+                });
+            }
+            None => self.manager.send(request, completed_tx),
+        }
 
         Ok(completed_rx)
     }
@@ -583,3 +1007,17 @@ impl TaskExecutionBackend for DockerBackend {
         )
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn task_containers() {
+        assert!(is_task_container("0-abc123-0", "0-abc123"));
+        assert!(is_task_container("0-abc123", "0-abc123"));
+        assert!(!is_task_container("0-abc1234-0", "0-abc123"));
+        assert!(!is_task_container("0-abc123-", "0-abc123"));
+        assert!(!is_task_container("0-abc123-x", "0-abc123"));
+    }
+}
@@ -31,6 +31,8 @@ use super::TaskExecutionConstraints;
 use super::TaskManager;
 use super::TaskManagerRequest;
 use super::TaskSpawnRequest;
+use super::rerun::local_script;
+use super::rerun::write_rerun_script;
 use crate::COMMAND_FILE_NAME;
 use crate::ONE_GIBIBYTE;
 use crate::PrimitiveValue;
@@ -46,6 +48,7 @@ use crate::config::DEFAULT_TASK_SHELL;
 use crate::config::LocalBackendConfig;
 use crate::config::TaskResourceLimitBehavior;
 use crate::convert_unit_string;
+use crate::logs::copy_limited;
 use crate::path::EvaluationPath;
 use crate::v1::cpu;
 use crate::v1::memory;
@@ -109,6 +112,18 @@ impl TaskManagerRequest for LocalTaskRequest {
                 )
             })?;
 
+            // Write a script for rerunning the task outside of the engine
+            let shell = self
+                .config
+                .task
+                .shell
+                .as_deref()
+                .unwrap_or(DEFAULT_TASK_SHELL);
+            write_rerun_script(
+                self.inner.attempt_dir(),
+                &local_script(&self.name, shell, &self.inner),
+            )?;
+
             // Create a file for the stdout
             let stdout = File::create(&stdout_path).with_context(|| {
                 format!(
@@ -125,13 +140,19 @@ impl TaskManagerRequest for LocalTaskRequest {
                 )
             })?;
 
-            let mut command = Command::new(
-                self.config
-                    .task
-                    .shell
-                    .as_deref()
-                    .unwrap_or(DEFAULT_TASK_SHELL),
-            );
+            // If the logs are limited, they are piped through the engine so that the
+            // dropped lines are never written to disk
+            let limit = self.inner.log_limit();
+            let mut files = None;
+            let (stdout, stderr) = match limit {
+                Some(_) => {
+                    files = Some((stdout, stderr));
+                    (Stdio::piped(), Stdio::piped())
+                }
+                None => (stdout.into(), stderr.into()),
+            };
+
+            let mut command = Command::new(shell);
             command
                 .current_dir(&work_dir)
                 .arg(command_path)
@@ -153,9 +174,18 @@ impl TaskManagerRequest for LocalTaskRequest {
                 command.env("PATH", path);
             }
 
+            let kills = oom_kills();
             let mut child = command.spawn().context("failed to spawn shell")?;
+            let mut logs = Vec::new();
+            if let (Some(limit), Some((stdout, stderr))) = (limit, files) {
+                let stdout_pipe = child.stdout.take().expect("stdout should be piped");
+                let stderr_pipe = child.stderr.take().expect("stderr should be piped");
+                logs.push(tokio::spawn(copy_limited(stdout_pipe, stdout, limit)));
+                logs.push(tokio::spawn(copy_limited(stderr_pipe, stderr, limit)));
+            }
 
             // Notify that the process has spawned
+            self.inner.notify_started();
             send_event!(self.events, Event::TaskStarted { id });
 
             let id = child.id().expect("should have id");
@@ -168,6 +198,24 @@ impl TaskManagerRequest for LocalTaskRequest {
                 format!("failed to wait for termination of task child process {id}")
             })?;
 
+            for (log, path) in logs.into_iter().zip([&stdout_path, &stderr_path]) {
+                let dropped = log
+                    .await
+                    .expect("failed to join log task")
+                    .with_context(|| {
+                        format!("failed to write log `{path}`", path = path.display())
+                    })?;
+                if let Some(dropped) = dropped {
+                    info!(
+                        "dropped {lines} line{s} from log `{path}` of task `{name}`",
+                        lines = dropped.lines,
+                        s = if dropped.lines == 1 { "" } else { "s" },
+                        path = path.display(),
+                        name = self.name
+                    );
+                }
+            }
+
             #[cfg(unix)]
             {
                 use std::os::unix::process::ExitStatusExt;
@@ -182,7 +230,10 @@ impl TaskManagerRequest for LocalTaskRequest {
                 }
             }
 
-            Ok(status)
+            // The kernel counts the processes killed for running out of memory in the
+            // cgroup of the engine, which task processes inherit
+            let out_of_memory = kills.zip(oom_kills()).map(|(before, after)| after > before);
+            Ok((status, out_of_memory))
         };
 
         // Send the created event
@@ -206,7 +257,7 @@ impl TaskManagerRequest for LocalTaskRequest {
             }
             result = run => {
                 match result {
-                    Ok(status) => {
+                    Ok((status, out_of_memory)) => {
                         send_event!(self.events, Event::TaskCompleted { id, exit_statuses: NonEmpty::new(status) });
 
                         let exit_code = status.code().expect("process should have exited");
@@ -216,6 +267,8 @@ impl TaskManagerRequest for LocalTaskRequest {
                             work_dir: EvaluationPath::Local(work_dir),
                             stdout: PrimitiveValue::new_file(stdout_path.into_os_string().into_string().expect("path should be UTF-8")).into(),
                             stderr: PrimitiveValue::new_file(stderr_path.into_os_string().into_string().expect("path should be UTF-8")).into(),
+                            out_of_memory,
+                            digest: None,
                         })
                     }
                     Err(e) => {
@@ -228,6 +281,36 @@ impl TaskManagerRequest for LocalTaskRequest {
     }
 }
 
+/// Gets the number of processes in the engine's cgroup that have been killed
+/// for running out of memory.
+///
+/// Returns `None` if the cgroup's `memory.events` file cannot be read.
+#[cfg(target_os = "linux")]
+fn oom_kills() -> Option<u64> {
+    // A cgroup v2 hierarchy has a single entry of the form `0::<path>`
+    let cgroup = fs::read_to_string("/proc/self/cgroup").ok()?;
+    let path = cgroup.lines().find_map(|l| l.strip_prefix("0::"))?;
+    let events = fs::read_to_string(format!(
+        "/sys/fs/cgroup/{path}/memory.events",
+        path = path.trim_start_matches('/')
+    ))
+    .ok()?;
+
+    events
+        .lines()
+        .find_map(|l| l.strip_prefix("oom_kill "))
+        .and_then(|c| c.trim().parse().ok())
+}
+
+/// Gets the number of processes in the engine's cgroup that have been killed
+/// for running out of memory.
+///
+/// Cgroups are only supported on Linux, so this always returns `None`.
+#[cfg(not(target_os = "linux"))]
+fn oom_kills() -> Option<u64> {
+    None
+}
+
 /// Represents a task execution backend that locally executes tasks.
 ///
 /// <div class="warning">
@@ -374,6 +457,10 @@ impl TaskExecutionBackend for LocalBackend {
         true
     }
 
+    fn limits_logs(&self) -> bool {
+        true
+    }
+
     fn spawn(
         &self,
         request: TaskSpawnRequest,
@@ -40,6 +40,8 @@ use super::TaskManager;
 use super::TaskManagerRequest;
 use super::TaskSpawnRequest;
 use super::apptainer::ApptainerConfig;
+use super::rerun::apptainer_script;
+use super::rerun::write_rerun_script;
 use crate::ONE_GIBIBYTE;
 use crate::PrimitiveValue;
 use crate::TaskExecutionResult;
@@ -172,6 +174,12 @@ impl TaskManagerRequest for LsfApptainerTaskRequest {
         )
         .await?;
 
+        // Write a script for rerunning the task outside of the engine
+        write_rerun_script(
+            attempt_dir,
+            &apptainer_script(&self.name, &self.spawn_request, &apptainer_command_path),
+        )?;
+
         // The path for the LSF-level stdout and stderr. This primarily contains the job
         // report, as we redirect Apptainer and WDL output separately.
         let lsf_stdout_path = attempt_dir.join("lsf.stdout");
@@ -196,6 +204,14 @@ impl TaskManagerRequest for LsfApptainerTaskRequest {
             bsub_command.arg("-gpu").arg(format!("num={n_gpu}/host"));
         }
 
+        // If the task has a maximum execution time, pass it to `bsub` via `-W` so that
+        // LSF enforces it; the run limit is specified in whole minutes.
+        if let Some(max_time) = v1::max_time(self.spawn_request.hints())? {
+            bsub_command
+                .arg("-W")
+                .arg(max_time.as_secs().div_ceil(60).max(1).to_string());
+        }
+
         // Add any user-configured extra arguments.
         if let Some(args) = &self.backend_config.extra_bsub_args {
             bsub_command.args(args);
@@ -343,6 +359,8 @@ impl TaskManagerRequest for LsfApptainerTaskRequest {
                     .expect("path should be UTF-8"),
             )
             .into(),
+            out_of_memory: None,
+            digest: None,
         })
     }
 }
@@ -489,6 +507,10 @@ impl TaskExecutionBackend for LsfApptainerBackend {
         true
     }
 
+    fn enforces_max_time(&self) -> bool {
+        false
+    }
+
     fn spawn(
         &self,
         request: TaskSpawnRequest,
@@ -0,0 +1,353 @@
+//! Implementation of rerun scripts.
+//!
+//! A rerun script is written to the attempt directory of each task execution
+//! that reproduces the execution's invocation (its container, mounts,
+//! environment, and command) so that a task can be re-executed and debugged
+//! outside of the engine.
+
+use std::borrow::Cow;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use anyhow::Result;
+
+use super::TaskSpawnRequest;
+
+/// The name of the rerun script written to the attempt directory.
+pub(crate) const RERUN_FILE_NAME: &str = "rerun.sh";
+
+/// Quotes a string for use as a single word in a shell script.
+fn quote(s: &str) -> Cow<'_, str> {
+    if !s.is_empty()
+        && s.chars().all(|c| {
+            c.is_ascii_alphanumeric() || matches!(c, '/' | '.' | '-' | '_' | ':' | '=' | '@' | ',')
+        })
+    {
+        return s.into();
+    }
+
+    format!("'{s}'", s = s.replace('\'', r"'\''")).into()
+}
+
+/// Quotes a path for use as a single word in a shell script.
+fn quote_path(path: &Path) -> String {
+    quote(&path.to_string_lossy()).into_owned()
+}
+
+/// Quotes a field of a Docker `--mount` option.
+///
+/// The option is parsed as CSV, so a field containing a comma or a double
+/// quote is wrapped in double quotes.
+fn csv_field(s: &str) -> Cow<'_, str> {
+    if !s.contains([',', '"']) {
+        return s.into();
+    }
+
+    format!("\"{s}\"", s = s.replace('"', "\"\"")).into()
+}
+
+/// The note of a rerun script that writes the task's output to the terminal.
+const TERMINAL_OUTPUT_NOTE: &str = "The task's stdout and stderr are written to the terminal \
+                                    rather than to\nthe attempt's `stdout` and `stderr` files.";
+
+/// The note of a rerun script that writes the task's output to the attempt's
+/// files.
+const FILE_OUTPUT_NOTE: &str =
+    "The task's stdout and stderr are written to the attempt's `stdout` and\n`stderr` files.";
+
+/// Starts a rerun script for the given task execution.
+fn header(name: &str, request: &TaskSpawnRequest, note: &str) -> String {
+    let mut script = String::new();
+    let _ = writeln!(script, "#!/usr/bin/env bash");
+    let _ = writeln!(
+        script,
+        "# Reruns attempt {attempt} of task `{name}` outside of the engine.",
+        attempt = request.attempt()
+    );
+    let _ = writeln!(script, "#");
+    for line in note.lines() {
+        let _ = writeln!(script, "# {line}");
+    }
+
+    let _ = writeln!(script, "set -euo pipefail");
+    let _ = writeln!(script);
+    script
+}
+
+/// Generates the rerun script of a task executed directly on the host.
+pub(crate) fn local_script(name: &str, shell: &str, request: &TaskSpawnRequest) -> String {
+    let mut script = header(name, request, TERMINAL_OUTPUT_NOTE);
+    for (k, v) in request.env() {
+        let _ = writeln!(script, "export {k}={v}", v = quote(v));
+    }
+
+    let _ = writeln!(
+        script,
+        "cd {dir}",
+        dir = quote_path(&request.wdl_work_dir_host_path())
+    );
+    let _ = writeln!(
+        script,
+        "exec {shell} {command}",
+        shell = quote(shell),
+        command = quote_path(&request.wdl_command_host_path())
+    );
+    script
+}
+
+/// Represents a bind mount of a container.
+pub(crate) struct Mount<'a> {
+    /// The path on the host.
+    pub host: Cow<'a, Path>,
+    /// The path in the container.
+    pub guest: &'a str,
+    /// Whether or not the mount is read-only.
+    pub read_only: bool,
+}
+
+/// Represents the Docker container invocation of a task execution.
+pub(crate) struct DockerInvocation<'a> {
+    /// The container image.
+    ///
+    /// The image is expected to be pinned to its digest if it could be
+    /// resolved.
+    pub container: &'a str,
+    /// The shell used to execute the command.
+    pub shell: &'a str,
+    /// The working directory in the container.
+    pub work_dir: &'a str,
+    /// The path of the command in the container.
+    pub command: &'a str,
+    /// The bind mounts of the container.
+    pub mounts: Vec<Mount<'a>>,
+    /// The number of CPUs available to the container.
+    pub cpu: f64,
+    /// The memory, in bytes, available to the container.
+    pub memory: u64,
+    /// The number of GPUs available to the container.
+    pub gpu: Option<u64>,
+    /// The platform of the container (e.g. `linux/arm64`), if configured.
+    pub platform: Option<String>,
+}
+
+impl DockerInvocation<'_> {
+    /// Generates the rerun script of the invocation.
+    pub(crate) fn script(&self, name: &str, request: &TaskSpawnRequest) -> String {
+        let mut script = header(name, request, TERMINAL_OUTPUT_NOTE);
+        let _ = writeln!(script, "exec docker run --rm \\");
+        let _ = writeln!(script, "  --workdir {dir} \\", dir = quote(self.work_dir));
+
+        // The work directory is group writable so that the container may be run with
+        // the current user's group
+        #[cfg(unix)]
+        let _ = writeln!(script, "  --group-add \"$(id -g)\" \\");
+
+        for mount in &self.mounts {
+            let _ = writeln!(
+                script,
+                "  --mount {spec} \\",
+                spec = quote(&format!(
+                    "type=bind,{src},{dst}{ro}",
+                    src = csv_field(&format!("src={host}", host = mount.host.display())),
+                    dst = csv_field(&format!("dst={guest}", guest = mount.guest)),
+                    ro = if mount.read_only { ",readonly" } else { "" }
+                ))
+            );
+        }
+
+        for (k, v) in request.env() {
+            let _ = writeln!(script, "  --env {env} \\", env = quote(&format!("{k}={v}")));
+        }
+
+        let _ = writeln!(
+            script,
+            "  --cpus {cpu} --memory {memory}b \\",
+            cpu = self.cpu,
+            memory = self.memory
+        );
+        if let Some(gpu) = self.gpu {
+            let _ = writeln!(script, "  --gpus {gpu} \\");
+        }
+
+        if let Some(platform) = &self.platform {
+            let _ = writeln!(
+                script,
+                "  --platform {platform} \\",
+                platform = quote(platform)
+            );
+        }
+
+        let _ = writeln!(
+            script,
+            "  {container} {shell} {command}",
+            container = quote(self.container),
+            shell = quote(self.shell),
+            command = quote(self.command)
+        );
+        script
+    }
+}
+
+/// Generates the rerun script of a task executed by an Apptainer command
+/// script.
+///
+/// The Apptainer command script is run directly on the host rather than being
+/// submitted to the cluster.
+pub(crate) fn apptainer_script(name: &str, request: &TaskSpawnRequest, command: &Path) -> String {
+    let mut script = header(name, request, FILE_OUTPUT_NOTE);
+    let _ = writeln!(script, "exec bash {command}", command = quote_path(command));
+    script
+}
+
+/// Writes a rerun script to the given attempt directory.
+pub(crate) fn write_rerun_script(attempt_dir: &Path, script: &str) -> Result<()> {
+    let path = attempt_dir.join(RERUN_FILE_NAME);
+    fs::write(&path, script).with_context(|| {
+        format!(
+            "failed to write rerun script `{path}`",
+            path = path.display()
+        )
+    })?;
+
+    #[cfg(unix)]
+    {
+        use std::fs::Permissions;
+        use std::fs::set_permissions;
+        use std::os::unix::fs::PermissionsExt;
+        set_permissions(&path, Permissions::from_mode(0o770)).with_context(|| {
+            format!(
+                "failed to set permissions for rerun script `{path}`",
+                path = path.display()
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use indexmap::IndexMap;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::TaskSpawnInfo;
+    use crate::http::Transferer;
+    use crate::v1::test::TestEnv;
+
+    /// Creates a spawn request for the second attempt of a task.
+    fn spawn_request() -> TaskSpawnRequest {
+        let mut env = IndexMap::new();
+        env.insert("FOO".to_string(), "bar baz".to_string());
+        let info = TaskSpawnInfo::new(
+            "echo hello".to_string(),
+            vec![],
+            HashMap::new().into(),
+            HashMap::new().into(),
+            env.into(),
+            Arc::new(TestEnv::default()) as Arc<dyn Transferer>,
+        );
+        TaskSpawnRequest::new(
+            "example".to_string(),
+            info,
+            1,
+            PathBuf::from("/runs/example/attempts/1"),
+            PathBuf::from("/runs/example"),
+            PathBuf::from("/runs/example/tmp"),
+        )
+    }
+
+    #[test]
+    fn shell_quoting() {
+        assert_eq!(quote("/mnt/task/work"), "/mnt/task/work");
+        assert_eq!(quote("ubuntu@sha256:abc"), "ubuntu@sha256:abc");
+        assert_eq!(quote(""), "''");
+        assert_eq!(quote("hello world"), "'hello world'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+        assert_eq!(quote("$HOME"), "'$HOME'");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn local_rerun_script() {
+        let script = local_script("example-abc", "bash", &spawn_request());
+        assert_eq!(
+            script,
+            r#"#!/usr/bin/env bash
+# Reruns attempt 1 of task `example-abc` outside of the engine.
+#
+# The task's stdout and stderr are written to the terminal rather than to
+# the attempt's `stdout` and `stderr` files.
+set -euo pipefail
+
+export FOO='bar baz'
+cd /runs/example/attempts/1/work
+exec bash /runs/example/attempts/1/command
+"#
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn docker_rerun_script() {
+        let invocation = DockerInvocation {
+            container: "ubuntu:22.04@sha256:abc",
+            shell: "bash",
+            work_dir: "/mnt/task/work",
+            command: "/mnt/task/command",
+            mounts: vec![
+                Mount {
+                    host: Path::new("/data/a,b.txt").into(),
+                    guest: "/mnt/task/inputs/0/a,b.txt",
+                    read_only: true,
+                },
+                Mount {
+                    host: Path::new("/runs/example/attempts/1/work").into(),
+                    guest: "/mnt/task/work",
+                    read_only: false,
+                },
+            ],
+            cpu: 2.0,
+            memory: 1073741824,
+            gpu: None,
+            platform: Some("linux/arm64".to_string()),
+        };
+
+        assert_eq!(
+            invocation.script("example-abc", &spawn_request()),
+            r#"#!/usr/bin/env bash
+# Reruns attempt 1 of task `example-abc` outside of the engine.
+#
+# The task's stdout and stderr are written to the terminal rather than to
+# the attempt's `stdout` and `stderr` files.
+set -euo pipefail
+
+exec docker run --rm \
+  --workdir /mnt/task/work \
+  --group-add "$(id -g)" \
+  --mount 'type=bind,"src=/data/a,b.txt","dst=/mnt/task/inputs/0/a,b.txt",readonly' \
+  --mount type=bind,src=/runs/example/attempts/1/work,dst=/mnt/task/work \
+  --env 'FOO=bar baz' \
+  --cpus 2 --memory 1073741824b \
+  --platform linux/arm64 \
+  ubuntu:22.04@sha256:abc bash /mnt/task/command
+"#
+        );
+    }
+
+    #[test]
+    fn mount_field_quoting() {
+        assert_eq!(csv_field("src=/data/a.txt"), "src=/data/a.txt");
+        assert_eq!(csv_field("src=/data/a,b.txt"), r#""src=/data/a,b.txt""#);
+        assert_eq!(
+            csv_field(r#"src=/data/"a".txt"#),
+            r#""src=/data/""a"".txt""#
+        );
+    }
+}
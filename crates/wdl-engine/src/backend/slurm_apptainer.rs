@@ -28,6 +28,7 @@ use tokio::io::AsyncBufReadExt;
 use tokio::io::BufReader;
 use tokio::process::Command;
 use tokio::sync::broadcast;
+use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
 use tracing::debug;
 use tracing::error;
@@ -40,6 +41,8 @@ use super::TaskExecutionBackend;
 use super::TaskManager;
 use super::TaskManagerRequest;
 use super::TaskSpawnRequest;
+use super::rerun::apptainer_script;
+use super::rerun::write_rerun_script;
 use crate::ONE_GIBIBYTE;
 use crate::PrimitiveValue;
 use crate::TaskExecutionResult;
@@ -174,6 +177,12 @@ impl TaskManagerRequest for SlurmApptainerTaskRequest {
         )
         .await?;
 
+        // Write a script for rerunning the task outside of the engine
+        write_rerun_script(
+            attempt_dir,
+            &apptainer_script(&self.name, &self.spawn_request, &apptainer_command_path),
+        )?;
+
         // The path for the Slurm-level stdout and stderr. This primarily contains the
         // job report, as we redirect Apptainer and WDL output separately.
         let slurm_stdout_path = attempt_dir.join("slurm.stdout");
@@ -199,6 +208,12 @@ impl TaskManagerRequest for SlurmApptainerTaskRequest {
             sbatch_command.arg(format!("--gpus-per-task={gpu_count}"));
         }
 
+        // If the task has a maximum execution time, pass it to `sbatch` via `--time` so
+        // that Slurm enforces it; the time limit is specified in whole minutes.
+        if let Some(max_time) = v1::max_time(self.spawn_request.hints())? {
+            sbatch_command.arg(format!("--time={}", max_time.as_secs().div_ceil(60).max(1)));
+        }
+
         // Add any user-configured extra arguments.
         if let Some(args) = &self.backend_config.extra_sbatch_args {
             sbatch_command.args(args);
@@ -273,7 +288,9 @@ impl TaskManagerRequest for SlurmApptainerTaskRequest {
             .ok_or_else(|| anyhow!("sbatch child stdout missing"))?;
         let task_name = self.name.clone();
         let stdout_crankshaft_events = self.crankshaft_events.clone();
+        let (job_id_tx, job_id_rx) = oneshot::channel();
         tokio::spawn(async move {
+            let mut job_id_tx = Some(job_id_tx);
             let mut lines = BufReader::new(sbatch_stdout).lines();
             while let Ok(Some(line)) = lines.next_line().await {
                 // TODO ACF 2025-10-14: `sbatch --wait` even on high verbosity doesn't tell us
@@ -282,7 +299,11 @@ impl TaskManagerRequest for SlurmApptainerTaskRequest {
                 // out directly, we'll have to set up a separate task to
                 // poll job statuses. For the moment, this is potentially misleading about what
                 // work has actually begun computation.
-                if line.starts_with("Submitted batch job") {
+                if let Some(job_id) = line.strip_prefix("Submitted batch job") {
+                    if let Some(tx) = job_id_tx.take() {
+                        let _ = tx.send(job_id.trim().to_string());
+                    }
+
                     crankshaft::events::send_event!(
                         stdout_crankshaft_events,
                         crankshaft::events::Event::TaskStarted {
@@ -328,6 +349,12 @@ impl TaskManagerRequest for SlurmApptainerTaskRequest {
             }
         );
 
+        // The sender is dropped without sending if the job was never submitted
+        let out_of_memory = match job_id_rx.await {
+            Ok(job_id) => job_out_of_memory(&job_id).await,
+            Err(_) => None,
+        };
+
         Ok(TaskExecutionResult {
             // Under normal circumstances, the exit code of `sbatch --wait` is the exit code of its
             // command, and the exit code of `apptainer exec` is likewise the exit code of its
@@ -352,10 +379,57 @@ impl TaskManagerRequest for SlurmApptainerTaskRequest {
                     .expect("path should be UTF-8"),
             )
             .into(),
+            out_of_memory,
+            digest: None,
         })
     }
 }
 
+/// Determines if the given Slurm job ran out of memory according to `sacct`.
+///
+/// Returns `None` if the state of the job could not be queried.
+async fn job_out_of_memory(job_id: &str) -> Option<bool> {
+    let output = tokio::time::timeout(
+        // Like `scontrol`, `sacct` ordinarily returns quickly, but we don't want to hang on a
+        // misconfigured system
+        std::time::Duration::from_secs(10),
+        Command::new("sacct")
+            .arg("--jobs")
+            .arg(job_id)
+            .arg("--noheader")
+            .arg("--parsable2")
+            .arg("--format=State")
+            .output(),
+    )
+    .await;
+
+    match output {
+        Ok(Ok(output)) if output.status.success() => {
+            // The job and each of its steps have a state; the batch step is the one
+            // that is typically marked as having run out of memory
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            Some(
+                stdout
+                    .lines()
+                    .any(|state| state.trim().starts_with("OUT_OF_MEMORY")),
+            )
+        }
+        Ok(Ok(output)) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            warn!(%stderr, "failed to query the state of Slurm job `{job_id}`");
+            None
+        }
+        Ok(Err(e)) => {
+            warn!("failed to query the state of Slurm job `{job_id}`: {e}");
+            None
+        }
+        Err(_) => {
+            warn!("timed out querying the state of Slurm job `{job_id}`");
+            None
+        }
+    }
+}
+
 /// The experimental Slurm + Apptainer backend.
 ///
 /// See the module-level documentation for details.
@@ -501,6 +575,10 @@ impl TaskExecutionBackend for SlurmApptainerBackend {
         true
     }
 
+    fn enforces_max_time(&self) -> bool {
+        false
+    }
+
     fn spawn(
         &self,
         request: TaskSpawnRequest,
@@ -0,0 +1,102 @@
+//! Implementation of the stub backend.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use crankshaft::events::Event;
+use tokio::sync::broadcast;
+use tokio::sync::oneshot::Receiver;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+use super::LocalBackend;
+use super::TaskExecutionBackend;
+use super::TaskExecutionConstraints;
+use super::TaskSpawnRequest;
+use crate::TaskExecutionResult;
+use crate::Value;
+use crate::config::Config;
+use crate::config::LocalBackendConfig;
+use crate::config::StubBackendConfig;
+use crate::config::TaskStubConfig;
+
+/// Represents a task execution backend that stubs the execution of tasks.
+///
+/// Task commands are never executed; instead, the script of a task's stub (if
+/// there is one) is executed on the host in place of the command and the
+/// literal outputs of the stub are used in place of evaluating the
+/// corresponding output expressions.
+///
+/// <div class="warning">
+/// Warning: stub scripts are executed on the host directly without the use of
+/// a container.
+/// </div>
+pub struct StubBackend {
+    /// The stub backend configuration.
+    config: Arc<StubBackendConfig>,
+    /// The stub used for tasks without a configured stub.
+    default: TaskStubConfig,
+    /// The local backend used to execute stub scripts.
+    local: LocalBackend,
+}
+
+impl StubBackend {
+    /// Constructs a new stub task execution backend with the given
+    /// configuration.
+    ///
+    /// The provided configuration is expected to have already been validated.
+    pub fn new(
+        config: Arc<Config>,
+        backend_config: Arc<StubBackendConfig>,
+        events: Option<broadcast::Sender<Event>>,
+    ) -> Result<Self> {
+        info!("initializing stub backend");
+
+        Ok(Self {
+            config: backend_config,
+            default: Default::default(),
+            local: LocalBackend::new(config, &LocalBackendConfig::default(), events)?,
+        })
+    }
+}
+
+impl TaskExecutionBackend for StubBackend {
+    fn max_concurrency(&self) -> u64 {
+        self.local.max_concurrency()
+    }
+
+    fn constraints(
+        &self,
+        requirements: &HashMap<String, Value>,
+        hints: &HashMap<String, Value>,
+    ) -> Result<TaskExecutionConstraints> {
+        self.local.constraints(requirements, hints)
+    }
+
+    fn guest_inputs_dir(&self) -> Option<&'static str> {
+        // Stub execution does not use a container
+        None
+    }
+
+    fn needs_local_inputs(&self) -> bool {
+        true
+    }
+
+    fn limits_logs(&self) -> bool {
+        self.local.limits_logs()
+    }
+
+    fn spawn(
+        &self,
+        request: TaskSpawnRequest,
+        token: CancellationToken,
+    ) -> Result<Receiver<Result<TaskExecutionResult>>> {
+        // The command of the request has already been replaced with the stub's script
+        self.local.spawn(request, token)
+    }
+
+    fn task_stub(&self, task: &str) -> Option<&TaskStubConfig> {
+        Some(self.config.tasks.get(task).unwrap_or(&self.default))
+    }
+}
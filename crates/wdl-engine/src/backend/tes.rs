@@ -341,6 +341,9 @@ impl TaskManagerRequest for TesTaskRequest {
                 )
                 .build();
 
+            // TES does not report when a task starts executing, so the task's execution
+            // is considered started once it is submitted
+            self.inner.notify_started();
             let statuses = match self.backend.run(task, self.token.clone())?.await {
                 Ok(statuses) => statuses,
                 Err(TaskRunError::Preempted) if preemptible > 0 => {
@@ -365,6 +368,8 @@ impl TaskManagerRequest for TesTaskRequest {
                 work_dir: EvaluationPath::Remote(work_dir_url),
                 stdout: PrimitiveValue::new_file(stdout_url).into(),
                 stderr: PrimitiveValue::new_file(stderr_url).into(),
+                out_of_memory: None,
+                digest: None,
             });
         }
     }
@@ -513,6 +518,10 @@ impl TaskExecutionBackend for TesBackend {
         false
     }
 
+    fn writes_local_logs(&self) -> bool {
+        false
+    }
+
     fn spawn(
         &self,
         request: TaskSpawnRequest,
@@ -13,6 +13,7 @@ use anyhow::Context;
 use anyhow::Result;
 use anyhow::bail;
 use arrayvec::ArrayString;
+use indexmap::IndexMap;
 use serde::Deserialize;
 use serde::Serialize;
 use tokio::fs;
@@ -280,6 +281,10 @@ pub struct KeyRequest<'a> {
     ///
     /// This field contributes to the digests stored in a cache entry.
     pub backend_inputs: &'a [Input],
+    /// The literal outputs of the task's stub, if the task is stubbed.
+    ///
+    /// This field directly contributes to the cache key.
+    pub stub_outputs: Option<&'a IndexMap<String, serde_json::Value>>,
 }
 
 /// Represents an evaluation call cache.
@@ -373,6 +378,12 @@ impl CallCache {
         request.document_uri.hash(&mut hasher);
         request.task_name.hash(&mut hasher);
         hash_sequence(&mut hasher, request.inputs.iter());
+        if let Some(outputs) = request.stub_outputs {
+            hash_sequence(
+                &mut hasher,
+                outputs.iter().map(|(k, v)| (k.as_str(), v.to_string())),
+            );
+        }
         let key = hasher.finalize().to_hex();
 
         Ok(Key {
@@ -434,6 +445,8 @@ impl CallCache {
             work_dir: work,
             stdout: PrimitiveValue::new_file(String::try_from(stdout)?).into(),
             stderr: PrimitiveValue::new_file(String::try_from(stderr)?).into(),
+            out_of_memory: None,
+            digest: None,
         }))
     }
 
@@ -572,6 +585,7 @@ mod test {
                 requirements: &self.requirements,
                 hints: &self.hints,
                 backend_inputs: &self.backend_inputs,
+                stub_outputs: None,
             }
         }
     }
@@ -633,6 +647,8 @@ mod test {
             work_dir: EvaluationPath::Local(task.paths.work_dir.clone()),
             stdout: PrimitiveValue::new_file(task.paths.stdout.to_str().unwrap()).into(),
             stderr: PrimitiveValue::new_file(task.paths.stderr.to_str().unwrap()).into(),
+            out_of_memory: None,
+            digest: None,
         };
         cache.put(key, &result).await.unwrap();
 
@@ -1084,4 +1100,35 @@ mod test {
             )
         );
     }
+
+    #[tokio::test]
+    async fn stub_outputs() {
+        let ctx = TestContext::new().await;
+        let request = ctx.task.key_request();
+        let key = ctx.cache.key(ctx.task.key_request()).await.unwrap();
+
+        // Stubbing the outputs of the task changes the key
+        let outputs = IndexMap::from([("count".to_string(), serde_json::Value::from(42))]);
+        let stubbed = ctx
+            .cache
+            .key(KeyRequest {
+                stub_outputs: Some(&outputs),
+                ..request
+            })
+            .await
+            .unwrap();
+        assert_ne!(key.key, stubbed.key);
+        assert!(ctx.cache.get(&stubbed).await.unwrap().is_none());
+
+        let outputs = IndexMap::from([("count".to_string(), serde_json::Value::from(43))]);
+        let modified = ctx
+            .cache
+            .key(KeyRequest {
+                stub_outputs: Some(&outputs),
+                ..ctx.task.key_request()
+            })
+            .await
+            .unwrap();
+        assert_ne!(stubbed.key, modified.key);
+    }
 }
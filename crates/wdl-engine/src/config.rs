@@ -1,6 +1,7 @@
 //! Implementation of engine configuration.
 
 use std::borrow::Cow;
+use std::fmt;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -25,6 +26,7 @@ use crate::LsfApptainerBackendConfig;
 use crate::SYSTEM;
 use crate::SlurmApptainerBackend;
 use crate::SlurmApptainerBackendConfig;
+use crate::StubBackend;
 use crate::TaskExecutionBackend;
 use crate::TesBackend;
 use crate::convert_unit_string;
@@ -200,6 +202,9 @@ pub struct Config {
     /// Storage configuration.
     #[serde(default)]
     pub storage: StorageConfig,
+    /// Hermetic evaluation configuration.
+    #[serde(default)]
+    pub hermetic: HermeticConfig,
     /// (Experimental) Avoid environment-specific output; default is `false`.
     ///
     /// If this option is `true`, selected error messages and log output will
@@ -257,6 +262,7 @@ impl Config {
         }
 
         self.storage.validate()?;
+        self.hermetic.validate()?;
 
         if self.suppress_env_specific_output && !self.experimental_features_enabled {
             bail!("`suppress_env_specific_output` requires enabling experimental features");
@@ -355,6 +361,17 @@ impl Config {
                 config.clone(),
                 events,
             ))),
+            BackendConfig::Stub(config) => {
+                warn!(
+                    "the engine is configured to use the stub backend: task commands will not be \
+                     executed"
+                );
+                Ok(Arc::new(StubBackend::new(
+                    self.clone(),
+                    config.clone(),
+                    events,
+                )?))
+            }
         }
     }
 }
@@ -392,6 +409,51 @@ impl HttpConfig {
     }
 }
 
+/// Represents hermetic evaluation configuration.
+///
+/// Hermetic evaluation prevents a task or workflow from depending on files
+/// that are only present on one host (e.g. a user's home directory).
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct HermeticConfig {
+    /// Whether hermetic evaluation is enabled; default is `false`.
+    ///
+    /// When enabled, every `File` and `Directory` input to the task or
+    /// workflow being evaluated must either be a remote URL or a local path
+    /// within one of the allowed roots; otherwise, evaluation fails before any
+    /// task is executed.
+    ///
+    /// Inputs evaluated from their default expressions, including the inputs
+    /// of called tasks and workflows, and call inputs specified with
+    /// expressions that do not reference any names (e.g. a literal path) are
+    /// checked once they are evaluated. As a workflow is evaluated in the
+    /// order of its dependencies, calls that do not depend on such an input
+    /// may have already executed when it is rejected.
+    #[serde(default)]
+    pub enabled: bool,
+    /// The local directories that `File` and `Directory` inputs may be within
+    /// when hermetic evaluation is enabled.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_roots: Vec<PathBuf>,
+}
+
+impl HermeticConfig {
+    /// Validates the hermetic evaluation configuration.
+    pub fn validate(&self) -> Result<()> {
+        for root in &self.allowed_roots {
+            if !root.is_dir() {
+                bail!(
+                    "configuration value `hermetic.allowed_roots` contains `{root}`, which is not \
+                     a directory",
+                    root = root.display()
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Represents storage configuration.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
@@ -753,6 +815,52 @@ pub struct TaskConfig {
     /// The call caching mode to use for tasks.
     #[serde(default)]
     pub cache: CallCachingMode,
+    /// Whether or not to salvage the outputs of failed tasks.
+    ///
+    /// When enabled, the outputs of a task that fails are still evaluated
+    /// after its final attempt; the outputs that exist are written to an
+    /// `outputs.salvaged.json` file in the task's directory so that debugging
+    /// artifacts aren't lost. The task still fails.
+    ///
+    /// Defaults to `false`.
+    #[serde(default)]
+    pub salvage_outputs: bool,
+    /// The policy for retrying tasks that run out of memory with more memory.
+    ///
+    /// Retries are still limited by a task's `max_retries` requirement (or the
+    /// `retries` configuration value).
+    ///
+    /// Defaults to `None` (tasks are retried with the same memory).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_retry: Option<MemoryRetryConfig>,
+    /// The sinks to forward the standard output and standard error of tasks
+    /// to as they execute.
+    ///
+    /// Logs are forwarded from the attempt directory of each task, so the
+    /// sinks are ignored with a warning for backends that execute tasks
+    /// remotely (e.g. TES).
+    ///
+    /// Defaults to no sinks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub log_sinks: Vec<LogSinkConfig>,
+    /// The total size of a call's localized inputs above which a warning is
+    /// emitted, as a number of bytes or a string with a unit (e.g. `100 GiB`).
+    ///
+    /// This helps to spot calls that were unintentionally given large inputs
+    /// (e.g. every shard of a scatter receiving a whole genome).
+    ///
+    /// Defaults to no warning.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_size_warning: Option<String>,
+    /// The limits on the size of the standard output and standard error of
+    /// tasks.
+    ///
+    /// A task's `log_head` and `log_tail` hints override the limits.
+    ///
+    /// Defaults to `None` (the logs of tasks that do not specify the hints are
+    /// not limited).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_limit: Option<LogLimitConfig>,
 }
 
 impl TaskConfig {
@@ -762,10 +870,304 @@ impl TaskConfig {
             bail!("configuration value `task.retries` cannot exceed {MAX_RETRIES}");
         }
 
+        if let Some(memory_retry) = &self.memory_retry {
+            memory_retry.validate()?;
+        }
+
+        for sink in &self.log_sinks {
+            sink.validate()?;
+        }
+
+        self.input_size_warning()?;
+
+        if let Some(log_limit) = &self.log_limit {
+            log_limit.validate()?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets the total size, in bytes, of a call's localized inputs above which
+    /// a warning is emitted.
+    pub fn input_size_warning(&self) -> Result<Option<u64>> {
+        self.input_size_warning
+            .as_deref()
+            .map(|s| {
+                convert_unit_string(s).with_context(|| {
+                    format!("configuration value `task.input_size_warning` has invalid value `{s}`")
+                })
+            })
+            .transpose()
+    }
+}
+
+/// Gets the default value of [`LogLimitConfig::head`] and
+/// [`LogLimitConfig::tail`].
+fn default_log_limit_size() -> String {
+    "5 MiB".to_string()
+}
+
+/// Represents the limits on the size of the standard output and standard error
+/// of tasks.
+///
+/// A log that exceeds the sum of the limits is downsampled to its first `head`
+/// bytes and its last `tail` bytes, separated by a line stating how many lines
+/// were dropped.
+///
+/// The local backend limits logs as a task executes, so at most `head` plus
+/// `tail` bytes of each log are written to disk and the dropped lines are not
+/// forwarded to log sinks. The logs of tasks executed by other backends are
+/// downsampled once the task exits. In either case, `stdout()` and `stderr()`
+/// refer to the downsampled logs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct LogLimitConfig {
+    /// The number of bytes at the start of a log to retain, as a number of
+    /// bytes or a string with a unit (e.g. `5 MiB`).
+    ///
+    /// Defaults to `5 MiB`.
+    #[serde(default = "default_log_limit_size")]
+    pub head: String,
+    /// The number of bytes at the end of a log to retain, as a number of bytes
+    /// or a string with a unit (e.g. `5 MiB`).
+    ///
+    /// Defaults to `5 MiB`.
+    #[serde(default = "default_log_limit_size")]
+    pub tail: String,
+}
+
+impl Default for LogLimitConfig {
+    fn default() -> Self {
+        Self {
+            head: default_log_limit_size(),
+            tail: default_log_limit_size(),
+        }
+    }
+}
+
+impl LogLimitConfig {
+    /// Validates the log limit configuration.
+    pub fn validate(&self) -> Result<()> {
+        self.head()?;
+        self.tail()?;
+        Ok(())
+    }
+
+    /// Gets the number of bytes at the start of a log to retain.
+    pub fn head(&self) -> Result<u64> {
+        convert_unit_string(&self.head).with_context(|| {
+            format!(
+                "configuration value `task.log_limit.head` has invalid value `{head}`",
+                head = self.head
+            )
+        })
+    }
+
+    /// Gets the number of bytes at the end of a log to retain.
+    pub fn tail(&self) -> Result<u64> {
+        convert_unit_string(&self.tail).with_context(|| {
+            format!(
+                "configuration value `task.log_limit.tail` has invalid value `{tail}`",
+                tail = self.tail
+            )
+        })
+    }
+}
+
+/// Gets the default value of [`MemoryRetryConfig::multiplier`].
+const fn default_memory_retry_multiplier() -> f64 {
+    2.0
+}
+
+/// Gets the default value of [`MemoryRetryConfig::error_patterns`].
+fn default_memory_retry_error_patterns() -> Vec<String> {
+    [
+        "Out of memory",
+        "OutOfMemoryError",
+        "Cannot allocate memory",
+        "MemoryError",
+        "std::bad_alloc",
+        "oom-kill",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+/// Represents the policy for retrying tasks that run out of memory.
+///
+/// A task is considered to have run out of memory if it was killed for running
+/// out of memory or if the tail of its standard error contains any of the
+/// configured error patterns.
+///
+/// Whether a task was killed for running out of memory is reported by the
+/// backend, such as from Docker's `oom` events, the `oom_kill` count of a
+/// cgroup, or a Slurm job state of `OUT_OF_MEMORY`. Without such a signal, a
+/// task that was killed (e.g. exited with status `137`) is not considered to
+/// have run out of memory unless its standard error matches an error pattern.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct MemoryRetryConfig {
+    /// The factor to multiply a task's memory requirement by each time it is
+    /// retried after running out of memory.
+    ///
+    /// Defaults to `2.0`.
+    #[serde(default = "default_memory_retry_multiplier")]
+    pub multiplier: f64,
+    /// The maximum amount of memory to retry a task with, as a number of bytes
+    /// or a string with a unit (e.g. `64 GiB`).
+    ///
+    /// Defaults to no maximum.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory: Option<String>,
+    /// The patterns in a task's standard error that indicate the task ran out
+    /// of memory.
+    ///
+    /// Defaults to a set of common out of memory errors.
+    #[serde(default = "default_memory_retry_error_patterns")]
+    pub error_patterns: Vec<String>,
+}
+
+impl Default for MemoryRetryConfig {
+    fn default() -> Self {
+        Self {
+            multiplier: default_memory_retry_multiplier(),
+            max_memory: None,
+            error_patterns: default_memory_retry_error_patterns(),
+        }
+    }
+}
+
+impl MemoryRetryConfig {
+    /// Validates the memory retry configuration.
+    pub fn validate(&self) -> Result<()> {
+        if !self.multiplier.is_finite() || self.multiplier <= 1.0 {
+            bail!("configuration value `task.memory_retry.multiplier` must be greater than 1");
+        }
+
+        self.max_memory()?;
+        Ok(())
+    }
+
+    /// Gets the maximum amount of memory, in bytes, to retry a task with.
+    pub fn max_memory(&self) -> Result<Option<i64>> {
+        self.max_memory
+            .as_deref()
+            .map(|m| {
+                convert_unit_string(m)
+                    .and_then(|m| i64::try_from(m).ok())
+                    .with_context(|| {
+                        format!(
+                            "configuration value `task.memory_retry.max_memory` has invalid value \
+                             `{m}`"
+                        )
+                    })
+            })
+            .transpose()
+    }
+
+    /// Determines if a failed task ran out of memory given the backend's signal
+    /// for the task being killed for running out of memory and the tail of its
+    /// standard error.
+    ///
+    /// A task that was killed (e.g. with an exit code of 137) is not considered
+    /// to have run out of memory without a signal from the backend, as it may
+    /// have been killed for another reason.
+    pub fn is_out_of_memory(&self, signal: Option<bool>, stderr: &str) -> bool {
+        signal.unwrap_or_default()
+            || self
+                .error_patterns
+                .iter()
+                .any(|p| stderr.contains(p.as_str()))
+    }
+}
+
+/// Represents a sink for forwarding task logs.
+///
+/// Each line written to a task's standard output or standard error is
+/// forwarded to the sink while the task executes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", deny_unknown_fields)]
+pub enum LogSinkConfig {
+    /// Appends the lines to a file.
+    ///
+    /// Each line is prefixed with the identifier of the task and the name of
+    /// the stream it was written to.
+    File {
+        /// The path to the file to append to.
+        path: PathBuf,
+    },
+    /// Multiplexes the lines onto the standard error of the engine's process.
+    ///
+    /// Each line is prefixed with the identifier of the task and the name of
+    /// the stream it was written to. The standard output of the process is
+    /// left for the outputs of the run.
+    Stderr,
+    /// Sends the lines to an HTTP endpoint (e.g. a Fluentd HTTP input) as a
+    /// JSON array of records.
+    ///
+    /// Each record has `task`, `attempt`, `stream`, and `line` fields.
+    ///
+    /// Sending is best-effort: requests time out and a task's completion does
+    /// not wait indefinitely for its remaining lines to be sent.
+    Http {
+        /// The URL of the endpoint to send the records to.
+        url: Url,
+    },
+}
+
+impl LogSinkConfig {
+    /// Validates the log sink configuration.
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::File { path } => {
+                if path.as_os_str().is_empty() {
+                    bail!(
+                        "configuration value `task.log_sinks` has a file sink with an empty path"
+                    );
+                }
+            }
+            Self::Stderr => {}
+            Self::Http { url } => {
+                if !matches!(url.scheme(), "http" | "https") {
+                    bail!(
+                        "configuration value `task.log_sinks` has an HTTP sink with unsupported \
+                         URL `{url}`: only `http` and `https` URLs are supported"
+                    );
+                }
+            }
+        }
+
         Ok(())
     }
 }
 
+impl std::str::FromStr for LogSinkConfig {
+    type Err = anyhow::Error;
+
+    /// Parses a log sink from `stderr`, `file:<path>`, or an `http` or `https`
+    /// URL.
+    fn from_str(s: &str) -> Result<Self> {
+        if s == "stderr" {
+            return Ok(Self::Stderr);
+        }
+
+        if let Some(path) = s.strip_prefix("file:") {
+            return Ok(Self::File { path: path.into() });
+        }
+
+        if s.starts_with("http://") || s.starts_with("https://") {
+            return Ok(Self::Http {
+                url: s
+                    .parse()
+                    .with_context(|| format!("invalid log sink URL `{s}`"))?,
+            });
+        }
+
+        bail!("invalid log sink `{s}`: expected `stderr`, `file:<path>`, or an HTTP URL")
+    }
+}
+
 /// The behavior when a task resource requirement, such as `cpu` or `memory`,
 /// cannot be met.
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -799,6 +1201,8 @@ pub enum BackendConfig {
     ///
     /// Requires enabling experimental features.
     SlurmApptainer(Arc<SlurmApptainerBackendConfig>),
+    /// Use the stub task execution backend.
+    Stub(Arc<StubBackendConfig>),
 }
 
 impl Default for BackendConfig {
@@ -816,6 +1220,7 @@ impl BackendConfig {
             Self::Tes(config) => config.validate(),
             Self::LsfApptainer(config) => config.validate(engine_config).await,
             Self::SlurmApptainer(config) => config.validate(engine_config).await,
+            Self::Stub(config) => config.validate(),
         }
     }
 
@@ -852,7 +1257,11 @@ impl BackendConfig {
     /// Redacts the secrets contained in the backend configuration.
     pub fn redact(&mut self) {
         match self {
-            Self::Local(_) | Self::Docker(_) | Self::LsfApptainer(_) | Self::SlurmApptainer(_) => {}
+            Self::Local(_)
+            | Self::Docker(_)
+            | Self::LsfApptainer(_)
+            | Self::SlurmApptainer(_)
+            | Self::Stub(_) => {}
             Self::Tes(config) => config.redact(),
         }
     }
@@ -860,7 +1269,11 @@ impl BackendConfig {
     /// Unredacts the secrets contained in the backend configuration.
     pub fn unredact(&mut self) {
         match self {
-            Self::Local(_) | Self::Docker(_) | Self::LsfApptainer(_) | Self::SlurmApptainer(_) => {}
+            Self::Local(_)
+            | Self::Docker(_)
+            | Self::LsfApptainer(_)
+            | Self::SlurmApptainer(_)
+            | Self::Stub(_) => {}
             Self::Tes(config) => config.unredact(),
         }
     }
@@ -932,6 +1345,60 @@ impl LocalBackendConfig {
     }
 }
 
+/// Represents configuration for the stub task execution backend.
+///
+/// The stub backend does not execute task commands; instead, tasks produce
+/// outputs from the configured stubs. This is useful for quickly testing the
+/// control flow of a workflow.
+///
+/// For tasks without a configured stub, an empty script is executed in place
+/// of the task's command and the task's outputs are evaluated as normal.
+///
+/// The stub backend is used like any other backend: it is selected by name
+/// with the `backend` setting of the configuration.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct StubBackendConfig {
+    /// The task stubs, keyed by task name.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub tasks: IndexMap<String, TaskStubConfig>,
+}
+
+impl StubBackendConfig {
+    /// Validates the stub task execution backend configuration.
+    pub fn validate(&self) -> Result<()> {
+        for (task, stub) in &self.tasks {
+            if let Some((name, _)) = stub.outputs.iter().find(|(_, v)| v.is_null()) {
+                bail!(
+                    "stub backend configuration value `tasks.{task}.outputs.{name}` cannot be \
+                     null: omit the output to evaluate its expression instead"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Represents a stub for a task.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub struct TaskStubConfig {
+    /// The literal values of the task's outputs, keyed by output name.
+    ///
+    /// Outputs without a literal value are evaluated from the output's
+    /// expression.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub outputs: IndexMap<String, serde_json::Value>,
+
+    /// A script to execute in place of the task's command.
+    ///
+    /// The script may be used to generate any files referenced by the task's
+    /// outputs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+}
+
 /// Gets the default value for the docker `cleanup` field.
 const fn cleanup_default() -> bool {
     true
@@ -946,6 +1413,15 @@ pub struct DockerBackendConfig {
     /// Defaults to `true`.
     #[serde(default = "cleanup_default")]
     pub cleanup: bool,
+
+    /// The architecture of the hosts that execute tasks.
+    ///
+    /// If set, the registry of each task's container image is queried before
+    /// the task is scheduled to verify that the image is available for the
+    /// architecture, and the image for the architecture is pulled before the
+    /// task's container is created.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub architecture: Option<Architecture>,
 }
 
 impl DockerBackendConfig {
@@ -957,7 +1433,82 @@ impl DockerBackendConfig {
 
 impl Default for DockerBackendConfig {
     fn default() -> Self {
-        Self { cleanup: true }
+        Self {
+            cleanup: true,
+            architecture: None,
+        }
+    }
+}
+
+/// Represents the architecture of the hosts that execute tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Architecture {
+    /// The 64-bit x86 architecture.
+    Amd64,
+    /// The 64-bit ARM architecture.
+    Arm64,
+}
+
+impl Architecture {
+    /// Gets the name of the architecture as used by container registries.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Amd64 => "amd64",
+            Self::Arm64 => "arm64",
+        }
+    }
+
+    /// Gets the platform of the architecture as used by container registries
+    /// (e.g. `linux/arm64`).
+    pub fn platform(&self) -> String {
+        format!("linux/{arch}", arch = self.as_str())
+    }
+}
+
+/// Normalizes the name of an architecture to the name used by container
+/// registries.
+///
+/// Common aliases such as `x86_64` and `aarch64` are normalized to `amd64` and
+/// `arm64`, respectively.
+fn normalize_architecture(name: &str) -> &str {
+    match name {
+        "x86_64" | "x86-64" => "amd64",
+        "aarch64" => "arm64",
+        _ => name,
+    }
+}
+
+/// Determines if a platform reported by a container registry (e.g.
+/// `linux/arm64/v8`) matches the target platform.
+///
+/// The target may be an architecture (e.g. `arm64`), an operating system and
+/// architecture separated by `/` (e.g. `linux/arm64`), or an operating system,
+/// architecture, and variant (e.g. `linux/arm64/v8`). Architectures are
+/// compared after normalizing common aliases such as `x86_64` and `aarch64`.
+pub fn matches_platform(platform: &str, target: &str) -> bool {
+    let mut platform = platform.split('/');
+    let (os, arch, variant) = (platform.next(), platform.next(), platform.next());
+    let arch = arch.map(normalize_architecture);
+
+    let target = target.split('/').collect::<Vec<_>>();
+    match target.as_slice() {
+        [target_arch] => arch == Some(normalize_architecture(target_arch)),
+        [target_os, target_arch] => {
+            os == Some(*target_os) && arch == Some(normalize_architecture(target_arch))
+        }
+        [target_os, target_arch, target_variant] => {
+            os == Some(*target_os)
+                && arch == Some(normalize_architecture(target_arch))
+                && variant == Some(*target_variant)
+        }
+        _ => false,
+    }
+}
+
+impl fmt::Display for Architecture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
     }
 }
 
@@ -1264,6 +1815,42 @@ mod test {
         assert!(json.contains("secret"), "`{json}` contains a secret");
     }
 
+    #[test]
+    fn parse_log_sinks() {
+        assert_eq!(
+            "stderr".parse::<LogSinkConfig>().unwrap(),
+            LogSinkConfig::Stderr
+        );
+        assert_eq!(
+            "file:logs/tasks.log".parse::<LogSinkConfig>().unwrap(),
+            LogSinkConfig::File {
+                path: "logs/tasks.log".into()
+            }
+        );
+        assert_eq!(
+            "http://localhost:9880/sprocket"
+                .parse::<LogSinkConfig>()
+                .unwrap(),
+            LogSinkConfig::Http {
+                url: "http://localhost:9880/sprocket".parse().unwrap()
+            }
+        );
+        assert_eq!(
+            "stdout".parse::<LogSinkConfig>().unwrap_err().to_string(),
+            "invalid log sink `stdout`: expected `stderr`, `file:<path>`, or an HTTP URL"
+        );
+    }
+
+    #[test]
+    fn architecture_matches() {
+        assert!(Architecture::Amd64.matches("amd64"));
+        assert!(Architecture::Amd64.matches("x86_64"));
+        assert!(!Architecture::Amd64.matches("arm64"));
+        assert!(Architecture::Arm64.matches("arm64"));
+        assert!(Architecture::Arm64.matches("aarch64"));
+        assert!(!Architecture::Arm64.matches("arm"));
+    }
+
     #[tokio::test]
     async fn test_config_validate() {
         // Test invalid task config
@@ -1282,6 +1869,17 @@ mod test {
             "configuration value `workflow.scatter.concurrency` cannot be zero"
         );
 
+        // Test invalid log sink config
+        let mut config = Config::default();
+        config.task.log_sinks = vec![LogSinkConfig::Http {
+            url: "ftp://example.com/logs".parse().unwrap(),
+        }];
+        assert_eq!(
+            config.validate().await.unwrap_err().to_string(),
+            "configuration value `task.log_sinks` has an HTTP sink with unsupported URL \
+             `ftp://example.com/logs`: only `http` and `https` URLs are supported"
+        );
+
         // Test invalid backend name
         let config = Config {
             backend: Some("foo".into()),
@@ -1504,4 +2102,35 @@ mod test {
             "should pass for default (None)"
         );
     }
+
+    #[test]
+    fn platform_matching() {
+        assert!(matches_platform("linux/arm64", "arm64"));
+        assert!(matches_platform("linux/arm64/v8", "arm64"));
+        assert!(matches_platform("linux/arm64/v8", "linux/arm64"));
+        assert!(matches_platform("linux/arm64/v8", "linux/arm64/v8"));
+        assert!(matches_platform("linux/amd64", "linux/amd64"));
+        assert!(matches_platform("linux/x86_64", "amd64"));
+        assert!(matches_platform("linux/arm64", "linux/aarch64"));
+        assert!(!matches_platform("linux/amd64", "arm64"));
+        assert!(!matches_platform("windows/arm64", "linux/arm64"));
+        assert!(!matches_platform("linux/arm64/v7", "linux/arm64/v8"));
+        assert!(!matches_platform("linux", "linux"));
+        assert_eq!(Architecture::Arm64.platform(), "linux/arm64");
+    }
+
+    #[test]
+    fn out_of_memory_detection() {
+        let policy = MemoryRetryConfig::default();
+
+        // Only a positive signal from the backend classifies a killed task
+        assert!(policy.is_out_of_memory(Some(true), ""));
+        assert!(!policy.is_out_of_memory(Some(false), "Killed"));
+        assert!(!policy.is_out_of_memory(None, "line 3: 42 Killed"));
+        assert!(!policy.is_out_of_memory(None, ""));
+
+        // The error patterns are always checked
+        assert!(policy.is_out_of_memory(Some(false), "java.lang.OutOfMemoryError"));
+        assert!(policy.is_out_of_memory(None, "std::bad_alloc"));
+    }
 }
@@ -144,6 +144,31 @@ pub fn function_call_failed(name: &str, error: impl fmt::Display, span: Span) ->
     Diagnostic::error(format!("call to function `{name}` failed: {error}")).with_highlight(span)
 }
 
+/// Creates a "sandbox denied function" diagnostic.
+pub fn sandbox_function_denied(name: &str, span: Span) -> Diagnostic {
+    Diagnostic::error(format!(
+        "function `{name}` cannot be called because the evaluation sandbox does not permit file \
+         system access"
+    ))
+    .with_highlight(span)
+}
+
+/// Creates a "sandbox step limit exceeded" diagnostic.
+pub fn sandbox_step_limit_exceeded(limit: u64, span: Span) -> Diagnostic {
+    Diagnostic::error(format!(
+        "expression evaluation exceeded the sandbox limit of {limit} steps"
+    ))
+    .with_label("evaluation stopped at this expression", span)
+}
+
+/// Creates a "sandbox memory limit exceeded" diagnostic.
+pub fn sandbox_memory_limit_exceeded(limit: u64, span: Span) -> Diagnostic {
+    Diagnostic::error(format!(
+        "expression evaluation exceeded the sandbox memory limit of {limit} bytes"
+    ))
+    .with_label("this expression exceeds the memory limit", span)
+}
+
 /// Creates a "input/output/declaration evaluation failed" diagnostic.
 pub fn decl_evaluation_failed(
     e: anyhow::Error,
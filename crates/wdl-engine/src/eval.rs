@@ -7,14 +7,17 @@ use std::fs;
 use std::io::BufRead;
 use std::path::Path;
 use std::sync::Arc;
+use std::sync::Mutex;
 use std::sync::atomic::AtomicU8;
 use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::bail;
 use cloud_copy::TransferEvent;
 use crankshaft::events::Event as CrankshaftEvent;
+use futures::future::BoxFuture;
 use indexmap::IndexMap;
 use itertools::Itertools;
 use num_enum::IntoPrimitive;
@@ -22,16 +25,19 @@ use rev_buf_reader::RevBufReader;
 use tokio::sync::broadcast;
 use tokio_util::sync::CancellationToken;
 use tracing::error;
+use url::Url;
 use wdl_analysis::Document;
 use wdl_analysis::document::Task;
 use wdl_analysis::types::Type;
 use wdl_ast::Diagnostic;
 use wdl_ast::Span;
 use wdl_ast::SupportedVersion;
+use wdl_ast::v1::TASK_REQUIREMENT_CONTINUE_ON_RETURN_CODE;
 use wdl_ast::v1::TASK_REQUIREMENT_RETURN_CODES;
 use wdl_ast::v1::TASK_REQUIREMENT_RETURN_CODES_ALIAS;
 
 use crate::CompoundValue;
+use crate::Inputs;
 use crate::Outputs;
 use crate::PrimitiveValue;
 use crate::Value;
@@ -266,6 +272,71 @@ impl Default for CancellationContext {
     }
 }
 
+/// Represents the kind of a document element that was evaluated.
+///
+/// This is used for reporting evaluation coverage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CoverageKind {
+    /// A task was evaluated.
+    Task,
+    /// A call statement was evaluated.
+    Call,
+    /// A scatter statement was evaluated.
+    Scatter {
+        /// Whether or not the scatter's body was evaluated.
+        ///
+        /// This is `false` when the scatter expression evaluated to an empty
+        /// array.
+        body: bool,
+    },
+    /// A clause of a conditional statement was taken.
+    ConditionalClause,
+}
+
+/// Records the elements of documents that were evaluated.
+///
+/// The registry is shared by every evaluation of an execution so that the
+/// coverage of a workflow includes the elements of nested workflows and
+/// tasks.
+#[derive(Debug, Default)]
+pub struct CoverageRegistry(Mutex<HashMap<Arc<Url>, HashMap<usize, usize>>>);
+
+impl CoverageRegistry {
+    /// Records the evaluation of a document element.
+    ///
+    /// For tasks, the span is the span of the task's name.
+    pub(crate) fn record(&self, document: &Arc<Url>, span: Span, kind: CoverageKind) {
+        let hits = match kind {
+            CoverageKind::Scatter { body: false } => 0,
+            _ => 1,
+        };
+
+        *self
+            .0
+            .lock()
+            .expect("failed to lock coverage registry")
+            .entry(document.clone())
+            .or_default()
+            .entry(span.start())
+            .or_default() += hits;
+    }
+
+    /// Gets the number of times the element of the given document starting at
+    /// the given offset was evaluated.
+    ///
+    /// A scatter statement whose expression evaluated to an empty array is
+    /// not counted as evaluated.
+    pub fn hits(&self, document: &Url, start: usize) -> usize {
+        self.0
+            .lock()
+            .expect("failed to lock coverage registry")
+            .get(document)
+            .and_then(|hits| hits.get(&start))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
 /// Represents an event from the WDL evaluation engine.
 #[derive(Debug, Clone)]
 pub enum EngineEvent {
@@ -274,6 +345,13 @@ pub enum EngineEvent {
         /// The id of the task that reused a cached execution result.
         id: String,
     },
+    /// A task was killed for exceeding its maximum execution time.
+    TaskTimedOut {
+        /// The id of the task that timed out.
+        id: String,
+        /// The maximum execution time of the task.
+        max_time: Duration,
+    },
 }
 
 /// Represents events that may be sent during WDL evaluation.
@@ -345,6 +423,47 @@ impl Events {
     }
 }
 
+/// Represents a workflow call that is about to be evaluated.
+///
+/// This is passed to a [`CallInterceptor`] prior to the evaluation of each
+/// call in a workflow.
+#[derive(Debug, Clone, Copy)]
+pub struct InterceptedCall<'a> {
+    /// The identifier of the call (e.g. `foo-0`).
+    pub id: &'a str,
+    /// The name of the call (i.e. the call alias or target name).
+    pub name: &'a str,
+    /// The name of the call's target task or workflow.
+    pub target: &'a str,
+    /// The evaluated inputs to the call.
+    pub inputs: &'a Inputs,
+    /// The call's type, describing the outputs of the call.
+    pub ty: &'a wdl_analysis::types::CallType,
+}
+
+/// Represents the action to take for an intercepted call.
+#[derive(Debug)]
+pub enum CallAction {
+    /// Continue with evaluating the call.
+    Continue,
+    /// Skip evaluating the call and use the given outputs instead.
+    ///
+    /// The outputs are coerced to the declared output types of the call; an
+    /// output may only be omitted if its type is optional.
+    Skip(Outputs),
+}
+
+/// A trait implemented by types that intercept workflow calls prior to their
+/// evaluation.
+///
+/// Interceptors may be used to implement debuggers or to stub out calls.
+pub trait CallInterceptor: Send + Sync {
+    /// Intercepts a call before it is evaluated.
+    ///
+    /// Returning an error aborts the evaluation of the workflow.
+    fn intercept<'a>(&'a self, call: InterceptedCall<'a>) -> BoxFuture<'a, Result<CallAction>>;
+}
+
 /// Represents the location of a call in an evaluation error.
 #[derive(Debug, Clone)]
 pub struct CallLocation {
@@ -874,11 +993,17 @@ impl EvaluatedTask {
         if let Some(return_codes) = requirements
             .get(TASK_REQUIREMENT_RETURN_CODES)
             .or_else(|| requirements.get(TASK_REQUIREMENT_RETURN_CODES_ALIAS))
+            .or_else(|| requirements.get(TASK_REQUIREMENT_CONTINUE_ON_RETURN_CODE))
         {
             match return_codes {
                 Value::Primitive(PrimitiveValue::String(s)) if s.as_ref() == "*" => {
                     error = false;
                 }
+                // A `Boolean` value is only accepted by the legacy `continueOnReturnCode`
+                // attribute, where `true` accepts any return code
+                Value::Primitive(PrimitiveValue::Boolean(continue_on)) => {
+                    error = !continue_on && self.result.exit_code != 0;
+                }
                 Value::Primitive(PrimitiveValue::String(s)) => {
                     bail!(
                         "invalid return code value `{s}`: only `*` is accepted when the return \
@@ -17,8 +17,15 @@ use serde::Serialize;
 pub use task::*;
 use tokio::sync::broadcast;
 use tracing::info;
+use tracing::warn;
+use wdl_analysis::Document;
+use wdl_ast::Span;
 
+use super::CallInterceptor;
 use super::CancellationContext;
+use super::CoverageKind;
+use super::CoverageRegistry;
+use super::EvaluationError;
 use super::Events;
 use crate::EngineEvent;
 use crate::TaskExecutionBackend;
@@ -27,6 +34,9 @@ use crate::config::CallCachingMode;
 use crate::config::Config;
 use crate::http::HttpTransferer;
 use crate::http::Transferer;
+use crate::logs::LogSinks;
+use crate::provenance::ProvenanceRegistry;
+use crate::sizes::SizesRegistry;
 
 /// The name of the inputs file to write for each task and workflow in the
 /// outputs directory.
@@ -36,6 +46,18 @@ const INPUTS_FILE: &str = "inputs.json";
 /// outputs directory.
 const OUTPUTS_FILE: &str = "outputs.json";
 
+/// The name of the file to write the outputs that were salvaged from a failed
+/// task to in the task's outputs directory.
+const SALVAGED_OUTPUTS_FILE: &str = "outputs.salvaged.json";
+
+/// The name of the file to record the memory escalations of a task that ran
+/// out of memory to in the task's outputs directory.
+const MEMORY_ESCALATIONS_FILE: &str = "memory_escalations.json";
+
+/// The name of the file to write for each task and workflow that failed to
+/// evaluate in the outputs directory.
+const FAILURE_FILE: &str = "failure.json";
+
 /// Serializes a value into a JSON file.
 fn write_json_file(path: impl AsRef<Path>, value: &impl Serialize) -> Result<()> {
     let path = path.as_ref();
@@ -45,6 +67,44 @@ fn write_json_file(path: impl AsRef<Path>, value: &impl Serialize) -> Result<()>
         .with_context(|| format!("failed to write file `{path}`", path = path.display()))
 }
 
+/// Represents the contents of the failure file of a task or workflow.
+#[derive(Serialize)]
+struct Failure<'a> {
+    /// Whether or not the evaluation was canceled.
+    canceled: bool,
+    /// The error that caused the evaluation to fail.
+    error: &'a str,
+}
+
+/// Writes the failure file of a task or workflow that failed to evaluate.
+///
+/// The file marks the evaluation as having terminated, as there are no outputs
+/// to write. A failure to write the file is logged so that the evaluation's
+/// error is still the one reported.
+fn write_failure_file(root: &Path, error: &EvaluationError) {
+    let message = match error {
+        EvaluationError::Canceled => "evaluation was canceled".to_string(),
+        EvaluationError::Source(e) => e.diagnostic.message().to_string(),
+        EvaluationError::Other(e) => format!("{e:#}"),
+    };
+
+    let path = root.join(FAILURE_FILE);
+    let result = std::fs::create_dir_all(root)
+        .with_context(|| format!("failed to create directory `{root}`", root = root.display()))
+        .and_then(|_| {
+            write_json_file(
+                &path,
+                &Failure {
+                    canceled: matches!(error, EvaluationError::Canceled),
+                    error: &message,
+                },
+            )
+        });
+    if let Err(e) = result {
+        warn!("failed to write the failure of an evaluation: {e:#}");
+    }
+}
+
 /// The top-level evaluation context.
 ///
 /// "Top-level" here means the outermost invocation of a task or workflow across
@@ -71,6 +131,16 @@ pub struct TopLevelEvaluator {
     cache: Option<CallCache>,
     /// The events for evaluation.
     events: Option<broadcast::Sender<EngineEvent>>,
+    /// The interceptor to invoke prior to evaluating workflow calls.
+    interceptor: Option<Arc<dyn CallInterceptor>>,
+    /// The registry to record the evaluated elements of documents into.
+    coverage: Option<Arc<CoverageRegistry>>,
+    /// The sinks to forward task logs to.
+    log_sinks: Option<Arc<LogSinks>>,
+    /// The registry of how the output files of task executions were produced.
+    provenance: Arc<ProvenanceRegistry>,
+    /// The registry of the sizes of the inputs and outputs of task executions.
+    sizes: Arc<SizesRegistry>,
 }
 
 impl TopLevelEvaluator {
@@ -105,6 +175,20 @@ impl TopLevelEvaluator {
             _ => Some(CallCache::new(config.task.cache_dir.as_deref(), transferer.clone()).await?),
         };
 
+        // The logs of tasks executed remotely are not available to forward
+        let log_sinks = if backend.writes_local_logs() {
+            LogSinks::new(&config.task.log_sinks)?.map(Arc::new)
+        } else {
+            if !config.task.log_sinks.is_empty() {
+                warn!(
+                    "task logs are not forwarded to the configured log sinks as the backend does \
+                     not write task logs locally"
+                );
+            }
+
+            None
+        };
+
         Ok(Self {
             root_dir: root_dir.to_path_buf(),
             config,
@@ -113,6 +197,32 @@ impl TopLevelEvaluator {
             transferer,
             cache,
             events: events.engine().clone(),
+            interceptor: None,
+            coverage: None,
+            log_sinks,
+            provenance: Default::default(),
+            sizes: Default::default(),
         })
     }
+
+    /// Records that an element of a document was evaluated.
+    fn record_coverage(&self, document: &Document, span: Span, kind: CoverageKind) {
+        if let Some(coverage) = &self.coverage {
+            coverage.record(document.uri(), span, kind);
+        }
+    }
+
+    /// Sets the interceptor to invoke prior to evaluating each workflow call.
+    pub fn with_call_interceptor(mut self, interceptor: Arc<dyn CallInterceptor>) -> Self {
+        self.interceptor = Some(interceptor);
+        self
+    }
+
+    /// Sets the registry to record the evaluated elements of documents into.
+    ///
+    /// Coverage is not recorded unless a registry is set.
+    pub fn with_coverage(mut self, coverage: Arc<CoverageRegistry>) -> Self {
+        self.coverage = Some(coverage);
+        self
+    }
 }
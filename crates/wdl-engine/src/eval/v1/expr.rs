@@ -98,6 +98,7 @@ use crate::Map;
 use crate::Object;
 use crate::Pair;
 use crate::PrimitiveValue;
+use crate::SandboxPolicy;
 use crate::Struct;
 use crate::Value;
 use crate::diagnostics::array_index_out_of_range;
@@ -112,6 +113,11 @@ use crate::diagnostics::multiline_string_requirement;
 use crate::diagnostics::not_an_object_member;
 use crate::diagnostics::numeric_overflow;
 use crate::diagnostics::runtime_type_mismatch;
+use crate::diagnostics::sandbox_function_denied;
+use crate::diagnostics::sandbox_memory_limit_exceeded;
+use crate::diagnostics::sandbox_step_limit_exceeded;
+use crate::sandbox::estimated_call_size;
+use crate::sandbox::estimated_size;
 use crate::stdlib::CallArgument;
 use crate::stdlib::CallContext;
 use crate::stdlib::STDLIB;
@@ -134,6 +140,12 @@ pub struct ExprEvaluator<C> {
     /// Tracks whether or not a `None`-resulting expression was evaluated during
     /// a placeholder evaluation.
     evaluated_none: bool,
+    /// The sandbox policy that restricts evaluation.
+    sandbox: SandboxPolicy,
+    /// The number of expressions evaluated.
+    ///
+    /// This is used to enforce the sandbox's step limit.
+    steps: u64,
 }
 
 impl<C: EvaluationContext> ExprEvaluator<C> {
@@ -143,9 +155,19 @@ impl<C: EvaluationContext> ExprEvaluator<C> {
             context,
             placeholders: 0,
             evaluated_none: false,
+            sandbox: SandboxPolicy::default(),
+            steps: 0,
         }
     }
 
+    /// Sets the sandbox policy that restricts evaluation.
+    ///
+    /// By default, evaluation is unrestricted.
+    pub fn with_sandbox(mut self, sandbox: SandboxPolicy) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
     /// Gets the context associated with the evaluator.
     pub fn context(&self) -> &C {
         &self.context
@@ -157,6 +179,13 @@ impl<C: EvaluationContext> ExprEvaluator<C> {
         expr: &'a Expr<SyntaxNode>,
     ) -> BoxFuture<'a, Result<Value, Diagnostic>> {
         async move {
+            self.steps += 1;
+            if let Some(limit) = self.sandbox.max_steps()
+                && self.steps > limit
+            {
+                return Err(sandbox_step_limit_exceeded(limit, expr.span()));
+            }
+
             let value = match expr {
                 Expr::Literal(expr) => self.evaluate_literal_expr(expr).await,
                 Expr::NameRef(r) => {
@@ -277,6 +306,12 @@ impl<C: EvaluationContext> ExprEvaluator<C> {
                 Expr::Access(expr) => self.evaluate_access_expr(expr).await,
             }?;
 
+            if let Some(limit) = self.sandbox.max_memory()
+                && estimated_size(&value) > limit
+            {
+                return Err(sandbox_memory_limit_exceeded(limit, expr.span()));
+            }
+
             self.evaluated_none |= self.placeholders > 0 && value.is_none();
             Ok(value)
         }
@@ -1304,6 +1339,9 @@ impl<C: EvaluationContext> ExprEvaluator<C> {
     ) -> Result<Value, Diagnostic> {
         let target = expr.target();
         match wdl_analysis::stdlib::STDLIB.function(target.text()) {
+            Some(_) if !self.sandbox.permits_function(target.text()) => {
+                Err(sandbox_function_denied(target.text(), target.span()))
+            }
             Some(f) => {
                 // Evaluate the argument expressions
                 let mut count = 0;
@@ -1323,6 +1361,12 @@ impl<C: EvaluationContext> ExprEvaluator<C> {
                 let types = &types[..count.min(MAX_PARAMETERS)];
                 let arguments = &arguments[..count.min(MAX_PARAMETERS)];
                 if count <= MAX_PARAMETERS {
+                    if let Some(limit) = self.sandbox.max_memory()
+                        && estimated_call_size(target.text(), arguments) > limit
+                    {
+                        return Err(sandbox_memory_limit_exceeded(limit, expr.span()));
+                    }
+
                     match f.bind(self.context.version(), types) {
                         Ok(binding) => {
                             let context = CallContext::new(
@@ -1724,9 +1768,31 @@ pub(crate) mod test {
         .await
     }
 
+    pub async fn eval_v1_expr_with_sandbox(
+        env: &TestEnv,
+        version: V1,
+        source: &str,
+        sandbox: SandboxPolicy,
+    ) -> Result<Value, Diagnostic> {
+        eval_v1_expr_with_context_and_sandbox(
+            TestEvaluationContext::new(env, SupportedVersion::V1(version)),
+            source,
+            sandbox,
+        )
+        .await
+    }
+
     async fn eval_v1_expr_with_context(
         context: TestEvaluationContext<'_>,
         source: &str,
+    ) -> Result<Value, Diagnostic> {
+        eval_v1_expr_with_context_and_sandbox(context, source, SandboxPolicy::default()).await
+    }
+
+    async fn eval_v1_expr_with_context_and_sandbox(
+        context: TestEvaluationContext<'_>,
+        source: &str,
+        sandbox: SandboxPolicy,
     ) -> Result<Value, Diagnostic> {
         let source = source.trim();
         let mut parser = v1::Parser::new(Lexer::new(source));
@@ -1747,7 +1813,7 @@ pub(crate) mod test {
                 let expr = Expr::cast(SyntaxNode::new_root(construct_tree(source, output.events)))
                     .expect("should be an expression");
 
-                let mut evaluator = ExprEvaluator::new(context);
+                let mut evaluator = ExprEvaluator::new(context).with_sandbox(sandbox);
                 evaluator.evaluate_expr(&expr).await
             }
             Err((marker, diagnostic)) => {
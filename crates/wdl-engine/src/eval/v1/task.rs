@@ -6,17 +6,22 @@ use std::collections::HashMap;
 use std::fs;
 use std::mem;
 use std::path::Path;
+use std::path::PathBuf;
 use std::path::absolute;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
 use anyhow::bail;
 use bimap::BiHashMap;
+use bytesize::ByteSize;
 use indexmap::IndexMap;
 use petgraph::algo::toposort;
+use serde::Serialize;
+use tokio::sync::oneshot;
 use tokio::task::JoinSet;
 use tracing::Level;
 use tracing::debug;
@@ -52,10 +57,14 @@ use wdl_ast::v1::StrippedCommandPart;
 use wdl_ast::v1::TASK_HINT_CACHEABLE;
 use wdl_ast::v1::TASK_HINT_DISKS;
 use wdl_ast::v1::TASK_HINT_GPU;
+use wdl_ast::v1::TASK_HINT_LOG_HEAD;
+use wdl_ast::v1::TASK_HINT_LOG_TAIL;
 use wdl_ast::v1::TASK_HINT_MAX_CPU;
 use wdl_ast::v1::TASK_HINT_MAX_CPU_ALIAS;
 use wdl_ast::v1::TASK_HINT_MAX_MEMORY;
 use wdl_ast::v1::TASK_HINT_MAX_MEMORY_ALIAS;
+use wdl_ast::v1::TASK_HINT_MAX_TIME;
+use wdl_ast::v1::TASK_HINT_TIME_MINUTES;
 use wdl_ast::v1::TASK_REQUIREMENT_CONTAINER;
 use wdl_ast::v1::TASK_REQUIREMENT_CONTAINER_ALIAS;
 use wdl_ast::v1::TASK_REQUIREMENT_CPU;
@@ -69,9 +78,11 @@ use wdl_ast::v1::TaskHintsSection;
 use wdl_ast::version::V1;
 
 use super::TopLevelEvaluator;
+use crate::CallSizes;
 use crate::CancellationContextState;
 use crate::Coercible;
 use crate::ContentKind;
+use crate::CoverageKind;
 use crate::EngineEvent;
 use crate::EvaluationContext;
 use crate::EvaluationError;
@@ -83,6 +94,9 @@ use crate::Input;
 use crate::ONE_GIBIBYTE;
 use crate::Object;
 use crate::Outputs;
+use crate::PROVENANCE_FILE;
+use crate::Provenance;
+use crate::SIZES_FILE;
 use crate::Scope;
 use crate::ScopeIndex;
 use crate::ScopeRef;
@@ -98,6 +112,7 @@ use crate::cache::KeyRequest;
 use crate::config::CallCachingMode;
 use crate::config::Config;
 use crate::config::DEFAULT_TASK_SHELL;
+use crate::config::LogLimitConfig;
 use crate::config::MAX_RETRIES;
 use crate::convert_unit_string;
 use crate::diagnostics::decl_evaluation_failed;
@@ -107,13 +122,20 @@ use crate::diagnostics::task_localization_failed;
 use crate::eval::EvaluatedTask;
 use crate::eval::trie::InputTrie;
 use crate::http::Transferer;
+use crate::inputs::check_hermetic_value;
+use crate::logs::LogLimit;
 use crate::path::EvaluationPath;
 use crate::path::is_file_url;
 use crate::path::is_supported_url;
+use crate::sizes::inputs_size;
+use crate::sizes::outputs_size;
 use crate::tree::SyntaxNode;
 use crate::v1::INPUTS_FILE;
+use crate::v1::MEMORY_ESCALATIONS_FILE;
 use crate::v1::OUTPUTS_FILE;
+use crate::v1::SALVAGED_OUTPUTS_FILE;
 use crate::v1::expr::ExprEvaluator;
+use crate::v1::write_failure_file;
 use crate::v1::write_json_file;
 
 /// The default container requirement.
@@ -249,6 +271,119 @@ pub(crate) fn max_memory(hints: &HashMap<String, Value>) -> Result<Option<i64>>
         .transpose()
 }
 
+/// Parses a duration string for the `max_time` hint.
+///
+/// The string is a positive number of seconds, optionally followed by a unit
+/// suffix of `s` (seconds), `m` (minutes), `h` (hours), or `d` (days).
+fn parse_max_time(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let (number, multiplier) = match s.char_indices().last()? {
+        (i, 's') => (&s[..i], 1),
+        (i, 'm') => (&s[..i], 60),
+        (i, 'h') => (&s[..i], 60 * 60),
+        (i, 'd') => (&s[..i], 24 * 60 * 60),
+        _ => (s, 1),
+    };
+
+    let number: f64 = number.trim().parse().ok()?;
+    if !number.is_finite() || number <= 0.0 {
+        return None;
+    }
+
+    Duration::try_from_secs_f64(number * multiplier as f64).ok()
+}
+
+/// Gets the maximum execution time of a task from a hints map.
+///
+/// The `max_time` hint is either an integer number of seconds or a string with
+/// a unit suffix; the `time_minutes` hint is an integer number of minutes.
+///
+/// If both hints are present, `max_time` takes precedence.
+pub(crate) fn max_time(hints: &HashMap<String, Value>) -> Result<Option<Duration>> {
+    if let Some(v) = hints.get(TASK_HINT_MAX_TIME) {
+        if let Some(v) = v.as_integer() {
+            if v <= 0 {
+                bail!("task specifies an invalid `max_time` hint `{v}`");
+            }
+
+            return Ok(Some(Duration::from_secs(v as u64)));
+        }
+
+        if let Some(s) = v.as_string() {
+            return parse_max_time(s)
+                .map(Some)
+                .with_context(|| format!("task specifies an invalid `max_time` hint `{s}`"));
+        }
+
+        // Unknown runtime attributes are not type checked, so the value may be of any
+        // type
+        bail!("task specifies an invalid `max_time` hint `{v}`");
+    }
+
+    hints
+        .get(TASK_HINT_TIME_MINUTES)
+        .map(|v| match v.as_integer() {
+            Some(minutes) if minutes > 0 => Ok(Duration::from_secs(minutes as u64 * 60)),
+            _ => bail!("task specifies an invalid `time_minutes` hint `{v}`"),
+        })
+        .transpose()
+}
+
+/// Gets the number of bytes of a log to retain from a `log_head` or `log_tail`
+/// hint.
+fn log_limit_hint(hints: &HashMap<String, Value>, name: &str) -> Result<Option<u64>> {
+    hints
+        .get(name)
+        .map(|v| {
+            if let Some(n) = v.as_integer() {
+                return u64::try_from(n)
+                    .with_context(|| format!("task specifies an invalid `{name}` hint `{v}`"));
+            }
+
+            if let Some(s) = v.as_string() {
+                return convert_unit_string(s)
+                    .with_context(|| format!("task specifies an invalid `{name}` hint `{s}`"));
+            }
+
+            // Unknown runtime attributes are not type checked, so the value may be of any
+            // type
+            bail!("task specifies an invalid `{name}` hint `{v}`");
+        })
+        .transpose()
+}
+
+/// Gets the limits on the size of a task's logs from the configuration and a
+/// hints map.
+///
+/// The `log_head` and `log_tail` hints override the configured limits; a task
+/// that specifies either hint has its logs limited even if no limits are
+/// configured.
+///
+/// Returns `Ok(None)` if the task's logs are not limited.
+pub(crate) fn log_limit(
+    config: Option<&LogLimitConfig>,
+    hints: &HashMap<String, Value>,
+) -> Result<Option<LogLimit>> {
+    let head = log_limit_hint(hints, TASK_HINT_LOG_HEAD)?;
+    let tail = log_limit_hint(hints, TASK_HINT_LOG_TAIL)?;
+    if config.is_none() && head.is_none() && tail.is_none() {
+        return Ok(None);
+    }
+
+    let default = LogLimitConfig::default();
+    let config = config.unwrap_or(&default);
+    Ok(Some(LogLimit {
+        head: match head {
+            Some(head) => head,
+            None => config.head()?,
+        },
+        tail: match tail {
+            Some(tail) => tail,
+            None => config.tail()?,
+        },
+    }))
+}
+
 /// Gets the number of required GPUs from requirements and hints.
 pub(crate) fn gpu(
     requirements: &HashMap<String, Value>,
@@ -895,6 +1030,32 @@ struct EvaluatedSections {
     hints: Arc<HashMap<String, Value>>,
 }
 
+/// Represents an increase to a task's memory after it ran out of memory.
+#[derive(Debug, Serialize)]
+struct MemoryEscalation {
+    /// The attempt that ran out of memory.
+    attempt: u64,
+    /// The memory of the attempt that ran out of memory, in bytes.
+    from: i64,
+    /// The memory to retry the task with, in bytes.
+    to: i64,
+}
+
+/// Represents the outputs salvaged from a failed task.
+///
+/// This is written to the task's root directory in place of the outputs file.
+#[derive(Serialize)]
+struct SalvagedOutputs<'a> {
+    /// Marks the outputs as having been salvaged from a failed task.
+    salvaged: bool,
+    /// The exit code of the failed task.
+    exit_code: i32,
+    /// The error that caused the task to fail.
+    error: String,
+    /// The outputs of the task that were successfully evaluated.
+    outputs: &'a Outputs,
+}
+
 impl TopLevelEvaluator {
     /// Evaluates the given task.
     ///
@@ -911,6 +1072,10 @@ impl TopLevelEvaluator {
             return Err(anyhow!("cannot evaluate a document with errors").into());
         }
 
+        if self.config.hermetic.enabled {
+            inputs.check_hermetic(&self.config.hermetic)?;
+        }
+
         let result = self
             .perform_task_evaluation(document, task, inputs, task_eval_root.as_ref(), task.name())
             .await;
@@ -924,6 +1089,9 @@ impl TopLevelEvaluator {
 
     /// Performs the evaluation of the given task.
     ///
+    /// If the evaluation fails, a failure file is written to the task's root
+    /// directory in place of its outputs.
+    ///
     /// This method skips checking the document (and its transitive imports) for
     /// analysis errors as the check occurs at the `evaluate` entrypoint.
     pub(crate) async fn perform_task_evaluation(
@@ -933,6 +1101,25 @@ impl TopLevelEvaluator {
         inputs: &TaskInputs,
         task_eval_root: &Path,
         id: &str,
+    ) -> EvaluationResult<EvaluatedTask> {
+        let result = self
+            .evaluate_task_in_root(document, task, inputs, task_eval_root, id)
+            .await;
+        if let Err(e) = &result {
+            write_failure_file(task_eval_root, e);
+        }
+
+        result
+    }
+
+    /// Evaluates the given task with the given root directory.
+    async fn evaluate_task_in_root(
+        &self,
+        document: &Document,
+        task: &Task,
+        inputs: &TaskInputs,
+        task_eval_root: &Path,
+        id: &str,
     ) -> EvaluationResult<EvaluatedTask> {
         inputs.validate(document, task, None).with_context(|| {
             format!(
@@ -1030,7 +1217,10 @@ impl TopLevelEvaluator {
         // Spawn the task in a retry loop
         let mut attempt = 0;
         let mut previous_task_data: Option<Arc<TaskPostEvaluationData>> = None;
-        let mut evaluated = loop {
+        let mut memory_override = None;
+        let mut escalations = Vec::new();
+        let mut localized_size = None;
+        let (mut evaluated, provenance, localized) = loop {
             if self.cancellation.state() != CancellationContextState::NotCanceled {
                 return Err(EvaluationError::Canceled);
             }
@@ -1040,7 +1230,14 @@ impl TopLevelEvaluator {
                 requirements,
                 hints,
             } = state
-                .evaluate_sections(id, &definition, inputs, attempt, previous_task_data.clone())
+                .evaluate_sections(
+                    id,
+                    &definition,
+                    inputs,
+                    attempt,
+                    previous_task_data.clone(),
+                    memory_override,
+                )
                 .await?;
 
             // Get the maximum number of retries, either from the task's requirements or
@@ -1054,8 +1251,43 @@ impl TopLevelEvaluator {
                 .into());
             }
 
+            // If the backend stubs task execution, spawn the stub's script in place of the
+            // task's command
+            let command = match self.backend.task_stub(task.name()) {
+                Some(stub) => stub.script.clone().unwrap_or_default(),
+                None => command,
+            };
+
             let backend_inputs = state.localize_inputs(id).await?;
 
+            // The size of the localized inputs is only calculated for the first attempt as
+            // every attempt is given the same inputs
+            let size = match localized_size {
+                Some(size) => size,
+                None => {
+                    let size = inputs_size(&backend_inputs).await;
+                    localized_size = Some(size);
+
+                    // Warn once per call if the localized inputs exceed the configured threshold
+                    if let Some(threshold) = self.config.task.input_size_warning()?
+                        && size > threshold
+                    {
+                        warn!(
+                            task_id = id,
+                            task_name = state.task.name(),
+                            document = state.document.uri().as_str(),
+                            "the inputs of task `{name}` (task id `{id}`) total {size}, which \
+                             exceeds the configured threshold of {threshold}",
+                            name = task.name(),
+                            size = ByteSize::b(size),
+                            threshold = ByteSize::b(threshold)
+                        );
+                    }
+
+                    size
+                }
+            };
+
             // Calculate the cache key on the first attempt only
             let mut key = if attempt == 0
                 && let Some(cache) = &self.cache
@@ -1076,6 +1308,10 @@ impl TopLevelEvaluator {
                             .as_deref()
                             .unwrap_or(DEFAULT_TASK_SHELL),
                         backend_inputs: &backend_inputs,
+                        stub_outputs: self
+                            .backend
+                            .task_stub(task.name())
+                            .map(|stub| &stub.outputs),
                     };
 
                     match cache.key(request).await {
@@ -1180,12 +1416,30 @@ impl TopLevelEvaluator {
                 None
             };
 
+            let mut attempt_dir = task_eval_root.clone();
+            attempt_dir.push("attempts");
+            attempt_dir.push(attempt.to_string());
+
+            // Record how the outputs are produced before the command is spawned
+            let provenance = Provenance::new(
+                id,
+                task.name(),
+                attempt,
+                cached,
+                self.backend.guest_inputs_dir().map(|_| {
+                    container(&requirements, self.config.task.container.as_deref()).into_owned()
+                }),
+                &command,
+                if cached {
+                    &task_eval_root
+                } else {
+                    &attempt_dir
+                },
+            );
+
             let result = match result {
                 Some(result) => result,
                 None => {
-                    let mut attempt_dir = task_eval_root.clone();
-                    attempt_dir.push("attempts");
-                    attempt_dir.push(attempt.to_string());
                     let request = TaskSpawnRequest::new(
                         id.to_string(),
                         TaskSpawnInfo::new(
@@ -1202,26 +1456,154 @@ impl TopLevelEvaluator {
                         temp_dir.clone(),
                     );
 
-                    self.backend
-                        .spawn(request, self.cancellation.token())
-                        .with_context(|| {
-                            format!(
-                                "failed to spawn task `{name}` in `{path}` (task id `{id}`)",
-                                name = task.name(),
-                                path = document.path(),
+                    // The timer of the task's maximum execution time starts once the backend
+                    // notifies that the task is no longer waiting for resources
+                    let (started_tx, started_rx) = oneshot::channel();
+                    let request = request.with_started(started_tx);
+
+                    let forwarding = self
+                        .log_sinks
+                        .as_ref()
+                        .map(|s| s.forward(id, attempt, &attempt_dir));
+
+                    // The engine enforces the task's maximum execution time by canceling
+                    // only this task's execution when the time elapses
+                    let timeout = if self.backend.enforces_max_time() {
+                        max_time(&hints).map_err(|e| {
+                            EvaluationError::new(
+                                state.document.clone(),
+                                task_execution_failed(e, task.name(), id, task.name_span()),
                             )
                         })?
-                        .await
-                        .expect("failed to receive response from spawned task")
+                    } else {
+                        None
+                    };
+
+                    // Backends that limit the logs as the task executes are given the
+                    // limits; otherwise the logs are downsampled once the task exits
+                    let log_limit = log_limit(self.config.task.log_limit.as_ref(), &hints)
                         .map_err(|e| {
                             EvaluationError::new(
                                 state.document.clone(),
                                 task_execution_failed(e, task.name(), id, task.name_span()),
                             )
-                        })?
+                        })?;
+                    let (request, log_limit) = if self.backend.limits_logs() {
+                        (request.with_log_limit(log_limit), None)
+                    } else {
+                        (request, log_limit)
+                    };
+
+                    let token = self.cancellation.token().child_token();
+                    let mut receiver =
+                        self.backend
+                            .spawn(request, token.clone())
+                            .with_context(|| {
+                                format!(
+                                    "failed to spawn task `{name}` in `{path}` (task id `{id}`)",
+                                    name = task.name(),
+                                    path = document.path(),
+                                )
+                            })?;
+
+                    let mut timed_out = false;
+                    let result = match timeout {
+                        Some(timeout) => {
+                            let elapsed = async {
+                                match started_rx.await {
+                                    Ok(()) => tokio::time::sleep(timeout).await,
+                                    // The task completed without starting
+                                    Err(_) => std::future::pending().await,
+                                }
+                            };
+
+                            tokio::select! {
+                                result = &mut receiver => result,
+                                _ = elapsed => {
+                                    timed_out = true;
+                                    token.cancel();
+                                    receiver.await
+                                }
+                            }
+                        }
+                        None => receiver.await,
+                    }
+                    .expect("failed to receive response from spawned task");
+
+                    if let Some(forwarding) = forwarding {
+                        forwarding.finish().await;
+                    }
+
+                    if timed_out {
+                        let max_time = timeout.expect("should have a maximum execution time");
+                        if let Some(sender) = &self.events {
+                            let _ = sender.send(EngineEvent::TaskTimedOut {
+                                id: id.to_string(),
+                                max_time,
+                            });
+                        }
+
+                        return Err(EvaluationError::new(
+                            state.document.clone(),
+                            task_execution_failed(
+                                anyhow!(
+                                    "task timed out after exceeding its maximum execution time of \
+                                     {secs} second{s}",
+                                    secs = max_time.as_secs(),
+                                    s = if max_time.as_secs() == 1 { "" } else { "s" }
+                                ),
+                                task.name(),
+                                id,
+                                task.name_span(),
+                            )
+                            .with_fix(
+                                "increase the task's `max_time` or `time_minutes` hint to allow \
+                                 it to run for longer",
+                            ),
+                        ));
+                    }
+
+                    let result = result.map_err(|e| {
+                        EvaluationError::new(
+                            state.document.clone(),
+                            task_execution_failed(e, task.name(), id, task.name_span()),
+                        )
+                    })?;
+
+                    // Downsample the logs before the cache entry records their digests
+                    if let Some(limit) = log_limit {
+                        for log in [&result.stdout, &result.stderr] {
+                            // Logs of tasks executed remotely cannot be downsampled
+                            let log = log.as_file().expect("log should be a file").as_str();
+                            if is_supported_url(log) {
+                                continue;
+                            }
+
+                            let path = PathBuf::from(log);
+                            let dropped =
+                                tokio::task::spawn_blocking(move || limit.downsample(&path))
+                                    .await
+                                    .expect("failed to join downsampling task")?;
+                            if let Some(dropped) = dropped {
+                                info!(
+                                    task_id = id,
+                                    task_name = task.name(),
+                                    document = document.uri().as_str(),
+                                    "downsampled log `{log}` by dropping {lines} line{s}",
+                                    lines = dropped.lines,
+                                    s = if dropped.lines == 1 { "" } else { "s" }
+                                );
+                            }
+                        }
+                    }
+
+                    result
                 }
             };
 
+            // Record the digest of the container image the backend executed the task in
+            let provenance = provenance.with_digest(result.digest.clone());
+
             // Update the task variable
             let evaluated = EvaluatedTask::new(cached, result);
             if version >= SupportedVersion::V1(V1::Two) {
@@ -1244,11 +1626,113 @@ impl TopLevelEvaluator {
                 .handle_exit(&requirements, state.transferer().as_ref())
                 .await
             {
+                let out_of_memory = self.config.task.memory_retry.as_ref().is_some_and(|p| {
+                    p.is_out_of_memory(evaluated.result.out_of_memory, &format!("{e:#}"))
+                });
+
                 if attempt >= max_retries {
-                    return Err(EvaluationError::new(
-                        state.document.clone(),
-                        task_execution_failed(e, task.name(), id, task.name_span()),
-                    ));
+                    if self.config.task.salvage_outputs {
+                        if !cached
+                            && let Some(cleanup) = self
+                                .backend
+                                .cleanup(&evaluated.result.work_dir, self.cancellation.token())
+                        {
+                            cleanup.await;
+                        }
+
+                        let outputs = state
+                            .evaluate_post_command(
+                                id,
+                                &definition,
+                                nodes[current..].iter().map(|i| &graph[*i]),
+                                &evaluated,
+                                true,
+                            )
+                            .await?;
+
+                        // A failure to write the salvaged outputs is logged so that the
+                        // task's error is still the one reported
+                        let path = task_eval_root.join(SALVAGED_OUTPUTS_FILE);
+                        match write_json_file(
+                            &path,
+                            &SalvagedOutputs {
+                                salvaged: true,
+                                exit_code: evaluated.exit_code(),
+                                error: format!("{e:#}"),
+                                outputs: &outputs,
+                            },
+                        ) {
+                            Ok(()) => {
+                                let count = outputs.iter().count();
+                                warn!(
+                                    task_id = id,
+                                    task_name = task.name(),
+                                    document = document.uri().as_str(),
+                                    "salvaged {count} output{s} of failed task to `{path}`",
+                                    s = if count == 1 { "" } else { "s" },
+                                    path = path.display()
+                                );
+                            }
+                            Err(write_error) => {
+                                error!(
+                                    task_id = id,
+                                    task_name = task.name(),
+                                    document = document.uri().as_str(),
+                                    "failed to salvage the outputs of failed task: {write_error:#}"
+                                );
+                            }
+                        }
+                    }
+
+                    let mut diagnostic =
+                        task_execution_failed(e, task.name(), id, task.name_span());
+                    if out_of_memory {
+                        diagnostic = diagnostic.with_fix(
+                            "the task appears to have run out of memory: increase the task's \
+                             `memory` requirement or the `task.memory_retry.max_memory` \
+                             configuration value",
+                        );
+                    }
+
+                    return Err(EvaluationError::new(state.document.clone(), diagnostic));
+                }
+
+                if out_of_memory {
+                    let policy = self
+                        .config
+                        .task
+                        .memory_retry
+                        .as_ref()
+                        .expect("should have memory retry policy");
+                    let from = memory(&requirements)?;
+                    let mut to = (from as f64 * policy.multiplier).min(i64::MAX as f64) as i64;
+                    if let Some(max) = policy.max_memory()? {
+                        to = to.min(max);
+                    }
+
+                    if to > from {
+                        info!(
+                            task_id = id,
+                            task_name = task.name(),
+                            document = document.uri().as_str(),
+                            "task ran out of memory; increasing memory from {from} to {to} bytes"
+                        );
+
+                        memory_override = Some(to);
+                        escalations.push(MemoryEscalation { attempt, from, to });
+                        write_json_file(
+                            task_eval_root.join(MEMORY_ESCALATIONS_FILE),
+                            &escalations,
+                        )?;
+                    } else {
+                        warn!(
+                            task_id = id,
+                            task_name = task.name(),
+                            document = document.uri().as_str(),
+                            "task ran out of memory but its memory of {from} bytes is already at \
+                             the configured maximum"
+                        );
+                    }
                 }
 
                 attempt += 1;
@@ -1293,7 +1777,7 @@ impl TopLevelEvaluator {
                 }
             }
 
-            break evaluated;
+            break (evaluated, provenance, size);
         };
 
         // Perform backend cleanup before output evaluation
@@ -1305,31 +1789,85 @@ impl TopLevelEvaluator {
             cleanup.await;
         }
 
-        // Evaluate the remaining inputs (unused), and decls, and outputs
-        for index in &nodes[current..] {
-            match &graph[*index] {
-                TaskGraphNode::Decl(decl) => {
-                    state
-                        .evaluate_decl(id, decl)
-                        .await
-                        .map_err(|d| EvaluationError::new(state.document.clone(), d))?;
-                }
-                TaskGraphNode::Output(decl) => {
-                    state
-                        .evaluate_output(id, decl, &evaluated)
-                        .await
-                        .map_err(|d| EvaluationError::new(state.document.clone(), d))?;
-                }
+        // Evaluate the remaining decls and outputs
+        let outputs = state
+            .evaluate_post_command(
+                id,
+                &definition,
+                nodes[current..].iter().map(|i| &graph[*i]),
+                &evaluated,
+                false,
+            )
+            .await?;
+
+        // Write the outputs to the task's root directory
+        write_json_file(task_eval_root.join(OUTPUTS_FILE), &outputs)?;
+
+        // Write the provenance of the output files to the task's root directory
+        let manifest = self.provenance.record(&outputs, provenance);
+        write_json_file(task_eval_root.join(PROVENANCE_FILE), &manifest)?;
+
+        // Write the sizes of the inputs and outputs to the task's root directory
+        let sizes = self.sizes.record(CallSizes::new(
+            id,
+            &task_eval_root,
+            localized,
+            outputs_size(&outputs).await,
+        ));
+        write_json_file(task_eval_root.join(SIZES_FILE), &sizes)?;
+
+        self.record_coverage(document, task.name_span(), CoverageKind::Task);
+        evaluated.outputs = Ok(outputs);
+        Ok(evaluated)
+    }
+}
+
+impl<'a> State<'a> {
+    /// Evaluates the declarations and outputs that follow a task's command
+    /// section.
+    ///
+    /// If `salvage` is `true`, the task has failed and any declaration or
+    /// output that fails to evaluate (e.g. a required file that was never
+    /// written) is skipped rather than returned as an error, so that the
+    /// outputs that do exist are kept.
+    ///
+    /// Returns the evaluated outputs in declaration order.
+    async fn evaluate_post_command<'n>(
+        &mut self,
+        id: &str,
+        definition: &TaskDefinition<SyntaxNode>,
+        nodes: impl Iterator<Item = &'n TaskGraphNode<SyntaxNode>>,
+        evaluated: &EvaluatedTask,
+        salvage: bool,
+    ) -> EvaluationResult<Outputs> {
+        for node in nodes {
+            let result = match node {
+                TaskGraphNode::Decl(decl) => self.evaluate_decl(id, decl).await,
+                TaskGraphNode::Output(decl) => self.evaluate_output(id, decl, evaluated).await,
                 _ => {
                     unreachable!(
                         "only declarations and outputs should be evaluated after the command"
                     )
                 }
+            };
+
+            match result {
+                Ok(()) => {}
+                Err(d) if salvage => {
+                    debug!(
+                        task_id = id,
+                        task_name = self.task.name(),
+                        document = self.document.uri().as_str(),
+                        "not salvaging output of failed task: {message}",
+                        message = d.message()
+                    );
+                }
+                Err(d) => return Err(EvaluationError::new(self.document.clone(), d)),
             }
         }
 
         // Take the output scope and return it in declaration sort order
-        let mut outputs: Outputs = mem::take(&mut state.scopes[OUTPUT_SCOPE_INDEX.0]).into();
+        let mut outputs: Outputs = mem::take(&mut self.scopes[OUTPUT_SCOPE_INDEX.0]).into();
         if let Some(section) = definition.output() {
             let indexes: HashMap<_, _> = section
                 .declarations()
@@ -1339,15 +1877,9 @@ impl TopLevelEvaluator {
             outputs.sort_by(move |a, b| indexes[a].cmp(&indexes[b]))
         }
 
-        // Write the outputs to the task's root directory
-        write_json_file(task_eval_root.join(OUTPUTS_FILE), &outputs)?;
-
-        evaluated.outputs = Ok(outputs);
-        Ok(evaluated)
+        Ok(outputs)
     }
-}
 
-impl<'a> State<'a> {
     /// Evaluates a task input.
     async fn evaluate_input(
         &mut self,
@@ -1360,7 +1892,7 @@ impl<'a> State<'a> {
         let expected_ty = crate::convert_ast_type_v1(self.document, &decl_ty)?;
 
         // Evaluate the input if not provided one
-        let (value, span) = match inputs.get(name.text()) {
+        let (value, span, defaulted) = match inputs.get(name.text()) {
             Some(input) => {
                 // For WDL 1.2 evaluation, a `None` value when the expected type is non-optional
                 // will invoke the default expression
@@ -1383,9 +1915,9 @@ impl<'a> State<'a> {
 
                     let mut evaluator =
                         ExprEvaluator::new(TaskEvaluationContext::new(self, ROOT_SCOPE_INDEX));
-                    (evaluator.evaluate_expr(&expr).await?, expr.span())
+                    (evaluator.evaluate_expr(&expr).await?, expr.span(), true)
                 } else {
-                    (input.clone(), name.span())
+                    (input.clone(), name.span(), false)
                 }
             }
             None => match decl.expr() {
@@ -1400,11 +1932,11 @@ impl<'a> State<'a> {
 
                     let mut evaluator =
                         ExprEvaluator::new(TaskEvaluationContext::new(self, ROOT_SCOPE_INDEX));
-                    (evaluator.evaluate_expr(&expr).await?, expr.span())
+                    (evaluator.evaluate_expr(&expr).await?, expr.span(), true)
                 }
                 _ => {
                     assert!(expected_ty.is_optional(), "type should be optional");
-                    (Value::new_none(expected_ty.clone()), name.span())
+                    (Value::new_none(expected_ty.clone()), name.span(), false)
                 }
             },
         };
@@ -1436,6 +1968,22 @@ impl<'a> State<'a> {
             )
         })?;
 
+        // Check that a defaulted input satisfies hermetic evaluation
+        if defaulted && self.top_level.config.hermetic.enabled {
+            check_hermetic_value(name.text(), &value, &self.top_level.config.hermetic).map_err(
+                |e| {
+                    decl_evaluation_failed(
+                        e,
+                        self.task.name(),
+                        true,
+                        name.text(),
+                        Some(Io::Input),
+                        span,
+                    )
+                },
+            )?;
+        }
+
         // Insert the name into the scope
         self.scopes[ROOT_SCOPE_INDEX.0].insert(name.text(), value.clone());
         self.inputs.insert(name.text().to_string(), value.clone());
@@ -1766,6 +2314,9 @@ impl<'a> State<'a> {
     ///   * requirements
     ///   * hints
     ///   * command
+    ///
+    /// If `memory_override` is specified, it replaces the evaluated `memory`
+    /// requirement of the task.
     async fn evaluate_sections(
         &mut self,
         id: &str,
@@ -1773,6 +2324,7 @@ impl<'a> State<'a> {
         inputs: &TaskInputs,
         attempt: u64,
         previous_task_data: Option<Arc<TaskPostEvaluationData>>,
+        memory_override: Option<i64>,
     ) -> EvaluationResult<EvaluatedSections> {
         let version = self.document.version();
 
@@ -1814,7 +2366,7 @@ impl<'a> State<'a> {
         }
 
         // Evaluate requirements and hints
-        let (requirements, hints) = match definition.runtime() {
+        let (mut requirements, hints) = match definition.runtime() {
             Some(section) => self
                 .evaluate_runtime_section(id, &section, inputs)
                 .await
@@ -1837,6 +2389,12 @@ impl<'a> State<'a> {
             ),
         };
 
+        // Override the memory requirement if the task is being retried after
+        // running out of memory
+        if let Some(memory) = memory_override {
+            requirements.insert(TASK_REQUIREMENT_MEMORY.to_string(), memory.into());
+        }
+
         // Now that those are evaluated, insert a [`TaskPostEvaluation`] for
         // `task` which includes those calculated requirements before the
         // command/output sections are evaluated.
@@ -1924,6 +2482,12 @@ impl<'a> State<'a> {
 
         let decl_ty = decl.ty();
         let ty = crate::convert_ast_type_v1(self.document, &decl_ty)?;
+        let top_level = self.top_level;
+        let task_name = self.task.name();
+        let stubbed = top_level
+            .backend
+            .task_stub(task_name)
+            .and_then(|stub| stub.outputs.get(name.text()));
         let mut evaluator = ExprEvaluator::new(
             TaskEvaluationContext::new(self, TASK_SCOPE_INDEX)
                 .with_work_dir(&evaluated.result.work_dir)
@@ -1932,7 +2496,19 @@ impl<'a> State<'a> {
         );
 
         let expr = decl.expr().expect("outputs should have expressions");
-        let value = evaluator.evaluate_expr(&expr).await?;
+        let value = match stubbed {
+            Some(value) => serde_json::from_value(value.clone()).map_err(|e| {
+                decl_evaluation_failed(
+                    e.into(),
+                    task_name,
+                    true,
+                    name.text(),
+                    Some(Io::Output),
+                    name.span(),
+                )
+            })?,
+            None => evaluator.evaluate_expr(&expr).await?,
+        };
 
         // Coerce the output value to the expected type
         let mut value = value
@@ -2110,6 +2686,7 @@ impl<'a> State<'a> {
 mod test {
     use std::fs;
     use std::path::Path;
+    use std::sync::Arc;
 
     use pretty_assertions::assert_eq;
     use tempfile::tempdir;
@@ -2121,14 +2698,33 @@ mod test {
     use crate::CancellationContext;
     use crate::EvaluatedTask;
     use crate::Events;
+    use crate::PrimitiveValue;
+    use crate::SIZES_FILE;
+    use crate::SizesReport;
     use crate::TaskInputs;
     use crate::config::BackendConfig;
     use crate::config::CallCachingMode;
     use crate::config::Config;
+    use crate::config::StubBackendConfig;
+    use crate::config::TaskStubConfig;
     use crate::v1::TopLevelEvaluator;
 
     /// Helper for evaluating a simple task with the given call cache mode.
     async fn evaluate_task(mode: CallCachingMode, root_dir: &Path, source: &str) -> EvaluatedTask {
+        let mut config = Config::default();
+        config.task.cache = mode;
+        config.task.cache_dir = Some(root_dir.join("cache"));
+        evaluate_task_with(config, root_dir, source, &TaskInputs::default()).await
+    }
+
+    /// Helper for evaluating a simple task with the given configuration and
+    /// inputs.
+    async fn evaluate_task_with(
+        mut config: Config,
+        root_dir: &Path,
+        source: &str,
+        inputs: &TaskInputs,
+    ) -> EvaluatedTask {
         fs::write(root_dir.join("source.wdl"), source).expect("failed to write WDL source file");
 
         // Analyze the source file
@@ -2148,9 +2744,6 @@ mod test {
 
         let document = results.first().expect("should have result").document();
 
-        let mut config = Config::default();
-        config.task.cache = mode;
-        config.task.cache_dir = Some(root_dir.join("cache"));
         config
             .backends
             .insert("default".into(), BackendConfig::Local(Default::default()));
@@ -2169,7 +2762,7 @@ mod test {
             .evaluate_task(
                 document,
                 document.task_by_name("test").expect("should have task"),
-                &TaskInputs::default(),
+                inputs,
                 &runs_dir,
             )
             .await
@@ -2216,6 +2809,51 @@ task test {
         );
     }
 
+    /// Tests that the sizes of a task's inputs and outputs are written and that
+    /// inputs exceeding the configured threshold are warned about.
+    #[tokio::test]
+    #[traced_test]
+    async fn input_sizes() {
+        const SOURCE: &str = r#"
+version 1.2
+
+task test {
+    input {
+        File data
+    }
+
+    command <<<printf 'copy' > copy.txt>>>
+
+    output {
+        File copy = "copy.txt"
+    }
+}
+"#;
+
+        let root_dir = tempdir().expect("failed to create temporary directory");
+        let data = root_dir.path().join("data.txt");
+        fs::write(&data, "0123456789").expect("failed to write input file");
+
+        let mut inputs = TaskInputs::default();
+        inputs.set(
+            "data",
+            PrimitiveValue::new_file(data.to_str().expect("path should be UTF-8")),
+        );
+        let mut config = Config::default();
+        config.task.input_size_warning = Some("8 B".to_string());
+
+        let evaluated = evaluate_task_with(config, root_dir.path(), SOURCE, &inputs).await;
+        assert_eq!(evaluated.exit_code(), 0);
+
+        let sizes = SizesReport::read(root_dir.path().join("runs").join(SIZES_FILE))
+            .expect("should have a sizes report");
+        assert_eq!((sizes.localized(), sizes.delocalized()), (10, 4));
+        assert!(
+            logs_contain("which exceeds the configured threshold of 8 B"),
+            "expected a warning for the size of the inputs"
+        );
+    }
+
     /// Tests task evaluation when call caching is enabled.
     #[tokio::test]
     #[traced_test]
@@ -2466,4 +3104,170 @@ task test {
             "expected second run to skip execution"
         );
     }
+
+    /// Tests task evaluation with the stub backend.
+    #[tokio::test]
+    async fn stub_backend() {
+        const SOURCE: &str = r#"
+version 1.2
+
+task test {
+    command <<<exit 1>>>
+
+    output {
+        String message = read_string("message.txt")
+        Int count = 10
+        File? missing = "missing.txt"
+    }
+}
+"#;
+
+        let root_dir = tempdir().expect("failed to create temporary directory");
+        fs::write(root_dir.path().join("source.wdl"), SOURCE)
+            .expect("failed to write WDL source file");
+
+        let analyzer = Analyzer::new(
+            AnalysisConfig::default().with_diagnostics_config(DiagnosticsConfig::except_all()),
+            |(), _, _, _| async {},
+        );
+        analyzer
+            .add_directory(root_dir.path())
+            .await
+            .expect("failed to add directory");
+        let results = analyzer
+            .analyze(())
+            .await
+            .expect("failed to analyze document");
+        let document = results.first().expect("should have result").document();
+
+        let mut stub = TaskStubConfig {
+            script: Some("echo stubbed > message.txt".to_string()),
+            ..Default::default()
+        };
+        stub.outputs.insert("count".to_string(), 42.into());
+
+        let mut config = Config::default();
+        config.backends.insert(
+            "default".into(),
+            BackendConfig::Stub(Arc::new(StubBackendConfig {
+                tasks: [("test".to_string(), stub)].into(),
+            })),
+        );
+
+        let evaluator = TopLevelEvaluator::new(
+            &root_dir.path().join("runs"),
+            config,
+            CancellationContext::default(),
+            Events::disabled(),
+        )
+        .await
+        .unwrap();
+
+        let outputs = evaluator
+            .evaluate_task(
+                document,
+                document.task_by_name("test").expect("should have task"),
+                &TaskInputs::default(),
+                &root_dir.path().join("runs"),
+            )
+            .await
+            .and_then(EvaluatedTask::into_result)
+            .map_err(|e| e.to_string())
+            .expect("failed to evaluate task");
+
+        assert_eq!(
+            outputs
+                .get("message")
+                .unwrap()
+                .clone()
+                .unwrap_string()
+                .as_str(),
+            "stubbed"
+        );
+        assert_eq!(outputs.get("count").unwrap().clone().unwrap_integer(), 42);
+        assert!(outputs.get("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn max_time_hint() {
+        use std::collections::HashMap;
+        use std::time::Duration;
+
+        use super::max_time;
+        use crate::PrimitiveValue;
+
+        let hints =
+            |key: &str, value: PrimitiveValue| HashMap::from([(key.to_string(), value.into())]);
+
+        assert_eq!(max_time(&HashMap::new()).unwrap(), None);
+        assert_eq!(
+            max_time(&hints("max_time", PrimitiveValue::Integer(90))).unwrap(),
+            Some(Duration::from_secs(90))
+        );
+        assert_eq!(
+            max_time(&hints("max_time", PrimitiveValue::new_string("1.5h"))).unwrap(),
+            Some(Duration::from_secs(5400))
+        );
+        assert_eq!(
+            max_time(&hints("max_time", PrimitiveValue::new_string("2d"))).unwrap(),
+            Some(Duration::from_secs(172800))
+        );
+        assert_eq!(
+            max_time(&hints("time_minutes", PrimitiveValue::Integer(10))).unwrap(),
+            Some(Duration::from_secs(600))
+        );
+        assert!(max_time(&hints("max_time", PrimitiveValue::Integer(0))).is_err());
+        assert!(max_time(&hints("max_time", PrimitiveValue::new_string("soon"))).is_err());
+        assert!(max_time(&hints("time_minutes", PrimitiveValue::Integer(-1))).is_err());
+    }
+
+    #[test]
+    fn log_limit_hints() {
+        use std::collections::HashMap;
+
+        use super::log_limit;
+        use crate::PrimitiveValue;
+        use crate::config::LogLimitConfig;
+        use crate::logs::LogLimit;
+
+        let hints =
+            |key: &str, value: PrimitiveValue| HashMap::from([(key.to_string(), value.into())]);
+        let config = LogLimitConfig {
+            head: "1 KiB".to_string(),
+            tail: "2 KiB".to_string(),
+        };
+
+        assert_eq!(log_limit(None, &HashMap::new()).unwrap(), None);
+        assert_eq!(
+            log_limit(Some(&config), &HashMap::new()).unwrap(),
+            Some(LogLimit {
+                head: 1024,
+                tail: 2048
+            })
+        );
+        assert_eq!(
+            log_limit(
+                Some(&config),
+                &hints("log_head", PrimitiveValue::Integer(10))
+            )
+            .unwrap(),
+            Some(LogLimit {
+                head: 10,
+                tail: 2048
+            })
+        );
+        assert_eq!(
+            log_limit(
+                None,
+                &hints("log_tail", PrimitiveValue::new_string("1 MiB"))
+            )
+            .unwrap(),
+            Some(LogLimit {
+                head: 5 * 1024 * 1024,
+                tail: 1024 * 1024
+            })
+        );
+        assert!(log_limit(None, &hints("log_head", PrimitiveValue::Integer(-1))).is_err());
+        assert!(log_limit(None, &hints("log_tail", PrimitiveValue::new_string("lots"))).is_err());
+    }
 }
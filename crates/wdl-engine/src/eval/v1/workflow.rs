@@ -33,6 +33,7 @@ use wdl_analysis::diagnostics::type_is_not_array;
 use wdl_analysis::diagnostics::unknown_name;
 use wdl_analysis::diagnostics::unknown_namespace;
 use wdl_analysis::diagnostics::unknown_task_or_workflow;
+use wdl_analysis::document::Input;
 use wdl_analysis::document::ScopeUnion;
 use wdl_analysis::document::Task;
 use wdl_analysis::eval::v1::WorkflowGraphBuilder;
@@ -54,28 +55,36 @@ use wdl_ast::v1::ConditionalStatement;
 use wdl_ast::v1::ConditionalStatementClauseKind;
 use wdl_ast::v1::Decl;
 use wdl_ast::v1::Expr;
+use wdl_ast::v1::NameRefExpr;
 use wdl_ast::v1::ScatterStatement;
 use wdl_ast::version::V1;
 
 use crate::Array;
+use crate::CallAction;
 use crate::CallLocation;
 use crate::CallValue;
 use crate::CancellationContextState;
 use crate::Coercible;
+use crate::CoverageKind;
 use crate::EvaluationContext;
 use crate::EvaluationError;
 use crate::EvaluationResult;
 use crate::Inputs;
+use crate::InterceptedCall;
 use crate::Outputs;
+use crate::PROVENANCE_FILE;
+use crate::SIZES_FILE;
 use crate::Scope;
 use crate::ScopeIndex;
 use crate::ScopeRef;
 use crate::Value;
 use crate::WorkflowInputs;
+use crate::config::HermeticConfig;
 use crate::diagnostics::decl_evaluation_failed;
 use crate::diagnostics::if_conditional_mismatch;
 use crate::diagnostics::runtime_type_mismatch;
 use crate::http::Transferer;
+use crate::inputs::check_hermetic_value;
 use crate::path::EvaluationPath;
 use crate::tree::SyntaxNode;
 use crate::tree::SyntaxToken;
@@ -83,6 +92,7 @@ use crate::v1::ExprEvaluator;
 use crate::v1::INPUTS_FILE;
 use crate::v1::OUTPUTS_FILE;
 use crate::v1::TopLevelEvaluator;
+use crate::v1::write_failure_file;
 use crate::v1::write_json_file;
 
 /// Helper for formatting a workflow or task identifier for a call statement.
@@ -120,6 +130,24 @@ fn format_id(namespace: Option<&str>, target: &str, alias: &str, scatter_index:
     }
 }
 
+/// Coerces the outputs of a skipped call to the output types of the call.
+fn coerce_skipped_outputs(ty: &CallType, outputs: Outputs) -> Result<Outputs> {
+    ty.outputs()
+        .iter()
+        .map(|(name, output)| {
+            let value = match outputs.get(name) {
+                Some(value) => value
+                    .coerce(None, output.ty())
+                    .with_context(|| format!("invalid value for output `{name}`"))?,
+                None if output.ty().is_optional() => Value::new_none(output.ty().optional()),
+                None => bail!("missing a value for required output `{name}`"),
+            };
+
+            Ok((name.clone(), value))
+        })
+        .collect()
+}
+
 /// A "hidden" scope variable for representing the scope's scatter index.
 ///
 /// This is only present in the scope created for a scatter statement.
@@ -512,6 +540,14 @@ impl Subgraph {
         subgraphs
     }
 
+    /// Retains only the given nodes in the subgraph.
+    ///
+    /// Every node that a retained node depends on must also be retained so that
+    /// the indegrees of the remaining nodes stay correct.
+    fn retain(&mut self, nodes: &HashSet<NodeIndex>) {
+        self.0.retain(|node, _| nodes.contains(node));
+    }
+
     /// Removes the given node from the subgraph.
     ///
     /// # Panics
@@ -534,6 +570,41 @@ impl Subgraph {
     }
 }
 
+/// Finds the nodes required to evaluate the call with the given name.
+///
+/// The returned nodes are the call's node and every node it transitively
+/// depends on, including the entry nodes of any enclosing scatter or
+/// conditional statements.
+///
+/// Returns `None` if the workflow does not contain a call with the given name.
+fn call_dependencies(
+    graph: &DiGraph<WorkflowGraphNode<SyntaxNode>, ()>,
+    name: &str,
+) -> Option<HashSet<NodeIndex>> {
+    let target = graph.node_indices().find(|index| match &graph[*index] {
+        WorkflowGraphNode::Call(stmt) => {
+            let alias = stmt
+                .alias()
+                .map(|a| a.name())
+                .or_else(|| stmt.target().names().last());
+            alias.is_some_and(|a| a.text() == name)
+        }
+        _ => false,
+    })?;
+
+    let mut nodes = HashSet::from([target]);
+    let mut stack = vec![target];
+    while let Some(node) = stack.pop() {
+        for edge in graph.edges_directed(node, Direction::Incoming) {
+            if nodes.insert(edge.source()) {
+                stack.push(edge.source());
+            }
+        }
+    }
+
+    Some(nodes)
+}
+
 /// Represents workflow evaluation state.
 struct State {
     /// The top-level evaluation context.
@@ -584,12 +655,62 @@ impl TopLevelEvaluator {
             return Err(anyhow!("cannot evaluate a document with errors").into());
         }
 
+        if self.config.hermetic.enabled {
+            inputs.check_hermetic(&self.config.hermetic)?;
+        }
+
+        let result = self
+            .perform_workflow_evaluation(
+                document,
+                inputs,
+                workflow_eval_root_dir.as_ref(),
+                workflow.name(),
+                None,
+            )
+            .await;
+
+        if self.cancellation.user_canceled() {
+            return Err(EvaluationError::Canceled);
+        }
+
+        result
+    }
+
+    /// Evaluates the workflow of the given document up to the call with the
+    /// given name.
+    ///
+    /// Only the calls and declarations that the call depends on are evaluated;
+    /// everything downstream of the call, including the workflow's outputs, is
+    /// skipped.
+    ///
+    /// Upon success, returns the outputs of the call.
+    pub async fn evaluate_workflow_until(
+        &self,
+        document: &Document,
+        inputs: WorkflowInputs,
+        workflow_eval_root_dir: impl AsRef<Path>,
+        call: &str,
+    ) -> EvaluationResult<Outputs> {
+        let workflow = document
+            .workflow()
+            .context("document does not contain a workflow")?;
+
+        // We cannot evaluate a document with errors
+        if document.has_errors() {
+            return Err(anyhow!("cannot evaluate a document with errors").into());
+        }
+
+        if self.config.hermetic.enabled {
+            inputs.check_hermetic(&self.config.hermetic)?;
+        }
+
         let result = self
             .perform_workflow_evaluation(
                 document,
                 inputs,
                 workflow_eval_root_dir.as_ref(),
                 workflow.name(),
+                Some(call),
             )
             .await;
 
@@ -602,6 +723,13 @@ impl TopLevelEvaluator {
 
     /// Performs the evaluation of the workflow of the given document.
     ///
+    /// If a target call is given, only the nodes required to evaluate the call
+    /// are evaluated and the call's outputs are returned instead of the
+    /// workflow's outputs.
+    ///
+    /// If the evaluation fails, a failure file is written to the workflow's
+    /// root directory in place of its outputs.
+    ///
     /// This method skips checking the document (and its transitive imports) for
     /// analysis errors as the check occurs at the `evaluate` entrypoint.
     async fn perform_workflow_evaluation(
@@ -610,6 +738,27 @@ impl TopLevelEvaluator {
         inputs: WorkflowInputs,
         workflow_eval_root_dir: &Path,
         id: &str,
+        target: Option<&str>,
+    ) -> EvaluationResult<Outputs> {
+        let result = self
+            .evaluate_workflow_in_root(document, inputs, workflow_eval_root_dir, id, target)
+            .await;
+        if let Err(e) = &result {
+            write_failure_file(workflow_eval_root_dir, e);
+        }
+
+        result
+    }
+
+    /// Evaluates the workflow of the given document with the given root
+    /// directory.
+    async fn evaluate_workflow_in_root(
+        &self,
+        document: &Document,
+        inputs: WorkflowInputs,
+        workflow_eval_root_dir: &Path,
+        id: &str,
+        target: Option<&str>,
     ) -> EvaluationResult<Outputs> {
         // Validate the inputs for the workflow
         let workflow = document
@@ -658,7 +807,22 @@ impl TopLevelEvaluator {
 
         // Split the root subgraph for every conditional and scatter statement
         let mut subgraph = Subgraph::new(&graph);
-        let subgraphs = subgraph.split(&graph);
+        let mut subgraphs = subgraph.split(&graph);
+
+        // Skip every node that the target call does not depend on
+        if let Some(target) = target {
+            let nodes = call_dependencies(&graph, target).ok_or_else(|| {
+                anyhow!(
+                    "workflow `{workflow}` does not contain a call named `{target}`",
+                    workflow = workflow.name()
+                )
+            })?;
+
+            subgraph.retain(&nodes);
+            for subgraph in subgraphs.values_mut() {
+                subgraph.retain(&nodes);
+            }
+        }
 
         let max_concurrency = self
             .config
@@ -718,18 +882,53 @@ impl TopLevelEvaluator {
             )
             .await?;
 
-        let mut outputs: Outputs = state.scopes.write().await.take(Scopes::OUTPUT_INDEX).into();
-        if let Some(section) = definition.output() {
-            let indexes: HashMap<_, _> = section
-                .declarations()
-                .enumerate()
-                .map(|(i, d)| (d.name().hashable(), i))
-                .collect();
-            outputs.sort_by(move |a, b| indexes[a].cmp(&indexes[b]))
-        }
+        let outputs = match target {
+            Some(target) => state
+                .scopes
+                .read()
+                .await
+                .reference(Scopes::ROOT_INDEX)
+                .local(target)
+                .and_then(Value::as_call)
+                .map(|call| call.outputs().clone())
+                .ok_or_else(|| {
+                    // A call inside a scatter over an empty array is never evaluated
+                    anyhow!(
+                        "call `{target}` of workflow `{workflow}` was not evaluated",
+                        workflow = workflow.name()
+                    )
+                })?,
+            None => {
+                let mut outputs: Outputs =
+                    state.scopes.write().await.take(Scopes::OUTPUT_INDEX).into();
+                if let Some(section) = definition.output() {
+                    let indexes: HashMap<_, _> = section
+                        .declarations()
+                        .enumerate()
+                        .map(|(i, d)| (d.name().hashable(), i))
+                        .collect();
+                    outputs.sort_by(move |a, b| indexes[a].cmp(&indexes[b]))
+                }
+
+                outputs
+            }
+        };
 
         // Write the outputs to the workflow's root directory
         write_json_file(workflow_eval_root_dir.join(OUTPUTS_FILE), &outputs)?;
+
+        // Write the provenance of the output files to the workflow's root directory
+        write_json_file(
+            workflow_eval_root_dir.join(PROVENANCE_FILE),
+            &self.provenance.manifest(&outputs),
+        )?;
+
+        // Write the sizes of the inputs and outputs of the workflow's calls to the
+        // workflow's root directory
+        write_json_file(
+            workflow_eval_root_dir.join(SIZES_FILE),
+            &self.sizes.report(workflow_eval_root_dir),
+        )?;
         Ok(outputs)
     }
 }
@@ -963,7 +1162,7 @@ impl State {
         let expr = decl.expr();
 
         // Either use the specified input or evaluate the input's expression
-        let (value, span) = match self.inputs.get(name.text()) {
+        let (value, span, defaulted) = match self.inputs.get(name.text()) {
             Some(input) => {
                 // For WDL 1.2 evaluation, a `None` value when the expected type is non-optional
                 // will invoke the default expression
@@ -987,9 +1186,10 @@ impl State {
                     (
                         self.evaluate_expr(Scopes::ROOT_INDEX, &expr).await?,
                         expr.span(),
+                        true,
                     )
                 } else {
-                    (input.clone(), name.span())
+                    (input.clone(), name.span(), false)
                 }
             }
             None => {
@@ -1005,10 +1205,11 @@ impl State {
                     (
                         self.evaluate_expr(Scopes::ROOT_INDEX, &expr).await?,
                         expr.span(),
+                        true,
                     )
                 } else {
                     assert!(expected_ty.is_optional(), "type should be optional");
-                    (Value::new_none(expected_ty.clone()), name.span())
+                    (Value::new_none(expected_ty.clone()), name.span(), false)
                 }
             }
         };
@@ -1048,6 +1249,25 @@ impl State {
                 })?;
         }
 
+        // Check that a defaulted input satisfies hermetic evaluation
+        if defaulted && self.top_level.config.hermetic.enabled {
+            check_hermetic_value(name.text(), &value, &self.top_level.config.hermetic).map_err(
+                |e| {
+                    decl_evaluation_failed(
+                        e,
+                        self.document
+                            .workflow()
+                            .expect("should have workflow")
+                            .name(),
+                        false,
+                        name.text(),
+                        Some(Io::Input),
+                        span,
+                    )
+                },
+            )?;
+        }
+
         // Write the value into the root scope
         self.scopes
             .write()
@@ -1265,6 +1485,12 @@ impl State {
             }
 
             // If we reach here, this clause should be executed
+            self.top_level.record_coverage(
+                &self.document,
+                clause.span(),
+                CoverageKind::ConditionalClause,
+            );
+
             let clause_node = self
                 .graph
                 .edges_directed(entry, Direction::Outgoing)
@@ -1434,6 +1660,14 @@ impl State {
             })?
             .as_slice();
 
+        self.top_level.record_coverage(
+            &self.document,
+            stmt.span(),
+            CoverageKind::Scatter {
+                body: !array.is_empty(),
+            },
+        );
+
         let mut gathers: HashMap<_, Gather> = HashMap::new();
         for (i, value) in array.iter().enumerate() {
             if self.top_level.cancellation.state() != CancellationContextState::NotCanceled {
@@ -1540,6 +1774,7 @@ impl State {
                                 inputs.unwrap_workflow_inputs(),
                                 root_dir,
                                 callee_id,
+                                None,
                             )
                             .await
                     }
@@ -1639,6 +1874,22 @@ impl State {
             .await
             .map_err(|d| EvaluationError::new(self.document.clone(), d))?;
 
+        if self.top_level.config.hermetic.enabled {
+            let input_types = match &call_target {
+                Target::Task(task) => task.inputs(),
+                Target::Workflow => document.workflow().expect("should have workflow").inputs(),
+            };
+
+            Self::check_hermetic_call_inputs(
+                stmt,
+                alias.text(),
+                input_types,
+                &inputs,
+                &self.top_level.config.hermetic,
+            )
+            .map_err(|d| EvaluationError::new(self.document.clone(), d))?;
+        }
+
         let dir = format!(
             "{alias}{sep}{scatter_index}",
             alias = alias.text(),
@@ -1652,32 +1903,6 @@ impl State {
             &scatter_index,
         );
 
-        // Finally, evaluate the task or workflow and return the outputs
-        let outputs = call_target
-            .evaluate(
-                &self.top_level,
-                id,
-                document,
-                inputs,
-                &self.calls_dir.join(&dir),
-                &call_id,
-            )
-            .await
-            .map_err(|mut e| {
-                if let EvaluationError::Source(e) = &mut e {
-                    e.backtrace.push(CallLocation {
-                        document: self.document.clone(),
-                        span: stmt
-                            .token::<CallKeyword<SyntaxToken>>()
-                            .expect("should have call keyword")
-                            .span(),
-                    });
-                }
-
-                e
-            })?
-            .with_name(alias.text());
-
         let ty = self
             .document
             .workflow()
@@ -1685,11 +1910,69 @@ impl State {
             .calls()
             .get(alias.text())
             .expect("should have call");
+
+        // Give the interceptor, if there is one, a chance to skip the call
+        let action = match &self.top_level.interceptor {
+            Some(interceptor) => interceptor
+                .intercept(InterceptedCall {
+                    id: &call_id,
+                    name: alias.text(),
+                    target: target.text(),
+                    inputs: &inputs,
+                    ty,
+                })
+                .await
+                .with_context(|| format!("failed to intercept call `{call_id}`"))?,
+            None => CallAction::Continue,
+        };
+
+        let outputs = match action {
+            CallAction::Continue => {
+                // Finally, evaluate the task or workflow and return the outputs
+                call_target
+                    .evaluate(
+                        &self.top_level,
+                        id,
+                        document,
+                        inputs,
+                        &self.calls_dir.join(&dir),
+                        &call_id,
+                    )
+                    .await
+                    .map_err(|mut e| {
+                        if let EvaluationError::Source(e) = &mut e {
+                            e.backtrace.push(CallLocation {
+                                document: self.document.clone(),
+                                span: stmt
+                                    .token::<CallKeyword<SyntaxToken>>()
+                                    .expect("should have call keyword")
+                                    .span(),
+                            });
+                        }
+
+                        e
+                    })?
+            }
+            CallAction::Skip(outputs) => {
+                debug!(
+                    workflow_id = id,
+                    call_id = call_id.as_str(),
+                    "skipping evaluation of call"
+                );
+
+                coerce_skipped_outputs(ty, outputs)
+                    .with_context(|| format!("invalid outputs for skipped call `{call_id}`"))?
+            }
+        }
+        .with_name(alias.text());
+
         self.scopes.write().await.get_mut(scope).insert(
             alias.text(),
             Value::Call(CallValue::new_unchecked(ty.clone(), Arc::new(outputs))),
         );
 
+        self.top_level
+            .record_coverage(&self.document, stmt.span(), CoverageKind::Call);
         Ok(())
     }
 
@@ -1749,6 +2032,55 @@ impl State {
 
         Ok(scopes.scatter_index(scope))
     }
+
+    /// Checks that the evaluated inputs of a call satisfy hermetic evaluation.
+    ///
+    /// Only the inputs specified with expressions that do not reference any
+    /// names (e.g. a literal path) are checked: the values of names are either
+    /// checked where they are introduced (e.g. workflow inputs) or are the
+    /// outputs of tasks, which are local to the run.
+    fn check_hermetic_call_inputs(
+        stmt: &CallStatement<SyntaxNode>,
+        alias: &str,
+        input_types: &IndexMap<String, Input>,
+        inputs: &Inputs,
+        config: &HermeticConfig,
+    ) -> Result<(), Diagnostic> {
+        for input in stmt.inputs() {
+            let name = input.name();
+            let Some(expr) = input.expr() else {
+                continue;
+            };
+
+            if expr.descendants::<NameRefExpr<_>>().next().is_some() {
+                continue;
+            }
+
+            // A value that cannot be coerced is reported when the call is evaluated
+            let (Some(ty), Some(value)) = (input_types.get(name.text()), inputs.get(name.text()))
+            else {
+                continue;
+            };
+            let Ok(value) = value.coerce(None, ty.ty()) else {
+                continue;
+            };
+
+            check_hermetic_value(
+                &format!("{alias}.{name}", name = name.text()),
+                &value,
+                config,
+            )
+            .map_err(|e| {
+                Diagnostic::error(format!(
+                    "{e:#}",
+                    e = e.context(format!("invalid input for call `{alias}`"))
+                ))
+                .with_highlight(expr.span())
+            })?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -1769,9 +2101,11 @@ mod test {
     use super::*;
     use crate::CancellationContext;
     use crate::Events;
+    use crate::ProvenanceManifest;
     use crate::config::BackendConfig;
     use crate::config::Config;
     use crate::config::FailureMode;
+    use crate::v1::FAILURE_FILE;
 
     #[tokio::test]
     async fn it_writes_input_and_output_files() {
@@ -1929,66 +2263,115 @@ workflow test {
     }
 
     #[tokio::test]
-    async fn it_handles_conditional_with_different_variables() {
+    async fn it_writes_failure_files() {
         let root_dir = TempDir::new().expect("failed to create temporary directory");
         fs::write(
             root_dir.path().join("source.wdl"),
             r#"
-version 1.3
+version 1.2
 
-task sayColor {
-    input {
-        String color
+task fail {
+    command <<<exit 1>>>
+}
+
+workflow test {
+    call fail
+}
+"#,
+        )
+        .expect("failed to create test source");
+
+        let analyzer = Analyzer::new(
+            AnalysisConfig::default().with_diagnostics_config(DiagnosticsConfig::except_all()),
+            |(), _, _, _| async {},
+        );
+        analyzer
+            .add_directory(root_dir.path())
+            .await
+            .expect("failed to add directory");
+        let results = analyzer
+            .analyze(())
+            .await
+            .expect("failed to analyze document");
+        assert_eq!(results.len(), 1, "expected only one result");
+
+        let config = Config {
+            backends: [(
+                "default".to_string(),
+                BackendConfig::Local(Default::default()),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let evaluator = TopLevelEvaluator::new(
+            root_dir.path(),
+            config,
+            Default::default(),
+            Events::disabled(),
+        )
+        .await
+        .unwrap();
+
+        let outputs_dir = root_dir.path().join("outputs");
+        evaluator
+            .evaluate_workflow(
+                results.first().expect("should have result").document(),
+                WorkflowInputs::default(),
+                &outputs_dir,
+            )
+            .await
+            .expect_err("workflow should fail");
+
+        // The workflow and the failed call both have a failure file and no outputs
+        for dir in [outputs_dir.clone(), outputs_dir.join("calls/fail")] {
+            let failure: serde_json::Value = serde_json::from_str(
+                &read_to_string(dir.join(FAILURE_FILE)).expect("failed to read `failure.json`"),
+            )
+            .expect("failed to parse `failure.json`");
+            assert_eq!(failure["canceled"], false);
+            assert!(
+                failure["error"]
+                    .as_str()
+                    .expect("error should be a string")
+                    .contains("exit code 1"),
+                "unexpected error `{failure}`"
+            );
+            assert!(!dir.join(OUTPUTS_FILE).exists());
+        }
     }
 
+    #[tokio::test]
+    async fn it_writes_provenance_files() {
+        let root_dir = TempDir::new().expect("failed to create temporary directory");
+        fs::write(
+            root_dir.path().join("source.wdl"),
+            r#"
+version 1.2
+
+task foo {
     command <<<
-      echo "Hello, ~{color} color!"
+        echo hello > out.txt
     >>>
 
     output {
-        String out = read_string(stdout())
+        File out = "out.txt"
     }
 }
 
-workflow foo {
-    input {
-        Boolean useRed = false
-        Boolean useGreen = false
-        Boolean useBlue = false
-    }
-
-    if (useRed) {
-        String my_variable = "foo"
-        String my_greeting = "Hello"
-        call sayColor { input: color = "red" }
-    } else if (useGreen) {
-        String my_variable = "bar"
-        String my_greeting = "Hi"
-        call sayColor { input: color = "green" }
-    } else if (useBlue) {
-        String my_variable = "baz"
-        call sayColor { input: color = "blue" }
-    } else {
-        String my_variable = "quux"
-        String my_greeting = "Salutations"
-        call sayColor { input: color = "unknown" }
-    }
+workflow test {
+    call foo
 
     output {
-        String variable = my_variable
-        String? greeting = my_greeting
-        String out = sayColor.out
+        File out = foo.out
+        String message = "hello"
     }
 }
 "#,
         )
         .expect("failed to write WDL source file");
 
-        // Analyze the source file
         let analyzer = Analyzer::new(
-            AnalysisConfig::default()
-                .with_diagnostics_config(DiagnosticsConfig::except_all())
-                .with_feature_flags(FeatureFlags::default().with_wdl_1_3()),
+            AnalysisConfig::default().with_diagnostics_config(DiagnosticsConfig::except_all()),
             |(), _, _, _| async {},
         );
         analyzer
@@ -1999,7 +2382,6 @@ workflow foo {
             .analyze(())
             .await
             .expect("failed to analyze document");
-        assert_eq!(results.len(), 1, "expected only one result");
 
         let config = Config {
             backends: [(
@@ -2007,7 +2389,6 @@ workflow foo {
                 BackendConfig::Local(Default::default()),
             )]
             .into(),
-            experimental_features_enabled: true,
             ..Default::default()
         };
         let evaluator = TopLevelEvaluator::new(
@@ -2019,7 +2400,134 @@ workflow foo {
         .await
         .unwrap();
 
-        let mut inputs = WorkflowInputs::default();
+        let outputs_dir = root_dir.path().join("outputs");
+        let outputs = evaluator
+            .evaluate_workflow(
+                results.first().expect("should have result").document(),
+                WorkflowInputs::default(),
+                &outputs_dir,
+            )
+            .await
+            .map_err(|e| e.to_string())
+            .expect("failed to evaluate workflow");
+
+        let path = outputs
+            .get("out")
+            .and_then(Value::as_file)
+            .expect("should have file output")
+            .as_str()
+            .to_string();
+
+        // Only the file output should be traced back to the `foo` call
+        let manifest = ProvenanceManifest::read(outputs_dir.join(PROVENANCE_FILE))
+            .expect("failed to read workflow provenance");
+        assert_eq!(manifest.iter().count(), 1);
+        let (_, provenance) = manifest.get(&path).expect("should have provenance");
+        assert_eq!(provenance.call(), "foo");
+        assert_eq!(provenance.task(), "foo");
+        assert_eq!(provenance.attempt(), 0);
+        assert!(!provenance.cached());
+        assert_eq!(provenance.container(), None);
+
+        // The call's manifest should record the same provenance
+        let call_manifest =
+            ProvenanceManifest::read(outputs_dir.join("calls/foo").join(PROVENANCE_FILE))
+                .expect("failed to read call provenance");
+        assert_eq!(call_manifest, manifest);
+    }
+
+    #[tokio::test]
+    async fn it_handles_conditional_with_different_variables() {
+        let root_dir = TempDir::new().expect("failed to create temporary directory");
+        fs::write(
+            root_dir.path().join("source.wdl"),
+            r#"
+version 1.3
+
+task sayColor {
+    input {
+        String color
+    }
+
+    command <<<
+      echo "Hello, ~{color} color!"
+    >>>
+
+    output {
+        String out = read_string(stdout())
+    }
+}
+
+workflow foo {
+    input {
+        Boolean useRed = false
+        Boolean useGreen = false
+        Boolean useBlue = false
+    }
+
+    if (useRed) {
+        String my_variable = "foo"
+        String my_greeting = "Hello"
+        call sayColor { input: color = "red" }
+    } else if (useGreen) {
+        String my_variable = "bar"
+        String my_greeting = "Hi"
+        call sayColor { input: color = "green" }
+    } else if (useBlue) {
+        String my_variable = "baz"
+        call sayColor { input: color = "blue" }
+    } else {
+        String my_variable = "quux"
+        String my_greeting = "Salutations"
+        call sayColor { input: color = "unknown" }
+    }
+
+    output {
+        String variable = my_variable
+        String? greeting = my_greeting
+        String out = sayColor.out
+    }
+}
+"#,
+        )
+        .expect("failed to write WDL source file");
+
+        // Analyze the source file
+        let analyzer = Analyzer::new(
+            AnalysisConfig::default()
+                .with_diagnostics_config(DiagnosticsConfig::except_all())
+                .with_feature_flags(FeatureFlags::default().with_wdl_1_3()),
+            |(), _, _, _| async {},
+        );
+        analyzer
+            .add_directory(root_dir.path())
+            .await
+            .expect("failed to add directory");
+        let results = analyzer
+            .analyze(())
+            .await
+            .expect("failed to analyze document");
+        assert_eq!(results.len(), 1, "expected only one result");
+
+        let config = Config {
+            backends: [(
+                "default".to_string(),
+                BackendConfig::Local(Default::default()),
+            )]
+            .into(),
+            experimental_features_enabled: true,
+            ..Default::default()
+        };
+        let evaluator = TopLevelEvaluator::new(
+            root_dir.path(),
+            config,
+            Default::default(),
+            Events::disabled(),
+        )
+        .await
+        .unwrap();
+
+        let mut inputs = WorkflowInputs::default();
         inputs.set("useBlue", true);
         let outputs_dir = root_dir.path().join("outputs_blue");
         let outputs = evaluator
@@ -2381,4 +2889,407 @@ workflow w {
             }
         }
     }
+
+    #[tokio::test]
+    async fn it_skips_intercepted_calls() {
+        /// An interceptor that skips every call to `foo`.
+        struct SkipFoo(AtomicUsize);
+
+        impl crate::CallInterceptor for SkipFoo {
+            fn intercept<'a>(
+                &'a self,
+                call: InterceptedCall<'a>,
+            ) -> BoxFuture<'a, Result<CallAction>> {
+                async move {
+                    self.0.fetch_add(1, Ordering::SeqCst);
+                    if call.name != "foo" {
+                        return Ok(CallAction::Continue);
+                    }
+
+                    assert_eq!(
+                        call.inputs
+                            .get("a")
+                            .and_then(|v| v.as_string())
+                            .map(|s| s.as_str()),
+                        Some("foo")
+                    );
+                    Ok(CallAction::Skip(Outputs::from_iter([(
+                        "x".to_string(),
+                        Value::from("stubbed".to_string()),
+                    )])))
+                }
+                .boxed()
+            }
+        }
+
+        let root_dir = TempDir::new().expect("failed to create temporary directory");
+        fs::write(
+            root_dir.path().join("source.wdl"),
+            r#"
+version 1.2
+
+task foo {
+    input {
+        String a
+    }
+
+    command <<<>>>
+
+    output {
+        String x = a
+        String? y = a
+    }
+}
+
+workflow test {
+    call foo { a = "foo" }
+    call foo as bar { a = "bar" }
+
+    output {
+        String x = foo.x
+        String? y = foo.y
+        String z = bar.x
+    }
+}
+"#,
+        )
+        .expect("failed to write WDL source file");
+
+        // Analyze the source file
+        let analyzer = Analyzer::new(
+            AnalysisConfig::default().with_diagnostics_config(DiagnosticsConfig::except_all()),
+            |(), _, _, _| async {},
+        );
+        analyzer
+            .add_directory(root_dir.path())
+            .await
+            .expect("failed to add directory");
+        let results = analyzer
+            .analyze(())
+            .await
+            .expect("failed to analyze document");
+        assert_eq!(results.len(), 1, "expected only one result");
+
+        let config = Config {
+            backends: [(
+                "default".to_string(),
+                BackendConfig::Local(Default::default()),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let interceptor = Arc::new(SkipFoo(AtomicUsize::new(0)));
+        let evaluator = TopLevelEvaluator::new(
+            root_dir.path(),
+            config,
+            Default::default(),
+            Events::disabled(),
+        )
+        .await
+        .unwrap()
+        .with_call_interceptor(interceptor.clone());
+
+        let outputs = evaluator
+            .evaluate_workflow(
+                results.first().expect("should have result").document(),
+                WorkflowInputs::default(),
+                &root_dir.path().join("outputs"),
+            )
+            .await
+            .map_err(|e| e.to_string())
+            .expect("failed to evaluate workflow");
+
+        assert_eq!(interceptor.0.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            outputs.get("x").unwrap().clone().unwrap_string().as_str(),
+            "stubbed"
+        );
+        assert!(outputs.get("y").unwrap().is_none(), "`y` should be `None`");
+        assert_eq!(
+            outputs.get("z").unwrap().clone().unwrap_string().as_str(),
+            "bar"
+        );
+        assert!(
+            !root_dir.path().join("outputs/calls/foo").exists(),
+            "skipped call should not have a call directory"
+        );
+    }
+
+    #[tokio::test]
+    async fn it_evaluates_until_a_call() {
+        let root_dir = TempDir::new().expect("failed to create temporary directory");
+        fs::write(
+            root_dir.path().join("source.wdl"),
+            r#"
+version 1.2
+
+task echo {
+    input {
+        String a
+    }
+
+    command <<<>>>
+
+    output {
+        String x = a
+    }
+}
+
+workflow test {
+    call echo as first { a = "first" }
+
+    scatter (i in [1, 2]) {
+        call echo as scattered { a = first.x }
+    }
+
+    call echo as second { a = scattered.x[0] + "!" }
+    call echo as third { a = second.x }
+    call echo as unrelated { a = "unrelated" }
+
+    output {
+        String x = third.x
+    }
+}
+"#,
+        )
+        .expect("failed to write WDL source file");
+
+        // Analyze the source file
+        let analyzer = Analyzer::new(
+            AnalysisConfig::default().with_diagnostics_config(DiagnosticsConfig::except_all()),
+            |(), _, _, _| async {},
+        );
+        analyzer
+            .add_directory(root_dir.path())
+            .await
+            .expect("failed to add directory");
+        let results = analyzer
+            .analyze(())
+            .await
+            .expect("failed to analyze document");
+        assert_eq!(results.len(), 1, "expected only one result");
+        let document = results.first().expect("should have result").document();
+
+        let config = Config {
+            backends: [(
+                "default".to_string(),
+                BackendConfig::Local(Default::default()),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let evaluator = TopLevelEvaluator::new(
+            root_dir.path(),
+            config,
+            Default::default(),
+            Events::disabled(),
+        )
+        .await
+        .unwrap();
+
+        let outputs = evaluator
+            .evaluate_workflow_until(
+                document,
+                WorkflowInputs::default(),
+                &root_dir.path().join("outputs"),
+                "second",
+            )
+            .await
+            .map_err(|e| e.to_string())
+            .expect("failed to evaluate workflow");
+
+        assert_eq!(
+            outputs.get("x").unwrap().clone().unwrap_string().as_str(),
+            "first!"
+        );
+        for call in ["first", "second"] {
+            assert!(
+                root_dir.path().join("outputs/calls").join(call).exists(),
+                "call `{call}` should have been evaluated"
+            );
+        }
+        for call in ["third", "unrelated"] {
+            assert!(
+                !root_dir.path().join("outputs/calls").join(call).exists(),
+                "call `{call}` should have been skipped"
+            );
+        }
+
+        let e = evaluator
+            .evaluate_workflow_until(
+                document,
+                WorkflowInputs::default(),
+                &root_dir.path().join("missing"),
+                "missing",
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            e.to_string()
+                .starts_with("workflow `test` does not contain a call named `missing`")
+        );
+    }
+
+    #[tokio::test]
+    async fn it_evaluates_until_a_call_in_a_scatter_or_conditional() {
+        let root_dir = TempDir::new().expect("failed to create temporary directory");
+        fs::write(
+            root_dir.path().join("source.wdl"),
+            r#"
+version 1.2
+
+task echo {
+    input {
+        String a
+    }
+
+    command <<<>>>
+
+    output {
+        String x = a
+    }
+}
+
+workflow test {
+    input {
+        Array[Int] numbers = [1, 2]
+        Boolean enabled = true
+    }
+
+    scatter (i in numbers) {
+        call echo as scattered { a = "~{i}" }
+    }
+
+    if (enabled) {
+        call echo as conditional { a = scattered.x[0] + "!" }
+    }
+
+    call echo as after { a = select_first([conditional.x, "none"]) }
+
+    output {
+        String x = after.x
+    }
+}
+"#,
+        )
+        .expect("failed to write WDL source file");
+
+        // Analyze the source file
+        let analyzer = Analyzer::new(
+            AnalysisConfig::default().with_diagnostics_config(DiagnosticsConfig::except_all()),
+            |(), _, _, _| async {},
+        );
+        analyzer
+            .add_directory(root_dir.path())
+            .await
+            .expect("failed to add directory");
+        let results = analyzer
+            .analyze(())
+            .await
+            .expect("failed to analyze document");
+        assert_eq!(results.len(), 1, "expected only one result");
+        let document = results.first().expect("should have result").document();
+
+        let config = Config {
+            backends: [(
+                "default".to_string(),
+                BackendConfig::Local(Default::default()),
+            )]
+            .into(),
+            ..Default::default()
+        };
+        let evaluator = TopLevelEvaluator::new(
+            root_dir.path(),
+            config,
+            Default::default(),
+            Events::disabled(),
+        )
+        .await
+        .unwrap();
+
+        // The outputs of a call in a scatter are gathered into arrays
+        let outputs = evaluator
+            .evaluate_workflow_until(
+                document,
+                WorkflowInputs::default(),
+                &root_dir.path().join("scatter"),
+                "scattered",
+            )
+            .await
+            .map_err(|e| e.to_string())
+            .expect("failed to evaluate workflow");
+        let x = outputs.get("x").unwrap().clone().unwrap_array();
+        assert_eq!(
+            x.as_slice()
+                .iter()
+                .map(|v| v.clone().unwrap_string().to_string())
+                .collect::<Vec<_>>(),
+            ["1", "2"]
+        );
+        for call in ["conditional", "after"] {
+            assert!(
+                !root_dir.path().join("scatter/calls").join(call).exists(),
+                "call `{call}` should have been skipped"
+            );
+        }
+
+        // The outputs of a call in a conditional are optional
+        let outputs = evaluator
+            .evaluate_workflow_until(
+                document,
+                WorkflowInputs::default(),
+                &root_dir.path().join("conditional"),
+                "conditional",
+            )
+            .await
+            .map_err(|e| e.to_string())
+            .expect("failed to evaluate workflow");
+        assert_eq!(
+            outputs.get("x").unwrap().clone().unwrap_string().as_str(),
+            "1!"
+        );
+        assert!(
+            !root_dir.path().join("conditional/calls/after").exists(),
+            "call `after` should have been skipped"
+        );
+
+        let mut inputs = WorkflowInputs::default();
+        inputs.set("enabled", false);
+        let outputs = evaluator
+            .evaluate_workflow_until(
+                document,
+                inputs,
+                &root_dir.path().join("disabled"),
+                "conditional",
+            )
+            .await
+            .map_err(|e| e.to_string())
+            .expect("failed to evaluate workflow");
+        assert!(outputs.get("x").unwrap().is_none());
+
+        // A call in a scatter over an empty array is never evaluated
+        let mut inputs = WorkflowInputs::default();
+        inputs.set(
+            "numbers",
+            Array::new(
+                None,
+                ArrayType::new(PrimitiveType::Integer),
+                Vec::<Value>::new(),
+            )
+            .unwrap(),
+        );
+        let e = evaluator
+            .evaluate_workflow_until(
+                document,
+                inputs,
+                &root_dir.path().join("empty"),
+                "scattered",
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            e.to_string()
+                .starts_with("call `scattered` of workflow `test` was not evaluated")
+        );
+    }
 }
@@ -6,15 +6,18 @@ use std::collections::HashSet;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
+use std::path::PathBuf;
 
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::bail;
 use indexmap::IndexMap;
+use path_clean::PathClean;
 use serde::Serialize;
 use serde::ser::SerializeMap;
 use serde_json::Value as JsonValue;
 use serde_yaml_ng::Value as YamlValue;
+use url::Url;
 use wdl_analysis::Document;
 use wdl_analysis::document::Input;
 use wdl_analysis::document::Task;
@@ -31,7 +34,10 @@ use wdl_ast::version::V1;
 
 use crate::Coercible;
 use crate::Value;
+use crate::config::HermeticConfig;
 use crate::path::EvaluationPath;
+use crate::path::is_file_url;
+use crate::path::is_supported_url;
 
 /// A type alias to a JSON map (object).
 pub type JsonMap = serde_json::Map<String, JsonValue>;
@@ -60,6 +66,93 @@ fn check_input_type(document: &Document, name: &str, input: &Input, value: &Valu
     Ok(())
 }
 
+/// Normalizes a local path for comparison with the allowed roots of hermetic
+/// evaluation.
+///
+/// The path is canonicalized if it exists so that symbolic links cannot be used
+/// to escape an allowed root.
+fn normalize_local_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| {
+        std::path::absolute(path)
+            .unwrap_or_else(|_| path.to_path_buf())
+            .clean()
+    })
+}
+
+/// Checks that the `File` and `Directory` values of an input that was evaluated
+/// from an expression (i.e. a default or a call input) satisfy hermetic
+/// evaluation.
+///
+/// Unlike the inputs supplied by the caller, these values are only known once
+/// they are evaluated, so this is checked as each input is evaluated.
+pub(crate) fn check_hermetic_value(
+    name: &str,
+    value: &Value,
+    config: &HermeticConfig,
+) -> Result<()> {
+    check_hermetic_inputs(std::iter::once((name.to_string(), value)), config)
+}
+
+/// Checks that the `File` and `Directory` values of the given inputs are remote
+/// URLs or local paths within the allowed roots of hermetic evaluation.
+fn check_hermetic_inputs<'a>(
+    inputs: impl Iterator<Item = (String, &'a Value)>,
+    config: &HermeticConfig,
+) -> Result<()> {
+    let roots: Vec<_> = config
+        .allowed_roots
+        .iter()
+        .map(|root| normalize_local_path(root))
+        .collect();
+
+    let mut violations = Vec::new();
+    for (name, value) in inputs {
+        value.visit_paths(&mut |_, path| {
+            let path = path.as_str();
+            let local = if is_file_url(path) {
+                Url::parse(path)
+                    .ok()
+                    .and_then(|url| url.to_file_path().ok())
+            } else if is_supported_url(path) {
+                None
+            } else {
+                Some(PathBuf::from(path))
+            };
+
+            if let Some(local) = local.map(|p| normalize_local_path(&p))
+                && !roots.iter().any(|root| local.starts_with(root))
+            {
+                violations.push(format!("  input `{name}` references `{path}`"));
+            }
+
+            Ok(())
+        })?;
+    }
+
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    bail!(
+        "hermetic evaluation requires `File` and `Directory` inputs to be remote URLs or local \
+         paths within an allowed root ({roots}):\n{violations}",
+        roots = if config.allowed_roots.is_empty() {
+            "no roots are allowed".to_string()
+        } else {
+            format!(
+                "allowed roots are `{roots}`",
+                roots = config
+                    .allowed_roots
+                    .iter()
+                    .map(|r| r.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join("`, `")
+            )
+        },
+        violations = violations.join("\n")
+    );
+}
+
 /// Represents inputs to a task.
 #[derive(Default, Debug, Clone)]
 pub struct TaskInputs {
@@ -135,6 +228,14 @@ impl TaskInputs {
         Ok(())
     }
 
+    /// Checks that the inputs satisfy hermetic evaluation.
+    ///
+    /// Every `File` and `Directory` input must be a remote URL or a local path
+    /// within one of the allowed roots of the given configuration.
+    pub(crate) fn check_hermetic(&self, config: &HermeticConfig) -> Result<()> {
+        check_hermetic_inputs(self.iter().map(|(k, v)| (k.to_string(), v)), config)
+    }
+
     /// Validates the inputs for the given task.
     ///
     /// The `specified` set of inputs are those that are present, but may not
@@ -401,6 +502,35 @@ impl WorkflowInputs {
         Ok(())
     }
 
+    /// Checks that the inputs, including nested call inputs, satisfy hermetic
+    /// evaluation.
+    ///
+    /// Every `File` and `Directory` input must be a remote URL or a local path
+    /// within one of the allowed roots of the given configuration.
+    pub(crate) fn check_hermetic(&self, config: &HermeticConfig) -> Result<()> {
+        let mut values = Vec::new();
+        self.collect_values("", &mut values);
+        check_hermetic_inputs(values.into_iter(), config)
+    }
+
+    /// Collects the input values, including nested call inputs, by
+    /// dot-separated name.
+    fn collect_values<'a>(&'a self, prefix: &str, values: &mut Vec<(String, &'a Value)>) {
+        values.extend(self.iter().map(|(k, v)| (format!("{prefix}{k}"), v)));
+        for (call, inputs) in &self.calls {
+            match inputs {
+                Inputs::Task(inputs) => values.extend(
+                    inputs
+                        .iter()
+                        .map(|(k, v)| (format!("{prefix}{call}.{k}"), v)),
+                ),
+                Inputs::Workflow(inputs) => {
+                    inputs.collect_values(&format!("{prefix}{call}."), values)
+                }
+            }
+        }
+    }
+
     /// Validates the inputs for the given workflow.
     ///
     /// The `specified` set of inputs are those that are present, but may not
@@ -410,6 +540,21 @@ impl WorkflowInputs {
         document: &Document,
         workflow: &Workflow,
         specified: Option<&HashSet<String>>,
+    ) -> Result<()> {
+        self.validate_at(document, workflow, specified, workflow.name())
+    }
+
+    /// Validates the inputs for the given workflow at the given dotted call
+    /// path.
+    ///
+    /// The path (e.g. `main.sub`) prefixes the keys of missing call inputs in
+    /// errors so that they match the keys of an inputs file.
+    fn validate_at(
+        &self,
+        document: &Document,
+        workflow: &Workflow,
+        specified: Option<&HashSet<String>>,
+        path: &str,
     ) -> Result<()> {
         // Start by validating all the specified inputs and their types
         for (name, value) in &self.inputs {
@@ -488,7 +633,12 @@ impl WorkflowInputs {
                         format!("`{name}` is a call to a workflow, but task inputs were supplied")
                     })?;
 
-                    workflow_inputs.validate(document, workflow, Some(call.specified()))?;
+                    workflow_inputs.validate_at(
+                        document,
+                        workflow,
+                        Some(call.specified()),
+                        &format!("{path}.{name}"),
+                    )?;
                     &workflow_inputs.inputs
                 }
             };
@@ -504,7 +654,7 @@ impl WorkflowInputs {
             }
         }
 
-        // Finally, check for missing call arguments
+        // Next, check for missing call arguments
         if workflow.allows_nested_inputs() {
             for (call, ty) in workflow.calls() {
                 let inputs = self.calls.get(call);
@@ -515,12 +665,38 @@ impl WorkflowInputs {
                     .filter(|(n, i)| i.required() && !ty.specified().contains(*n))
                 {
                     if !inputs.map(|i| i.get(input).is_some()).unwrap_or(false) {
-                        bail!("missing required input `{input}` for call `{call}`");
+                        bail!("missing required input `{path}.{call}.{input}` for call `{call}`");
                     }
                 }
             }
         }
 
+        // Finally, calls to workflows with nested inputs were validated above; check
+        // that the calls nested within the other called workflows have their
+        // required inputs
+        for (name, call) in workflow.calls() {
+            if call.kind() != CallKind::Workflow || self.calls.contains_key(name) {
+                continue;
+            }
+
+            let document = call
+                .namespace()
+                .map(|ns| {
+                    document
+                        .namespace(ns)
+                        .expect("namespace should be present")
+                        .document()
+                })
+                .unwrap_or(document);
+            let workflow = document.workflow().expect("should have a workflow");
+            Self::default().validate_at(
+                document,
+                workflow,
+                Some(call.specified()),
+                &format!("{path}.{name}"),
+            )?;
+        }
+
         Ok(())
     }
 
@@ -1006,3 +1182,69 @@ impl From<WorkflowInputs> for Inputs {
         Self::Workflow(inputs)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::PrimitiveValue;
+
+    #[test]
+    fn hermetic_inputs() {
+        let dir = TempDir::new().unwrap();
+        let allowed = dir.path().join("allowed");
+        fs::create_dir(&allowed).unwrap();
+        fs::write(allowed.join("in.txt"), "").unwrap();
+        fs::write(dir.path().join("out.txt"), "").unwrap();
+
+        let config = HermeticConfig {
+            enabled: true,
+            allowed_roots: vec![allowed.clone()],
+        };
+
+        let mut inputs = TaskInputs::default();
+        inputs.set(
+            "inside",
+            PrimitiveValue::new_file(allowed.join("in.txt").to_str().unwrap()),
+        );
+        inputs.set(
+            "remote",
+            PrimitiveValue::new_file("https://example.com/in.txt"),
+        );
+        inputs.check_hermetic(&config).unwrap();
+
+        // A path that escapes the allowed root is not permitted
+        let escaped = allowed.join("..").join("out.txt");
+        inputs.set(
+            "escaped",
+            PrimitiveValue::new_file(escaped.to_str().unwrap()),
+        );
+        let error = inputs.check_hermetic(&config).unwrap_err().to_string();
+        assert_eq!(
+            error,
+            format!(
+                "hermetic evaluation requires `File` and `Directory` inputs to be remote URLs or \
+                 local paths within an allowed root (allowed roots are `{allowed}`):\n  input \
+                 `escaped` references `{escaped}`",
+                allowed = allowed.display(),
+                escaped = escaped.display()
+            )
+        );
+
+        // No local paths are permitted without allowed roots
+        let error = inputs
+            .check_hermetic(&HermeticConfig {
+                enabled: true,
+                allowed_roots: Vec::new(),
+            })
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("(no roots are allowed)"));
+        assert!(error.contains("input `inside`"));
+        assert!(!error.contains("input `remote`"));
+    }
+}
@@ -8,8 +8,12 @@ mod digest;
 mod eval;
 pub(crate) mod http;
 mod inputs;
+mod logs;
 mod outputs;
 pub mod path;
+mod provenance;
+mod sandbox;
+mod sizes;
 mod stdlib;
 pub(crate) mod tree;
 mod units;
@@ -21,6 +25,9 @@ pub use backend::*;
 pub use eval::*;
 pub use inputs::*;
 pub use outputs::*;
+pub use provenance::*;
+pub use sandbox::*;
+pub use sizes::*;
 use sysinfo::CpuRefreshKind;
 use sysinfo::MemoryRefreshKind;
 use sysinfo::System;
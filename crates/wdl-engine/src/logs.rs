@@ -0,0 +1,908 @@
+//! Implementation of task logs.
+//!
+//! The logs of a task may be forwarded to external sinks while the task
+//! executes and are downsampled once the task exits so that tasks that write
+//! excessive output do not fill the disk of the run directory.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::Result;
+use reqwest::header::CONTENT_TYPE;
+use serde::Serialize;
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncSeekExt;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+use url::Url;
+
+use crate::STDERR_FILE_NAME;
+use crate::STDOUT_FILE_NAME;
+use crate::config::LogSinkConfig;
+
+/// The interval at which the standard output and standard error of a task are
+/// checked for new lines.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The timeout for connecting to the endpoint of an HTTP log sink.
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The timeout for sending a request to the endpoint of an HTTP log sink.
+const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The maximum time to wait for the remaining lines of a task's logs to be
+/// forwarded once the task has exited.
+///
+/// Forwarding is best-effort so that an unresponsive sink cannot delay the
+/// completion of a task indefinitely.
+const FINISH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The size of the buffer used to copy and scan logs when downsampling.
+const DOWNSAMPLE_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Represents a line written by a task to one of its streams.
+#[derive(Debug, Serialize)]
+struct LogRecord<'a> {
+    /// The identifier of the task that wrote the line.
+    task: &'a str,
+    /// The attempt number of the task.
+    attempt: u64,
+    /// The name of the stream the line was written to.
+    stream: &'a str,
+    /// The line that was written.
+    line: &'a str,
+}
+
+impl std::fmt::Display for LogRecord<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{task} {stream}] {line}",
+            task = self.task,
+            stream = self.stream,
+            line = self.line
+        )
+    }
+}
+
+/// Formats the given records as lines of text.
+fn format_records(records: &[LogRecord<'_>]) -> String {
+    let mut buffer = String::new();
+    for record in records {
+        buffer.push_str(&record.to_string());
+        buffer.push('\n');
+    }
+
+    buffer
+}
+
+/// Represents an external sink for task logs.
+enum LogSink {
+    /// Appends the lines to a file.
+    File(Mutex<tokio::fs::File>),
+    /// Multiplexes the lines onto the standard error of the process.
+    Stderr,
+    /// Sends the lines to an HTTP endpoint.
+    Http {
+        /// The client to use for sending the lines.
+        client: reqwest::Client,
+        /// The URL of the endpoint.
+        url: Url,
+        /// Whether or not a failure to send has been reported.
+        ///
+        /// This prevents an unavailable endpoint from producing a warning each
+        /// time the logs are forwarded.
+        warned: AtomicBool,
+    },
+}
+
+impl LogSink {
+    /// Creates a new log sink from its configuration.
+    fn new(config: &LogSinkConfig) -> Result<Self> {
+        match config {
+            LogSinkConfig::File { path } => {
+                let file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| {
+                        format!("failed to open log file `{path}`", path = path.display())
+                    })?;
+                Ok(Self::File(Mutex::new(tokio::fs::File::from_std(file))))
+            }
+            LogSinkConfig::Stderr => Ok(Self::Stderr),
+            LogSinkConfig::Http { url } => Ok(Self::Http {
+                client: reqwest::Client::builder()
+                    .connect_timeout(HTTP_CONNECT_TIMEOUT)
+                    .timeout(HTTP_REQUEST_TIMEOUT)
+                    .build()
+                    .with_context(|| {
+                        format!("failed to create HTTP client for log sink `{url}`")
+                    })?,
+                url: url.clone(),
+                warned: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Writes the given records to the sink.
+    async fn write(&self, records: &[LogRecord<'_>]) {
+        match self {
+            Self::File(file) => {
+                let buffer = format_records(records);
+
+                // Write the records with a single call so that the lines of concurrently
+                // executing tasks are not interleaved
+                let mut file = file.lock().await;
+                let result = async {
+                    file.write_all(buffer.as_bytes()).await?;
+                    file.flush().await
+                };
+                if let Err(e) = result.await {
+                    warn!("failed to write task logs to file: {e}");
+                }
+            }
+            Self::Stderr => {
+                let buffer = format_records(records);
+
+                // Write through the progress bars of the process, if there are any, so
+                // that the lines are not overwritten by them
+                let _ = match tracing_indicatif::writer::get_indicatif_stderr_writer() {
+                    Some(mut writer) => writer.write_all(buffer.as_bytes()),
+                    None => std::io::stderr().lock().write_all(buffer.as_bytes()),
+                };
+            }
+            Self::Http {
+                client,
+                url,
+                warned,
+            } => {
+                let body = serde_json::to_vec(records).expect("records should serialize");
+                let result = client
+                    .post(url.clone())
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(body)
+                    .send()
+                    .await
+                    .and_then(|r| r.error_for_status());
+
+                if let Err(e) = result
+                    && !warned.swap(true, Ordering::SeqCst)
+                {
+                    warn!("failed to send task logs to `{url}`: {e}");
+                }
+            }
+        }
+    }
+}
+
+/// Represents the set of sinks that task logs are forwarded to.
+pub(crate) struct LogSinks(Vec<LogSink>);
+
+impl LogSinks {
+    /// Creates the log sinks from the given configuration.
+    ///
+    /// Returns `Ok(None)` if no sinks are configured.
+    pub(crate) fn new(config: &[LogSinkConfig]) -> Result<Option<Self>> {
+        if config.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(Self(
+            config.iter().map(LogSink::new).collect::<Result<_>>()?,
+        )))
+    }
+
+    /// Starts forwarding the logs of a task attempt to the sinks.
+    ///
+    /// The standard output and standard error files in the attempt directory
+    /// are periodically checked for new lines until the returned
+    /// [`LogForwarding`] is finished or dropped.
+    pub(crate) fn forward(
+        self: &Arc<Self>,
+        id: &str,
+        attempt: u64,
+        attempt_dir: &Path,
+    ) -> LogForwarding {
+        let sinks = self.clone();
+        let id = id.to_string();
+        let token = CancellationToken::new();
+        let cancelled = token.clone();
+        let mut stdout = Tail::new(attempt_dir.join(STDOUT_FILE_NAME));
+        let mut stderr = Tail::new(attempt_dir.join(STDERR_FILE_NAME));
+
+        let handle = tokio::spawn(async move {
+            loop {
+                let done = tokio::select! {
+                    _ = cancelled.cancelled() => true,
+                    _ = tokio::time::sleep(POLL_INTERVAL) => false,
+                };
+
+                for (stream, tail) in [
+                    (STDOUT_FILE_NAME, &mut stdout),
+                    (STDERR_FILE_NAME, &mut stderr),
+                ] {
+                    let lines = tail.read(done).await;
+                    if lines.is_empty() {
+                        continue;
+                    }
+
+                    let records = lines
+                        .iter()
+                        .map(|line| LogRecord {
+                            task: &id,
+                            attempt,
+                            stream,
+                            line,
+                        })
+                        .collect::<Vec<_>>();
+
+                    for sink in &sinks.0 {
+                        sink.write(&records).await;
+                    }
+                }
+
+                if done {
+                    break;
+                }
+            }
+        });
+
+        LogForwarding {
+            token,
+            handle: Some(handle),
+        }
+    }
+}
+
+/// Represents the forwarding of a task attempt's logs.
+///
+/// Dropping the forwarding stops it after forwarding any remaining lines.
+pub(crate) struct LogForwarding {
+    /// The token for stopping the forwarding.
+    token: CancellationToken,
+    /// The handle of the forwarding task.
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LogForwarding {
+    /// Stops the forwarding and waits for the remaining lines to be
+    /// forwarded.
+    ///
+    /// The forwarding is aborted if the remaining lines are not forwarded
+    /// within [`FINISH_TIMEOUT`].
+    pub(crate) async fn finish(mut self) {
+        self.token.cancel();
+        if let Some(mut handle) = self.handle.take()
+            && tokio::time::timeout(FINISH_TIMEOUT, &mut handle)
+                .await
+                .is_err()
+        {
+            warn!(
+                "timed out forwarding the remaining task logs after {secs} seconds",
+                secs = FINISH_TIMEOUT.as_secs()
+            );
+            handle.abort();
+        }
+    }
+}
+
+impl Drop for LogForwarding {
+    fn drop(&mut self) {
+        self.token.cancel();
+    }
+}
+
+/// Reads the lines appended to a file since it was last read.
+struct Tail {
+    /// The path to the file.
+    path: PathBuf,
+    /// The offset in the file to read from.
+    offset: u64,
+    /// The bytes after the last newline read from the file.
+    ///
+    /// The bytes are only decoded once a line is complete so that a multibyte
+    /// character split across reads is not replaced.
+    partial: Vec<u8>,
+}
+
+impl Tail {
+    /// Constructs a new tail for the given file path.
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            offset: 0,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Reads the complete lines appended to the file.
+    ///
+    /// If `last` is `true`, any trailing text without a newline is also
+    /// returned.
+    async fn read(&mut self, last: bool) -> Vec<String> {
+        if let Ok(mut file) = tokio::fs::File::open(&self.path).await
+            && file
+                .seek(std::io::SeekFrom::Start(self.offset))
+                .await
+                .is_ok()
+            && let Ok(read) = file.read_to_end(&mut self.partial).await
+        {
+            self.offset += read as u64;
+        }
+
+        let mut lines = Vec::new();
+        if let Some(end) = self.partial.iter().rposition(|b| *b == b'\n') {
+            let rest = self.partial.split_off(end + 1);
+            lines.extend(
+                String::from_utf8_lossy(&self.partial)
+                    .lines()
+                    .map(str::to_string),
+            );
+            self.partial = rest;
+        }
+
+        if last && !self.partial.is_empty() {
+            lines.push(String::from_utf8_lossy(&std::mem::take(&mut self.partial)).into_owned());
+        }
+
+        lines
+    }
+}
+
+/// Represents the limits on the size of a task's logs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LogLimit {
+    /// The number of bytes at the start of a log to retain.
+    pub head: u64,
+    /// The number of bytes at the end of a log to retain.
+    pub tail: u64,
+}
+
+/// Represents the lines dropped from a log by downsampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DroppedLines {
+    /// The number of lines that were dropped.
+    pub lines: u64,
+    /// The number of bytes that were dropped.
+    pub bytes: u64,
+}
+
+impl LogLimit {
+    /// Downsamples the log at the given path.
+    ///
+    /// A log larger than the sum of the limits is rewritten in place to its
+    /// first `head` bytes and its last `tail` bytes, separated by a line
+    /// stating how many lines were dropped. The retained bytes are adjusted
+    /// to line boundaries so that no partial lines are kept.
+    ///
+    /// Returns `Ok(None)` if the log does not exist or was not downsampled.
+    pub(crate) fn downsample(&self, path: &Path) -> Result<Option<DroppedLines>> {
+        let mut file = match OpenOptions::new().read(true).write(true).open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("failed to open log `{path}`", path = path.display())
+                });
+            }
+        };
+
+        self.downsample_file(&mut file)
+            .with_context(|| format!("failed to downsample log `{path}`", path = path.display()))
+    }
+
+    /// Downsamples the given log file.
+    fn downsample_file(&self, file: &mut File) -> std::io::Result<Option<DroppedLines>> {
+        let len = file.metadata()?.len();
+        if len <= self.head.saturating_add(self.tail) {
+            return Ok(None);
+        }
+
+        // End the head after its last newline and start the tail after its first
+        // newline; a head or tail without a newline is kept as is. The byte preceding
+        // the tail is also searched to determine if the tail already starts a line
+        let mut buffer = vec![0; DOWNSAMPLE_BUFFER_SIZE];
+        let head_newline = rfind_newline(file, 0, self.head, &mut buffer)?;
+        let head_end = head_newline.map(|i| i + 1).unwrap_or(self.head);
+        let tail_start = find_newline(file, len - self.tail - 1, len, &mut buffer)?
+            .map(|i| i + 1)
+            .unwrap_or(len - self.tail);
+
+        // Count the lines being dropped
+        let mut lines = 0;
+        let mut offset = head_end;
+        file.seek(SeekFrom::Start(offset))?;
+        while offset < tail_start {
+            let size = (tail_start - offset).min(buffer.len() as u64) as usize;
+            file.read_exact(&mut buffer[..size])?;
+            lines += count_newlines(&buffer[..size]);
+            offset += size as u64;
+        }
+
+        let dropped = DroppedLines {
+            lines,
+            bytes: tail_start - head_end,
+        };
+        let marker = marker(dropped, head_end > 0 && head_newline.is_none());
+
+        // Downsampling must shrink the log
+        if marker.len() as u64 >= dropped.bytes {
+            return Ok(None);
+        }
+
+        file.seek(SeekFrom::Start(head_end))?;
+        file.write_all(marker.as_bytes())?;
+
+        // Move the tail to follow the marker; the tail is copied front to back, so
+        // the copy never overwrites bytes that have yet to be read
+        let mut read = tail_start;
+        let mut write = head_end + marker.len() as u64;
+        while read < len {
+            let size = (len - read).min(buffer.len() as u64) as usize;
+            file.seek(SeekFrom::Start(read))?;
+            file.read_exact(&mut buffer[..size])?;
+            file.seek(SeekFrom::Start(write))?;
+            file.write_all(&buffer[..size])?;
+            read += size as u64;
+            write += size as u64;
+        }
+
+        file.set_len(write)?;
+        Ok(Some(dropped))
+    }
+}
+
+/// Counts the newlines in the given bytes.
+fn count_newlines(bytes: &[u8]) -> u64 {
+    bytes.iter().filter(|b| **b == b'\n').count() as u64
+}
+
+/// Formats the line that replaces the lines dropped from a log.
+///
+/// If `newline` is `true`, the line is preceded by a newline as the retained
+/// head does not end with one.
+fn marker(dropped: DroppedLines, newline: bool) -> String {
+    format!(
+        "{newline}... {lines} line{s} ({bytes} byte{bs}) dropped ...\n",
+        newline = if newline { "\n" } else { "" },
+        lines = dropped.lines,
+        s = if dropped.lines == 1 { "" } else { "s" },
+        bytes = dropped.bytes,
+        bs = if dropped.bytes == 1 { "" } else { "s" }
+    )
+}
+
+/// Finds the offset of the last newline in the given range of a file.
+///
+/// The range is read backwards in chunks so that the range need not fit in
+/// memory.
+fn rfind_newline(
+    file: &mut File,
+    start: u64,
+    end: u64,
+    buffer: &mut [u8],
+) -> std::io::Result<Option<u64>> {
+    let mut end = end;
+    while end > start {
+        let size = (end - start).min(buffer.len() as u64) as usize;
+        let offset = end - size as u64;
+        file.seek(SeekFrom::Start(offset))?;
+        file.read_exact(&mut buffer[..size])?;
+        if let Some(i) = buffer[..size].iter().rposition(|b| *b == b'\n') {
+            return Ok(Some(offset + i as u64));
+        }
+
+        end = offset;
+    }
+
+    Ok(None)
+}
+
+/// Finds the offset of the first newline in the given range of a file.
+///
+/// The range is read forwards in chunks so that the range need not fit in
+/// memory.
+fn find_newline(
+    file: &mut File,
+    start: u64,
+    end: u64,
+    buffer: &mut [u8],
+) -> std::io::Result<Option<u64>> {
+    let mut offset = start;
+    file.seek(SeekFrom::Start(offset))?;
+    while offset < end {
+        let size = (end - offset).min(buffer.len() as u64) as usize;
+        file.read_exact(&mut buffer[..size])?;
+        if let Some(i) = buffer[..size].iter().position(|b| *b == b'\n') {
+            return Ok(Some(offset + i as u64));
+        }
+
+        offset += size as u64;
+    }
+
+    Ok(None)
+}
+
+/// Copies a task's log from the given reader to a file while limiting its
+/// size.
+///
+/// Returns the lines that were dropped from the log, if any.
+pub(crate) async fn copy_limited(
+    mut reader: impl AsyncRead + Unpin,
+    file: File,
+    limit: LogLimit,
+) -> std::io::Result<Option<DroppedLines>> {
+    let mut log = LimitedLog::new(file, limit);
+    let mut buffer = vec![0; DOWNSAMPLE_BUFFER_SIZE];
+    loop {
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+
+        log.write(&buffer[..read])?;
+    }
+
+    log.finish()
+}
+
+/// Writes a task's log while limiting its size.
+///
+/// The first `head` bytes of the log are written as they are received; the
+/// bytes that follow are held in a buffer of the last `tail` bytes, counting
+/// the lines that are evicted from the buffer. When the log is finished, a
+/// line stating how many lines were dropped and the buffered tail are
+/// written. As with [`LogLimit::downsample`], the retained bytes are adjusted
+/// to line boundaries.
+///
+/// At most `head` plus `tail` bytes of a log are written to disk, so tasks
+/// that write excessive output do not fill the disk while they execute.
+pub(crate) struct LimitedLog<W> {
+    /// The writer of the log.
+    inner: W,
+    /// The limits on the size of the log.
+    limit: LogLimit,
+    /// The number of bytes of the head that have been received.
+    received: u64,
+    /// Whether or not a newline has been written as part of the head.
+    head_newline: bool,
+    /// The bytes of the head received after its last newline.
+    ///
+    /// The bytes are withheld until the line is complete so that a partial
+    /// line is not kept if the head is exceeded.
+    partial: Vec<u8>,
+    /// Whether or not the head has been exceeded.
+    exceeded: bool,
+    /// The last bytes received after the head was exceeded.
+    tail: VecDeque<u8>,
+    /// Whether or not the last byte evicted from the tail was a newline.
+    evicted_newline: bool,
+    /// The lines that have been dropped.
+    dropped: DroppedLines,
+}
+
+impl<W: Write> LimitedLog<W> {
+    /// Constructs a new limited log with the given writer.
+    pub(crate) fn new(inner: W, limit: LogLimit) -> Self {
+        Self {
+            inner,
+            limit,
+            received: 0,
+            head_newline: false,
+            partial: Vec::new(),
+            exceeded: false,
+            tail: VecDeque::new(),
+            evicted_newline: false,
+            dropped: DroppedLines { lines: 0, bytes: 0 },
+        }
+    }
+
+    /// Writes the given bytes to the log.
+    pub(crate) fn write(&mut self, mut bytes: &[u8]) -> std::io::Result<()> {
+        if !self.exceeded {
+            let remaining = (self.limit.head - self.received).min(bytes.len() as u64) as usize;
+            let (head, rest) = bytes.split_at(remaining);
+            match head.iter().rposition(|b| *b == b'\n') {
+                Some(i) => {
+                    self.inner.write_all(&self.partial)?;
+                    self.inner.write_all(&head[..=i])?;
+                    self.partial.clear();
+                    self.partial.extend_from_slice(&head[i + 1..]);
+                    self.head_newline = true;
+                }
+                None => self.partial.extend_from_slice(head),
+            }
+
+            self.received += head.len() as u64;
+            if rest.is_empty() {
+                return Ok(());
+            }
+
+            // The head is exceeded; a partial line at the end of the head is kept only
+            // if the head has no complete line
+            self.exceeded = true;
+            let partial = std::mem::take(&mut self.partial);
+            if self.head_newline {
+                self.push_tail(&partial);
+            } else {
+                self.inner.write_all(&partial)?;
+            }
+
+            bytes = rest;
+        }
+
+        self.push_tail(bytes);
+        Ok(())
+    }
+
+    /// Pushes bytes to the tail, evicting the bytes that exceed the limit.
+    fn push_tail(&mut self, bytes: &[u8]) {
+        self.tail.extend(bytes);
+        let excess = self.tail.len().saturating_sub(self.limit.tail as usize);
+        if excess > 0 {
+            let evicted = self.tail.drain(..excess).collect::<Vec<_>>();
+            self.dropped.lines += count_newlines(&evicted);
+            self.dropped.bytes += evicted.len() as u64;
+            self.evicted_newline = evicted.last() == Some(&b'\n');
+        }
+    }
+
+    /// Finishes the log by writing the retained tail.
+    ///
+    /// Returns the lines that were dropped from the log, if any.
+    pub(crate) fn finish(mut self) -> std::io::Result<Option<DroppedLines>> {
+        let partial = std::mem::take(&mut self.partial);
+        self.inner.write_all(&partial)?;
+
+        let mut tail = std::mem::take(&mut self.tail);
+        if self.dropped.bytes == 0 {
+            let (first, second) = tail.as_slices();
+            self.inner.write_all(first)?;
+            self.inner.write_all(second)?;
+            self.inner.flush()?;
+            return Ok(None);
+        }
+
+        // Start the tail after its first newline unless it already starts a line
+        if !self.evicted_newline
+            && let Some(i) = tail.iter().position(|b| *b == b'\n')
+        {
+            tail.drain(..=i);
+            self.dropped.lines += 1;
+            self.dropped.bytes += i as u64 + 1;
+        }
+
+        let newline = self.received > 0 && !self.head_newline;
+        self.inner
+            .write_all(marker(self.dropped, newline).as_bytes())?;
+        let (first, second) = tail.as_slices();
+        self.inner.write_all(first)?;
+        self.inner.write_all(second)?;
+        self.inner.flush()?;
+        Ok(Some(self.dropped))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn tail_lines() {
+        let dir = tempdir().expect("should create temp dir");
+        let path = dir.path().join("stdout");
+        let mut tail = Tail::new(path.clone());
+
+        // A missing file has no lines
+        assert!(tail.read(false).await.is_empty());
+
+        std::fs::write(&path, "first\nsec").unwrap();
+        assert_eq!(tail.read(false).await, ["first"]);
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        write!(file, "ond\nthird").unwrap();
+        assert_eq!(tail.read(false).await, ["second"]);
+        assert_eq!(tail.read(true).await, ["third"]);
+        assert!(tail.read(true).await.is_empty());
+    }
+
+    #[test]
+    fn downsample_log() {
+        let dir = tempdir().expect("should create temp dir");
+        let path = dir.path().join("stdout");
+        let log = (0..100)
+            .map(|i| format!("line {i:02}\n"))
+            .collect::<String>();
+        let limit = LogLimit { head: 18, tail: 16 };
+
+        // A missing log is not downsampled
+        assert_eq!(limit.downsample(&path).unwrap(), None);
+
+        // A log within the limits is not downsampled
+        std::fs::write(&path, &log[..32]).unwrap();
+        assert_eq!(limit.downsample(&path).unwrap(), None);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), &log[..32]);
+
+        // The head ends and the tail starts at line boundaries
+        std::fs::write(&path, &log).unwrap();
+        assert_eq!(
+            limit.downsample(&path).unwrap(),
+            Some(DroppedLines {
+                lines: 96,
+                bytes: 768
+            })
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "line 00\nline 01\n... 96 lines (768 bytes) dropped ...\nline 98\nline 99\n"
+        );
+    }
+
+    #[test]
+    fn limited_log() {
+        let log = (0..100)
+            .map(|i| format!("line {i:02}\n"))
+            .collect::<String>();
+        let limit = LogLimit { head: 18, tail: 16 };
+
+        // A log within the limits is written as is
+        let mut written = Vec::new();
+        let mut limited = LimitedLog::new(&mut written, limit);
+        for chunk in log.as_bytes()[..32].chunks(5) {
+            limited.write(chunk).unwrap();
+        }
+        assert_eq!(limited.finish().unwrap(), None);
+        assert_eq!(String::from_utf8(written).unwrap(), &log[..32]);
+
+        // The head ends and the tail starts at line boundaries
+        let mut written = Vec::new();
+        let mut limited = LimitedLog::new(&mut written, limit);
+        for chunk in log.as_bytes().chunks(7) {
+            limited.write(chunk).unwrap();
+        }
+        assert_eq!(
+            limited.finish().unwrap(),
+            Some(DroppedLines {
+                lines: 96,
+                bytes: 768
+            })
+        );
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            "line 00\nline 01\n... 96 lines (768 bytes) dropped ...\nline 98\nline 99\n"
+        );
+
+        // A head without a newline is kept as is
+        let mut written = Vec::new();
+        let mut limited = LimitedLog::new(&mut written, LogLimit { head: 4, tail: 4 });
+        limited.write(b"abcdefghij\nklm").unwrap();
+        assert_eq!(
+            limited.finish().unwrap(),
+            Some(DroppedLines { lines: 1, bytes: 7 })
+        );
+        assert_eq!(
+            String::from_utf8(written).unwrap(),
+            "abcd\n... 1 line (7 bytes) dropped ...\nklm"
+        );
+    }
+
+    #[tokio::test]
+    async fn tail_split_characters() {
+        let dir = tempdir().expect("should create temp dir");
+        let path = dir.path().join(STDOUT_FILE_NAME);
+        let mut tail = Tail::new(path.clone());
+
+        // Write a line with a multibyte character split across two writes
+        let line = "héllo wörld\n".as_bytes();
+        let (first, second) = line.split_at(line.len() - 5);
+        std::fs::write(&path, first).unwrap();
+        assert!(tail.read(false).await.is_empty());
+
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(second).unwrap();
+        file.write_all("ünfinished".as_bytes()).unwrap();
+        assert_eq!(tail.read(false).await, ["héllo wörld"]);
+        assert_eq!(tail.read(true).await, ["ünfinished"]);
+    }
+
+    #[tokio::test]
+    async fn forward_to_file() {
+        let dir = tempdir().expect("should create temp dir");
+        let log = dir.path().join("tasks.log");
+        let sinks = Arc::new(
+            LogSinks::new(&[LogSinkConfig::File { path: log.clone() }])
+                .unwrap()
+                .expect("should have sinks"),
+        );
+
+        std::fs::write(dir.path().join(STDOUT_FILE_NAME), "hello\nworld\n").unwrap();
+        std::fs::write(dir.path().join(STDERR_FILE_NAME), "oops").unwrap();
+
+        sinks.forward("hello-0", 0, dir.path()).finish().await;
+        assert_eq!(
+            std::fs::read_to_string(&log).unwrap(),
+            "[hello-0 stdout] hello\n[hello-0 stdout] world\n[hello-0 stderr] oops\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn forward_to_http() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{addr}/logs", addr = listener.local_addr().unwrap());
+
+        // Accept a single request and respond once its body has been read
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+            let (line, body) = loop {
+                let read = stream.read(&mut buffer).await.unwrap();
+                assert!(read > 0, "request should be complete");
+                request.extend_from_slice(&buffer[..read]);
+
+                let text = String::from_utf8_lossy(&request);
+                if let Some((head, body)) = text.split_once("\r\n\r\n") {
+                    let len: usize = head
+                        .lines()
+                        .find_map(|l| {
+                            let (name, value) = l.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse().unwrap())
+                        })
+                        .expect("request should have a content length");
+                    if body.len() >= len {
+                        break (head.lines().next().unwrap().to_string(), body.to_string());
+                    }
+                }
+            };
+
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            (line, body)
+        });
+
+        let dir = tempdir().expect("should create temp dir");
+        let sinks = Arc::new(
+            LogSinks::new(&[LogSinkConfig::Http {
+                url: url.parse().unwrap(),
+            }])
+            .unwrap()
+            .expect("should have sinks"),
+        );
+
+        std::fs::write(dir.path().join(STDOUT_FILE_NAME), "hello\nworld\n").unwrap();
+        sinks.forward("hello-0", 1, dir.path()).finish().await;
+
+        let (line, body) = server.await.unwrap();
+        assert_eq!(line, "POST /logs HTTP/1.1");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&body).unwrap(),
+            serde_json::json!([
+                { "task": "hello-0", "attempt": 1, "stream": "stdout", "line": "hello" },
+                { "task": "hello-0", "attempt": 1, "stream": "stdout", "line": "world" },
+            ])
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Implementation of output provenance.
+//!
+//! Provenance links each file output by a task or workflow back to the task
+//! execution that produced it. A provenance manifest is written next to the
+//! outputs file of every task and workflow.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use anyhow::Context;
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::Outputs;
+
+/// The name of the provenance manifest written for each task and workflow in
+/// the outputs directory.
+pub const PROVENANCE_FILE: &str = "provenance.json";
+
+/// Represents how an output file was produced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// The identifier of the call that produced the file.
+    call: String,
+    /// The name of the task that produced the file.
+    task: String,
+    /// The attempt of the task that produced the file.
+    attempt: u64,
+    /// Whether or not the task's result was reused from the call cache.
+    cached: bool,
+    /// The container image the task was executed in.
+    ///
+    /// This is `None` if the task was not executed in a container.
+    container: Option<String>,
+    /// The digest of the container image.
+    ///
+    /// This is `None` if the image reference is not pinned to a digest and the
+    /// backend did not resolve the digest.
+    digest: Option<String>,
+    /// The BLAKE3 hash of the evaluated command of the task, as hex.
+    command_hash: String,
+    /// The root directory of the task's evaluation.
+    directory: String,
+}
+
+impl Provenance {
+    /// Constructs a new provenance for a task execution.
+    ///
+    /// The digest of the container image is taken from the image reference if
+    /// it is pinned to a digest (e.g. `ubuntu@sha256:...`).
+    pub(crate) fn new(
+        call: impl Into<String>,
+        task: impl Into<String>,
+        attempt: u64,
+        cached: bool,
+        container: Option<String>,
+        command: &str,
+        directory: &Path,
+    ) -> Self {
+        let digest = container
+            .as_deref()
+            .and_then(|c| c.split_once('@'))
+            .map(|(_, digest)| digest.to_string());
+
+        Self {
+            call: call.into(),
+            task: task.into(),
+            attempt,
+            cached,
+            container,
+            digest,
+            command_hash: blake3::hash(command.as_bytes()).to_hex().to_string(),
+            directory: directory.display().to_string(),
+        }
+    }
+
+    /// Gets the identifier of the call that produced the file.
+    pub fn call(&self) -> &str {
+        &self.call
+    }
+
+    /// Sets the digest of the container image as resolved by the backend.
+    ///
+    /// The digest of a pinned image reference is kept if the backend did not
+    /// resolve the digest.
+    pub(crate) fn with_digest(mut self, digest: Option<String>) -> Self {
+        if digest.is_some() {
+            self.digest = digest;
+        }
+
+        self
+    }
+
+    /// Gets the name of the task that produced the file.
+    pub fn task(&self) -> &str {
+        &self.task
+    }
+
+    /// Gets the attempt of the task that produced the file.
+    pub fn attempt(&self) -> u64 {
+        self.attempt
+    }
+
+    /// Determines if the task's result was reused from the call cache.
+    pub fn cached(&self) -> bool {
+        self.cached
+    }
+
+    /// Gets the container image the task was executed in.
+    ///
+    /// Returns `None` if the task was not executed in a container.
+    pub fn container(&self) -> Option<&str> {
+        self.container.as_deref()
+    }
+
+    /// Gets the digest of the container image.
+    ///
+    /// Returns `None` if the digest of the image is unknown.
+    pub fn digest(&self) -> Option<&str> {
+        self.digest.as_deref()
+    }
+
+    /// Gets the BLAKE3 hash of the evaluated command of the task, as hex.
+    pub fn command_hash(&self) -> &str {
+        &self.command_hash
+    }
+
+    /// Gets the root directory of the task's evaluation.
+    pub fn directory(&self) -> &str {
+        &self.directory
+    }
+}
+
+/// Represents the provenance manifest of a task or workflow.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvenanceManifest {
+    /// The map of output file path to how it was produced.
+    outputs: BTreeMap<String, Provenance>,
+}
+
+impl ProvenanceManifest {
+    /// Reads a provenance manifest from the given path.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read file `{path}`", path = path.display()))?;
+        serde_json::from_str(&contents).with_context(|| {
+            format!(
+                "failed to parse provenance manifest `{path}`",
+                path = path.display()
+            )
+        })
+    }
+
+    /// Iterates over the output file paths and how they were produced.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Provenance)> + use<'_> {
+        self.outputs.iter().map(|(k, v)| (k.as_str(), v))
+    }
+
+    /// Gets how the given output file path was produced.
+    ///
+    /// If the path is not an output, the output directory containing the path
+    /// is looked up instead.
+    pub fn get(&self, path: &str) -> Option<(&str, &Provenance)> {
+        if let Some((path, provenance)) = self.outputs.get_key_value(path) {
+            return Some((path, provenance));
+        }
+
+        self.outputs
+            .iter()
+            .filter(|(dir, _)| {
+                path.strip_prefix(dir.as_str())
+                    .is_some_and(|rest| rest.starts_with(['/', '\\']))
+            })
+            .max_by_key(|(dir, _)| dir.len())
+            .map(|(dir, provenance)| (dir.as_str(), provenance))
+    }
+}
+
+/// Collects the paths of the files and directories of the given outputs.
+fn output_paths(outputs: &Outputs) -> Vec<String> {
+    let mut paths = Vec::new();
+    for (_, value) in outputs.iter() {
+        let _ = value.visit_paths(&mut |_, path| {
+            paths.push(path.as_str().to_string());
+            Ok(())
+        });
+    }
+
+    paths
+}
+
+/// Records how the output files of each task execution were produced.
+///
+/// The registry is shared by every evaluation of an execution so that the
+/// outputs of a workflow can be traced to the tasks of nested calls.
+#[derive(Debug, Default)]
+pub(crate) struct ProvenanceRegistry(Mutex<HashMap<String, Arc<Provenance>>>);
+
+impl ProvenanceRegistry {
+    /// Records the provenance of the files of the given task outputs.
+    ///
+    /// Returns the provenance manifest of the outputs.
+    pub fn record(&self, outputs: &Outputs, provenance: Provenance) -> ProvenanceManifest {
+        let provenance = Arc::new(provenance);
+        let mut manifest = ProvenanceManifest::default();
+        let mut registry = self.0.lock().expect("failed to lock provenance registry");
+        for path in output_paths(outputs) {
+            registry.insert(path.clone(), provenance.clone());
+            manifest.outputs.insert(path, provenance.as_ref().clone());
+        }
+
+        manifest
+    }
+
+    /// Gets the provenance manifest of the given workflow outputs.
+    ///
+    /// Files that were not produced by a task (e.g. files that were passed
+    /// through from the workflow's inputs) are not included.
+    pub fn manifest(&self, outputs: &Outputs) -> ProvenanceManifest {
+        let mut manifest = ProvenanceManifest::default();
+        let registry = self.0.lock().expect("failed to lock provenance registry");
+        for path in output_paths(outputs) {
+            if let Some(provenance) = registry.get(&path) {
+                manifest.outputs.insert(path, provenance.as_ref().clone());
+            }
+        }
+
+        manifest
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn container_digest() {
+        let provenance = Provenance::new(
+            "call",
+            "task",
+            0,
+            false,
+            Some("ubuntu@sha256:abc".to_string()),
+            "echo hi",
+            Path::new("/tmp"),
+        );
+        assert_eq!(provenance.digest(), Some("sha256:abc"));
+        assert_eq!(
+            provenance.command_hash(),
+            blake3::hash(b"echo hi").to_hex().as_str()
+        );
+
+        let provenance = Provenance::new(
+            "call",
+            "task",
+            0,
+            false,
+            Some("ubuntu:latest".to_string()),
+            "",
+            Path::new("/tmp"),
+        );
+        assert_eq!(provenance.digest(), None);
+
+        // The digest resolved by the backend is used for unpinned images
+        let provenance = provenance.with_digest(Some("sha256:def".to_string()));
+        assert_eq!(provenance.digest(), Some("sha256:def"));
+        let provenance = provenance.with_digest(None);
+        assert_eq!(provenance.digest(), Some("sha256:def"));
+    }
+
+    #[test]
+    fn manifest_lookup() {
+        let provenance = Provenance::new("call", "task", 1, false, None, "", Path::new("/tmp"));
+        let mut manifest = ProvenanceManifest::default();
+        manifest
+            .outputs
+            .insert("/runs/out.txt".to_string(), provenance.clone());
+        manifest
+            .outputs
+            .insert("/runs/dir".to_string(), provenance.clone());
+
+        assert_eq!(
+            manifest.get("/runs/out.txt"),
+            Some(("/runs/out.txt", &provenance))
+        );
+        assert_eq!(
+            manifest.get("/runs/dir/nested/file"),
+            Some(("/runs/dir", &provenance))
+        );
+        assert_eq!(manifest.get("/runs/directory"), None);
+        assert_eq!(manifest.get("/other"), None);
+    }
+}
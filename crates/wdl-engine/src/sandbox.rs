@@ -0,0 +1,283 @@
+//! Implementation of the evaluation sandbox policy.
+//!
+//! A sandbox policy restricts what expression evaluation may do, allowing
+//! embedders (e.g. a language server or a service that previews the values of
+//! declarations) to evaluate expressions of untrusted documents.
+
+use std::mem::size_of;
+
+use crate::CompoundValue;
+use crate::PrimitiveValue;
+use crate::Value;
+use crate::stdlib::CallArgument;
+
+/// The default maximum number of expression evaluation steps for untrusted
+/// documents.
+const DEFAULT_UNTRUSTED_MAX_STEPS: u64 = 100_000;
+
+/// The default maximum memory, in bytes, of a value evaluated for untrusted
+/// documents.
+const DEFAULT_UNTRUSTED_MAX_MEMORY: u64 = 16 * 1024 * 1024;
+
+/// The names of the standard library functions that access the file system.
+const IO_FUNCTIONS: &[&str] = &[
+    "glob",
+    "read_boolean",
+    "read_float",
+    "read_int",
+    "read_json",
+    "read_lines",
+    "read_map",
+    "read_object",
+    "read_objects",
+    "read_string",
+    "read_tsv",
+    "size",
+    "write_json",
+    "write_lines",
+    "write_map",
+    "write_object",
+    "write_objects",
+    "write_tsv",
+];
+
+/// Represents a policy that restricts expression evaluation.
+///
+/// The default policy places no restrictions on evaluation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandboxPolicy {
+    /// Whether or not standard library functions may access the file system.
+    io: bool,
+    /// The maximum number of expressions that may be evaluated.
+    max_steps: Option<u64>,
+    /// The maximum estimated memory, in bytes, of any evaluated value.
+    max_memory: Option<u64>,
+}
+
+impl Default for SandboxPolicy {
+    fn default() -> Self {
+        Self {
+            io: true,
+            max_steps: None,
+            max_memory: None,
+        }
+    }
+}
+
+impl SandboxPolicy {
+    /// Creates a policy suitable for evaluating expressions of untrusted
+    /// documents.
+    ///
+    /// The policy denies file system access and limits the number of
+    /// evaluation steps and the memory of evaluated values.
+    pub fn untrusted() -> Self {
+        Self {
+            io: false,
+            max_steps: Some(DEFAULT_UNTRUSTED_MAX_STEPS),
+            max_memory: Some(DEFAULT_UNTRUSTED_MAX_MEMORY),
+        }
+    }
+
+    /// Sets whether or not standard library functions may access the file
+    /// system.
+    pub fn with_io(mut self, io: bool) -> Self {
+        self.io = io;
+        self
+    }
+
+    /// Sets the maximum number of expressions that may be evaluated.
+    ///
+    /// Each expression, including each subexpression, counts as one step.
+    pub fn with_max_steps(mut self, max_steps: Option<u64>) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    /// Sets the maximum estimated memory, in bytes, of any evaluated value.
+    pub fn with_max_memory(mut self, max_memory: Option<u64>) -> Self {
+        self.max_memory = max_memory;
+        self
+    }
+
+    /// Gets whether or not standard library functions may access the file
+    /// system.
+    pub fn io(&self) -> bool {
+        self.io
+    }
+
+    /// Gets the maximum number of expressions that may be evaluated.
+    pub fn max_steps(&self) -> Option<u64> {
+        self.max_steps
+    }
+
+    /// Gets the maximum estimated memory, in bytes, of any evaluated value.
+    pub fn max_memory(&self) -> Option<u64> {
+        self.max_memory
+    }
+
+    /// Determines if the policy permits calling the given standard library
+    /// function.
+    pub(crate) fn permits_function(&self, name: &str) -> bool {
+        self.io || !IO_FUNCTIONS.contains(&name)
+    }
+}
+
+/// Estimates the memory, in bytes, used by a value.
+///
+/// Values shared between multiple containers are counted once per container,
+/// so the estimate is an upper bound.
+pub(crate) fn estimated_size(value: &Value) -> u64 {
+    let payload = match value {
+        Value::Primitive(PrimitiveValue::String(s)) => s.len(),
+        Value::Primitive(PrimitiveValue::File(p))
+        | Value::Primitive(PrimitiveValue::Directory(p)) => p.as_str().len(),
+        Value::Compound(CompoundValue::Pair(p)) => {
+            return size_of::<Value>() as u64
+                + estimated_size(p.left())
+                + estimated_size(p.right());
+        }
+        Value::Compound(CompoundValue::Array(a)) => {
+            return size_of::<Value>() as u64
+                + a.as_slice().iter().map(estimated_size).sum::<u64>();
+        }
+        Value::Compound(CompoundValue::Map(m)) => {
+            return size_of::<Value>() as u64
+                + m.iter()
+                    .map(|(k, v)| {
+                        k.clone()
+                            .map(|k| estimated_size(&k.into()))
+                            .unwrap_or_default()
+                            + estimated_size(v)
+                    })
+                    .sum::<u64>();
+        }
+        Value::Compound(CompoundValue::Object(o)) => {
+            return size_of::<Value>() as u64
+                + o.iter()
+                    .map(|(k, v)| k.len() as u64 + estimated_size(v))
+                    .sum::<u64>();
+        }
+        Value::Compound(CompoundValue::Struct(s)) => {
+            return size_of::<Value>() as u64
+                + s.iter()
+                    .map(|(k, v)| k.len() as u64 + estimated_size(v))
+                    .sum::<u64>();
+        }
+        _ => 0,
+    };
+
+    (size_of::<Value>() + payload) as u64
+}
+
+/// Estimates the memory, in bytes, of the value returned by a call to a
+/// standard library function before the call is made.
+///
+/// This prevents functions that produce values much larger than their
+/// arguments from allocating before the value can be checked.
+///
+/// Returns zero for functions whose result is not estimated.
+pub(crate) fn estimated_call_size(name: &str, arguments: &[CallArgument]) -> u64 {
+    let element = size_of::<Value>() as u64;
+    match (name, arguments) {
+        ("range", [n]) => n
+            .value()
+            .as_integer()
+            .map(|n| (n.max(0) as u64).saturating_mul(element))
+            .unwrap_or_default(),
+        ("cross", [a, b]) => match (a.value().as_array(), b.value().as_array()) {
+            (Some(a), Some(b)) => (a.len() as u64)
+                .saturating_mul(b.len() as u64)
+                .saturating_mul(element * 3),
+            _ => 0,
+        },
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use wdl_ast::version::V1;
+
+    use super::*;
+    use crate::v1::test::TestEnv;
+    use crate::v1::test::eval_v1_expr_with_sandbox;
+
+    #[test]
+    fn io_functions() {
+        let policy = SandboxPolicy::untrusted();
+        assert!(!policy.permits_function("read_string"));
+        assert!(!policy.permits_function("write_lines"));
+        assert!(policy.permits_function("length"));
+        assert!(SandboxPolicy::default().permits_function("read_string"));
+    }
+
+    #[tokio::test]
+    async fn sandboxed_evaluation() {
+        let env = TestEnv::default();
+        env.write_file("foo", "hello");
+
+        let diagnostic = eval_v1_expr_with_sandbox(
+            &env,
+            V1::Two,
+            "read_string('foo')",
+            SandboxPolicy::untrusted(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            diagnostic.message(),
+            "function `read_string` cannot be called because the evaluation sandbox does not \
+             permit file system access"
+        );
+
+        let value = eval_v1_expr_with_sandbox(
+            &env,
+            V1::Two,
+            "read_string('foo')",
+            SandboxPolicy::untrusted().with_io(true),
+        )
+        .await
+        .unwrap();
+        assert_eq!(value.unwrap_string().as_str(), "hello");
+
+        let diagnostic = eval_v1_expr_with_sandbox(
+            &env,
+            V1::Two,
+            "1 + 2 + 3 + 4",
+            SandboxPolicy::default().with_max_steps(Some(3)),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            diagnostic.message(),
+            "expression evaluation exceeded the sandbox limit of 3 steps"
+        );
+
+        let diagnostic = eval_v1_expr_with_sandbox(
+            &env,
+            V1::Two,
+            "range(1000000000)",
+            SandboxPolicy::default().with_max_memory(Some(1024)),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            diagnostic.message(),
+            "expression evaluation exceeded the sandbox memory limit of 1024 bytes"
+        );
+
+        let diagnostic = eval_v1_expr_with_sandbox(
+            &env,
+            V1::Two,
+            "['aaaaaaaaaaaaaaaa', 'bbbbbbbbbbbbbbbb']",
+            SandboxPolicy::default().with_max_memory(Some(64)),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            diagnostic.message(),
+            "expression evaluation exceeded the sandbox memory limit of 64 bytes"
+        );
+    }
+}
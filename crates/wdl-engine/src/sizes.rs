@@ -0,0 +1,279 @@
+//! Implementation of input and output size reporting.
+//!
+//! The total size of the files and directories localized for each task
+//! execution (i.e. its inputs) and delocalized from it (i.e. its outputs) is
+//! recorded. A sizes report is written next to the outputs file of every task
+//! and workflow.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::path::absolute;
+use std::sync::Mutex;
+
+use anyhow::Context;
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::Input;
+use crate::Outputs;
+use crate::path::parse_supported_url;
+
+/// The name of the sizes report written for each task and workflow in the
+/// outputs directory.
+pub const SIZES_FILE: &str = "sizes.json";
+
+/// Gets the size, in bytes, of a local file or directory.
+///
+/// The size of a directory is the sum of the sizes of the files it contains;
+/// symbolic links are not followed. Paths that cannot be read are treated as
+/// empty.
+fn local_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::metadata(path) else {
+        return 0;
+    };
+
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Gets the total size, in bytes, of the given local files and directories.
+///
+/// The file system is walked on a blocking thread so that the sizes of large
+/// directories do not stall the executor.
+async fn local_sizes(paths: Vec<PathBuf>) -> u64 {
+    if paths.is_empty() {
+        return 0;
+    }
+
+    tokio::task::spawn_blocking(move || paths.iter().map(|p| local_size(p)).sum())
+        .await
+        .expect("failed to join size calculation task")
+}
+
+/// Calculates the total size, in bytes, of the given task inputs.
+///
+/// Remote inputs are only counted once they have been downloaded.
+pub(crate) async fn inputs_size(inputs: &[Input]) -> u64 {
+    local_sizes(
+        inputs
+            .iter()
+            .filter_map(|i| i.local_path())
+            .map(Path::to_path_buf)
+            .collect(),
+    )
+    .await
+}
+
+/// Calculates the total size, in bytes, of the files and directories of the
+/// given outputs.
+///
+/// Outputs that are remote URLs are not counted.
+pub(crate) async fn outputs_size(outputs: &Outputs) -> u64 {
+    let mut paths = Vec::new();
+    for (_, value) in outputs.iter() {
+        let _ = value.visit_paths(&mut |_, path| {
+            if parse_supported_url(path.as_str()).is_none() {
+                paths.push(PathBuf::from(path.as_str()));
+            }
+
+            Ok(())
+        });
+    }
+
+    local_sizes(paths).await
+}
+
+/// Represents the sizes of the inputs and outputs of a task execution.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallSizes {
+    /// The identifier of the call.
+    call: String,
+    /// The root directory of the task's evaluation.
+    directory: String,
+    /// The total size, in bytes, of the localized inputs.
+    localized: u64,
+    /// The total size, in bytes, of the delocalized outputs.
+    delocalized: u64,
+}
+
+impl CallSizes {
+    /// Constructs a new sizes record for a task execution.
+    pub(crate) fn new(
+        call: impl Into<String>,
+        directory: &Path,
+        localized: u64,
+        delocalized: u64,
+    ) -> Self {
+        Self {
+            call: call.into(),
+            directory: directory.display().to_string(),
+            localized,
+            delocalized,
+        }
+    }
+
+    /// Gets the identifier of the call.
+    pub fn call(&self) -> &str {
+        &self.call
+    }
+
+    /// Gets the root directory of the task's evaluation.
+    pub fn directory(&self) -> &str {
+        &self.directory
+    }
+
+    /// Gets the total size, in bytes, of the localized inputs.
+    pub fn localized(&self) -> u64 {
+        self.localized
+    }
+
+    /// Gets the total size, in bytes, of the delocalized outputs.
+    pub fn delocalized(&self) -> u64 {
+        self.delocalized
+    }
+}
+
+/// Represents the sizes report of a task or workflow.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SizesReport {
+    /// The total size, in bytes, of the inputs localized for every call.
+    localized: u64,
+    /// The total size, in bytes, of the outputs delocalized from every call.
+    delocalized: u64,
+    /// The sizes of each call, ordered by directory.
+    calls: Vec<CallSizes>,
+}
+
+impl SizesReport {
+    /// Constructs a new sizes report from the given calls.
+    fn new(mut calls: Vec<CallSizes>) -> Self {
+        calls.sort_by(|a, b| a.directory.cmp(&b.directory));
+        Self {
+            localized: calls.iter().map(|c| c.localized).sum(),
+            delocalized: calls.iter().map(|c| c.delocalized).sum(),
+            calls,
+        }
+    }
+
+    /// Reads a sizes report from the given path.
+    pub fn read(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed to read file `{path}`", path = path.display()))?;
+        serde_json::from_str(&contents).with_context(|| {
+            format!(
+                "failed to parse sizes report `{path}`",
+                path = path.display()
+            )
+        })
+    }
+
+    /// Gets the total size, in bytes, of the inputs localized for every call.
+    pub fn localized(&self) -> u64 {
+        self.localized
+    }
+
+    /// Gets the total size, in bytes, of the outputs delocalized from every
+    /// call.
+    pub fn delocalized(&self) -> u64 {
+        self.delocalized
+    }
+
+    /// Gets the sizes of each call, ordered by directory.
+    pub fn calls(&self) -> &[CallSizes] {
+        &self.calls
+    }
+}
+
+/// Records the sizes of the inputs and outputs of each task execution.
+///
+/// The registry is shared by every evaluation of an execution so that the
+/// report of a workflow includes the calls of nested workflows.
+#[derive(Debug, Default)]
+pub(crate) struct SizesRegistry(Mutex<Vec<CallSizes>>);
+
+impl SizesRegistry {
+    /// Records the sizes of a task execution.
+    ///
+    /// Returns the sizes report of the task.
+    pub fn record(&self, sizes: CallSizes) -> SizesReport {
+        self.0
+            .lock()
+            .expect("failed to lock sizes registry")
+            .push(sizes.clone());
+        SizesReport::new(vec![sizes])
+    }
+
+    /// Gets the sizes report of the calls evaluated within the given workflow
+    /// evaluation directory.
+    pub fn report(&self, dir: &Path) -> SizesReport {
+        // The directories of task evaluations are always absolute
+        let dir = absolute(dir).unwrap_or_else(|_| dir.to_path_buf());
+        let calls = self.0.lock().expect("failed to lock sizes registry");
+        SizesReport::new(
+            calls
+                .iter()
+                .filter(|c| Path::new(&c.directory).starts_with(&dir))
+                .cloned()
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn local_sizes() {
+        let dir = TempDir::new().unwrap();
+        fs::write(dir.path().join("a.txt"), "hello").unwrap();
+        fs::create_dir_all(dir.path().join("nested")).unwrap();
+        fs::write(dir.path().join("nested").join("b.txt"), "world!").unwrap();
+
+        assert_eq!(local_size(&dir.path().join("a.txt")), 5);
+        assert_eq!(local_size(dir.path()), 11);
+        assert_eq!(local_size(&dir.path().join("missing")), 0);
+    }
+
+    #[test]
+    fn workflow_report() {
+        let dir = TempDir::new().unwrap();
+        let run = dir.path().join("run");
+        let calls = run.join("calls");
+
+        let registry = SizesRegistry::default();
+        let report = registry.record(CallSizes::new("b", &calls.join("b"), 10, 1));
+        assert_eq!(report.localized(), 10);
+        assert_eq!(report.calls().len(), 1);
+
+        registry.record(CallSizes::new("a", &calls.join("a"), 5, 2));
+        registry.record(CallSizes::new("c", &calls.join("sub/calls/c"), 1, 1));
+        registry.record(CallSizes::new("d", &dir.path().join("other"), 100, 100));
+
+        let report = registry.report(&run);
+        assert_eq!((report.localized(), report.delocalized()), (16, 4));
+        assert_eq!(
+            report.calls().iter().map(|c| c.call()).collect::<Vec<_>>(),
+            ["a", "b", "c"]
+        );
+
+        let report = registry.report(&calls.join("sub"));
+        assert_eq!(report.localized(), 1);
+    }
+}
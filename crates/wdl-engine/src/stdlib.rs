@@ -196,6 +196,11 @@ impl CallArgument {
             span: Span::new(0, 0),
         }
     }
+
+    /// Gets the value of the argument.
+    pub(crate) fn value(&self) -> &Value {
+        &self.value
+    }
 }
 
 /// Represents function call context.
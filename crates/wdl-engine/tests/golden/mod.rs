@@ -0,0 +1,239 @@
+//! Golden file assertions for the `wdl-engine` workflow integration tests.
+//!
+//! A test directory may contain an `assertions.yaml` file that describes
+//! assertions to make against the `File` outputs of a workflow, keyed by
+//! output name:
+//!
+//! ```yaml
+//! # Compares the output file to `golden/out.txt` exactly
+//! out:
+//!   golden: golden/out.txt
+//! # Checks the line count and BLAKE3 checksum of the output file
+//! counts:
+//!   lines: 3
+//!   checksum: 6d9c5c1a...
+//! # Compares the output TSV to a golden TSV, allowing numeric columns to
+//! # differ within a tolerance
+//! stats:
+//!   tsv:
+//!     golden: golden/stats.tsv
+//!     tolerance: 0.001
+//!     columns: [1, 2]
+//! ```
+//!
+//! Golden files are automatically generated or updated by setting the `BLESS`
+//! environment variable or by passing `--update-golden` to the test binary.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use indexmap::IndexMap;
+use pretty_assertions::StrComparison;
+use serde::Deserialize;
+use wdl_engine::Outputs;
+
+/// The name of the command line flag used to update golden files.
+pub const UPDATE_GOLDEN_FLAG: &str = "--update-golden";
+
+/// The name of the assertions file in a test directory.
+const ASSERTIONS_FILE: &str = "assertions.yaml";
+
+/// Whether or not golden files should be updated rather than compared.
+static UPDATE_GOLDEN: AtomicBool = AtomicBool::new(false);
+
+/// Sets whether or not golden files should be updated rather than compared.
+pub fn set_update_golden(update: bool) {
+    UPDATE_GOLDEN.store(update, Ordering::SeqCst);
+}
+
+/// Determines if golden files should be updated rather than compared.
+fn update_golden() -> bool {
+    UPDATE_GOLDEN.load(Ordering::SeqCst) || std::env::var_os("BLESS").is_some()
+}
+
+/// Represents a TSV comparison assertion.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TsvAssertion {
+    /// The path to the golden TSV file, relative to the test directory.
+    golden: PathBuf,
+    /// The absolute tolerance to use when comparing numeric values.
+    #[serde(default)]
+    tolerance: f64,
+    /// The zero-based indexes of the columns to compare with the tolerance.
+    ///
+    /// If not specified, every column containing numeric values is compared
+    /// with the tolerance.
+    #[serde(default)]
+    columns: Option<Vec<usize>>,
+}
+
+/// Represents the assertions to make for a single output.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OutputAssertion {
+    /// The path to a golden file to compare exactly against, relative to the
+    /// test directory.
+    #[serde(default)]
+    golden: Option<PathBuf>,
+    /// The expected BLAKE3 checksum of the file, as a hex string.
+    #[serde(default)]
+    checksum: Option<String>,
+    /// The expected number of lines in the file.
+    #[serde(default)]
+    lines: Option<usize>,
+    /// A TSV comparison to make against the file.
+    #[serde(default)]
+    tsv: Option<TsvAssertion>,
+}
+
+/// Updates a golden file with the contents of an output file.
+fn bless(output: &Path, golden: &Path) -> Result<()> {
+    if let Some(parent) = golden.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "failed to create directory `{path}`",
+                path = parent.display()
+            )
+        })?;
+    }
+
+    fs::copy(output, golden).with_context(|| {
+        format!(
+            "failed to copy `{output}` to `{golden}`",
+            output = output.display(),
+            golden = golden.display()
+        )
+    })?;
+
+    Ok(())
+}
+
+/// Reads a file as a string, normalizing line endings.
+fn read(path: &Path) -> Result<String> {
+    Ok(fs::read_to_string(path)
+        .with_context(|| format!("failed to read file `{path}`", path = path.display()))?
+        .replace("\r\n", "\n"))
+}
+
+/// Compares a TSV file against a golden TSV file.
+fn compare_tsv(name: &str, actual: &str, expected: &str, assertion: &TsvAssertion) -> Result<()> {
+    let actual = actual.lines().collect::<Vec<_>>();
+    let expected = expected.lines().collect::<Vec<_>>();
+    if actual.len() != expected.len() {
+        bail!(
+            "output `{name}` has {actual} row(s) but {expected} row(s) were expected",
+            actual = actual.len(),
+            expected = expected.len()
+        );
+    }
+
+    for (row, (actual, expected)) in actual.iter().zip(expected.iter()).enumerate() {
+        let actual = actual.split('\t').collect::<Vec<_>>();
+        let expected = expected.split('\t').collect::<Vec<_>>();
+        if actual.len() != expected.len() {
+            bail!(
+                "output `{name}` has {actual} column(s) in row {row} but {expected} column(s) \
+                 were expected",
+                actual = actual.len(),
+                expected = expected.len()
+            );
+        }
+
+        for (column, (a, e)) in actual.iter().zip(expected.iter()).enumerate() {
+            if a == e {
+                continue;
+            }
+
+            let tolerant = assertion
+                .columns
+                .as_ref()
+                .map(|c| c.contains(&column))
+                .unwrap_or(true);
+            match (tolerant, a.parse::<f64>(), e.parse::<f64>()) {
+                (true, Ok(a), Ok(e)) if (a - e).abs() <= assertion.tolerance => continue,
+                _ => bail!(
+                    "output `{name}` has value `{a}` at row {row}, column {column} but `{e}` was \
+                     expected (tolerance {tolerance})",
+                    tolerance = assertion.tolerance
+                ),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Asserts the outputs of a workflow against the assertions file in the given
+/// test directory.
+///
+/// It is not an error if the assertions file does not exist.
+pub fn assert_outputs(test: &Path, outputs: &Outputs) -> Result<()> {
+    let path = test.join(ASSERTIONS_FILE);
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let assertions: IndexMap<String, OutputAssertion> = serde_yaml_ng::from_str(&read(&path)?)
+        .with_context(|| format!("failed to parse `{path}`", path = path.display()))?;
+
+    for (name, assertion) in assertions {
+        let value = outputs
+            .get(&name)
+            .with_context(|| format!("workflow does not have an output named `{name}`"))?;
+        let Some(file) = value.as_file() else {
+            bail!("output `{name}` is not a file");
+        };
+        let file = Path::new(file.as_str());
+
+        if let Some(golden) = &assertion.golden {
+            let golden = test.join(golden);
+            if update_golden() {
+                bless(file, &golden)?;
+            } else {
+                let expected = read(&golden)?;
+                let actual = read(file)?;
+                if expected != actual {
+                    bail!(
+                        "output `{name}` does not match golden file `{path}`:\n{diff}",
+                        path = golden.display(),
+                        diff = StrComparison::new(&expected, &actual),
+                    );
+                }
+            }
+        }
+
+        if let Some(tsv) = &assertion.tsv {
+            let golden = test.join(&tsv.golden);
+            if update_golden() {
+                bless(file, &golden)?;
+            } else {
+                compare_tsv(&name, &read(file)?, &read(&golden)?, tsv)?;
+            }
+        }
+
+        if let Some(expected) = &assertion.checksum {
+            let bytes = fs::read(file)
+                .with_context(|| format!("failed to read file `{path}`", path = file.display()))?;
+            let actual = blake3::hash(&bytes).to_hex();
+            if !actual.as_str().eq_ignore_ascii_case(expected) {
+                bail!("output `{name}` has checksum `{actual}` but `{expected}` was expected");
+            }
+        }
+
+        if let Some(expected) = assertion.lines {
+            let actual = read(file)?.lines().count();
+            if actual != expected {
+                bail!("output `{name}` has {actual} line(s) but {expected} line(s) were expected");
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -10,6 +10,8 @@
 //! * `inputs.json` - the inputs to the task.
 //! * `outputs.json` - the expected outputs from the task, if the task runs
 //!   successfully.
+//! * `outputs.salvaged.json` - the expected outputs salvaged from the task, if
+//!   the task fails with output salvaging enabled (optional).
 //! * `stdout` - the expected stdout from the task.
 //! * `stderr` - the expected stderr from the task.
 //! * `files` - a directory containing any expected files written by the task.
@@ -173,6 +175,7 @@ fn run_test(test: &Path, config: TestConfig) -> BoxFuture<'_, Result<()>> {
                 let error = e.to_string();
                 let error = strip_paths(dir.path(), &error);
                 compare_result(&test.join("error.txt"), &error)?;
+                compare_salvaged_outputs(test, dir.path())?;
             }
         }
 
@@ -181,6 +184,34 @@ fn run_test(test: &Path, config: TestConfig) -> BoxFuture<'_, Result<()>> {
     .boxed()
 }
 
+/// Compares the outputs salvaged from a failed task against the baseline.
+///
+/// The comparison is only performed if the test has a salvaged outputs
+/// baseline.
+fn compare_salvaged_outputs(test: &Path, temp_dir: &Path) -> Result<()> {
+    let baseline = test.join("outputs.salvaged.json");
+    if !baseline.exists() {
+        return Ok(());
+    }
+
+    let path = temp_dir.join("outputs.salvaged.json");
+    let contents = fs::read_to_string(&path).with_context(|| {
+        format!(
+            "failed to read salvaged outputs file `{path}`",
+            path = path.display()
+        )
+    })?;
+    let salvaged: serde_json::Value =
+        serde_json::from_str(&contents).context("failed to deserialize salvaged outputs")?;
+    assert_eq!(
+        salvaged["salvaged"], true,
+        "outputs should be marked as salvaged"
+    );
+
+    let outputs = to_string_pretty(&salvaged["outputs"]).context("failed to serialize outputs")?;
+    compare_result(&baseline, &strip_paths(temp_dir, &outputs))
+}
+
 /// Compares the evaluation output files against the baselines.
 fn compare_evaluation_results(
     test_dir: &Path,
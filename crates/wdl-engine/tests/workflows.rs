@@ -10,9 +10,12 @@
 //! * `inputs.json` - the inputs to the workflow.
 //! * `outputs.json` - the expected outputs from the workflow, if the workflow
 //!   runs successfully.
+//! * `assertions.yaml` - optional assertions against the workflow's `File`
+//!   outputs (see the `golden` module).
 //!
 //! The expected files may be automatically generated or updated by setting the
-//! `BLESS` environment variable when running this test.
+//! `BLESS` environment variable when running this test; golden files may also
+//! be updated by passing `--update-golden` to the test binary.
 
 use std::env;
 use std::path::Path;
@@ -41,6 +44,7 @@ use wdl_engine::path::EvaluationPath;
 use wdl_engine::v1::TopLevelEvaluator;
 
 mod common;
+mod golden;
 
 /// Runs a single test.
 fn run_test(test: &Path, config: TestConfig) -> BoxFuture<'_, Result<()>> {
@@ -115,6 +119,7 @@ fn run_test(test: &Path, config: TestConfig) -> BoxFuture<'_, Result<()>> {
             .await
         {
             Ok(outputs) => {
+                golden::assert_outputs(test, &outputs)?;
                 let outputs = outputs.with_name(workflow.name());
                 let outputs = to_string_pretty(&outputs).context("failed to serialize outputs")?;
                 let outputs = strip_paths(dir.path(), &outputs);
@@ -143,7 +148,18 @@ fn main() -> Result<()> {
         )
         .init();
 
-    let args = libtest_mimic::Arguments::from_args();
+    // Remove the flag for updating golden files as it is not a libtest argument
+    let mut update_golden = false;
+    let args = libtest_mimic::Arguments::from_iter(env::args_os().filter(|arg| {
+        if arg == golden::UPDATE_GOLDEN_FLAG {
+            update_golden = true;
+            return false;
+        }
+
+        true
+    }));
+    golden::set_update_golden(update_golden);
+
     let runtime = tokio::runtime::Runtime::new()?;
     let tests = find_tests(
         run_test,
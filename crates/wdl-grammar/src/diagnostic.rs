@@ -283,6 +283,22 @@ impl Diagnostic {
         self.labels.iter()
     }
 
+    /// Compares two diagnostics by location for stable output.
+    ///
+    /// Diagnostics are ordered by the span of their primary label, then by
+    /// rule, and then by the remaining fields of the diagnostic; unlike the
+    /// [`Ord`] implementation, the rule is compared before the label messages.
+    ///
+    /// Diagnostics without labels are ordered before those with labels.
+    pub fn cmp_by_location(&self, other: &Self) -> Ordering {
+        self.labels
+            .first()
+            .map(Label::span)
+            .cmp(&other.labels.first().map(Label::span))
+            .then_with(|| self.rule.cmp(&other.rule))
+            .then_with(|| self.cmp(other))
+    }
+
     /// Gets the mutable labels of the diagnostic.
     pub fn labels_mut(&mut self) -> impl Iterator<Item = &mut Label> {
         self.labels.iter_mut()
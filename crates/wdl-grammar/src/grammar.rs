@@ -1,7 +1,9 @@
 //! Module for the WDL grammar functions.
 
 use super::Diagnostic;
+use super::Span;
 use super::lexer::PreambleToken;
+use super::lexer::v1::find_version_statement;
 use super::parser::Event;
 use super::parser::Parser;
 use super::tree::SyntaxKind;
@@ -96,6 +98,13 @@ pub fn document(mut parser: PreambleParser<'_>) -> (Vec<Event>, Vec<Diagnostic>)
             if let Some((_, span)) = found {
                 diagnostic =
                     diagnostic.with_label("a version statement must come before this", span);
+
+                // If the document has a version statement after other content, point at it
+                if let Some(version) = misplaced_version_statement(&parser, span.start()) {
+                    diagnostic = diagnostic
+                        .with_label("the version statement is here", version)
+                        .with_fix("move the version statement before any other content");
+                }
             }
 
             (parser, diagnostic)
@@ -112,6 +121,15 @@ pub fn document(mut parser: PreambleParser<'_>) -> (Vec<Event>, Vec<Diagnostic>)
     (output.events, output.diagnostics)
 }
 
+/// Finds a version statement that appears after the given offset in the
+/// source.
+///
+/// Returns the span of the statement's `version` keyword.
+fn misplaced_version_statement(parser: &PreambleParser<'_>, offset: usize) -> Option<Span> {
+    let source = parser.source(Span::new(offset, parser.source_len() - offset));
+    find_version_statement(source).map(|span| Span::new(span.start() + offset, span.len()))
+}
+
 /// Parses the version statement of a WDL source file.
 ///
 /// Returns a diagnostic upon failure.
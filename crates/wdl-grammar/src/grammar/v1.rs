@@ -17,6 +17,7 @@ use crate::parser::Event;
 use crate::parser::Interpolator;
 use crate::parser::Marker;
 use crate::parser::ParserToken;
+use crate::parser::duplicate_version_statement;
 use crate::parser::expected_found;
 use crate::parser::expected_one_of;
 use crate::parser::unmatched;
@@ -459,6 +460,7 @@ fn item(parser: &mut Parser<'_>, marker: Marker) -> Result<(), (Marker, Diagnost
         Some((Token::StructKeyword, _)) => struct_definition(parser, marker),
         Some((Token::TaskKeyword, _)) => task_definition(parser, marker),
         Some((Token::WorkflowKeyword, _)) => workflow_definition(parser, marker),
+        Some((Token::VersionKeyword, span)) => Err((marker, duplicate_version_statement(span))),
         found => {
             let (found, span) = found
                 .map(|(t, s)| (Some(t.describe()), s))
@@ -7,6 +7,8 @@ use crate::grammar::v1::HeredocContext;
 use crate::grammar::v1::interpolate_dq_string;
 use crate::grammar::v1::interpolate_heredoc;
 use crate::grammar::v1::interpolate_sq_string;
+use crate::lexer::Lexer;
+use crate::lexer::VersionStatementToken;
 use crate::parser::Parser;
 use crate::parser::ParserToken;
 use crate::tree::SyntaxKind;
@@ -736,12 +738,151 @@ impl<'a> ParserToken<'a> for Token {
     }
 }
 
+/// Implemented on the tokens of string and command literals.
+///
+/// This is used to skip over literals when scanning a token stream.
+trait LiteralToken {
+    /// Determines if the token ends the literal.
+    fn is_end(self) -> bool;
+
+    /// Determines if the token starts a placeholder in the literal.
+    fn is_placeholder_start(self) -> bool;
+}
+
+impl LiteralToken for SQStringToken {
+    fn is_end(self) -> bool {
+        self == Self::End
+    }
+
+    fn is_placeholder_start(self) -> bool {
+        self == Self::PlaceholderStart
+    }
+}
+
+impl LiteralToken for DQStringToken {
+    fn is_end(self) -> bool {
+        self == Self::End
+    }
+
+    fn is_placeholder_start(self) -> bool {
+        self == Self::PlaceholderStart
+    }
+}
+
+impl LiteralToken for HeredocToken {
+    fn is_end(self) -> bool {
+        self == Self::End
+    }
+
+    fn is_placeholder_start(self) -> bool {
+        // Both placeholder styles are treated as placeholders; in heredoc
+        // commands `${` is literal text, but scanning a balanced `${...}` as a
+        // placeholder skips the same text
+        matches!(
+            self,
+            Self::DollarPlaceholderStart | Self::TildePlaceholderStart
+        )
+    }
+}
+
+impl LiteralToken for BraceCommandToken {
+    fn is_end(self) -> bool {
+        self == Self::End
+    }
+
+    fn is_placeholder_start(self) -> bool {
+        self == Self::PlaceholderStart
+    }
+}
+
+/// Skips over a literal whose start token was just consumed from the lexer.
+///
+/// Returns `None` if the literal is not terminated.
+fn skip_literal<'a, T>(lexer: Lexer<'a, Token>) -> Option<Lexer<'a, Token>>
+where
+    T: LiteralToken + Logos<'a, Source = str, Error = (), Extras = ()> + Copy,
+{
+    let mut lexer = lexer.morph::<T>();
+    loop {
+        match lexer.next()? {
+            (Ok(t), _) if t.is_end() => return Some(lexer.morph()),
+            (Ok(t), _) if t.is_placeholder_start() => {
+                lexer = skip_placeholder(lexer.morph())?.morph();
+            }
+            _ => continue,
+        }
+    }
+}
+
+/// Skips over a placeholder whose start token was just consumed from the
+/// lexer.
+///
+/// Returns `None` if the placeholder is not terminated.
+fn skip_placeholder(mut lexer: Lexer<'_, Token>) -> Option<Lexer<'_, Token>> {
+    let mut depth = 0usize;
+    loop {
+        match lexer.next()?.0 {
+            Ok(Token::OpenBrace) => depth += 1,
+            Ok(Token::CloseBrace) if depth == 0 => return Some(lexer),
+            Ok(Token::CloseBrace) => depth -= 1,
+            Ok(Token::SingleQuote) => lexer = skip_literal::<SQStringToken>(lexer)?,
+            Ok(Token::DoubleQuote) => lexer = skip_literal::<DQStringToken>(lexer)?,
+            Ok(Token::OpenHeredoc) => lexer = skip_literal::<HeredocToken>(lexer)?,
+            _ => continue,
+        }
+    }
+}
+
+/// Finds the first version statement in the given WDL source that starts a
+/// line.
+///
+/// Unlike the preamble lexer, this scans the full token stream so that the
+/// contents of comments, strings, and commands are never mistaken for a
+/// version statement.
+///
+/// Returns the span of the statement's `version` keyword.
+pub fn find_version_statement(source: &str) -> Option<Span> {
+    let mut lexer = Lexer::<Token>::new(source);
+    let mut line_start = true;
+    let mut command = false;
+    loop {
+        let (token, span) = lexer.next()?;
+        match token {
+            Ok(Token::Whitespace) => {
+                line_start = line_start || lexer.source(span).contains('\n');
+                continue;
+            }
+            Ok(Token::Comment) => continue,
+            Ok(Token::VersionKeyword) if line_start => {
+                let mut rest = Lexer::<VersionStatementToken>::new(&source[span.end()..]);
+                if let (
+                    Some((Ok(VersionStatementToken::Whitespace), ws)),
+                    Some((Ok(VersionStatementToken::Version), _)),
+                ) = (rest.next(), rest.next())
+                    && !rest.source(ws).contains('\n')
+                {
+                    return Some(span);
+                }
+            }
+            Ok(Token::SingleQuote) => lexer = skip_literal::<SQStringToken>(lexer)?,
+            Ok(Token::DoubleQuote) => lexer = skip_literal::<DQStringToken>(lexer)?,
+            Ok(Token::OpenHeredoc) => lexer = skip_literal::<HeredocToken>(lexer)?,
+            Ok(Token::OpenBrace) if command => {
+                lexer = skip_literal::<BraceCommandToken>(lexer)?;
+            }
+            _ => {}
+        }
+
+        command = token == Ok(Token::CommandKeyword);
+        line_start = false;
+    }
+}
+
 #[cfg(test)]
 mod test {
     use pretty_assertions::assert_eq;
 
     use super::*;
-    use crate::lexer::Lexer;
     use crate::lexer::test::map;
 
     #[test]
@@ -1547,4 +1688,39 @@ env"#,
             ],
         );
     }
+
+    #[test]
+    fn version_statements() {
+        let source = r#"# version 1.0
+task foo {
+    command {
+version 1.0 ~{"}"}
+    }
+
+    command <<<
+version 1.0 ${x} ~{"}"}
+    >>>
+
+    meta {
+        version: "1.0"
+    }
+
+    String s = "
+version 1.0"
+}
+version 1.2
+"#;
+        let span = find_version_statement(source).expect("should find the statement");
+        assert_eq!(&source[span.start()..], "version 1.2\n");
+
+        assert_eq!(find_version_statement("task foo {}\n"), None);
+        assert_eq!(
+            find_version_statement("task foo { command { ~{x}\nversion 1.0 } }\n"),
+            None
+        );
+        assert_eq!(
+            find_version_statement("version 1.1\n").map(|s| s.start()..s.end()),
+            Some(0..7)
+        );
+    }
 }
@@ -143,6 +143,13 @@ pub(crate) fn unmatched(
         .with_label(format!("this {open} is not matched"), open_span)
 }
 
+/// Creates a "duplicate version statement" diagnostic error.
+pub(crate) fn duplicate_version_statement(span: Span) -> Diagnostic {
+    Diagnostic::error("a WDL document may only have one version statement")
+        .with_label("this version statement is a duplicate", span)
+        .with_fix("remove the duplicate version statement")
+}
+
 /// A trait implemented by parser tokens.
 pub trait ParserToken<'a>: Eq + Copy + Logos<'a, Source = str, Error = (), Extras = ()> {
     /// Converts the token into its syntax representation.
@@ -441,6 +448,11 @@ where
         self.lexer.as_ref().expect("expected a lexer").source(span)
     }
 
+    /// Gets the length of the source being parsed.
+    pub fn source_len(&self) -> usize {
+        self.lexer.as_ref().expect("expected a lexer").source_len()
+    }
+
     /// Peeks at the next token (i.e. lookahead 1) from the lexer without
     /// consuming it.
     ///
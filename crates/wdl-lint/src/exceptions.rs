@@ -0,0 +1,164 @@
+//! Implementation of inserting `#@ except` comments for rule violations.
+
+use std::collections::HashSet;
+
+use wdl_analysis::EXCEPT_COMMENT_PREFIX;
+use wdl_analysis::SyntaxNodeExt;
+use wdl_ast::Diagnostic;
+use wdl_ast::SyntaxKind;
+use wdl_ast::SyntaxNode;
+
+use crate::Rule;
+use crate::fix::Fixer;
+use crate::fix::InsertionPoint;
+use crate::fix::Replacement;
+
+/// Gets the indentation of the line containing the given offset.
+///
+/// Returns `None` if the offset is preceded on its line by anything other than
+/// whitespace.
+fn line_indent(source: &str, offset: usize) -> Option<&str> {
+    let line = source[..offset].rsplit('\n').next().unwrap_or_default();
+    line.chars().all(|c| c == ' ' || c == '\t').then_some(line)
+}
+
+/// Finds the node to except for a diagnostic at the given offset.
+///
+/// The node is the nearest enclosing node that is exceptable for the rule and
+/// that begins its own line.
+fn find_exceptable_node(
+    root: &SyntaxNode,
+    source: &str,
+    rule: &dyn Rule,
+    offset: usize,
+) -> Option<SyntaxNode> {
+    let token = root
+        .token_at_offset(offset.try_into().ok()?)
+        .right_biased()?;
+    let exceptable = rule.exceptable_nodes();
+    token.parent_ancestors().find(|node| {
+        node.kind() != SyntaxKind::RootNode
+            && exceptable.is_none_or(|kinds| kinds.contains(&node.kind()))
+            && line_indent(source, node.text_range().start().into()).is_some()
+    })
+}
+
+/// Adds `#@ except` comments for a rule above each of the given diagnostics.
+///
+/// Only diagnostics reported by the rule are considered. Each comment is
+/// placed above the nearest enclosing node that is exceptable for the rule and
+/// begins its own line; if that node is already preceded by a `#@ except`
+/// comment, the rule is appended to the existing comment instead.
+///
+/// Diagnostics that have no such node are ignored.
+///
+/// Returns the rewritten source and the number of nodes that were excepted.
+pub fn add_exceptions<'a>(
+    root: &SyntaxNode,
+    rule: &dyn Rule,
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+) -> (String, usize) {
+    let source = root.text().to_string();
+    let mut seen = HashSet::new();
+    let mut replacements = Vec::new();
+
+    for diagnostic in diagnostics {
+        if diagnostic.rule() != Some(rule.id()) {
+            continue;
+        }
+
+        let Some(label) = diagnostic.labels().next() else {
+            continue;
+        };
+
+        let Some(node) = find_exceptable_node(root, &source, rule, label.span().start()) else {
+            continue;
+        };
+
+        let start: usize = node.text_range().start().into();
+        if !seen.insert(start) {
+            continue;
+        }
+
+        let replacement = match node
+            .except_comments()
+            .find(|c| c.text().starts_with(EXCEPT_COMMENT_PREFIX))
+        {
+            Some(comment) => {
+                let end = comment.text_range().end().into();
+                Replacement::new(
+                    end,
+                    end,
+                    InsertionPoint::BeforeStart,
+                    format!(", {id}", id = rule.id()),
+                    0,
+                )
+            }
+            None => {
+                let indent = line_indent(&source, start).unwrap_or_default();
+                Replacement::new(
+                    start,
+                    start,
+                    InsertionPoint::BeforeStart,
+                    format!("{EXCEPT_COMMENT_PREFIX} {id}\n{indent}", id = rule.id()),
+                    0,
+                )
+            }
+        };
+
+        replacements.push(replacement);
+    }
+
+    let count = replacements.len();
+    let mut fixer = Fixer::new(source);
+    fixer.apply_replacements(replacements);
+    (fixer.value().to_string(), count)
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use wdl_ast::AstNode;
+    use wdl_ast::Document;
+
+    use super::*;
+    use crate::rules::DeprecatedObjectRule;
+
+    #[test]
+    fn it_adds_exceptions() {
+        let source = r#"version 1.1
+
+workflow test {
+    Object a = object { a: 1 }
+    #@ except: SnakeCase
+    Object b = object { b: 2 }
+}
+"#;
+        let (document, _) = Document::parse(source);
+        let rule = DeprecatedObjectRule::default();
+        let diagnostics = [
+            Diagnostic::note("first")
+                .with_rule(rule.id())
+                .with_highlight(wdl_ast::Span::new(33, 6)),
+            Diagnostic::note("second")
+                .with_rule(rule.id())
+                .with_highlight(wdl_ast::Span::new(89, 6)),
+            Diagnostic::note("other").with_highlight(wdl_ast::Span::new(33, 6)),
+        ];
+
+        let (result, count) = add_exceptions(document.inner(), &rule, &diagnostics);
+        assert_eq!(count, 2);
+        assert_eq!(
+            result,
+            r#"version 1.1
+
+workflow test {
+    #@ except: DeprecatedObject
+    Object a = object { a: 1 }
+    #@ except: SnakeCase, DeprecatedObject
+    Object b = object { b: 2 }
+}
+"#
+        );
+    }
+}
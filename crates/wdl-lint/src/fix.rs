@@ -4,6 +4,7 @@ use std::ops::Range;
 
 use ftree::FenwickTree;
 use serde::Deserialize;
+use wdl_ast::Span;
 
 /// An insertion point.
 #[derive(Copy, Clone, Debug, Deserialize)]
@@ -72,6 +73,162 @@ impl Replacement {
     pub fn precedence(&self) -> usize {
         self.precedence
     }
+
+    /// Determines if the replacement conflicts with another replacement.
+    ///
+    /// Replacements conflict if their ranges overlap or if one inserts at the
+    /// start of (or within) the range of the other, as the result would then
+    /// depend on the order in which they are applied.
+    pub fn conflicts_with(&self, other: &Self) -> bool {
+        if self.start == self.end || other.start == other.end {
+            let (point, range) = if self.start == self.end {
+                (self, other)
+            } else {
+                (other, self)
+            };
+
+            return range.start <= point.start && point.start < range.end.max(range.start + 1);
+        }
+
+        self.start < other.end && other.start < self.end
+    }
+}
+
+/// A fix for a diagnostic.
+///
+/// The replacements of a fix are applied together or not at all.
+#[derive(Clone, Debug)]
+pub struct Fix {
+    /// The identifier of the rule that reported the diagnostic.
+    rule: String,
+    /// The span of the diagnostic's primary label.
+    span: Span,
+    /// The replacements that fix the diagnostic.
+    replacements: Vec<Replacement>,
+}
+
+impl Fix {
+    /// Creates a new fix for a diagnostic.
+    pub fn new(rule: impl Into<String>, span: Span, replacements: Vec<Replacement>) -> Self {
+        Self {
+            rule: rule.into(),
+            span,
+            replacements,
+        }
+    }
+
+    /// The identifier of the rule that reported the diagnostic.
+    pub fn rule(&self) -> &str {
+        &self.rule
+    }
+
+    /// The span of the diagnostic's primary label.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// The replacements that fix the diagnostic.
+    pub fn replacements(&self) -> &[Replacement] {
+        &self.replacements
+    }
+
+    /// Determines if any replacement of the fix conflicts with a replacement
+    /// of another fix.
+    pub fn conflicts_with(&self, other: &Self) -> bool {
+        self.replacements
+            .iter()
+            .any(|a| other.replacements.iter().any(|b| a.conflicts_with(b)))
+    }
+}
+
+/// A fix that was skipped because it conflicts with an applied fix.
+#[derive(Clone, Debug)]
+pub struct Conflict {
+    /// The skipped fix.
+    fix: Fix,
+    /// The identifier of the rule of the applied fix it conflicts with.
+    with: String,
+}
+
+impl Conflict {
+    /// The skipped fix.
+    pub fn fix(&self) -> &Fix {
+        &self.fix
+    }
+
+    /// The identifier of the rule of the applied fix the skipped fix conflicts
+    /// with.
+    pub fn with(&self) -> &str {
+        &self.with
+    }
+}
+
+/// The result of merging the fixes for a document.
+#[derive(Clone, Debug, Default)]
+pub struct Merge {
+    /// The fixes that can be applied together.
+    applied: Vec<Fix>,
+    /// The fixes that were skipped due to conflicts.
+    skipped: Vec<Conflict>,
+}
+
+impl Merge {
+    /// The fixes that can be applied together.
+    pub fn applied(&self) -> &[Fix] {
+        &self.applied
+    }
+
+    /// The fixes that were skipped due to conflicts.
+    pub fn skipped(&self) -> &[Conflict] {
+        &self.skipped
+    }
+
+    /// Applies the merged fixes to the source they were created for.
+    pub fn apply(&self, source: String) -> String {
+        let mut fixer = Fixer::new(source);
+        fixer.apply_replacements(
+            self.applied
+                .iter()
+                .flat_map(|f| f.replacements.iter().cloned())
+                .collect(),
+        );
+        fixer.value
+    }
+}
+
+/// Merges the fixes for a document into a set of fixes that can be applied
+/// together.
+///
+/// Fixes are considered in order of the start of their diagnostic, with ties
+/// broken by rule identifier; a fix is skipped if it conflicts with a fix that
+/// was already accepted. Fixes without replacements are ignored.
+///
+/// Skipped fixes are typically resolved by linting the fixed document again,
+/// as their diagnostics are then reported against the new source.
+pub fn merge_fixes(fixes: impl IntoIterator<Item = Fix>) -> Merge {
+    let mut fixes: Vec<_> = fixes
+        .into_iter()
+        .filter(|f| !f.replacements.is_empty())
+        .collect();
+    fixes.sort_by(|a, b| {
+        a.span
+            .start()
+            .cmp(&b.span.start())
+            .then_with(|| a.rule.cmp(&b.rule))
+    });
+
+    let mut merge = Merge::default();
+    for fix in fixes {
+        match merge.applied.iter().find(|f| f.conflicts_with(&fix)) {
+            Some(applied) => {
+                let with = applied.rule.clone();
+                merge.skipped.push(Conflict { fix, with });
+            }
+            None => merge.applied.push(fix),
+        }
+    }
+
+    merge
 }
 
 // Adapted from ShellCheck's [Fixer](https://github.com/koalaman/shellcheck/blob/master/src/ShellCheck/Fixer.hs)
@@ -183,10 +340,39 @@ impl Fixer {
 #[cfg(test)]
 mod tests {
     use pretty_assertions::assert_eq;
+    use wdl_ast::AstNode;
+    use wdl_ast::Document;
+    use wdl_ast::Span;
 
+    use crate::Rule;
+    use crate::fix::Fix;
     use crate::fix::Fixer;
     use crate::fix::InsertionPoint;
     use crate::fix::Replacement;
+    use crate::fix::merge_fixes;
+    use crate::rules::DoubleQuotesRule;
+    use crate::rules::EndingNewlineRule;
+    use crate::rules::WhitespaceRule;
+    use crate::testing::lint;
+
+    /// Lints a document with the given rule and applies a single pass of the
+    /// fixes for its diagnostics.
+    async fn apply_fixes(source: &str, rule: fn() -> Box<dyn Rule>) -> String {
+        let diagnostics = lint(source, move || vec![rule()])
+            .await
+            .expect("should lint");
+        let (document, _) = Document::parse(source);
+        let rule = rule();
+        merge_fixes(diagnostics.iter().filter_map(|d| {
+            d.rule()?;
+            Some(Fix::new(
+                rule.id(),
+                d.labels().next()?.span(),
+                rule.replacements(document.inner(), d),
+            ))
+        }))
+        .apply(source.to_string())
+    }
 
     #[test]
     fn test_fixer_insertion() {
@@ -260,4 +446,103 @@ mod tests {
         let mut fixer = Fixer::new(value);
         fixer.apply_replacement(&rep);
     }
+
+    #[test]
+    fn test_replacement_conflicts() {
+        let rep =
+            |start, end| Replacement::new(start, end, InsertionPoint::BeforeStart, "".into(), 0);
+        assert!(rep(0, 5).conflicts_with(&rep(4, 8)));
+        assert!(!rep(0, 5).conflicts_with(&rep(5, 8)));
+        assert!(rep(0, 5).conflicts_with(&rep(0, 0)));
+        assert!(rep(0, 5).conflicts_with(&rep(3, 3)));
+        assert!(!rep(0, 5).conflicts_with(&rep(5, 5)));
+        assert!(rep(5, 5).conflicts_with(&rep(5, 5)));
+        assert!(!rep(4, 4).conflicts_with(&rep(5, 5)));
+    }
+
+    #[test]
+    fn test_merge_fixes() {
+        let value = String::from("a  b ");
+        let fix = |rule: &str, start, end, value: &str| {
+            Fix::new(
+                rule,
+                Span::new(start, end - start),
+                vec![Replacement::new(
+                    start,
+                    end,
+                    InsertionPoint::BeforeStart,
+                    value.into(),
+                    0,
+                )],
+            )
+        };
+
+        let merge = merge_fixes([
+            fix("Trailing", 4, 5, ""),
+            fix("Spacing", 1, 3, " "),
+            fix("Other", 2, 4, "-"),
+        ]);
+        assert_eq!(merge.applied().len(), 2);
+        assert_eq!(merge.skipped().len(), 1);
+        assert_eq!(merge.skipped()[0].fix().rule(), "Other");
+        assert_eq!(merge.skipped()[0].with(), "Spacing");
+        assert_eq!(merge.apply(value), "a b");
+    }
+
+    #[tokio::test]
+    async fn test_whitespace_replacements() {
+        let rule = || Box::new(WhitespaceRule::default()) as Box<dyn Rule>;
+        assert_eq!(
+            apply_fixes("version 1.2\n\nworkflow test {  \n}\n", rule).await,
+            "version 1.2\n\nworkflow test {\n}\n"
+        );
+        assert_eq!(
+            apply_fixes(
+                "version 1.2\n\nworkflow test {\n    Int a = 1\n    \n    Int b = 2\n}\n",
+                rule
+            )
+            .await,
+            "version 1.2\n\nworkflow test {\n    Int a = 1\n\n    Int b = 2\n}\n"
+        );
+        assert_eq!(
+            apply_fixes(
+                "version 1.2\n\nworkflow test {\n    Int a = 1\n\n\n\n    Int b = 2\n}\n",
+                rule
+            )
+            .await,
+            "version 1.2\n\nworkflow test {\n    Int a = 1\n\n    Int b = 2\n}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_double_quotes_replacements() {
+        // Strings containing a double quote are not fixed
+        assert_eq!(
+            apply_fixes(
+                "version 1.2\n\nworkflow test {\n    String a = 'hello'\n    String b = 'say \
+                 \"hi\"'\n}\n",
+                || Box::new(DoubleQuotesRule)
+            )
+            .await,
+            "version 1.2\n\nworkflow test {\n    String a = \"hello\"\n    String b = 'say \
+             \"hi\"'\n}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ending_newline_replacements() {
+        let rule = || Box::new(EndingNewlineRule) as Box<dyn Rule>;
+        assert_eq!(
+            apply_fixes("version 1.2\n\nworkflow test {\n}", rule).await,
+            "version 1.2\n\nworkflow test {\n}\n"
+        );
+        assert_eq!(
+            apply_fixes("version 1.2\n\nworkflow test {\n}  ", rule).await,
+            "version 1.2\n\nworkflow test {\n}  \n"
+        );
+        assert_eq!(
+            apply_fixes("version 1.2\n\nworkflow test {\n}\n\n\n", rule).await,
+            "version 1.2\n\nworkflow test {\n}\n"
+        );
+    }
 }
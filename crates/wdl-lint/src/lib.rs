@@ -24,14 +24,22 @@
 #![warn(rustdoc::broken_intra_doc_links)]
 
 use wdl_analysis::Visitor;
+use wdl_ast::Diagnostic;
+use wdl_ast::SupportedVersion;
 use wdl_ast::SyntaxKind;
+use wdl_ast::SyntaxNode;
 
-pub(crate) mod fix;
+mod exceptions;
+pub mod fix;
 mod linter;
+mod macros;
 pub mod rules;
 mod tags;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub(crate) mod util;
 
+pub use exceptions::*;
 pub use linter::*;
 pub use tags::*;
 pub use util::find_nearest_rule;
@@ -76,6 +84,25 @@ pub trait Rule: Visitor {
     /// relevant rules to the user based on potential logical connections or
     /// common co-occurrences of issues.
     fn related_rules(&self) -> &[&'static str];
+
+    /// Gets the minimum WDL version the lint rule applies to.
+    ///
+    /// The linter does not run the rule on documents with an earlier version.
+    ///
+    /// If `None` is returned, the rule applies to all versions.
+    fn min_version(&self) -> Option<SupportedVersion> {
+        None
+    }
+
+    /// Gets the replacements that fix a diagnostic reported by the lint rule.
+    ///
+    /// The replacements are relative to the text of the given document root.
+    ///
+    /// If an empty set of replacements is returned, the diagnostic cannot be
+    /// fixed automatically.
+    fn replacements(&self, _root: &SyntaxNode, _diagnostic: &Diagnostic) -> Vec<fix::Replacement> {
+        Vec::new()
+    }
 }
 
 /// Gets all of the lint rules.
@@ -127,6 +154,7 @@ pub fn rules() -> Vec<Box<dyn Rule>> {
         Box::<rules::ConciseInputRule>::default(),
         Box::<rules::ShellCheckRule>::default(),
         Box::<rules::DescriptionLengthRule>::default(),
+        Box::<rules::ScatterFileReadRule>::default(),
     ];
 
     // Ensure all the rule IDs are unique and pascal case and that related rules are
@@ -38,6 +38,8 @@ pub struct Linter {
     rules: IndexMap<&'static str, Box<dyn Rule>>,
     /// The set of rule ids that are disabled for the current document.
     document_exceptions: HashSet<String>,
+    /// The version of the current document.
+    version: Option<SupportedVersion>,
 }
 
 impl Linter {
@@ -46,6 +48,7 @@ impl Linter {
         Self {
             rules: rules.into_iter().map(|r| (r.id(), r)).collect(),
             document_exceptions: HashSet::default(),
+            version: None,
         }
     }
 
@@ -58,6 +61,13 @@ impl Linter {
             if self.document_exceptions.contains(id.to_owned()) {
                 continue;
             }
+
+            if let (Some(min), Some(version)) = (rule.min_version(), self.version)
+                && version < min
+            {
+                continue;
+            }
+
             cb(diagnostics, rule.as_mut());
         }
     }
@@ -68,6 +78,7 @@ impl Default for Linter {
         Self {
             rules: rules().into_iter().map(|r| (r.id(), r)).collect(),
             document_exceptions: HashSet::default(),
+            version: None,
         }
     }
 }
@@ -81,6 +92,7 @@ impl Visitor for Linter {
 
         // Reset the document exceptions
         self.document_exceptions.clear();
+        self.version = None;
     }
 
     fn document(
@@ -91,6 +103,7 @@ impl Visitor for Linter {
         version: SupportedVersion,
     ) {
         if reason == VisitReason::Enter {
+            self.version = Some(version);
             self.document_exceptions.extend(
                 doc.root()
                     .version_statement()
@@ -0,0 +1,183 @@
+//! Macros for implementing lint rules.
+
+/// Generates the boilerplate of a lint rule.
+///
+/// The macro defines the rule's struct, implements [`Rule`](crate::Rule) from
+/// the rule's metadata, and implements
+/// [`Visitor`](crate::analysis::Visitor) with the given visitor callbacks.
+///
+/// The generated struct tracks the document being linted and its version; they
+/// are available to visitor callbacks via `self.analyzed_document()` and
+/// `self.version()`, respectively. Any additional fields of the struct are
+/// reset along with the version at the start of each document, so the struct
+/// must implement `Default`.
+///
+/// An optional `min_version` may be specified after `related_rules`; the
+/// linter will not run the rule on documents with an earlier version.
+///
+/// # Examples
+///
+/// ```rust
+/// use wdl_lint::analysis::Diagnostics;
+/// use wdl_lint::analysis::VisitReason;
+/// use wdl_lint::ast::AstNode;
+/// use wdl_lint::ast::Diagnostic;
+/// use wdl_lint::ast::SyntaxElement;
+/// use wdl_lint::ast::v1::CallStatement;
+///
+/// wdl_lint::rule! {
+///     /// Detects calls without an alias.
+///     #[derive(Default, Debug, Clone)]
+///     pub struct CallAliasRule {
+///         /// The number of calls seen in the document.
+///         calls: usize,
+///     }
+///
+///     id: "CallAlias",
+///     description: "Ensures that calls have an alias.",
+///     explanation: "Aliasing calls makes the outputs of a workflow easier to follow.",
+///     tags: [Clarity],
+///     exceptable_nodes: [VersionStatementNode, CallStatementNode, WorkflowDefinitionNode],
+///     related_rules: [],
+///
+///     visitor {
+///         fn call_statement(
+///             &mut self,
+///             diagnostics: &mut Diagnostics,
+///             reason: VisitReason,
+///             call: &CallStatement,
+///         ) {
+///             if reason == VisitReason::Exit {
+///                 return;
+///             }
+///
+///             self.calls += 1;
+///             if call.alias().is_none() {
+///                 diagnostics.exceptable_add(
+///                     Diagnostic::note("call is missing an alias")
+///                         .with_rule("CallAlias")
+///                         .with_highlight(call.target().span()),
+///                     SyntaxElement::from(call.inner().clone()),
+///                     &wdl_lint::Rule::exceptable_nodes(self),
+///                 );
+///             }
+///         }
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! rule {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$field_attr:meta])*
+                $field:ident: $field_ty:ty
+            ),* $(,)?
+        }
+
+        id: $id:expr,
+        description: $description:expr,
+        explanation: $explanation:expr,
+        $(url: $url:expr,)?
+        tags: [$($tag:ident),* $(,)?],
+        exceptable_nodes: [$($node:ident),* $(,)?],
+        related_rules: [$($related:expr),* $(,)?],
+        $(min_version: $min_version:expr,)?
+
+        visitor {
+            $($visitor:tt)*
+        }
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            /// The document being linted.
+            document: ::std::option::Option<$crate::analysis::Document>,
+            /// The version of the document being linted.
+            version: ::std::option::Option<$crate::ast::SupportedVersion>,
+            $(
+                $(#[$field_attr])*
+                $field: $field_ty,
+            )*
+        }
+
+        impl $name {
+            /// Gets the document being linted.
+            ///
+            /// # Panics
+            ///
+            /// Panics if called before the document has been visited.
+            pub fn analyzed_document(&self) -> &$crate::analysis::Document {
+                self.document.as_ref().expect("document should have been visited")
+            }
+
+            /// Gets the version of the document being linted.
+            ///
+            /// # Panics
+            ///
+            /// Panics if called before the document has been visited.
+            pub fn version(&self) -> $crate::ast::SupportedVersion {
+                self.version.expect("document should have a version")
+            }
+        }
+
+        impl $crate::Rule for $name {
+            fn id(&self) -> &'static str {
+                $id
+            }
+
+            fn description(&self) -> &'static str {
+                $description
+            }
+
+            fn explanation(&self) -> &'static str {
+                $explanation
+            }
+
+            $(
+                fn url(&self) -> ::std::option::Option<&'static str> {
+                    ::std::option::Option::Some($url)
+                }
+            )?
+
+            fn tags(&self) -> $crate::TagSet {
+                $crate::TagSet::new(&[$($crate::Tag::$tag),*])
+            }
+
+            fn exceptable_nodes(&self) -> ::std::option::Option<&'static [$crate::ast::SyntaxKind]> {
+                ::std::option::Option::Some(&[$($crate::ast::SyntaxKind::$node),*])
+            }
+
+            fn related_rules(&self) -> &[&'static str] {
+                &[$($related),*]
+            }
+
+            $(
+                fn min_version(&self) -> ::std::option::Option<$crate::ast::SupportedVersion> {
+                    ::std::option::Option::Some($min_version)
+                }
+            )?
+        }
+
+        impl $crate::analysis::Visitor for $name {
+            fn reset(&mut self) {
+                *self = Self::default();
+            }
+
+            fn document(
+                &mut self,
+                _: &mut $crate::analysis::Diagnostics,
+                reason: $crate::analysis::VisitReason,
+                document: &$crate::analysis::Document,
+                version: $crate::ast::SupportedVersion,
+            ) {
+                if reason == $crate::analysis::VisitReason::Enter {
+                    self.document = ::std::option::Option::Some(document.clone());
+                    self.version = ::std::option::Option::Some(version);
+                }
+            }
+
+            $($visitor)*
+        }
+    };
+}
@@ -39,6 +39,7 @@ mod preamble_formatted;
 mod redundant_none;
 mod requirements_section;
 mod runtime_section;
+mod scatter_file_read;
 mod section_order;
 mod shellcheck;
 mod snake_case;
@@ -86,6 +87,7 @@ pub use preamble_formatted::*;
 pub use redundant_none::*;
 pub use requirements_section::*;
 pub use runtime_section::*;
+pub use scatter_file_read::*;
 pub use section_order::*;
 pub use shellcheck::*;
 pub use snake_case::*;
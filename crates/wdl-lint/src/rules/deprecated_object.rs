@@ -1,13 +1,20 @@
 //! A lint rule for flagging `Object`s as deprecated.
 
+use convert_case::Case;
+use convert_case::Casing;
 use wdl_analysis::Diagnostics;
 use wdl_analysis::VisitReason;
 use wdl_analysis::Visitor;
 use wdl_ast::AstNode;
+use wdl_ast::AstToken;
 use wdl_ast::Diagnostic;
 use wdl_ast::Span;
 use wdl_ast::SyntaxElement;
 use wdl_ast::SyntaxKind;
+use wdl_ast::SyntaxNode;
+use wdl_ast::v1::AccessExpr;
+use wdl_ast::v1::Expr;
+use wdl_ast::v1::LiteralExpr;
 use wdl_ast::v1::Type;
 
 use crate::Rule;
@@ -17,12 +24,96 @@ use crate::TagSet;
 /// The identifier for the deprecated object rule.
 const ID: &str = "DeprecatedObject";
 
+/// The deprecated standard library functions that read or write `Object`s
+/// along with their replacements.
+const OBJECT_FUNCTIONS: &[(&str, &str)] = &[
+    ("read_object", "read_json"),
+    ("read_objects", "read_json"),
+    ("write_object", "write_json"),
+    ("write_objects", "write_json"),
+];
+
 /// Creates a deprecated object use diagnostic.
-fn deprecated_object_use(span: Span) -> Diagnostic {
+///
+/// If the members of the object are known, the fix suggests a struct with
+/// those members.
+fn deprecated_object_use(span: Span, name: &str, members: &[(String, &str)]) -> Diagnostic {
+    let fix = if members.is_empty() {
+        "replace the `Object` with a `Map` or a `Struct`".to_string()
+    } else {
+        format!(
+            "replace the `Object` with a `Map` or a `Struct`, such as `struct {name} {{ {members} \
+             }}`",
+            name = name.to_case(Case::Pascal),
+            members = members
+                .iter()
+                .map(|(name, ty)| format!("{ty} {name}"))
+                .collect::<Vec<_>>()
+                .join(" ")
+        )
+    };
+
     Diagnostic::note(String::from("use of a deprecated `Object` type"))
         .with_rule(ID)
         .with_highlight(span)
-        .with_fix("replace the `Object` with a `Map` or a `Struct`")
+        .with_fix(fix)
+}
+
+/// Creates a deprecated object function use diagnostic.
+fn deprecated_object_function(function: &str, replacement: &str, span: Span) -> Diagnostic {
+    Diagnostic::note(format!("use of the deprecated `{function}` function"))
+        .with_rule(ID)
+        .with_highlight(span)
+        .with_fix(format!(
+            "replace the call with `{replacement}` using a `Map` or a `Struct`"
+        ))
+}
+
+/// Gets the type of a member of an object literal.
+///
+/// Members with non-literal values are assumed to be `String`.
+fn member_type(expr: &Expr) -> &'static str {
+    match expr {
+        Expr::Literal(LiteralExpr::Boolean(_)) => "Boolean",
+        Expr::Literal(LiteralExpr::Integer(_)) => "Int",
+        Expr::Literal(LiteralExpr::Float(_)) => "Float",
+        _ => "String",
+    }
+}
+
+/// Infers the members of an `Object` declaration.
+///
+/// The members are taken from the declaration's object literal, if any, and
+/// from accesses of the declaration within its enclosing task or workflow.
+fn members(decl: &SyntaxNode, name: &str, expr: Option<Expr>) -> Vec<(String, &'static str)> {
+    let mut members = Vec::new();
+    if let Some(Expr::Literal(LiteralExpr::Object(object))) = expr {
+        for item in object.items() {
+            let (member, value) = item.name_value();
+            members.push((member.text().to_string(), member_type(&value)));
+        }
+    }
+
+    let Some(definition) = decl.ancestors().find(|n| {
+        matches!(
+            n.kind(),
+            SyntaxKind::TaskDefinitionNode | SyntaxKind::WorkflowDefinitionNode
+        )
+    }) else {
+        return members;
+    };
+
+    for access in definition.descendants().filter_map(AccessExpr::cast) {
+        let (operand, member) = access.operands();
+        if let Expr::NameRef(r) = operand
+            && r.name().text() == name
+            && !members.iter().any(|(m, _)| m == member.text())
+        {
+            members.push((member.text().to_string(), "String"));
+        }
+    }
+
+    members
 }
 
 /// Detects the use of the deprecated `Object` types.
@@ -35,7 +126,7 @@ impl Rule for DeprecatedObjectRule {
     }
 
     fn description(&self) -> &'static str {
-        "Ensures that the deprecated `Object` types are not used."
+        "Ensures that the deprecated `Object` types and functions are not used."
     }
 
     fn explanation(&self) -> &'static str {
@@ -46,6 +137,9 @@ impl Rule for DeprecatedObjectRule {
          the time of the v1.0 release, the type was not removed. It was later decided \
          that `Object`s overlapped with `Map`s and `Struct`s in functionality, and the type was marked for removal.
 
+         The `read_object`, `read_objects`, `write_object`, and `write_objects` functions are \
+         deprecated along with the type; `read_json` and `write_json` should be used instead.
+
          See this issue for more details: https://github.com/openwdl/wdl/pull/228."
     }
 
@@ -84,8 +178,10 @@ impl Visitor for DeprecatedObjectRule {
         }
 
         if let Type::Object(ty) = decl.ty() {
+            let name = decl.name();
+            let members = members(decl.inner(), name.text(), Some(decl.expr()));
             diagnostics.exceptable_add(
-                deprecated_object_use(ty.span()),
+                deprecated_object_use(ty.span(), name.text(), &members),
                 SyntaxElement::from(decl.inner().clone()),
                 &self.exceptable_nodes(),
             )
@@ -103,11 +199,33 @@ impl Visitor for DeprecatedObjectRule {
         }
 
         if let Type::Object(ty) = decl.ty() {
+            let name = decl.name();
+            let members = members(decl.inner(), name.text(), None);
             diagnostics.exceptable_add(
-                deprecated_object_use(ty.span()),
+                deprecated_object_use(ty.span(), name.text(), &members),
                 SyntaxElement::from(decl.inner().clone()),
                 &self.exceptable_nodes(),
             )
         }
     }
+
+    fn expr(&mut self, diagnostics: &mut Diagnostics, reason: VisitReason, expr: &Expr) {
+        if reason == VisitReason::Exit {
+            return;
+        }
+
+        if let Expr::Call(call) = expr {
+            let target = call.target();
+            if let Some((_, replacement)) = OBJECT_FUNCTIONS
+                .iter()
+                .find(|(function, _)| *function == target.text())
+            {
+                diagnostics.exceptable_add(
+                    deprecated_object_function(target.text(), replacement, target.span()),
+                    SyntaxElement::from(call.inner().clone()),
+                    &self.exceptable_nodes(),
+                )
+            }
+        }
+    }
 }
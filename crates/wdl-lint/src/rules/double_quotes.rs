@@ -8,6 +8,7 @@ use wdl_ast::Diagnostic;
 use wdl_ast::Span;
 use wdl_ast::SyntaxElement;
 use wdl_ast::SyntaxKind;
+use wdl_ast::SyntaxNode;
 use wdl_ast::v1::Expr;
 use wdl_ast::v1::LiteralExpr;
 use wdl_ast::v1::LiteralStringKind;
@@ -15,6 +16,8 @@ use wdl_ast::v1::LiteralStringKind;
 use crate::Rule;
 use crate::Tag;
 use crate::TagSet;
+use crate::fix::InsertionPoint;
+use crate::fix::Replacement;
 
 /// The identifier for the double quotes rule.
 const ID: &str = "DoubleQuotes";
@@ -66,6 +69,31 @@ impl Rule for DoubleQuotesRule {
     fn related_rules(&self) -> &[&'static str] {
         &[]
     }
+
+    fn replacements(&self, root: &SyntaxNode, diagnostic: &Diagnostic) -> Vec<Replacement> {
+        let Some(span) = diagnostic.labels().next().map(|l| l.span()) else {
+            return Vec::new();
+        };
+
+        // Only the quotes are replaced, so strings that contain a double quote are
+        // not fixed as the quote would need to be escaped
+        let source = root.text().to_string();
+        match source.get(span.start()..span.end()) {
+            Some(text) if text.len() >= 2 && !text.contains('"') => [span.start(), span.end() - 1]
+                .into_iter()
+                .map(|offset| {
+                    Replacement::new(
+                        offset,
+                        offset + 1,
+                        InsertionPoint::BeforeStart,
+                        "\"".to_string(),
+                        0,
+                    )
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 impl Visitor for DoubleQuotesRule {
@@ -9,10 +9,13 @@ use wdl_ast::Diagnostic;
 use wdl_ast::Span;
 use wdl_ast::SupportedVersion;
 use wdl_ast::SyntaxKind;
+use wdl_ast::SyntaxNode;
 
 use crate::Rule;
 use crate::Tag;
 use crate::TagSet;
+use crate::fix::InsertionPoint;
+use crate::fix::Replacement;
 use crate::util::strip_newline;
 
 /// The identifier for the ending newline rule.
@@ -69,6 +72,37 @@ impl Rule for EndingNewlineRule {
     fn related_rules(&self) -> &[&'static str] {
         &[]
     }
+
+    fn replacements(&self, root: &SyntaxNode, diagnostic: &Diagnostic) -> Vec<Replacement> {
+        let Some(span) = diagnostic.labels().next().map(|l| l.span()) else {
+            return Vec::new();
+        };
+
+        let source = root.text().to_string();
+        let Some(text) = source.get(span.start()..span.end()) else {
+            return Vec::new();
+        };
+
+        // Duplicate newlines are labeled and removed; otherwise, the last character
+        // of the document is labeled and a newline is inserted after it
+        if !text.chars().all(|c| c == '\n' || c == '\r') {
+            vec![Replacement::new(
+                span.end(),
+                span.end(),
+                InsertionPoint::BeforeStart,
+                "\n".to_string(),
+                0,
+            )]
+        } else {
+            vec![Replacement::new(
+                span.start(),
+                span.end(),
+                InsertionPoint::BeforeStart,
+                String::new(),
+                0,
+            )]
+        }
+    }
 }
 
 impl Visitor for EndingNewlineRule {
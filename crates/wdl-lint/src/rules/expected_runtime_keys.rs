@@ -31,6 +31,7 @@ use wdl_ast::v1::TASK_HINT_OUTPUTS;
 use wdl_ast::v1::TASK_HINT_SHORT_TASK_ALIAS;
 use wdl_ast::v1::TASK_REQUIREMENT_CONTAINER;
 use wdl_ast::v1::TASK_REQUIREMENT_CONTAINER_ALIAS;
+use wdl_ast::v1::TASK_REQUIREMENT_CONTINUE_ON_RETURN_CODE;
 use wdl_ast::v1::TASK_REQUIREMENT_CPU;
 use wdl_ast::v1::TASK_REQUIREMENT_DISKS;
 use wdl_ast::v1::TASK_REQUIREMENT_GPU;
@@ -118,6 +119,10 @@ fn keys_v1_1() -> &'static HashMap<&'static str, KeyKind> {
             TASK_REQUIREMENT_RETURN_CODES_ALIAS,
             KeyKind::ReservedMandatory,
         );
+        keys.insert(
+            TASK_REQUIREMENT_CONTINUE_ON_RETURN_CODE,
+            KeyKind::Deprecated(TASK_REQUIREMENT_RETURN_CODES_ALIAS),
+        );
         keys.insert(TASK_HINT_MAX_CPU_ALIAS, KeyKind::ReservedHint);
         keys.insert(TASK_HINT_MAX_MEMORY_ALIAS, KeyKind::ReservedHint);
         keys.insert(TASK_HINT_SHORT_TASK_ALIAS, KeyKind::ReservedHint);
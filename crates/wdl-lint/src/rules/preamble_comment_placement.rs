@@ -22,7 +22,56 @@ fn preamble_comment_outside_preamble(span: Span) -> Diagnostic {
     Diagnostic::note("preamble comment after the version statement")
         .with_rule(ID)
         .with_highlight(span)
-        .with_fix("do not use `##` comments outside the preamble")
+        .with_fix(
+            "do not use `##` comments outside the preamble except immediately before a task, \
+             workflow, struct, or declaration",
+        )
+}
+
+/// The kinds of nodes that may have documentation comments.
+const DOCUMENTED_KINDS: &[SyntaxKind] = &[
+    SyntaxKind::TaskDefinitionNode,
+    SyntaxKind::WorkflowDefinitionNode,
+    SyntaxKind::StructDefinitionNode,
+    SyntaxKind::BoundDeclNode,
+    SyntaxKind::UnboundDeclNode,
+];
+
+/// Determines if a comment is part of a documentation comment.
+///
+/// A documentation comment is a block of full-line `##` comments that
+/// immediately precedes a task, workflow, struct, or declaration.
+fn is_doc_comment(comment: &Comment) -> bool {
+    let at_line_start = comment
+        .inner()
+        .prev_sibling_or_token()
+        .is_none_or(|s| s.kind() == SyntaxKind::Whitespace && s.to_string().contains('\n'));
+    if !at_line_start {
+        return false;
+    }
+
+    let mut current = comment.inner().next_sibling_or_token();
+    while let Some(sibling) = current {
+        match &sibling {
+            SyntaxElement::Token(t) if t.kind() == SyntaxKind::Whitespace => {
+                if t.text().matches('\n').count() > 1 {
+                    return false;
+                }
+            }
+            SyntaxElement::Token(t) if t.kind() == SyntaxKind::Comment => {
+                let text = t.text();
+                if text != "##" && !text.starts_with("## ") && !text.starts_with("#@") {
+                    return false;
+                }
+            }
+            SyntaxElement::Node(n) => return DOCUMENTED_KINDS.contains(&n.kind()),
+            SyntaxElement::Token(_) => return false,
+        }
+
+        current = sibling.next_sibling_or_token();
+    }
+
+    false
 }
 
 /// A lint rule for flagging preamble comments which are outside the preamble.
@@ -48,7 +97,9 @@ impl Rule for PreambleCommentPlacementRule {
 
     fn explanation(&self) -> &'static str {
         "Preamble comments should only appear in the preamble section of a WDL document. This rule \
-         ensures that double-pound comments (`##`) are not used after the version statement."
+         ensures that double-pound comments (`##`) are not used after the version statement, \
+         except as documentation comments placed immediately before a task, workflow, struct, or \
+         declaration."
     }
 
     fn tags(&self) -> TagSet {
@@ -90,7 +141,7 @@ impl Visitor for PreambleCommentPlacementRule {
             return;
         }
 
-        if !comment.text().starts_with("## ") {
+        if !comment.text().starts_with("## ") || is_doc_comment(comment) {
             return;
         }
 
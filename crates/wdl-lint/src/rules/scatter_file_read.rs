@@ -0,0 +1,251 @@
+//! A lint rule for reading task output files in workflow scatter bodies.
+
+use std::collections::HashSet;
+
+use wdl_analysis::Diagnostics;
+use wdl_analysis::Document;
+use wdl_analysis::VisitReason;
+use wdl_analysis::types::CompoundType;
+use wdl_analysis::types::PrimitiveType;
+use wdl_analysis::types::Type;
+use wdl_ast::AstNode;
+use wdl_ast::AstToken;
+use wdl_ast::Diagnostic;
+use wdl_ast::Span;
+use wdl_ast::SyntaxElement;
+use wdl_ast::SyntaxNode;
+use wdl_ast::v1::BoundDecl;
+use wdl_ast::v1::CallExpr;
+use wdl_ast::v1::CallStatement;
+use wdl_ast::v1::Expr;
+use wdl_ast::v1::NameRefExpr;
+use wdl_ast::v1::ScatterStatement;
+use wdl_ast::v1::WorkflowDefinition;
+
+use crate::Rule;
+
+/// The identifier for this rule.
+const ID: &str = "ScatterFileRead";
+
+/// The standard library functions that read and parse a file.
+const READ_FUNCTIONS: &[&str] = &[
+    "read_json",
+    "read_lines",
+    "read_map",
+    "read_object",
+    "read_objects",
+    "read_tsv",
+];
+
+/// Creates a diagnostic for reading a task output file in a scatter body.
+fn scatter_file_read(function: &str, span: Span) -> Diagnostic {
+    Diagnostic::note(format!(
+        "`{function}` is called on a task output within a scatter body"
+    ))
+    .with_rule(ID)
+    .with_highlight(span)
+    .with_fix(
+        "parse the file in the task that produces it (or in a separate task) and output the \
+         parsed value instead",
+    )
+}
+
+/// Determines if a value of the given type may contain a file.
+fn may_contain_file(ty: &Type) -> bool {
+    match ty {
+        Type::Primitive(ty, _) => *ty == PrimitiveType::File,
+        Type::Compound(CompoundType::Array(ty), _) => may_contain_file(ty.element_type()),
+        Type::Compound(CompoundType::Pair(ty), _) => {
+            may_contain_file(ty.left_type()) || may_contain_file(ty.right_type())
+        }
+        Type::Compound(CompoundType::Map(ty), _) => {
+            may_contain_file(ty.key_type()) || may_contain_file(ty.value_type())
+        }
+        Type::Compound(CompoundType::Struct(ty), _) => ty.members().values().any(may_contain_file),
+        Type::Object | Type::OptionalObject | Type::Call(_) => true,
+        _ => false,
+    }
+}
+
+/// Determines if a node references a task output.
+///
+/// A name references a task output if it names a call or if its definition is
+/// one of the given task output definitions.
+fn references_task_output(document: &Document, node: &SyntaxNode, outputs: &HashSet<Span>) -> bool {
+    node.descendants().filter_map(NameRefExpr::cast).any(|r| {
+        let name = r.name();
+        document
+            .find_scope_by_position(name.span().start())
+            .and_then(|scope| scope.lookup(name.text()).cloned())
+            .is_some_and(|n| matches!(n.ty(), Type::Call(_)) || outputs.contains(&n.span()))
+    })
+}
+
+/// Gets the definitions in a workflow whose values are derived from task
+/// outputs.
+///
+/// This includes the declarations and scatter variables that may contain a
+/// file (as determined by their analyzed types) and whose expressions
+/// reference a task output. The definitions are identified by the spans of
+/// their names.
+fn task_output_definitions(document: &Document, workflow: &WorkflowDefinition) -> HashSet<Span> {
+    let mut outputs = HashSet::new();
+
+    // Propagate through declarations and scatter variables until no new definitions
+    // are found, as workflow statements may reference names declared after them
+    loop {
+        let mut changed = false;
+        for node in workflow.inner().descendants() {
+            let (name, expr, scope) = if let Some(decl) = BoundDecl::cast(node.clone()) {
+                (decl.name(), decl.expr(), decl.span())
+            } else if let Some(scatter) = ScatterStatement::cast(node) {
+                let Some(scope) = scatter.braced_scope_span() else {
+                    continue;
+                };
+
+                (scatter.variable(), scatter.expr(), scope)
+            } else {
+                continue;
+            };
+
+            let Some(definition) = document
+                .find_scope_by_position(scope.start())
+                .and_then(|scope| scope.local(name.text()).cloned())
+            else {
+                continue;
+            };
+
+            if may_contain_file(definition.ty())
+                && !outputs.contains(&definition.span())
+                && references_task_output(document, expr.inner(), &outputs)
+            {
+                outputs.insert(definition.span());
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return outputs;
+        }
+    }
+}
+
+crate::rule! {
+    /// Detects workflow declarations and call inputs in scatter bodies that
+    /// read task output files.
+    #[derive(Default, Debug, Clone)]
+    pub struct ScatterFileReadRule {
+        /// The definitions in the current workflow whose values are derived
+        /// from task outputs.
+        task_outputs: HashSet<Span>,
+        /// The current depth of scatter statements.
+        scatter_depth: usize,
+    }
+
+    id: ID,
+    description: "Ensures that task output files are not read by workflow declarations or call \
+                  inputs within scatter bodies.",
+    explanation: "Functions such as `read_tsv` and `read_json` that are called in a workflow are \
+                  evaluated by the engine itself rather than by a task. Within a scatter body, \
+                  such a call on a task output is evaluated once per scatter iteration, which \
+                  requires the engine to localize and parse each file as the workflow runs and \
+                  can become a bottleneck for wide scatters. Parsing the file in the task that \
+                  produces it (or in a dedicated task) and outputting the parsed value keeps \
+                  the work distributed along with the rest of the task executions.",
+    tags: [Performance],
+    exceptable_nodes: [
+        VersionStatementNode,
+        WorkflowDefinitionNode,
+        ScatterStatementNode,
+        BoundDeclNode,
+        CallStatementNode,
+    ],
+    related_rules: [],
+
+    visitor {
+        fn workflow_definition(
+            &mut self,
+            _: &mut Diagnostics,
+            reason: VisitReason,
+            workflow: &WorkflowDefinition,
+        ) {
+            if reason == VisitReason::Exit {
+                self.task_outputs.clear();
+                return;
+            }
+
+            self.task_outputs = task_output_definitions(self.analyzed_document(), workflow);
+        }
+
+        fn scatter_statement(
+            &mut self,
+            _: &mut Diagnostics,
+            reason: VisitReason,
+            _: &ScatterStatement,
+        ) {
+            match reason {
+                VisitReason::Enter => self.scatter_depth += 1,
+                VisitReason::Exit => self.scatter_depth -= 1,
+            }
+        }
+
+        fn bound_decl(
+            &mut self,
+            diagnostics: &mut Diagnostics,
+            reason: VisitReason,
+            decl: &BoundDecl,
+        ) {
+            if reason == VisitReason::Exit || self.scatter_depth == 0 {
+                return;
+            }
+
+            self.check_reads(
+                diagnostics,
+                &decl.expr(),
+                SyntaxElement::from(decl.inner().clone()),
+            );
+        }
+
+        fn call_statement(
+            &mut self,
+            diagnostics: &mut Diagnostics,
+            reason: VisitReason,
+            call: &CallStatement,
+        ) {
+            if reason == VisitReason::Exit || self.scatter_depth == 0 {
+                return;
+            }
+
+            for expr in call.inputs().filter_map(|i| i.expr()) {
+                self.check_reads(
+                    diagnostics,
+                    &expr,
+                    SyntaxElement::from(call.inner().clone()),
+                );
+            }
+        }
+    }
+}
+
+impl ScatterFileReadRule {
+    /// Reports the calls within an expression that read a task output file.
+    ///
+    /// The diagnostics are excepted by the given element.
+    fn check_reads(&self, diagnostics: &mut Diagnostics, expr: &Expr, element: SyntaxElement) {
+        let document = self.analyzed_document();
+        for call in expr.inner().descendants().filter_map(CallExpr::cast) {
+            let target = call.target();
+            if READ_FUNCTIONS.contains(&target.text())
+                && call
+                    .arguments()
+                    .any(|a| references_task_output(document, a.inner(), &self.task_outputs))
+            {
+                diagnostics.exceptable_add(
+                    scatter_file_read(target.text(), call.span()),
+                    element.clone(),
+                    &self.exceptable_nodes(),
+                );
+            }
+        }
+    }
+}
@@ -32,6 +32,26 @@ fn expected_blank_line_before_version(span: Span) -> Diagnostic {
     )
 }
 
+/// Creates a diagnostic for leading comments that are not separated from the
+/// version statement by a blank line.
+fn missing_blank_line_before_version(span: Span) -> Diagnostic {
+    Diagnostic::note("expected a blank line between the leading comments and the version statement")
+        .with_rule(ID)
+        .with_highlight(span)
+        .with_fix("add a blank line between the leading comments and the version statement")
+}
+
+/// Creates a diagnostic for leading comments that are separated from the
+/// version statement by more than one blank line.
+fn extra_blank_lines_before_version(span: Span) -> Diagnostic {
+    Diagnostic::note(
+        "expected only one blank line between the leading comments and the version statement",
+    )
+    .with_rule(ID)
+    .with_highlight(span)
+    .with_fix("remove the extra blank lines between the leading comments and the version statement")
+}
+
 /// Creates a diagnostic for an expected blank line after the version statement.
 fn expected_blank_line_after_version(span: Span) -> Diagnostic {
     Diagnostic::note("expected exactly one blank line after the version statement")
@@ -81,8 +101,9 @@ impl Rule for VersionStatementFormattedRule {
         "The version statement should be formatted correctly. This rule checks that the version \
          statement is followed by a blank line and that there is exactly one space between \
          'version' and the version number. It also checks that if there are comments before the \
-         version statement, they are separated by exactly one blank line. If there are no \
-         comments, there should be no whitespace before the version statement."
+         version statement, they are separated from it by exactly one blank line; leading comments \
+         directly above the version statement or followed by multiple blank lines are flagged. If \
+         there are no comments, there should be no whitespace before the version statement."
     }
 
     fn tags(&self) -> TagSet {
@@ -122,28 +143,35 @@ impl Visitor for VersionStatementFormattedRule {
             // If there's a previous sibling or token, it must be a comment
             match prev_ws.prev_sibling_or_token() {
                 Some(_prev_comment) => {
-                    if ws != "\n\n" && ws != "\r\n\r\n" {
+                    let newlines = ws.chars().filter(|&c| c == '\n').count();
+                    if newlines < 2 {
+                        diagnostics.add(missing_blank_line_before_version(
+                            prev_ws.text_range().into(),
+                        ));
+                    } else if newlines > 2 {
+                        diagnostics.add(extra_blank_lines_before_version(
+                            prev_ws.text_range().into(),
+                        ));
+                    } else if ws != "\n\n" && ws != "\r\n\r\n" {
                         // There's a special case where the blank line has extra whitespace
                         // but that doesn't appear in the printed diagnostic.
                         let mut diagnostic =
                             expected_blank_line_before_version(prev_ws.text_range().into());
 
-                        if ws.chars().filter(|&c| c == '\n').count() == 2 {
-                            for (line, start, end) in lines_with_offset(ws) {
-                                if !line.is_empty() {
-                                    let end_offset = if ws.ends_with("\r\n") {
-                                        2
-                                    } else if ws.ends_with('\n') {
-                                        1
-                                    } else {
-                                        0
-                                    };
-
-                                    diagnostic = diagnostic.with_highlight(Span::new(
-                                        usize::from(prev_ws.text_range().start()) + start,
-                                        end - start - end_offset,
-                                    ));
-                                }
+                        for (line, start, end) in lines_with_offset(ws) {
+                            if !line.is_empty() {
+                                let end_offset = if ws.ends_with("\r\n") {
+                                    2
+                                } else if ws.ends_with('\n') {
+                                    1
+                                } else {
+                                    0
+                                };
+
+                                diagnostic = diagnostic.with_highlight(Span::new(
+                                    usize::from(prev_ws.text_range().start()) + start,
+                                    end - start - end_offset,
+                                ));
                             }
                         }
                         diagnostics.add(diagnostic);
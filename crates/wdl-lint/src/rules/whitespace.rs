@@ -8,12 +8,15 @@ use wdl_ast::Diagnostic;
 use wdl_ast::Span;
 use wdl_ast::SyntaxElement;
 use wdl_ast::SyntaxKind;
+use wdl_ast::SyntaxNode;
 use wdl_ast::VersionStatement;
 use wdl_ast::Whitespace;
 
 use crate::Rule;
 use crate::Tag;
 use crate::TagSet;
+use crate::fix::InsertionPoint;
+use crate::fix::Replacement;
 use crate::util::lines_with_offset;
 
 /// The identifier for the whitespace rule.
@@ -78,6 +81,36 @@ impl Rule for WhitespaceRule {
     fn related_rules(&self) -> &[&'static str] {
         &[]
     }
+
+    fn replacements(&self, root: &SyntaxNode, diagnostic: &Diagnostic) -> Vec<Replacement> {
+        let Some(span) = diagnostic.labels().next().map(|l| l.span()) else {
+            return Vec::new();
+        };
+
+        let source = root.text().to_string();
+        let Some(text) = source.get(span.start()..span.end()) else {
+            return Vec::new();
+        };
+
+        // Trailing whitespace and whitespace-only lines are labeled without their
+        // newline and removed; blank lines in a row are labeled with their newlines
+        // and collapsed to one, preserving the indentation of the line that follows
+        let value = if text.contains('\n') {
+            let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+            let indent = text.rsplit('\n').next().unwrap_or_default();
+            format!("{newline}{newline}{indent}")
+        } else {
+            String::new()
+        };
+
+        vec![Replacement::new(
+            span.start(),
+            span.end(),
+            InsertionPoint::BeforeStart,
+            value,
+            0,
+        )]
+    }
 }
 
 impl Visitor for WhitespaceRule {
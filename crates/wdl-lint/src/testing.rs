@@ -0,0 +1,358 @@
+//! Utilities for testing lint rules.
+//!
+//! These utilities run lint rules the same way as the tests of this crate's
+//! own rules: the document is analyzed with the analysis rules (e.g.
+//! `UnusedInput`) disabled and the linter runs only the given rules, so that
+//! custom rules behave under test as they do when run by the linter.
+//!
+//! This module requires the `testing` feature.
+//!
+//! # Examples
+//!
+//! Asserting the diagnostics of a rule:
+//!
+//! ```rust
+//! use wdl_lint::ast::Span;
+//! use wdl_lint::rules::TodoCommentRule;
+//! use wdl_lint::testing::ExpectedLint;
+//! use wdl_lint::testing::assert_lints;
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! assert_lints(
+//!     "version 1.2\n\nworkflow test {\n    # TODO: write the workflow\n}\n",
+//!     || vec![Box::new(TodoCommentRule)],
+//!     &[
+//!         ExpectedLint::new("TodoComment", "remaining `TODO` item found")
+//!             .with_span(Span::new(35, 4))
+//!             .with_fix("remove the `TODO` item once it has been implemented"),
+//!     ],
+//! )
+//! .await;
+//! # }
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context as _;
+use anyhow::Result;
+use codespan_reporting::files::SimpleFile;
+use codespan_reporting::term;
+use codespan_reporting::term::Config as CodespanConfig;
+use codespan_reporting::term::termcolor::Buffer;
+use tempfile::TempDir;
+use wdl_analysis::Analyzer;
+use wdl_analysis::Config as AnalysisConfig;
+use wdl_analysis::DiagnosticsConfig;
+use wdl_analysis::Validator;
+use wdl_ast::Diagnostic;
+use wdl_ast::Span;
+
+use crate::Linter;
+use crate::Rule;
+
+/// The name of the file a linted document is written to.
+const SOURCE_FILE_NAME: &str = "source.wdl";
+
+/// Represents an expected lint diagnostic.
+///
+/// The span and fix of a diagnostic are only compared if they are set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpectedLint {
+    /// The identifier of the rule that reports the diagnostic.
+    rule: String,
+    /// The message of the diagnostic.
+    message: String,
+    /// The span of the diagnostic's primary label.
+    span: Option<Span>,
+    /// The fix of the diagnostic.
+    fix: Option<String>,
+}
+
+impl ExpectedLint {
+    /// Creates a new expected diagnostic with the given rule identifier and
+    /// message.
+    pub fn new(rule: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            rule: rule.into(),
+            message: message.into(),
+            span: None,
+            fix: None,
+        }
+    }
+
+    /// Sets the expected span of the diagnostic's primary label.
+    pub fn with_span(mut self, span: Span) -> Self {
+        self.span = Some(span);
+        self
+    }
+
+    /// Sets the expected fix of the diagnostic.
+    pub fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+
+    /// Determines if a diagnostic matches the expected diagnostic.
+    pub fn matches(&self, diagnostic: &Diagnostic) -> bool {
+        diagnostic.rule() == Some(self.rule.as_str())
+            && diagnostic.message() == self.message
+            && self
+                .span
+                .is_none_or(|span| diagnostic.labels().next().map(|l| l.span()) == Some(span))
+            && self
+                .fix
+                .as_deref()
+                .is_none_or(|fix| diagnostic.fix() == Some(fix))
+    }
+}
+
+impl fmt::Display for ExpectedLint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "[{rule}] {message}",
+            rule = self.rule,
+            message = self.message
+        )?;
+        if let Some(span) = self.span {
+            write!(f, " at {span}")?;
+        }
+
+        if let Some(fix) = &self.fix {
+            write!(f, " (fix: {fix})")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Lints a document with the given rules and returns its diagnostics.
+///
+/// The `rules` function creates the rules to run; it is called once per
+/// analysis thread.
+///
+/// Parse and validation errors of the document are returned along with any
+/// lint diagnostics.
+pub async fn lint<F>(source: &str, rules: F) -> Result<Vec<Diagnostic>>
+where
+    F: Fn() -> Vec<Box<dyn Rule>> + Send + Sync + 'static,
+{
+    let dir = TempDir::new().context("failed to create temporary directory")?;
+    let path = dir.path().join(SOURCE_FILE_NAME);
+    fs::write(&path, source).context("failed to write document")?;
+    lint_file(&path, rules).await
+}
+
+/// Lints the document at the given path with the given rules and returns its
+/// diagnostics.
+///
+/// The other documents in the document's directory are also analyzed so that
+/// the document's imports are resolved.
+///
+/// See [`lint`] for how the rules are run.
+pub async fn lint_file<F>(path: &Path, rules: F) -> Result<Vec<Diagnostic>>
+where
+    F: Fn() -> Vec<Box<dyn Rule>> + Send + Sync + 'static,
+{
+    let path = std::path::absolute(path).with_context(|| {
+        format!(
+            "failed to make path `{path}` absolute",
+            path = path.display()
+        )
+    })?;
+    let dir = path
+        .parent()
+        .context("the document has no parent directory")?;
+
+    let analyzer = Analyzer::new_with_validator(
+        AnalysisConfig::default().with_diagnostics_config(DiagnosticsConfig::except_all()),
+        |_, _, _, _| async {},
+        move || {
+            let mut validator = Validator::default();
+            validator.add_visitor(Linter::new(rules()));
+            validator
+        },
+    );
+    analyzer
+        .add_directory(dir)
+        .await
+        .context("failed to add document")?;
+    let results = analyzer
+        .analyze(())
+        .await
+        .context("failed to analyze document")?;
+
+    let result = results
+        .into_iter()
+        .find(|r| r.document().uri().to_file_path().ok().as_deref() == Some(path.as_path()))
+        .context("the document was not analyzed")?;
+    Ok(result.document().diagnostics().cloned().collect())
+}
+
+/// Lints a document with the given rules and asserts that exactly the
+/// expected lint diagnostics are reported, in order.
+///
+/// Diagnostics that are not associated with a rule (e.g. parse errors) are
+/// not compared.
+///
+/// # Panics
+///
+/// Panics if the document could not be linted or if the diagnostics differ
+/// from the expected diagnostics.
+pub async fn assert_lints<F>(source: &str, rules: F, expected: &[ExpectedLint])
+where
+    F: Fn() -> Vec<Box<dyn Rule>> + Send + Sync + 'static,
+{
+    let diagnostics = lint(source, rules).await.expect("failed to lint document");
+    let actual: Vec<_> = diagnostics.iter().filter(|d| d.rule().is_some()).collect();
+
+    let matches =
+        actual.len() == expected.len() && actual.iter().zip(expected).all(|(d, e)| e.matches(d));
+    if !matches {
+        panic!(
+            "lint diagnostics are not as expected\n\nexpected:\n{expected}\nactual:\n{actual}",
+            expected = expected
+                .iter()
+                .map(|e| format!("  {e}\n"))
+                .collect::<String>(),
+            actual = actual
+                .iter()
+                .map(|d| format!(
+                    "  [{rule}] {message}{span}{fix}\n",
+                    rule = d.rule().unwrap_or_default(),
+                    message = d.message(),
+                    span = d
+                        .labels()
+                        .next()
+                        .map(|l| format!(" at {span}", span = l.span()))
+                        .unwrap_or_default(),
+                    fix = d.fix().map(|f| format!(" (fix: {f})")).unwrap_or_default()
+                ))
+                .collect::<String>(),
+        );
+    }
+}
+
+/// Renders diagnostics as they appear in a rule's snapshot (e.g. a
+/// `source.errors` file).
+///
+/// The given path is the path displayed for the document.
+pub fn render_diagnostics<'a>(
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+    path: &Path,
+    source: &str,
+) -> String {
+    let file = SimpleFile::new(path.to_string_lossy(), source);
+    let mut buffer = Buffer::no_color();
+    for diagnostic in diagnostics {
+        term::emit(
+            &mut buffer,
+            &CodespanConfig::default(),
+            &file,
+            &diagnostic.to_codespan(()),
+        )
+        .expect("should emit");
+    }
+
+    String::from_utf8(buffer.into_inner()).expect("should be UTF-8")
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::rules::TodoCommentRule;
+
+    /// A document with a single `TODO` comment.
+    const SOURCE: &str = "version 1.2\n\nworkflow test {\n    # TODO: write the workflow\n}\n";
+
+    #[tokio::test]
+    async fn it_lints() {
+        let diagnostics = lint(SOURCE, || vec![Box::new(TodoCommentRule)])
+            .await
+            .expect("should lint");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule(), Some("TodoComment"));
+        assert_eq!(diagnostics[0].message(), "remaining `TODO` item found");
+    }
+
+    #[tokio::test]
+    async fn it_asserts_expected_lints() {
+        assert_lints(
+            SOURCE,
+            || vec![Box::new(TodoCommentRule)],
+            &[
+                ExpectedLint::new("TodoComment", "remaining `TODO` item found")
+                    .with_span(Span::new(35, 4))
+                    .with_fix("remove the `TODO` item once it has been implemented"),
+            ],
+        )
+        .await;
+
+        // The span and fix are only compared when set
+        assert_lints(
+            SOURCE,
+            || vec![Box::new(TodoCommentRule)],
+            &[ExpectedLint::new(
+                "TodoComment",
+                "remaining `TODO` item found",
+            )],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn it_ignores_diagnostics_without_a_rule() {
+        // The missing closing brace is a parse error rather than a lint
+        assert_lints(
+            "version 1.2\n\nworkflow test {\n",
+            || vec![Box::new(TodoCommentRule)],
+            &[],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "lint diagnostics are not as expected")]
+    async fn it_panics_on_a_missing_lint() {
+        assert_lints(SOURCE, || vec![Box::new(TodoCommentRule)], &[]).await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "lint diagnostics are not as expected")]
+    async fn it_panics_on_a_mismatched_span() {
+        assert_lints(
+            SOURCE,
+            || vec![Box::new(TodoCommentRule)],
+            &[
+                ExpectedLint::new("TodoComment", "remaining `TODO` item found")
+                    .with_span(Span::new(0, 4)),
+            ],
+        )
+        .await;
+    }
+
+    #[test]
+    fn it_renders_diagnostics() {
+        let diagnostic = Diagnostic::note("remaining `TODO` item found")
+            .with_rule("TodoComment")
+            .with_highlight(Span::new(35, 4))
+            .with_fix("remove the `TODO` item once it has been implemented");
+        assert_eq!(
+            render_diagnostics([&diagnostic], Path::new("source.wdl"), SOURCE),
+            r#"note[TodoComment]: remaining `TODO` item found
+  ┌─ source.wdl:4:7
+  │
+4 │     # TODO: write the workflow
+  │       ^^^^
+  │
+  = fix: remove the `TODO` item once it has been implemented
+
+"#
+        );
+    }
+}
@@ -15,24 +15,14 @@ use std::env;
 use std::ffi::OsStr;
 use std::fs;
 use std::path::Path;
-use std::path::absolute;
 
 use anyhow::Context as _;
 use anyhow::bail;
-use codespan_reporting::files::SimpleFile;
-use codespan_reporting::term;
-use codespan_reporting::term::Config as CodespanConfig;
-use codespan_reporting::term::termcolor::Buffer;
 use libtest_mimic::Trial;
-use path_clean::PathClean;
 use pretty_assertions::StrComparison;
-use wdl_analysis::Analyzer;
-use wdl_analysis::Config as AnalysisConfig;
-use wdl_analysis::DiagnosticsConfig;
-use wdl_analysis::Validator;
-use wdl_ast::AstNode;
-use wdl_ast::Diagnostic;
-use wdl_lint::Linter;
+use wdl_lint::rules;
+use wdl_lint::testing::lint_file;
+use wdl_lint::testing::render_diagnostics;
 
 /// Finds tests for this package.
 fn find_tests(runtime: &tokio::runtime::Handle) -> Vec<Trial> {
@@ -66,27 +56,6 @@ fn normalize(s: &str) -> String {
     s.replace('\\', "/").replace("\r\n", "\n")
 }
 
-/// Formats diagnostics.
-fn format_diagnostics<'a>(
-    diagnostics: impl Iterator<Item = &'a Diagnostic>,
-    path: &Path,
-    source: &str,
-) -> String {
-    let file = SimpleFile::new(path.as_os_str().to_str().unwrap(), source);
-    let mut buffer = Buffer::no_color();
-    for diagnostic in diagnostics {
-        term::emit(
-            &mut buffer,
-            &CodespanConfig::default(),
-            &file,
-            &diagnostic.to_codespan(()),
-        )
-        .expect("should emit");
-    }
-
-    String::from_utf8(buffer.into_inner()).expect("should be UTF-8")
-}
-
 /// Compares a test result.
 fn compare_result(path: &Path, result: &str) -> Result<(), anyhow::Error> {
     let result = normalize(result);
@@ -112,38 +81,12 @@ fn compare_result(path: &Path, result: &str) -> Result<(), anyhow::Error> {
 
 /// Runs a lint test.
 async fn run_test(test: &Path) -> Result<(), anyhow::Error> {
-    let analyzer = Analyzer::new_with_validator(
-        AnalysisConfig::default().with_diagnostics_config(DiagnosticsConfig::except_all()),
-        |_, _, _, _| async {},
-        || {
-            let mut validator = Validator::default();
-            validator.add_visitor(Linter::default());
-            validator
-        },
-    );
-    analyzer
-        .add_directory(test)
-        .await
-        .context("adding directory")?;
-    let results = analyzer.analyze(()).await.context("running analysis")?;
-
-    let base = absolute(test).unwrap().clean();
-    let source_path = base.join("source.wdl");
-    let errors_path = base.join("source.errors");
-
-    let Some(result) = results
-        .into_iter()
-        .find(|result| result.document().uri().to_file_path().unwrap() == source_path)
-    else {
-        bail!("failed to find test result");
-    };
+    let source_path = test.join("source.wdl");
+    let diagnostics = lint_file(&source_path, rules).await?;
+    let source = fs::read_to_string(&source_path).context("reading source file")?;
     compare_result(
-        &errors_path,
-        &format_diagnostics(
-            result.document().diagnostics(),
-            &test.join("source.wdl"),
-            &result.document().root().text().to_string(),
-        ),
+        &test.join("source.errors"),
+        &render_diagnostics(&diagnostics, &source_path, &source),
     )
 }
 
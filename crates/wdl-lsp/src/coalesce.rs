@@ -0,0 +1,135 @@
+//! Coalescing of document diagnostics requests.
+
+use std::collections::HashMap;
+use std::future::poll_fn;
+use std::time::Duration;
+
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+use tokio_util::time::DelayQueue;
+use tokio_util::time::delay_queue::Key;
+use tower_lsp::jsonrpc::Error as RpcError;
+use tower_lsp::jsonrpc::ErrorCode;
+use tower_lsp::jsonrpc::Result as RpcResult;
+use tracing::debug;
+use url::Url;
+
+/// The JSON-RPC error code for a request cancelled by the server.
+///
+/// This is the `ServerCancelled` error code defined by the LSP specification.
+const SERVER_CANCELLED: i64 = -32802;
+
+/// Creates the error for a request that was superseded before it was handled.
+///
+/// The error data asks the client to send the request again.
+fn superseded() -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(SERVER_CANCELLED),
+        message: "the request was superseded by a change to the document".into(),
+        data: Some(json!({ "retriggerRequest": true })),
+    }
+}
+
+/// A message sent to the coalescing worker.
+#[derive(Debug)]
+enum Message {
+    /// A diagnostics request for a document.
+    ///
+    /// The sender is sent `true` once the document has settled or `false` if
+    /// the request is superseded.
+    Request(Url, oneshot::Sender<bool>),
+    /// A document was changed or closed.
+    Changed(Url),
+}
+
+/// A pending diagnostics request.
+#[derive(Debug)]
+struct Pending {
+    /// The key of the request's deadline in the delay queue.
+    key: Key,
+    /// The sender for notifying the request.
+    tx: oneshot::Sender<bool>,
+}
+
+/// Coalesces diagnostics requests for documents that are changing rapidly.
+///
+/// A worker task holds each request until its document has not changed for
+/// the debounce duration. A request is superseded when its document changes
+/// or when a newer request for the same document is received, so a burst of
+/// changes results in a single analysis.
+#[derive(Debug)]
+pub struct Coalescer {
+    /// The sender for messages to the worker.
+    tx: mpsc::UnboundedSender<Message>,
+}
+
+impl Coalescer {
+    /// Creates a new coalescer with the given debounce duration.
+    ///
+    /// The worker is spawned on the current tokio runtime.
+    pub fn new(debounce: Duration) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        tokio::spawn(Self::run(debounce, rx));
+        Self { tx }
+    }
+
+    /// Waits for the given document to settle.
+    ///
+    /// Returns a `ServerCancelled` error if the request is superseded.
+    pub async fn settle(&self, uri: Url) -> RpcResult<()> {
+        let (tx, rx) = oneshot::channel();
+        if self.tx.send(Message::Request(uri, tx)).is_err() {
+            // The worker has stopped, so there is nothing to coalesce with
+            return Ok(());
+        }
+
+        match rx.await {
+            Ok(true) | Err(_) => Ok(()),
+            Ok(false) => Err(superseded()),
+        }
+    }
+
+    /// Notifies the coalescer that a document was changed or closed.
+    ///
+    /// Any pending request for the document is superseded.
+    pub fn changed(&self, uri: &Url) {
+        let _ = self.tx.send(Message::Changed(uri.clone()));
+    }
+
+    /// Runs the coalescing worker until the coalescer is dropped.
+    async fn run(debounce: Duration, mut rx: mpsc::UnboundedReceiver<Message>) {
+        let mut deadlines = DelayQueue::new();
+        let mut pending: HashMap<Url, Pending> = HashMap::new();
+
+        loop {
+            tokio::select! {
+                message = rx.recv() => match message {
+                    Some(Message::Request(uri, tx)) => {
+                        if let Some(previous) = pending.remove(&uri) {
+                            debug!("superseding diagnostics request for document `{uri}`");
+                            deadlines.remove(&previous.key);
+                            let _ = previous.tx.send(false);
+                        }
+
+                        let key = deadlines.insert(uri.clone(), debounce);
+                        pending.insert(uri, Pending { key, tx });
+                    }
+                    Some(Message::Changed(uri)) => {
+                        if let Some(previous) = pending.remove(&uri) {
+                            debug!("superseding diagnostics request for document `{uri}`");
+                            deadlines.remove(&previous.key);
+                            let _ = previous.tx.send(false);
+                        }
+                    }
+                    None => break,
+                },
+                Some(expired) = poll_fn(|cx| deadlines.poll_expired(cx)) => {
+                    if let Some(settled) = pending.remove(expired.get_ref()) {
+                        let _ = settled.tx.send(true);
+                    }
+                }
+            }
+        }
+    }
+}
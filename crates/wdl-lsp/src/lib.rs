@@ -7,7 +7,10 @@
 #![warn(clippy::missing_docs_in_private_items)]
 #![warn(rustdoc::broken_intra_doc_links)]
 
+mod coalesce;
+mod metrics;
 mod proto;
 mod server;
 
+pub use metrics::METRICS_REQUEST;
 pub use server::*;
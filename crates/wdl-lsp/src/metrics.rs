@@ -0,0 +1,123 @@
+//! Latency metrics for LSP requests.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use parking_lot::Mutex;
+use serde_json::Value;
+use serde_json::json;
+use tracing::warn;
+
+/// The name of the custom request for retrieving request metrics.
+pub const METRICS_REQUEST: &str = "wdl/metrics";
+
+/// Represents the latency metrics of a single request method.
+#[derive(Debug, Default, Clone, Copy)]
+struct MethodMetrics {
+    /// The number of requests that have completed.
+    count: u64,
+    /// The total time spent handling requests.
+    total: Duration,
+    /// The longest time spent handling a single request.
+    max: Duration,
+    /// The number of requests that exceeded the latency budget.
+    over_budget: u64,
+}
+
+/// Records latency metrics for requests handled by the server.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// The latency budget for interactive requests.
+    ///
+    /// Requests that take longer than the budget are logged as warnings.
+    budget: Option<Duration>,
+    /// The metrics of each request method.
+    methods: Mutex<BTreeMap<&'static str, MethodMetrics>>,
+}
+
+impl Metrics {
+    /// Creates a new metrics recorder with the given latency budget.
+    pub fn new(budget: Option<Duration>) -> Self {
+        Self {
+            budget,
+            methods: Default::default(),
+        }
+    }
+
+    /// Starts timing a request for the given method.
+    ///
+    /// The request's latency is recorded when the returned timer is dropped.
+    pub fn start(&self, method: &'static str) -> RequestTimer<'_> {
+        RequestTimer {
+            metrics: self,
+            method,
+            start: Instant::now(),
+        }
+    }
+
+    /// Records the latency of a completed request.
+    fn record(&self, method: &'static str, elapsed: Duration) {
+        let over_budget = self.budget.is_some_and(|b| elapsed > b);
+        if over_budget {
+            warn!(
+                "`{method}` request took {elapsed:?}, exceeding the latency budget of {budget:?}",
+                budget = self.budget.unwrap_or_default()
+            );
+        }
+
+        let mut methods = self.methods.lock();
+        let metrics = methods.entry(method).or_default();
+        metrics.count += 1;
+        metrics.total += elapsed;
+        metrics.max = metrics.max.max(elapsed);
+        metrics.over_budget += u64::from(over_budget);
+    }
+
+    /// Gets a JSON representation of the recorded metrics.
+    ///
+    /// Latencies are reported in milliseconds.
+    pub fn to_json(&self) -> Value {
+        let methods = self.methods.lock();
+        json!({
+            "budgetMs": self.budget.map(|b| b.as_secs_f64() * 1000.0),
+            "methods": methods
+                .iter()
+                .map(|(method, m)| {
+                    let mean = if m.count > 0 {
+                        m.total.as_secs_f64() * 1000.0 / m.count as f64
+                    } else {
+                        0.0
+                    };
+
+                    (
+                        method.to_string(),
+                        json!({
+                            "count": m.count,
+                            "meanMs": mean,
+                            "maxMs": m.max.as_secs_f64() * 1000.0,
+                            "overBudget": m.over_budget,
+                        }),
+                    )
+                })
+                .collect::<serde_json::Map<_, _>>(),
+        })
+    }
+}
+
+/// Times a request and records its latency when dropped.
+#[derive(Debug)]
+pub struct RequestTimer<'a> {
+    /// The metrics to record into.
+    metrics: &'a Metrics,
+    /// The method of the request.
+    method: &'static str,
+    /// The time the request started.
+    start: Instant,
+}
+
+impl Drop for RequestTimer<'_> {
+    fn drop(&mut self) {
+        self.metrics.record(self.method, self.start.elapsed());
+    }
+}
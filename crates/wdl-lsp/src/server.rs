@@ -1,18 +1,23 @@
 //! Implementation of the LSP server.
 
+use std::collections::HashMap;
 use std::ffi::OsStr;
 use std::mem;
 use std::path::Component;
 use std::path::PathBuf;
 use std::path::Prefix;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use notification::Progress;
 use parking_lot::RwLock;
 use request::WorkDoneProgressCreate;
+use serde_json::Value;
+use serde_json::json;
 use serde_json::to_value;
 use tower_lsp::Client;
+use tower_lsp::ClientSocket;
 use tower_lsp::LanguageServer;
 use tower_lsp::LspService;
 use tower_lsp::jsonrpc::Error as RpcError;
@@ -34,10 +39,24 @@ use wdl_analysis::Validator;
 use wdl_analysis::handlers::WDL_SEMANTIC_TOKEN_MODIFIERS;
 use wdl_analysis::handlers::WDL_SEMANTIC_TOKEN_TYPES;
 use wdl_analysis::path_to_uri;
+use wdl_ast::AstNode;
+use wdl_ast::Span;
 use wdl_lint::Linter;
+use wdl_lint::fix::Fix;
+use wdl_lint::fix::merge_fixes;
 
+use crate::coalesce::Coalescer;
+use crate::metrics::METRICS_REQUEST;
+use crate::metrics::Metrics;
 use crate::proto;
 
+/// The command that applies the automatic fixes of lint rules to every
+/// document in the workspace.
+pub const FIX_ALL_COMMAND: &str = "sprocket.fixAllInWorkspace";
+
+/// The command that formats every document in the workspace.
+pub const FORMAT_WORKSPACE_COMMAND: &str = "sprocket.formatWorkspace";
+
 /// Normalizes the path of a URI.
 ///
 /// If the path contains percent encoded sequences, the sequences are decoded.
@@ -231,6 +250,23 @@ pub struct ServerOptions {
 
     /// Basename for any ignorefiles which should be respected.
     pub ignore_filename: Option<String>,
+
+    /// The amount of time to wait after a document changes before analyzing
+    /// it in response to a diagnostics request.
+    ///
+    /// Diagnostics requests are coalesced: a request is superseded when its
+    /// document changes during the wait or when a newer request for the same
+    /// document is received, so bursts of changes result in a single
+    /// analysis. Superseded requests fail with a `ServerCancelled` error that
+    /// asks the client to retrigger the request.
+    ///
+    /// Defaults to no delay.
+    pub debounce: Option<Duration>,
+
+    /// The target latency for handling requests.
+    ///
+    /// Requests that take longer than the budget are logged as warnings.
+    pub latency_budget: Option<Duration>,
 }
 
 /// Represents an LSP server for analyzing WDL documents.
@@ -246,6 +282,12 @@ pub struct Server {
     client_support: Arc<RwLock<ClientSupport>>,
     /// The current set of workspace folders.
     folders: Arc<RwLock<Vec<WorkspaceFolder>>>,
+    /// The coalescer for diagnostics requests.
+    ///
+    /// This is `None` when diagnostics requests are not debounced.
+    coalescer: Option<Coalescer>,
+    /// The latency metrics of handled requests.
+    metrics: Arc<Metrics>,
 }
 
 impl Server {
@@ -254,6 +296,7 @@ impl Server {
         let lint = options.lint;
         let exceptions = options.exceptions.clone();
         let ignore_name = options.ignore_filename.clone();
+        let latency_budget = options.latency_budget;
         let analyzer_client = client.clone();
 
         let mut all_rules: Vec<_> = wdl_analysis::rules()
@@ -306,14 +349,31 @@ impl Server {
             ),
             client_support: Default::default(),
             folders: Default::default(),
+            coalescer: options.debounce.map(Coalescer::new),
+            metrics: Arc::new(Metrics::new(latency_budget)),
         }
     }
 
+    /// Creates the LSP service for the server.
+    ///
+    /// In addition to the standard LSP methods, the service handles the custom
+    /// `wdl/metrics` request, which responds with the latency metrics of the
+    /// requests handled by the server.
+    ///
+    /// The server also provides the [`FIX_ALL_COMMAND`] and
+    /// [`FORMAT_WORKSPACE_COMMAND`] commands, which edit every document in the
+    /// workspace and respond with a summary of the changed files.
+    pub fn service(options: ServerOptions) -> (LspService<Self>, ClientSocket) {
+        LspService::build(|client| Self::new(client, options))
+            .custom_method(METRICS_REQUEST, Self::metrics)
+            .finish()
+    }
+
     /// Runs the server until a request is received to shut down.
     pub async fn run(options: ServerOptions) -> Result<()> {
         debug!("running LSP server: {options:#?}");
 
-        let (service, socket) = LspService::new(|client| Self::new(client, options.clone()));
+        let (service, socket) = Self::service(options);
 
         let stdin = tokio::io::stdin();
         let stdout = tokio::io::stdout();
@@ -340,6 +400,235 @@ impl Server {
             .unwrap_or(env!("CARGO_PKG_VERSION"))
     }
 
+    /// Handles the custom `wdl/metrics` request.
+    async fn metrics(&self) -> RpcResult<Value> {
+        debug!("received `{METRICS_REQUEST}` request");
+        Ok(self.metrics.to_json())
+    }
+
+    /// Analyzes the workspace for a workspace command.
+    ///
+    /// Returns the analysis results along with the progress token of the
+    /// command.
+    async fn analyze_workspace(
+        &self,
+        title: &str,
+    ) -> RpcResult<(Vec<wdl_analysis::AnalysisResult>, ProgressToken)> {
+        let work_done_progress = self.client_support.read().work_done_progress;
+        let progress = ProgressToken::new(&self.client, work_done_progress).await;
+        progress.start(&self.client, title, "analyzing...").await;
+        let results = self
+            .analyzer
+            .analyze(progress.clone())
+            .await
+            .map_err(|e| RpcError {
+                code: ErrorCode::InternalError,
+                message: e.to_string().into(),
+                data: None,
+            })?;
+
+        Ok((results, progress))
+    }
+
+    /// Requests the client to apply the edits of a workspace command and
+    /// shows the command's summary.
+    async fn apply_workspace_edits(
+        &self,
+        progress: ProgressToken,
+        changes: HashMap<Url, Vec<TextEdit>>,
+        summary: String,
+    ) -> RpcResult<()> {
+        if !changes.is_empty() {
+            let response = self
+                .client
+                .apply_edit(WorkspaceEdit {
+                    changes: Some(changes),
+                    ..Default::default()
+                })
+                .await?;
+
+            if !response.applied {
+                progress
+                    .complete(&self.client, "edits were not applied")
+                    .await;
+                return Err(RpcError {
+                    code: ErrorCode::InternalError,
+                    message: format!(
+                        "the client did not apply the workspace edits: {reason}",
+                        reason = response
+                            .failure_reason
+                            .as_deref()
+                            .unwrap_or("unknown reason")
+                    )
+                    .into(),
+                    data: None,
+                });
+            }
+        }
+
+        progress.complete(&self.client, summary.clone()).await;
+        self.client.show_message(MessageType::INFO, summary).await;
+        Ok(())
+    }
+
+    /// Handles the `sprocket.fixAllInWorkspace` command.
+    ///
+    /// The fixes of each document are merged and fixes that conflict with
+    /// another fix are skipped; running the command again applies them to the
+    /// fixed documents.
+    async fn fix_all(&self) -> RpcResult<Value> {
+        let (results, progress) = self.analyze_workspace("Fixing workspace").await?;
+        let rules = wdl_lint::rules();
+        let mut changes = HashMap::new();
+        let mut applied = 0;
+        let mut skipped = 0;
+        let total = results.len();
+        for (i, result) in results.iter().enumerate() {
+            let document = result.document();
+            let uri = document.uri();
+            let Some(lines) = result.lines() else {
+                continue;
+            };
+
+            if uri.scheme() != "file" {
+                continue;
+            }
+
+            let root = document.root();
+            let merge = merge_fixes(document.diagnostics().filter_map(|d| {
+                let rule = rules.iter().find(|r| Some(r.id()) == d.rule())?;
+                let span = d.labels().next()?.span();
+                Some(Fix::new(
+                    rule.id(),
+                    span,
+                    rule.replacements(root.inner(), d),
+                ))
+            }));
+
+            skipped += merge.skipped().len();
+            if merge.applied().is_empty() {
+                continue;
+            }
+
+            let edits = merge
+                .applied()
+                .iter()
+                .flat_map(|f| f.replacements())
+                .map(|r| {
+                    Ok(TextEdit {
+                        range: proto::range_from_span(
+                            lines,
+                            Span::new(r.start(), r.end() - r.start()),
+                        )?,
+                        new_text: r.value().to_string(),
+                    })
+                })
+                .collect::<Result<Vec<_>>>()
+                .map_err(|e| RpcError {
+                    code: ErrorCode::InternalError,
+                    message: e.to_string().into(),
+                    data: None,
+                })?;
+
+            applied += merge.applied().len();
+            changes.insert(Url::clone(uri), edits);
+            progress
+                .update(
+                    &self.client,
+                    format!("fixed {current}/{total} files", current = i + 1),
+                    ((i + 1) * 100 / total) as u32,
+                )
+                .await;
+        }
+
+        let files = changes.len();
+        let mut summary = format!(
+            "applied {applied} fix{es} to {files} file{s}",
+            es = if applied == 1 { "" } else { "es" },
+            s = if files == 1 { "" } else { "s" }
+        );
+        if skipped > 0 {
+            summary.push_str(&format!(
+                "; {skipped} conflicting fix{es} skipped (run the command again to apply them)",
+                es = if skipped == 1 { "" } else { "es" }
+            ));
+        }
+
+        self.apply_workspace_edits(progress, changes, summary)
+            .await?;
+        Ok(json!({
+            "filesChanged": files,
+            "fixesApplied": applied,
+            "fixesSkipped": skipped,
+        }))
+    }
+
+    /// Handles the `sprocket.formatWorkspace` command.
+    ///
+    /// Documents with parse errors are not formatted.
+    async fn format_workspace(&self) -> RpcResult<Value> {
+        let (results, progress) = self.analyze_workspace("Formatting workspace").await?;
+        let mut changes = HashMap::new();
+        let total = results.len();
+        for (i, result) in results.iter().enumerate() {
+            let document = result.document();
+            let uri = document.uri();
+            if uri.scheme() != "file" {
+                continue;
+            }
+
+            let Some((end_line, end_col, formatted)) = self
+                .analyzer
+                .format_document(Url::clone(uri))
+                .await
+                .map_err(|e| RpcError {
+                    code: ErrorCode::InternalError,
+                    message: e.to_string().into(),
+                    data: None,
+                })?
+            else {
+                continue;
+            };
+
+            if formatted == document.root().text().to_string() {
+                continue;
+            }
+
+            changes.insert(
+                Url::clone(uri),
+                vec![TextEdit {
+                    range: Range {
+                        start: Position {
+                            line: 0,
+                            character: 0,
+                        },
+                        end: Position {
+                            line: end_line,
+                            character: end_col,
+                        },
+                    },
+                    new_text: formatted,
+                }],
+            );
+            progress
+                .update(
+                    &self.client,
+                    format!("formatted {current}/{total} files", current = i + 1),
+                    ((i + 1) * 100 / total) as u32,
+                )
+                .await;
+        }
+
+        let files = changes.len();
+        let summary = format!(
+            "formatted {files} file{s}",
+            s = if files == 1 { "" } else { "s" }
+        );
+        self.apply_workspace_edits(progress, changes, summary)
+            .await?;
+        Ok(json!({ "filesChanged": files }))
+    }
+
     /// Registers a generic watcher for all files/directories in the workspace.
     async fn register_watcher(&self) {
         self.client
@@ -413,6 +702,13 @@ impl LanguageServer for Server {
                     ..Default::default()
                 }),
                 workspace_symbol_provider: Some(OneOf::Left(true)),
+                execute_command_provider: Some(ExecuteCommandOptions {
+                    commands: vec![
+                        FIX_ALL_COMMAND.to_string(),
+                        FORMAT_WORKSPACE_COMMAND.to_string(),
+                    ],
+                    ..Default::default()
+                }),
                 diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
                     DiagnosticOptions {
                         inter_file_dependencies: true,
@@ -440,6 +736,12 @@ impl LanguageServer for Server {
                     ..Default::default()
                 }),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+                        ..Default::default()
+                    },
+                )),
                 rename_provider: Some(OneOf::Left(true)),
                 semantic_tokens_provider: Some(
                     SemanticTokensServerCapabilities::SemanticTokensOptions(
@@ -526,6 +828,10 @@ impl LanguageServer for Server {
             version = params.text_document.version
         );
 
+        if let Some(coalescer) = &self.coalescer {
+            coalescer.changed(&params.text_document.uri);
+        }
+
         // Look for the last full change (one without a range) and start there
         let (start, changes) = match params
             .content_changes
@@ -567,6 +873,9 @@ impl LanguageServer for Server {
         normalize_uri_path(&mut params.text_document.uri);
 
         debug!("received `textDocument/didClose` request: {params:#?}");
+        if let Some(coalescer) = &self.coalescer {
+            coalescer.changed(&params.text_document.uri);
+        }
         if let Err(e) = self.analyzer.notify_change(params.text_document.uri, true) {
             error!("failed to notify change: {e}");
         }
@@ -580,6 +889,13 @@ impl LanguageServer for Server {
 
         debug!("received `textDocument/diagnostic` request: {params:#?}");
 
+        // Wait for the document to settle before analyzing it; the client retriggers
+        // the request if it is superseded in the meantime
+        if let Some(coalescer) = &self.coalescer {
+            coalescer.settle(params.text_document.uri.clone()).await?;
+        }
+
+        let _timer = self.metrics.start("textDocument/diagnostic");
         let results: Vec<wdl_analysis::AnalysisResult> = self
             .analyzer
             .analyze_document(ProgressToken::default(), params.text_document.uri.clone())
@@ -600,6 +916,8 @@ impl LanguageServer for Server {
     ) -> RpcResult<WorkspaceDiagnosticReportResult> {
         debug!("received `workspace/diagnostic` request: {params:#?}");
 
+        let _timer = self.metrics.start("workspace/diagnostic");
+
         let work_done_progress = self.client_support.read().work_done_progress;
         let progress = ProgressToken::new(&self.client, work_done_progress).await;
         progress
@@ -675,21 +993,41 @@ impl LanguageServer for Server {
             None
         }
 
+        // Checking the file system may block, so resolve the paths of the
+        // changed files on the blocking thread pool
+        let changes = match tokio::task::spawn_blocking(move || {
+            params
+                .changes
+                .into_iter()
+                .map(|mut event| {
+                    normalize_uri_path(&mut event.uri);
+                    let path = to_wdl_file_path(&event.uri);
+                    (event, path)
+                })
+                .collect::<Vec<_>>()
+        })
+        .await
+        {
+            Ok(changes) => changes,
+            Err(e) => {
+                error!("failed to process watched file changes: {e}");
+                return;
+            }
+        };
+
         let mut added = Vec::new();
         let mut deleted = Vec::new();
 
-        for mut event in params.changes {
-            normalize_uri_path(&mut event.uri);
-
+        for (event, path) in changes {
             match event.typ {
                 FileChangeType::CREATED => {
-                    if let Some(path) = to_wdl_file_path(&event.uri) {
+                    if let Some(path) = path {
                         debug!("document `{uri}` has been created", uri = event.uri);
                         added.push(path);
                     }
                 }
                 FileChangeType::CHANGED => {
-                    if to_wdl_file_path(&event.uri).is_some() {
+                    if path.is_some() {
                         debug!("document `{uri}` has been changed", uri = event.uri);
                         if let Err(e) = self.analyzer.notify_change(event.uri, false) {
                             error!("failed to notify change: {e}");
@@ -697,7 +1035,7 @@ impl LanguageServer for Server {
                     }
                 }
                 FileChangeType::DELETED => {
-                    if to_wdl_file_path(&event.uri).is_some() {
+                    if path.is_some() {
                         debug!("document `{uri}` has been deleted", uri = event.uri);
                         deleted.push(event.uri);
                     }
@@ -735,6 +1073,8 @@ impl LanguageServer for Server {
 
         debug!("received `textDocument/formatting` request: {params:#?}");
 
+        let _timer = self.metrics.start("textDocument/formatting");
+
         let result = self
             .analyzer
             .format_document(params.text_document.uri)
@@ -765,6 +1105,20 @@ impl LanguageServer for Server {
         Ok(result)
     }
 
+    async fn execute_command(&self, params: ExecuteCommandParams) -> RpcResult<Option<Value>> {
+        debug!("received `workspace/executeCommand` request: {params:#?}");
+
+        let _timer = self.metrics.start("workspace/executeCommand");
+
+        match params.command.as_str() {
+            FIX_ALL_COMMAND => self.fix_all().await.map(Some),
+            FORMAT_WORKSPACE_COMMAND => self.format_workspace().await.map(Some),
+            command => Err(RpcError::invalid_params(format!(
+                "unknown command `{command}`"
+            ))),
+        }
+    }
+
     async fn goto_definition(
         &self,
         mut params: GotoDefinitionParams,
@@ -773,6 +1127,8 @@ impl LanguageServer for Server {
 
         debug!("received `textDocument/gotoDefinition` request: {params:#?}");
 
+        let _timer = self.metrics.start("textDocument/gotoDefinition");
+
         let position = SourcePosition::new(
             params.text_document_position_params.position.line,
             params.text_document_position_params.position.character,
@@ -800,6 +1156,8 @@ impl LanguageServer for Server {
 
         debug!("received `textDocument/references` request: {params:#?}");
 
+        let _timer = self.metrics.start("textDocument/references");
+
         let position = SourcePosition::new(
             params.text_document_position.position.line,
             params.text_document_position.position.character,
@@ -831,6 +1189,8 @@ impl LanguageServer for Server {
 
         debug!("received `textDocument/completion` request: {params:#?}");
 
+        let _timer = self.metrics.start("textDocument/completion");
+
         let position = SourcePosition::new(
             params.text_document_position.position.line,
             params.text_document_position.position.character,
@@ -859,6 +1219,8 @@ impl LanguageServer for Server {
 
         debug!("received `textDocument/hover` request: {params:#?}");
 
+        let _timer = self.metrics.start("textDocument/hover");
+
         let position = SourcePosition::new(
             params.text_document_position_params.position.line,
             params.text_document_position_params.position.character,
@@ -885,6 +1247,8 @@ impl LanguageServer for Server {
 
         debug!("received `textDocument/rename` request: {params:#?}");
 
+        let _timer = self.metrics.start("textDocument/rename");
+
         let position = SourcePosition::new(
             params.text_document_position.position.line,
             params.text_document_position.position.character,
@@ -916,6 +1280,8 @@ impl LanguageServer for Server {
 
         debug!("received `textDocument/semanticTokens/full` request: {params:#?}");
 
+        let _timer = self.metrics.start("textDocument/semanticTokens/full");
+
         let result = self
             .analyzer
             .semantic_tokens(params.text_document.uri)
@@ -937,6 +1303,8 @@ impl LanguageServer for Server {
 
         debug!("received `textDocument/documentSymbol` request: {params:#?}");
 
+        let _timer = self.metrics.start("textDocument/documentSymbol");
+
         let result = self
             .analyzer
             .document_symbol(params.text_document.uri)
@@ -956,6 +1324,8 @@ impl LanguageServer for Server {
     ) -> RpcResult<Option<Vec<SymbolInformation>>> {
         debug!("received `workspace/symbol` request: {params:#?}");
 
+        let _timer = self.metrics.start("workspace/symbol");
+
         let result = self
             .analyzer
             .workspace_symbol(params.query)
@@ -977,6 +1347,8 @@ impl LanguageServer for Server {
 
         debug!("received `textDocument/signatureHelp` request: {params:#?}");
 
+        let _timer = self.metrics.start("textDocument/signatureHelp");
+
         let position = SourcePosition::new(
             params.text_document_position_params.position.line,
             params.text_document_position_params.position.character,
@@ -998,4 +1370,40 @@ impl LanguageServer for Server {
 
         Ok(result)
     }
+
+    async fn code_action(
+        &self,
+        mut params: CodeActionParams,
+    ) -> RpcResult<Option<CodeActionResponse>> {
+        normalize_uri_path(&mut params.text_document.uri);
+
+        debug!("received `textDocument/codeAction` request: {params:#?}");
+
+        let _timer = self.metrics.start("textDocument/codeAction");
+
+        let start = SourcePosition::new(params.range.start.line, params.range.start.character);
+        let end = SourcePosition::new(params.range.end.line, params.range.end.character);
+
+        let result = self
+            .analyzer
+            .code_action(
+                params.text_document.uri,
+                start,
+                end,
+                SourcePositionEncoding::UTF16,
+            )
+            .await
+            .map_err(|e| RpcError {
+                code: ErrorCode::InternalError,
+                message: e.to_string().into(),
+                data: None,
+            })?;
+
+        Ok(result.map(|actions| {
+            actions
+                .into_iter()
+                .map(CodeActionOrCommand::CodeAction)
+                .collect()
+        }))
+    }
 }
@@ -0,0 +1,126 @@
+//! Integration tests for the `textDocument/codeAction` request.
+
+mod common;
+
+use common::TestContext;
+use pretty_assertions::assert_eq;
+use tower_lsp::lsp_types::CodeActionContext;
+use tower_lsp::lsp_types::CodeActionOrCommand;
+use tower_lsp::lsp_types::CodeActionParams;
+use tower_lsp::lsp_types::CodeActionResponse;
+use tower_lsp::lsp_types::Position;
+use tower_lsp::lsp_types::Range;
+use tower_lsp::lsp_types::TextDocumentIdentifier;
+use tower_lsp::lsp_types::request::CodeActionRequest;
+
+async fn code_action_request(
+    ctx: &mut TestContext,
+    path: &str,
+    range: Range,
+) -> Option<CodeActionResponse> {
+    ctx.request::<CodeActionRequest>(CodeActionParams {
+        text_document: TextDocumentIdentifier {
+            uri: ctx.doc_uri(path),
+        },
+        range,
+        context: CodeActionContext::default(),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    })
+    .await
+}
+
+async fn setup() -> TestContext {
+    let mut ctx = TestContext::new("code_action");
+    ctx.initialize().await;
+    ctx
+}
+
+/// Gets the title and replacement text of each code action in a response.
+fn edits(response: CodeActionResponse) -> Vec<(String, String)> {
+    response
+        .into_iter()
+        .map(|action| match action {
+            CodeActionOrCommand::CodeAction(action) => {
+                let edit = action
+                    .edit
+                    .and_then(|e| e.changes)
+                    .and_then(|c| c.into_values().next())
+                    .and_then(|e| e.into_iter().next())
+                    .expect("code action should have an edit");
+                (action.title, edit.new_text)
+            }
+            CodeActionOrCommand::Command(_) => panic!("expected a code action"),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn should_qualify_call_to_imported_task() {
+    let mut ctx = setup().await;
+    let response = code_action_request(
+        &mut ctx,
+        "source.wdl",
+        Range::new(Position::new(5, 9), Position::new(5, 14)),
+    )
+    .await
+    .expect("should have code actions");
+
+    assert_eq!(
+        edits(response),
+        [(
+            "Replace with `util.greet`".to_string(),
+            "util.greet".to_string()
+        )]
+    );
+}
+
+#[tokio::test]
+async fn should_replace_wrong_namespace() {
+    let mut ctx = setup().await;
+    let response = code_action_request(
+        &mut ctx,
+        "source.wdl",
+        Range::new(Position::new(6, 9), Position::new(6, 9)),
+    )
+    .await
+    .expect("should have code actions");
+
+    assert_eq!(
+        edits(response),
+        [(
+            "Replace with `util.greet`".to_string(),
+            "util.greet".to_string()
+        )]
+    );
+}
+
+#[tokio::test]
+async fn should_insert_missing_version_after_preamble() {
+    let mut ctx = setup().await;
+    let response = code_action_request(
+        &mut ctx,
+        "missing_version.wdl",
+        Range::new(Position::new(2, 0), Position::new(2, 0)),
+    )
+    .await
+    .expect("should have code actions");
+
+    let [CodeActionOrCommand::CodeAction(action)] = response.as_slice() else {
+        panic!("expected a single code action");
+    };
+    let edit = action
+        .edit
+        .as_ref()
+        .and_then(|e| e.changes.as_ref())
+        .and_then(|c| c.values().next())
+        .and_then(|e| e.first())
+        .expect("code action should have an edit");
+
+    assert_eq!(action.title, "Insert `version 1.2`");
+    assert_eq!(
+        edit.range,
+        Range::new(Position::new(0, 50), Position::new(0, 50))
+    );
+    assert_eq!(edit.new_text, "\n\nversion 1.2");
+}
@@ -25,7 +25,6 @@ use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
 use tokio::io::DuplexStream;
 use tokio::io::duplex;
-use tower_lsp::LspService;
 use tower_lsp::jsonrpc;
 use tower_lsp::lsp_types;
 use tower_lsp::lsp_types::ClientCapabilities;
@@ -69,6 +68,8 @@ pub struct TestContext {
     pub request_id: i64,
     /// The temporary directory representing the workspace root.
     pub workspace: TempDir,
+    /// The workspace edits the server has requested the client to apply.
+    pub applied_edits: Vec<lsp_types::ApplyWorkspaceEditParams>,
 }
 
 const MAX_BUF_SIZE: usize = 4096;
@@ -80,19 +81,22 @@ impl TestContext {
     /// which contains the WDL files for the test. These files are copied
     /// into a temporary workspace directory.
     pub fn new(base: &str) -> Self {
+        Self::with_options(
+            base,
+            ServerOptions {
+                lint: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Creates a new test context with the given server options.
+    pub fn with_options(base: &str, options: ServerOptions) -> Self {
         let (request_tx, req_server) = duplex(MAX_BUF_SIZE);
         let (resp_server, response_rx) = duplex(MAX_BUF_SIZE);
         let response_rx = BufReader::new(response_rx);
 
-        let (service, socket) = LspService::new(|client| {
-            Server::new(
-                client,
-                ServerOptions {
-                    lint: true,
-                    ..Default::default()
-                },
-            )
-        });
+        let (service, socket) = Server::service(options);
         let server =
             tokio::spawn(tower_lsp::Server::new(req_server, resp_server, socket).serve(service));
 
@@ -113,6 +117,7 @@ impl TestContext {
             _server: server,
             request_id: 0,
             workspace,
+            applied_edits: Vec::new(),
         }
     }
 
@@ -174,10 +179,25 @@ impl TestContext {
     /// requests to acknowledge progress token creation. We automatically
     /// respond with `null` to keep the server's progress reporting
     /// functional without blocking tests.
+    ///
+    /// The server also sends `workspace/applyEdit` requests from workspace
+    /// commands; the edits are recorded in `applied_edits` and reported as
+    /// applied.
     pub async fn response<R>(&mut self, expected_id: jsonrpc::Id) -> R
     where
         R: Debug + serde::de::DeserializeOwned,
     {
+        serde_json::from_value(self.response_result(expected_id).await.unwrap()).unwrap()
+    }
+
+    /// Receives the result of the JSON-RPC response with the `expected_id`.
+    ///
+    /// Unlike [`TestContext::response`], an error response is returned rather
+    /// than causing a panic.
+    pub async fn response_result(
+        &mut self,
+        expected_id: jsonrpc::Id,
+    ) -> jsonrpc::Result<serde_json::Value> {
         loop {
             let content_str = self
                 .read_message_str()
@@ -187,20 +207,29 @@ impl TestContext {
             if let Ok(response) = serde_json::from_str::<jsonrpc::Response>(&content_str) {
                 let (id, result) = response.into_parts();
                 if id == expected_id {
-                    return serde_json::from_value(result.unwrap()).unwrap();
+                    return result;
                 } else {
                     continue;
                 }
             }
 
             if let Ok(request) = serde_json::from_str::<jsonrpc::Request>(&content_str) {
-                let (method, id_opt, _params) = request.into_parts();
+                let (method, id_opt, params) = request.into_parts();
                 if method == "window/workDoneProgress/create"
                     && let Some(id) = id_opt
                 {
                     let response = jsonrpc::Response::from_ok(id, serde_json::Value::Null);
                     let response_str = serde_json::to_string(&response).unwrap();
                     self.send_raw(&response_str).await;
+                } else if method == "workspace/applyEdit"
+                    && let Some(id) = id_opt
+                {
+                    self.applied_edits
+                        .push(serde_json::from_value(params.unwrap()).unwrap());
+                    let response =
+                        jsonrpc::Response::from_ok(id, serde_json::json!({ "applied": true }));
+                    let response_str = serde_json::to_string(&response).unwrap();
+                    self.send_raw(&response_str).await;
                 }
                 continue;
             }
@@ -0,0 +1,65 @@
+//! Integration tests for debounced diagnostics requests.
+
+mod common;
+
+use std::time::Duration;
+
+use common::TestContext;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use tower_lsp::jsonrpc;
+use tower_lsp::jsonrpc::ErrorCode;
+use tower_lsp::lsp_types::DocumentDiagnosticParams;
+use tower_lsp::lsp_types::DocumentDiagnosticReportResult;
+use tower_lsp::lsp_types::TextDocumentIdentifier;
+use tower_lsp::lsp_types::request::DocumentDiagnosticRequest;
+use tower_lsp::lsp_types::request::Request;
+use wdl_lsp::ServerOptions;
+
+/// Sends a diagnostics request for the given document without waiting for
+/// the response.
+async fn send_diagnostic_request(ctx: &mut TestContext, path: &str) -> jsonrpc::Id {
+    let id = jsonrpc::Id::Number(ctx.request_id);
+    let params = DocumentDiagnosticParams {
+        text_document: TextDocumentIdentifier {
+            uri: ctx.doc_uri(path),
+        },
+        identifier: None,
+        previous_result_id: None,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    let request = jsonrpc::Request::build(DocumentDiagnosticRequest::METHOD)
+        .id(ctx.request_id)
+        .params(serde_json::to_value(params).unwrap())
+        .finish();
+    ctx.request_id += 1;
+    ctx.send(&request).await;
+    id
+}
+
+#[tokio::test]
+async fn should_supersede_pending_diagnostics_requests() {
+    let mut ctx = TestContext::with_options(
+        "hover",
+        ServerOptions {
+            lint: true,
+            debounce: Some(Duration::from_millis(200)),
+            ..Default::default()
+        },
+    );
+    ctx.initialize().await;
+
+    let first = send_diagnostic_request(&mut ctx, "source.wdl").await;
+    let second = send_diagnostic_request(&mut ctx, "source.wdl").await;
+
+    // The first request is superseded by the second and should be retriggered
+    let error = ctx.response_result(first).await.unwrap_err();
+    assert_eq!(error.code, ErrorCode::ServerError(-32802));
+    assert_eq!(error.data, Some(json!({ "retriggerRequest": true })));
+
+    // The second request is answered once the document settles
+    let result = ctx.response_result(second).await.unwrap();
+    let report: DocumentDiagnosticReportResult = serde_json::from_value(result).unwrap();
+    assert!(matches!(report, DocumentDiagnosticReportResult::Report(_)));
+}
@@ -173,3 +173,20 @@ async fn should_hover_local_struct_literal_docs() {
     assert_hover_content(&response, "(property) name: String");
     assert_hover_content(&response, "Name of the person");
 }
+
+#[tokio::test]
+async fn should_hover_local_variable_doc_comment() {
+    let mut ctx = setup().await;
+    let response = hover_request(&mut ctx, "doc_comments.wdl", Position::new(10, 16)).await;
+    assert_hover_content(&response, "(variable) name: String");
+    assert_hover_content(&response, "The name to greet.");
+}
+
+#[tokio::test]
+async fn should_hover_task_doc_comment() {
+    let mut ctx = setup().await;
+    let response = hover_request(&mut ctx, "doc_comments.wdl", Position::new(15, 10)).await;
+    assert_hover_content(&response, "task say_hello");
+    assert_hover_content(&response, "Says hello to someone.");
+    assert_hover_content(&response, "**name**: `String`\n  - The name to greet.");
+}
@@ -0,0 +1,49 @@
+//! Integration tests for the custom `wdl/metrics` request.
+
+mod common;
+
+use common::TestContext;
+use serde_json::Value;
+use tower_lsp::jsonrpc;
+use tower_lsp::lsp_types::HoverParams;
+use tower_lsp::lsp_types::Position;
+use tower_lsp::lsp_types::TextDocumentIdentifier;
+use tower_lsp::lsp_types::TextDocumentPositionParams;
+use tower_lsp::lsp_types::request::HoverRequest;
+use wdl_lsp::METRICS_REQUEST;
+
+async fn metrics_request(ctx: &mut TestContext) -> Value {
+    let id = jsonrpc::Id::Number(ctx.request_id);
+    let request = jsonrpc::Request::build(METRICS_REQUEST)
+        .id(ctx.request_id)
+        .finish();
+    ctx.request_id += 1;
+    ctx.send(&request).await;
+    ctx.response(id).await
+}
+
+#[tokio::test]
+async fn should_report_request_metrics() {
+    let mut ctx = TestContext::new("hover");
+    ctx.initialize().await;
+
+    for _ in 0..2 {
+        ctx.request::<HoverRequest>(HoverParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: ctx.doc_uri("source.wdl"),
+                },
+                position: Position::new(6, 15),
+            },
+            work_done_progress_params: Default::default(),
+        })
+        .await;
+    }
+
+    let metrics = metrics_request(&mut ctx).await;
+    let methods = &metrics["methods"];
+    assert_eq!(methods["textDocument/hover"]["count"], 2);
+    assert_eq!(methods["workspace/diagnostic"]["count"], 1);
+    assert!(methods["textDocument/hover"]["maxMs"].as_f64().is_some());
+    assert!(metrics["budgetMs"].is_null());
+}
@@ -0,0 +1,86 @@
+//! Integration tests for the workspace commands.
+
+mod common;
+
+use common::TestContext;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use tower_lsp::lsp_types::ExecuteCommandParams;
+use tower_lsp::lsp_types::Position;
+use tower_lsp::lsp_types::Range;
+use tower_lsp::lsp_types::TextEdit;
+use tower_lsp::lsp_types::request::ExecuteCommand;
+use wdl_lsp::FIX_ALL_COMMAND;
+use wdl_lsp::FORMAT_WORKSPACE_COMMAND;
+
+async fn execute_command(ctx: &mut TestContext, command: &str) -> Option<Value> {
+    ctx.request::<ExecuteCommand>(ExecuteCommandParams {
+        command: command.to_string(),
+        arguments: Vec::new(),
+        work_done_progress_params: Default::default(),
+    })
+    .await
+}
+
+#[tokio::test]
+async fn should_advertise_workspace_commands() {
+    let mut ctx = TestContext::new("workspace_commands");
+    let result = ctx.initialize().await;
+    let commands = result
+        .capabilities
+        .execute_command_provider
+        .expect("should provide commands")
+        .commands;
+    assert_eq!(commands, [FIX_ALL_COMMAND, FORMAT_WORKSPACE_COMMAND]);
+}
+
+#[tokio::test]
+async fn should_format_workspace() {
+    let mut ctx = TestContext::new("workspace_commands");
+    ctx.initialize().await;
+
+    let summary = execute_command(&mut ctx, FORMAT_WORKSPACE_COMMAND)
+        .await
+        .expect("should have a summary");
+    assert_eq!(summary["filesChanged"], 1);
+
+    assert_eq!(ctx.applied_edits.len(), 1);
+    let changes = ctx.applied_edits[0]
+        .edit
+        .changes
+        .as_ref()
+        .expect("should have changes");
+    let edits = &changes[&ctx.doc_uri("source.wdl")];
+    assert_eq!(edits.len(), 1);
+    assert!(edits[0].new_text.contains("    String x = \"a\""));
+}
+
+#[tokio::test]
+async fn should_summarize_fixes() {
+    let mut ctx = TestContext::new("workspace_fixes");
+    ctx.initialize().await;
+
+    let summary = execute_command(&mut ctx, FIX_ALL_COMMAND)
+        .await
+        .expect("should have a summary");
+    assert_eq!(
+        summary,
+        json!({ "filesChanged": 1, "fixesApplied": 1, "fixesSkipped": 0 })
+    );
+
+    // The trailing whitespace is removed
+    assert_eq!(ctx.applied_edits.len(), 1);
+    let changes = ctx.applied_edits[0]
+        .edit
+        .changes
+        .as_ref()
+        .expect("should have changes");
+    assert_eq!(
+        changes[&ctx.doc_uri("source.wdl")],
+        [TextEdit {
+            range: Range::new(Position::new(2, 15), Position::new(2, 18)),
+            new_text: String::new(),
+        }]
+    );
+}
@@ -1,15 +1,19 @@
 //! Facilities for performing a typical analysis using the `wdl-*` crates.
 
 use std::collections::HashSet;
+use std::path::Path;
 use std::sync::Arc;
 
 use anyhow::Error;
 use futures::future::BoxFuture;
+use indexmap::IndexSet;
 use nonempty::NonEmpty;
 use tracing::info;
 use tracing::warn;
+use url::Url;
 use wdl::analysis::Analyzer;
 use wdl::analysis::DiagnosticsConfig;
+use wdl::analysis::IncrementalChange;
 use wdl::analysis::ProgressKind;
 use wdl::analysis::Validator;
 use wdl::lint::Linter;
@@ -50,6 +54,25 @@ pub struct Analysis {
     /// Basename for any ignorefiles which should be respected.
     ignore_filename: Option<String>,
 
+    /// Whether `.gitignore` files should be respected.
+    respect_gitignore: bool,
+
+    /// Whether symbolic links should be followed.
+    follow_symlinks: bool,
+
+    /// The globs of documents to include when searching directories.
+    include_globs: Vec<String>,
+
+    /// The globs of documents to exclude when searching directories.
+    exclude_globs: Vec<String>,
+
+    /// Whether diagnostics should be reported in a stable order.
+    stable_output: bool,
+
+    /// The contents of documents to analyze in place of the contents of the
+    /// documents' files.
+    contents: Vec<(Url, String)>,
+
     /// The initialization callback.
     init: InitCb,
 
@@ -76,6 +99,48 @@ impl Analysis {
         self
     }
 
+    /// Sets whether `.gitignore` files are respected when searching
+    /// directories.
+    pub fn respect_gitignore(mut self, respect: bool) -> Self {
+        self.respect_gitignore = respect;
+        self
+    }
+
+    /// Sets whether symbolic links are followed when searching directories.
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+
+    /// Adds globs of documents to include when searching directories.
+    pub fn extend_include_globs(mut self, globs: impl IntoIterator<Item = String>) -> Self {
+        self.include_globs.extend(globs);
+        self
+    }
+
+    /// Adds globs of documents to exclude when searching directories.
+    pub fn extend_exclude_globs(mut self, globs: impl IntoIterator<Item = String>) -> Self {
+        self.exclude_globs.extend(globs);
+        self
+    }
+
+    /// Sets whether diagnostics are reported in a stable order.
+    ///
+    /// See [`wdl::analysis::Config::with_stable_output`].
+    pub fn stable_output(mut self, stable: bool) -> Self {
+        self.stable_output = stable;
+        self
+    }
+
+    /// Extends the contents of documents to analyze in place of the contents
+    /// of the documents' files.
+    ///
+    /// The documents must also be found from the sources of the analysis.
+    pub fn extend_contents(mut self, contents: impl IntoIterator<Item = (Url, String)>) -> Self {
+        self.contents.extend(contents);
+        self
+    }
+
     /// Sets the initialization callback.
     pub fn init<F>(mut self, init: F) -> Self
     where
@@ -106,6 +171,23 @@ impl Analysis {
         self
     }
 
+    /// Finds the documents in the given directory that would be analyzed if it
+    /// were added as a source.
+    pub fn find_documents(&self, dir: &Path) -> anyhow::Result<IndexSet<Url>> {
+        wdl::analysis::find_documents(dir, &self.discovery_config())
+    }
+
+    /// Gets the analysis configuration for searching directories for
+    /// documents.
+    fn discovery_config(&self) -> wdl::analysis::Config {
+        wdl::analysis::Config::default()
+            .with_ignore_filename(self.ignore_filename.clone())
+            .with_respect_gitignore(self.respect_gitignore)
+            .with_follow_symlinks(self.follow_symlinks)
+            .with_include_globs(self.include_globs.clone())
+            .with_exclude_globs(self.exclude_globs.clone())
+    }
+
     /// Runs the analysis and returns all results (if any exist).
     pub async fn run(self) -> std::result::Result<AnalysisResults, NonEmpty<Arc<Error>>> {
         warn_unknown_rules(&self.exceptions);
@@ -127,9 +209,10 @@ impl Analysis {
             info!("enabled lint rules: {:?}", enabled_rules);
             info!("disabled lint rules: {:?}", disabled_rules);
         }
-        let config = wdl::analysis::Config::default()
+        let config = self
+            .discovery_config()
             .with_diagnostics_config(get_diagnostics_config(&self.exceptions))
-            .with_ignore_filename(self.ignore_filename);
+            .with_stable_output(self.stable_output);
 
         (self.init)();
 
@@ -160,6 +243,18 @@ impl Analysis {
             }
         }
 
+        for (uri, source) in self.contents {
+            let change = IncrementalChange {
+                version: 0,
+                start: Some(source),
+                edits: Vec::new(),
+            };
+
+            if let Err(error) = analyzer.notify_incremental_change(uri, change) {
+                return Err(NonEmpty::new(Arc::new(error)));
+            }
+        }
+
         let results = analyzer
             .analyze(())
             .await
@@ -177,6 +272,12 @@ impl Default for Analysis {
             enabled_lint_tags: TagSet::new(&[]),
             disabled_lint_tags: TagSet::new(&[]),
             ignore_filename: Some(IGNORE_FILENAME.to_string()),
+            respect_gitignore: true,
+            follow_symlinks: true,
+            include_globs: Default::default(),
+            exclude_globs: Default::default(),
+            stable_output: false,
+            contents: Default::default(),
             init: Box::new(|| {}),
             progress: Box::new(|_, _, _| Box::pin(async {})),
         }
@@ -4,6 +4,7 @@ use std::fmt;
 use std::io::IsTerminal;
 use std::sync::Arc;
 
+use clap::Parser;
 use clap::Subcommand;
 use colored::Colorize;
 use nonempty::NonEmpty;
@@ -12,12 +13,20 @@ pub mod analyzer;
 pub mod check;
 pub mod completions;
 pub mod config;
+pub mod convert;
+pub mod daemon;
 pub mod doc;
 pub mod explain;
 pub mod format;
+pub mod images;
 pub mod inputs;
 pub mod lock;
+pub mod new_rule;
+pub mod plan;
 pub mod run;
+pub mod runs;
+pub mod server;
+pub mod stats;
 pub mod validate;
 
 /// Represents an error that may result from a command.
@@ -77,6 +86,48 @@ impl From<NonEmpty<Arc<anyhow::Error>>> for CommandError {
 /// Represents the result of a command.
 pub type CommandResult<T> = std::result::Result<T, CommandError>;
 
+/// Arguments that control how directories are searched for documents.
+#[derive(Parser, Debug, Clone, Default)]
+pub struct DiscoveryArgs {
+    /// Only includes documents matching the given glob when searching
+    /// directories.
+    ///
+    /// Globs are matched against paths relative to the directory being
+    /// searched. Repeat the flag multiple times to include multiple globs.
+    #[arg(long, value_name = "GLOB", action = clap::ArgAction::Append, global = true)]
+    pub include: Vec<String>,
+
+    /// Excludes documents and directories matching the given glob when
+    /// searching directories.
+    ///
+    /// Globs are matched against paths relative to the directory being
+    /// searched. Repeat the flag multiple times to exclude multiple globs.
+    /// Exclusions take precedence over inclusions.
+    #[arg(long, value_name = "GLOB", action = clap::ArgAction::Append, global = true)]
+    pub exclude: Vec<String>,
+
+    /// Does not respect `.gitignore` files when searching directories.
+    ///
+    /// `.sprocketignore` files are always respected.
+    #[arg(long, global = true)]
+    pub no_gitignore: bool,
+
+    /// Does not follow symbolic links when searching directories.
+    #[arg(long, global = true)]
+    pub no_follow_symlinks: bool,
+}
+
+impl DiscoveryArgs {
+    /// Applies the discovery arguments to the given analysis.
+    pub fn apply(self, analysis: crate::analysis::Analysis) -> crate::analysis::Analysis {
+        analysis
+            .respect_gitignore(!self.no_gitignore)
+            .follow_symlinks(!self.no_follow_symlinks)
+            .extend_include_globs(self.include)
+            .extend_exclude_globs(self.exclude)
+    }
+}
+
 /// Represents the available commands for the Sprocket CLI.
 #[derive(Subcommand, Debug)]
 #[allow(clippy::large_enum_variant)]
@@ -93,6 +144,21 @@ pub enum Commands {
     /// Display the effective configuration.
     Config(config::Args),
 
+    /// Converts a document to another workflow language.
+    ///
+    /// Conversion produces a starting point for a migration: tasks are
+    /// converted to process stubs and the calls at the top level of a workflow
+    /// are wired together. Constructs that cannot be converted are reported.
+    Convert(convert::Args),
+
+    /// Periodically re-validates a set of documents.
+    ///
+    /// Documents are re-analyzed on an interval to detect breakage that occurs
+    /// without a change to the documents themselves, such as a remote import
+    /// that has drifted or a container image that was removed from its
+    /// registry. Results may be served over HTTP and sent to webhooks.
+    Daemon(daemon::Args),
+
     /// Explains linting and validation rules.
     Explain(explain::Args),
 
@@ -100,15 +166,50 @@ pub enum Commands {
     #[clap(alias = "fmt")]
     Format(format::Args),
 
+    /// Lists the container images referenced by tasks.
+    ///
+    /// Container expressions are statically evaluated where possible (e.g.
+    /// string literals and declarations with string literal values). The
+    /// registry of each image may optionally be queried for its digest and the
+    /// platforms it is available for.
+    Images(images::Args),
+
     /// Writes the inputs schema for a WDL document.
     Inputs(inputs::Args),
 
     /// Lints a document or a directory containing documents.
     Lint(check::LintArgs),
 
+    /// Estimates the task executions of a workflow without running it.
+    ///
+    /// Scatter collections are statically evaluated where possible (e.g. the
+    /// lengths of provided arrays or the number of lines in a provided file
+    /// passed to `read_lines`) to report the expected number of task
+    /// executions and the peak parallelism of the workflow.
+    Plan(plan::Args),
+
     /// Runs a task or workflow.
     Run(run::Args),
 
+    /// Inspects the runs of tasks and workflows.
+    Runs(runs::Args),
+
+    /// Serves Cromwell-compatible metadata of runs over HTTP.
+    ///
+    /// The metadata and status of a run are served as JSON from `GET
+    /// /api/workflows/v1/{id}/metadata` and `GET
+    /// /api/workflows/v1/{id}/status`, where the id is the name of the run
+    /// beneath the runs directory (e.g. `<timestamp>` or
+    /// `<entrypoint>/<timestamp>`, with the `/` encoded as `%2F`).
+    Server(server::Args),
+
+    /// Reports statistics for a set of documents.
+    ///
+    /// This includes the number of workflows, tasks, and structs, the lines of
+    /// WDL, the distribution of WDL versions, the most-imported documents, and
+    /// the density of rule violations in each document.
+    Stats(stats::Args),
+
     /// Validate a set of inputs against a task or workflow.
     ///
     /// This ensures that every required input is supplied, every supplied input
@@ -132,4 +233,6 @@ pub enum DevCommands {
     Doc(doc::Args),
     /// Locks Docker images to a sha256 digest.
     Lock(lock::Args),
+    /// Generates the skeleton of a new lint rule.
+    NewRule(new_rule::Args),
 }
@@ -1,5 +1,7 @@
 //! Implementation of the language server protocol (LSP) subcommand.
 
+use std::time::Duration;
+
 use clap::Parser;
 use clap::builder::PossibleValuesParser;
 use wdl::lsp::Server;
@@ -33,6 +35,17 @@ pub struct Args {
         hide_possible_values = true,
     )]
     pub except: Vec<String>,
+
+    /// The number of milliseconds to wait for a document to stop changing
+    /// before analyzing it.
+    #[clap(long, value_name = "MS")]
+    pub debounce: Option<u64>,
+
+    /// The target latency, in milliseconds, for handling requests.
+    ///
+    /// Requests that take longer are logged as warnings.
+    #[clap(long, value_name = "MS")]
+    pub latency_budget: Option<u64>,
 }
 
 impl Args {
@@ -59,6 +72,8 @@ pub async fn analyzer(args: Args) -> CommandResult<()> {
         lint: args.lint,
         exceptions: args.except,
         ignore_filename: Some(IGNORE_FILENAME.to_string()),
+        debounce: args.debounce.map(Duration::from_millis),
+        latency_budget: args.latency_budget.map(Duration::from_millis),
     })
     .await
     .map_err(CommandError::from)
@@ -1,21 +1,38 @@
 //! Implementation of the `check` and `lint` subcommands.
 
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::io::BufRead;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
 use std::str::FromStr;
 
 use anyhow::Context;
 use anyhow::anyhow;
 use clap::Parser;
+use clap::ValueEnum;
 use clap::builder::PossibleValuesParser;
 use codespan_reporting::diagnostic::Diagnostic;
 use codespan_reporting::files::SimpleFiles;
+use indexmap::IndexMap;
+use path_clean::PathClean;
+use similar::DiffTag;
+use similar::TextDiff;
+use similar::udiff::UnifiedDiffHunk;
 use strum::VariantArray;
 use tracing::info;
+use tracing::warn;
+use url::Url;
+use wdl::analysis::unused_definitions;
 use wdl::ast::AstNode;
 use wdl::ast::Severity;
+use wdl::lint::Rule;
 use wdl::lint::Tag;
 use wdl::lint::TagSet;
 use wdl::lint::find_nearest_rule;
+use wdl::lint::fix::Fix;
+use wdl::lint::fix::merge_fixes;
 
 use super::explain::ALL_RULE_IDS;
 use super::explain::ALL_TAG_NAMES;
@@ -23,12 +40,17 @@ use crate::analysis::Analysis;
 use crate::analysis::Source;
 use crate::commands::CommandError;
 use crate::commands::CommandResult;
+use crate::commands::DiscoveryArgs;
+use crate::config::Config;
+use crate::config::nested_config_files;
 use crate::diagnostics::Mode;
 use crate::diagnostics::emit_diagnostics;
 use crate::diagnostics::get_diagnostics_display_config;
+use crate::effectiveness::EffectivenessReport;
+use crate::report::HtmlReport;
 
 /// The [`Tag`]s which will run with the default `lint` configuration.
-const DEFAULT_TAG_SET: TagSet = TagSet::new(&[
+pub const DEFAULT_TAG_SET: TagSet = TagSet::new(&[
     Tag::Completeness,
     Tag::Naming,
     Tag::Clarity,
@@ -36,10 +58,11 @@ const DEFAULT_TAG_SET: TagSet = TagSet::new(&[
     Tag::Correctness,
     Tag::Deprecated,
     Tag::Documentation,
+    Tag::Performance,
 ]);
 
 /// Common arguments for the `check` and `lint` subcommands.
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(author, version, about)]
 pub struct Common {
     /// A set of source documents as files, directories, or URLs.
@@ -67,6 +90,13 @@ pub struct Common {
     #[clap(short, long, conflicts_with_all = ["only_lint_tag"])]
     pub all_lint_rules: bool,
 
+    /// Enables strict mode.
+    ///
+    /// Strict mode enables all lint rules (as with `--all-lint-rules`) and
+    /// reports violations of lint rules tagged `Correctness` as errors.
+    #[clap(long, conflicts_with_all = ["only_lint_tag"])]
+    pub strict: bool,
+
     /// Excludes a lint tag from running if it would have been included
     /// otherwise.
     ///
@@ -129,6 +159,63 @@ pub struct Common {
     /// The report mode.
     #[arg(short = 'm', long, value_name = "MODE")]
     pub report_mode: Option<Mode>,
+
+    /// Reports the diagnostics of each document ordered by location and then
+    /// by rule.
+    ///
+    /// This is useful for snapshot tests and for diffing output in CI.
+    #[arg(long)]
+    pub stable_output: bool,
+
+    /// The arguments that control how directories are searched.
+    #[command(flatten)]
+    pub discovery: DiscoveryArgs,
+
+    /// Does not apply the `sprocket.toml` files of the directories below the
+    /// current directory to the documents within them.
+    ///
+    /// These files are applied even when `--skip-config-search` is given.
+    #[arg(long)]
+    pub no_nested_config: bool,
+
+    /// The arguments and configuration used to apply nested configuration
+    /// files.
+    ///
+    /// This is `None` if nested configuration files are not searched for.
+    #[clap(skip)]
+    pub nested: Option<Box<NestedConfig>>,
+}
+
+/// The command line arguments and configuration from before the configuration
+/// was applied.
+///
+/// These are used to apply the `sprocket.toml` files of the directories below
+/// the current directory to the documents within them.
+#[derive(Debug, Clone)]
+pub struct NestedConfig {
+    /// The command line arguments.
+    common: Common,
+    /// Whether or not linting was enabled on the command line.
+    lint: bool,
+    /// The configuration that was applied to the arguments.
+    config: Config,
+}
+
+impl NestedConfig {
+    /// Gets the rules selected for documents to which the given nested
+    /// configuration files apply.
+    fn selection(&self, files: &[PathBuf]) -> anyhow::Result<Selection> {
+        let args = CheckArgs {
+            common: self.common.clone(),
+            lint: self.lint,
+            effectiveness_report: None,
+            format: LintFormat::Text,
+            report_unused: false,
+            unused_report: None,
+        }
+        .apply(self.config.merge_nested(files)?);
+        Ok(Selection::new(&args.common, args.lint))
+    }
 }
 
 /// Arguments for the `check` subcommand.
@@ -142,12 +229,45 @@ pub struct CheckArgs {
     /// Enable lint checks in addition to validation errors.
     #[arg(short, long)]
     pub lint: bool,
+
+    /// The path to write a lint rule effectiveness report to.
+    ///
+    /// This is set by the `lint` subcommand.
+    #[clap(skip)]
+    pub effectiveness_report: Option<PathBuf>,
+
+    /// The format of the report of the results.
+    ///
+    /// This is set by the `lint` subcommand.
+    #[clap(skip)]
+    pub format: LintFormat,
+
+    /// Report structs that are not used and tasks that are not called by any
+    /// of the analyzed documents.
+    ///
+    /// As libraries may intentionally define structs and tasks for use by
+    /// other documents, these are only reported when requested.
+    #[arg(long)]
+    pub report_unused: bool,
+
+    /// Writes the structs and tasks that are not used by any of the analyzed
+    /// documents to the given JSON file.
+    ///
+    /// This implies `--report-unused`.
+    #[arg(long, value_name = "PATH")]
+    pub unused_report: Option<PathBuf>,
 }
 
 impl CheckArgs {
     /// Applies the configuration from the given config file to the command line
     /// arguments.
     pub fn apply(mut self, config: crate::config::Config) -> Self {
+        let nested = NestedConfig {
+            common: self.common.clone(),
+            lint: self.lint,
+            config: config.clone(),
+        };
+
         self.common.except = self
             .common
             .except
@@ -168,11 +288,16 @@ impl CheckArgs {
         if !self.common.filter_lint_tag.is_empty()
             || !self.common.only_lint_tag.is_empty()
             || self.common.all_lint_rules
+            || self.common.strict
         {
             self.lint = true;
         }
 
-        self.common.all_lint_rules = self.common.all_lint_rules || config.check.all_lint_rules;
+        // Strict mode from the config also implies linting
+        self.common.strict = self.common.strict || config.check.strict;
+        self.lint = self.lint || self.common.strict;
+        self.common.all_lint_rules =
+            self.common.all_lint_rules || config.check.all_lint_rules || self.common.strict;
         self.common.filter_lint_tag = self
             .common
             .filter_lint_tag
@@ -190,10 +315,34 @@ impl CheckArgs {
                 .collect();
         }
 
+        if !self.common.no_nested_config {
+            self.common.nested = Some(Box::new(nested));
+        }
+
         self
     }
 }
 
+/// The format of the report of lint results.
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum LintFormat {
+    /// Diagnostics are reported to stderr.
+    #[default]
+    Text,
+
+    /// A self-contained HTML report is printed to stdout.
+    Html,
+}
+
+impl std::fmt::Display for LintFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Html => write!(f, "html"),
+        }
+    }
+}
+
 /// Arguments for the `lint` subcommand.
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
@@ -201,6 +350,70 @@ pub struct LintArgs {
     /// The command command line arguments.
     #[command(flatten)]
     pub common: Common,
+
+    /// Inserts `#@ except` comments for the given lint rule above each of its
+    /// current violations.
+    ///
+    /// The rule is run regardless of the selected lint tags and the modified
+    /// documents are written in place. No diagnostics are reported.
+    #[clap(long, value_name = "RULE")]
+    pub add_exceptions: Option<String>,
+
+    /// Applies the automatic fixes of the enabled lint rules.
+    ///
+    /// Documents are linted and fixed repeatedly until no more fixes apply.
+    /// Fixes that conflict with another fix in the same pass are skipped and
+    /// retried by the next pass; fixes that still conflict after the last pass
+    /// are reported. The modified documents are written in place.
+    #[clap(long, conflicts_with = "add_exceptions")]
+    pub fix: bool,
+
+    /// Prints a unified diff of the fixes that would be applied by `--fix`
+    /// without modifying any documents.
+    ///
+    /// Every pass of fixes is made in memory, so the diff is of the documents
+    /// as they would be after `--fix`.
+    #[clap(long, requires = "fix", conflicts_with = "interactive")]
+    pub dry_run: bool,
+
+    /// Prompts for whether or not to apply each hunk of the fixes made by
+    /// `--fix`.
+    ///
+    /// Only a single pass of fixes is made so that declined fixes are not
+    /// offered again; run `lint --fix` again to apply fixes that depend on
+    /// the accepted ones.
+    #[clap(long, requires = "fix")]
+    pub interactive: bool,
+
+    /// Writes a report of the effectiveness of each lint rule to the given
+    /// JSON file.
+    ///
+    /// The report counts the current findings and `#@ except` comments of each
+    /// rule. If the file already contains a report, the findings that have
+    /// since gone away are counted as either fixed or suppressed and the
+    /// totals are carried forward, so that repeated runs show which rules'
+    /// findings tend to be fixed rather than suppressed. The report is only
+    /// written locally.
+    #[clap(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["add_exceptions", "fix"]
+    )]
+    pub effectiveness_report: Option<PathBuf>,
+
+    /// The format of the report of the lint results.
+    ///
+    /// The `html` format prints a single, self-contained HTML report to stdout
+    /// instead of reporting diagnostics to stderr. The report's diagnostics
+    /// can be searched and filtered by rule, severity, and file, and it
+    /// includes the explanations of the reported rules.
+    #[clap(
+        long,
+        value_name = "FORMAT",
+        default_value_t,
+        conflicts_with_all = ["add_exceptions", "fix"]
+    )]
+    pub format: LintFormat,
 }
 
 impl LintArgs {
@@ -210,19 +423,235 @@ impl LintArgs {
         let args = CheckArgs {
             common: self.common,
             lint: true,
+            effectiveness_report: None,
+            format: LintFormat::Text,
+            report_unused: false,
+            unused_report: None,
         }
         .apply(config);
         self = LintArgs {
             common: args.common,
+            add_exceptions: self.add_exceptions,
+            fix: self.fix,
+            dry_run: self.dry_run,
+            interactive: self.interactive,
+            effectiveness_report: self.effectiveness_report,
+            format: self.format,
         };
 
         self
     }
 }
 
+/// Gets the lint tags to enable and disable for the given arguments.
+fn lint_tags(common: &Common, lint: bool) -> (TagSet, TagSet) {
+    let enabled_tags = if lint {
+        if common.all_lint_rules {
+            TagSet::new(Tag::VARIANTS)
+        } else if !common.only_lint_tag.is_empty() {
+            TagSet::new(
+                common
+                    .only_lint_tag
+                    .iter()
+                    .filter_map(|t| Tag::from_str(t).ok())
+                    .collect::<Vec<_>>()
+                    .as_slice(),
+            )
+        } else {
+            DEFAULT_TAG_SET
+        }
+    } else {
+        TagSet::new(&[])
+    };
+
+    let disabled_tags = if lint && !common.filter_lint_tag.is_empty() {
+        TagSet::new(
+            common
+                .filter_lint_tag
+                .iter()
+                .filter_map(|t| Tag::from_str(t).ok())
+                .collect::<Vec<_>>()
+                .as_slice(),
+        )
+    } else {
+        TagSet::new(&[])
+    };
+
+    (enabled_tags, disabled_tags)
+}
+
+/// Represents the rules selected for a set of documents.
+#[derive(Debug, Clone)]
+struct Selection {
+    /// The enabled lint tags.
+    enabled_tags: TagSet,
+    /// The disabled lint tags.
+    disabled_tags: TagSet,
+    /// The excepted rules.
+    exceptions: Vec<String>,
+    /// Whether or not violations of `Correctness` lint rules are errors.
+    strict: bool,
+}
+
+impl Selection {
+    /// Creates the selection for the given arguments.
+    fn new(common: &Common, lint: bool) -> Self {
+        let (enabled_tags, disabled_tags) = lint_tags(common, lint);
+        Self {
+            enabled_tags,
+            disabled_tags,
+            exceptions: common.except.clone(),
+            strict: common.strict,
+        }
+    }
+
+    /// Combines the selection with another so that the rules of both are
+    /// selected.
+    fn union(mut self, other: &Self) -> Self {
+        self.enabled_tags = self.enabled_tags.union(other.enabled_tags);
+        self.disabled_tags = self.disabled_tags.intersect(other.disabled_tags);
+        self.exceptions.retain(|e| other.is_excepted(e));
+        self.strict = self.strict || other.strict;
+        self
+    }
+
+    /// Determines if the given rule is excepted.
+    fn is_excepted(&self, rule: &str) -> bool {
+        self.exceptions.iter().any(|e| e.eq_ignore_ascii_case(rule))
+    }
+
+    /// Determines if the given lint rule is selected.
+    fn selects(&self, rule: &dyn Rule) -> bool {
+        let tags = rule.tags();
+        !self.is_excepted(rule.id())
+            && self.enabled_tags.intersect(tags).count() > 0
+            && self.disabled_tags.intersect(tags).count() == 0
+    }
+
+    /// Applies the selection to a diagnostic.
+    ///
+    /// Returns `None` if the rule of the diagnostic is not selected. In strict
+    /// mode, diagnostics of `Correctness` lint rules are reported as errors.
+    fn apply(
+        &self,
+        rules: &[Box<dyn Rule>],
+        diagnostic: &wdl::ast::Diagnostic,
+    ) -> Option<wdl::ast::Diagnostic> {
+        let Some(id) = diagnostic.rule() else {
+            return Some(diagnostic.clone());
+        };
+
+        if self.is_excepted(id) {
+            return None;
+        }
+
+        let Some(rule) = rules.iter().find(|r| r.id() == id) else {
+            return Some(diagnostic.clone());
+        };
+
+        if !self.selects(rule.as_ref()) {
+            return None;
+        }
+
+        if self.strict && rule.tags().contains(Tag::Correctness) {
+            return Some(diagnostic.clone().with_severity(Severity::Error));
+        }
+
+        Some(diagnostic.clone())
+    }
+}
+
+/// Represents the rules selected for each of the analyzed documents.
+///
+/// Nested configuration files select the rules of the documents below them;
+/// an analysis runs the rules selected for any document and the diagnostics of
+/// each document are then filtered by the document's selection.
+struct Selections {
+    /// The directory that nested configuration files are found below.
+    base: PathBuf,
+    /// The nested configuration to apply.
+    ///
+    /// This is `None` if nested configuration files are not searched for.
+    nested: Option<Box<NestedConfig>>,
+    /// The selection for documents without nested configuration files.
+    default: Selection,
+    /// The selections for each distinct list of nested configuration files.
+    nested_selections: HashMap<Vec<PathBuf>, Selection>,
+}
+
+impl Selections {
+    /// Creates the selections for the documents of the given sources.
+    ///
+    /// Directories are searched for documents with the discovery arguments.
+    fn new(common: &Common, lint: bool, sources: &[Source]) -> anyhow::Result<Self> {
+        let mut selections = Self {
+            base: PathBuf::new(),
+            nested: common.nested.clone(),
+            default: Selection::new(common, lint),
+            nested_selections: HashMap::new(),
+        };
+
+        if selections.nested.is_none() {
+            return Ok(selections);
+        }
+
+        selections.base = std::env::current_dir().context("failed to get the current directory")?;
+        let analysis = common.discovery.clone().apply(Analysis::default());
+        for source in sources {
+            match source {
+                Source::Directory(dir) => {
+                    let dir = selections.base.join(dir).clean();
+                    for uri in analysis.find_documents(&dir)? {
+                        selections.get(&uri)?;
+                    }
+                }
+                Source::File(uri) => {
+                    selections.get(uri)?;
+                }
+                Source::Remote(_) => {}
+            }
+        }
+
+        Ok(selections)
+    }
+
+    /// Gets the selection of the rules to run in an analysis.
+    ///
+    /// This selects the rules of every document found when the selections
+    /// were created.
+    fn analysis(&self) -> Selection {
+        self.nested_selections
+            .values()
+            .fold(self.default.clone(), |s, other| s.union(other))
+    }
+
+    /// Gets the selection for the document with the given URI.
+    fn get(&mut self, uri: &Url) -> anyhow::Result<&Selection> {
+        let files = match (&self.nested, uri.to_file_path()) {
+            (Some(_), Ok(path)) => path
+                .parent()
+                .map(|dir| nested_config_files(&self.base, dir))
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+
+        match &self.nested {
+            Some(nested) if !files.is_empty() => {
+                if !self.nested_selections.contains_key(&files) {
+                    let selection = nested.selection(&files)?;
+                    self.nested_selections.insert(files.clone(), selection);
+                }
+
+                Ok(&self.nested_selections[&files])
+            }
+            _ => Ok(&self.default),
+        }
+    }
+}
+
 /// Performs the `check` subcommand.
 pub async fn check(args: CheckArgs) -> CommandResult<()> {
-    let mut sources = args.common.sources;
+    let mut sources = args.common.sources.clone();
     if sources.is_empty() {
         sources.push(Source::default());
     }
@@ -266,44 +695,20 @@ pub async fn check(args: CheckArgs) -> CommandResult<()> {
         .cloned()
         .collect::<HashSet<_>>();
 
-    let enabled_tags = if args.lint {
-        if args.common.all_lint_rules {
-            TagSet::new(Tag::VARIANTS)
-        } else if !args.common.only_lint_tag.is_empty() {
-            TagSet::new(
-                args.common
-                    .only_lint_tag
-                    .iter()
-                    .filter_map(|t| Tag::from_str(t).ok())
-                    .collect::<Vec<_>>()
-                    .as_slice(),
-            )
-        } else {
-            DEFAULT_TAG_SET
-        }
-    } else {
-        TagSet::new(&[])
-    };
-
-    let disabled_tags = if args.lint && !args.common.filter_lint_tag.is_empty() {
-        TagSet::new(
-            args.common
-                .filter_lint_tag
-                .iter()
-                .filter_map(|t| Tag::from_str(t).ok())
-                .collect::<Vec<_>>()
-                .as_slice(),
-        )
-    } else {
-        TagSet::new(&[])
-    };
+    let mut selections = Selections::new(&args.common, args.lint, &sources)?;
+    let analysis_selection = selections.analysis();
+    let rules = wdl::lint::rules();
 
     // Run analysis
-    let results = Analysis::default()
+    let results = args
+        .common
+        .discovery
+        .apply(Analysis::default())
         .extend_sources(sources)
-        .extend_exceptions(args.common.except)
-        .enabled_lint_tags(enabled_tags)
-        .disabled_lint_tags(disabled_tags)
+        .extend_exceptions(analysis_selection.exceptions)
+        .enabled_lint_tags(analysis_selection.enabled_tags)
+        .disabled_lint_tags(analysis_selection.disabled_tags)
+        .stable_output(args.common.stable_output)
         .run()
         .await
         .map_err(CommandError::from)?;
@@ -318,7 +723,20 @@ pub async fn check(args: CheckArgs) -> CommandResult<()> {
         pub notes: usize,
     }
 
+    // Unused structs and tasks are found across all of the analyzed documents
+    let unused = if args.report_unused || args.unused_report.is_some() {
+        unused_definitions(results.as_slice().iter().map(|r| r.document()))
+    } else {
+        Vec::new()
+    };
+
     let mut counts = Counts::default();
+    let rule_ids = rules.iter().map(|r| r.id()).collect::<Vec<_>>();
+    let mut report = args
+        .effectiveness_report
+        .as_ref()
+        .map(|_| EffectivenessReport::default());
+    let mut html = (args.format == LintFormat::Html).then(HtmlReport::default);
 
     for result in results {
         let uri = &result.document().uri();
@@ -333,16 +751,45 @@ pub async fn check(args: CheckArgs) -> CommandResult<()> {
             v => todo!("unhandled uri scheme: {v}"),
         };
 
-        let mut diagnostics = result.document().diagnostics().peekable();
+        let selection = selections.get(uri)?;
+
+        let unused_diagnostics = unused
+            .iter()
+            .filter(|u| u.uri() == *uri)
+            .map(|u| u.to_diagnostic())
+            .collect::<Vec<_>>();
+        let diagnostics = result
+            .document()
+            .diagnostics()
+            .chain(&unused_diagnostics)
+            .filter_map(|d| selection.apply(&rules, d))
+            .collect::<Vec<_>>();
+
+        if let Some(report) = &mut report
+            && uri.scheme() == "file"
+        {
+            report.add_document(
+                result.document().path().as_ref(),
+                result.document().root().inner(),
+                diagnostics
+                    .iter()
+                    .filter_map(|d| d.rule())
+                    .filter(|id| rule_ids.contains(id)),
+                &rule_ids,
+                rules
+                    .iter()
+                    .filter(|r| selection.selects(r.as_ref()))
+                    .map(|r| r.id()),
+            );
+        }
 
-        if diagnostics.peek().is_some() {
+        if !diagnostics.is_empty() {
             let path = result.document().path().to_string();
             let source = result.document().root().text().to_string();
 
-            emit_diagnostics(
-                &path,
-                source,
-                diagnostics.filter(|d| {
+            let diagnostics = diagnostics
+                .iter()
+                .filter(|d| {
                     let severity = d.severity();
 
                     match severity {
@@ -371,15 +818,49 @@ pub async fn check(args: CheckArgs) -> CommandResult<()> {
                             }
                         }
                     }
-                }),
-                &[],
-                args.common.report_mode.unwrap_or_default(),
-                args.common.no_color,
-            )
-            .context("failed to emit diagnostics")?;
+                })
+                .collect::<Vec<_>>();
+
+            match &mut html {
+                Some(html) => html.add_document(&path, &source, diagnostics),
+                None => emit_diagnostics(
+                    &path,
+                    source,
+                    diagnostics,
+                    &[],
+                    args.common.report_mode.unwrap_or_default(),
+                    args.common.no_color,
+                )
+                .context("failed to emit diagnostics")?,
+            }
         }
     }
 
+    if let Some(html) = &html {
+        print!("{}", html.render());
+    }
+
+    if let (Some(path), Some(mut report)) = (&args.effectiveness_report, report) {
+        let previous = EffectivenessReport::read(path)?;
+        report.summarize(previous.as_ref());
+        report.write(path)?;
+        info!(
+            "wrote rule effectiveness report to `{path}`",
+            path = path.display()
+        );
+    }
+
+    if let Some(path) = &args.unused_report {
+        let contents = serde_json::to_string_pretty(&unused)
+            .context("failed to serialize unused definitions")?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("failed to write `{path}`", path = path.display()))?;
+        info!(
+            "wrote unused definitions report to `{path}`",
+            path = path.display()
+        );
+    }
+
     if counts.errors > 0 {
         return Err(anyhow!(
             "failing due to {errors} error{s}",
@@ -408,13 +889,406 @@ pub async fn check(args: CheckArgs) -> CommandResult<()> {
 
 /// Performs the `lint` subcommand.
 pub async fn lint(args: LintArgs) -> CommandResult<()> {
+    if let Some(rule) = args.add_exceptions {
+        return add_exceptions(args.common, &rule).await;
+    }
+
+    if args.fix {
+        let mode = if args.dry_run {
+            FixMode::DryRun
+        } else if args.interactive {
+            FixMode::Interactive
+        } else {
+            FixMode::Write
+        };
+
+        return fix(args.common, mode).await;
+    }
+
     check(CheckArgs {
         common: args.common,
         lint: true,
+        effectiveness_report: args.effectiveness_report,
+        format: args.format,
+        report_unused: false,
+        unused_report: None,
     })
     .await
 }
 
+/// Inserts `#@ except` comments for a lint rule above each of its violations.
+async fn add_exceptions(common: Common, id: &str) -> CommandResult<()> {
+    let rule = wdl::lint::rules()
+        .into_iter()
+        .find(|r| r.id().eq_ignore_ascii_case(id))
+        .ok_or_else(|| match find_nearest_rule(id) {
+            Some(nearest) => anyhow!("unknown lint rule `{id}`: did you mean `{nearest}`?"),
+            None => anyhow!("unknown lint rule `{id}`"),
+        })?;
+
+    let mut sources = common.sources.clone();
+    if sources.is_empty() {
+        sources.push(Source::default());
+    }
+
+    let mut selections = Selections::new(&common, true, &sources)?;
+    let results = common
+        .discovery
+        .apply(Analysis::default())
+        .extend_sources(sources)
+        .extend_exceptions(selections.analysis().exceptions)
+        .enabled_lint_tags(TagSet::new(Tag::VARIANTS))
+        .run()
+        .await
+        .map_err(CommandError::from)?;
+
+    let mut total = 0;
+    for result in results {
+        let document = result.document();
+        let uri = document.uri();
+        if uri.scheme() != "file" || selections.get(uri)?.is_excepted(rule.id()) {
+            continue;
+        }
+
+        let (source, count) = wdl::lint::add_exceptions(
+            document.root().inner(),
+            rule.as_ref(),
+            document.diagnostics(),
+        );
+        if count == 0 {
+            continue;
+        }
+
+        let path = uri
+            .to_file_path()
+            .map_err(|_| anyhow!("document URI `{uri}` is not a valid file path"))?;
+        std::fs::write(&path, source)
+            .with_context(|| format!("failed to write `{path}`", path = path.display()))?;
+
+        info!(
+            "added {count} exception{s} to `{path}`",
+            s = if count == 1 { "" } else { "s" },
+            path = path.display()
+        );
+        total += count;
+    }
+
+    println!(
+        "added {total} exception{s} for rule `{id}`",
+        s = if total == 1 { "" } else { "s" },
+        id = rule.id()
+    );
+
+    Ok(())
+}
+
+/// The maximum number of passes made by `lint --fix`.
+const MAX_FIX_PASSES: usize = 10;
+
+/// Represents how `lint --fix` applies fixes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FixMode {
+    /// The fixes are written to the documents.
+    Write,
+    /// A unified diff of the fixes is printed; no documents are written.
+    DryRun,
+    /// The user is prompted for whether or not to apply each hunk of the
+    /// fixes.
+    Interactive,
+}
+
+/// Represents a response to an interactive fix prompt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HunkResponse {
+    /// The hunk is applied.
+    Yes,
+    /// The hunk is not applied.
+    No,
+    /// The hunk and all remaining hunks are applied.
+    All,
+    /// The hunk and all remaining hunks are not applied.
+    Quit,
+}
+
+/// Applies the hunks of the difference between a source and its fixed
+/// version that are accepted by the given callback.
+///
+/// Returns the resulting source and the number of accepted hunks.
+fn apply_hunks(
+    source: &str,
+    fixed: &str,
+    mut accept: impl FnMut(&UnifiedDiffHunk<'_, '_, '_, '_, str>) -> anyhow::Result<bool>,
+) -> anyhow::Result<(String, usize)> {
+    let diff = TextDiff::from_lines(source, fixed);
+    let old = diff.old_slices();
+    let new = diff.new_slices();
+
+    let mut result = String::with_capacity(fixed.len());
+    let mut accepted = 0;
+    let mut cursor = 0;
+    for hunk in diff.unified_diff().iter_hunks() {
+        let apply = accept(&hunk)?;
+        accepted += usize::from(apply);
+
+        for op in hunk.ops() {
+            let range = op.old_range();
+            result.extend(old[cursor..range.start].iter().copied());
+            if apply && op.tag() != DiffTag::Equal {
+                result.extend(new[op.new_range()].iter().copied());
+            } else {
+                result.extend(old[range.clone()].iter().copied());
+            }
+
+            cursor = range.end;
+        }
+    }
+
+    result.extend(old[cursor..].iter().copied());
+    Ok((result, accepted))
+}
+
+/// Prompts for whether or not to apply a hunk of fixes to a document.
+///
+/// Reaching the end of stdin is treated as quitting.
+fn prompt_hunk(
+    path: &Path,
+    hunk: &UnifiedDiffHunk<'_, '_, '_, '_, str>,
+) -> anyhow::Result<HunkResponse> {
+    let mut stderr = std::io::stderr().lock();
+    write!(
+        stderr,
+        "--- {path}\n+++ {path}\n{hunk}",
+        path = path.display()
+    )?;
+
+    let stdin = std::io::stdin();
+    let mut line = String::new();
+    loop {
+        write!(stderr, "apply this fix [y,n,a,q,?]? ")?;
+        stderr.flush()?;
+
+        line.clear();
+        if stdin
+            .lock()
+            .read_line(&mut line)
+            .context("failed to read from stdin")?
+            == 0
+        {
+            return Ok(HunkResponse::Quit);
+        }
+
+        match line.trim() {
+            "y" | "yes" => return Ok(HunkResponse::Yes),
+            "n" | "no" => return Ok(HunkResponse::No),
+            "a" | "all" => return Ok(HunkResponse::All),
+            "q" | "quit" => return Ok(HunkResponse::Quit),
+            _ => writeln!(
+                stderr,
+                "y - apply this fix\nn - do not apply this fix\na - apply this fix and all \
+                 remaining fixes\nq - do not apply this fix or any remaining fixes"
+            )?,
+        }
+    }
+}
+
+/// Applies the automatic fixes of the enabled lint rules.
+///
+/// Each pass lints the documents and applies the fixes that do not conflict
+/// with one another; passes are repeated until no fixes apply.
+///
+/// When previewing fixes, the fixed documents are kept in memory and the
+/// difference between the original and the final documents is printed. When
+/// prompting for fixes, only a single pass is made.
+async fn fix(common: Common, mode: FixMode) -> CommandResult<()> {
+    let rules = wdl::lint::rules();
+    let max_passes = if mode == FixMode::Interactive {
+        1
+    } else {
+        MAX_FIX_PASSES
+    };
+
+    let mut sources = common.sources.clone();
+    if sources.is_empty() {
+        sources.push(Source::default());
+    }
+
+    let mut selections = Selections::new(&common, true, &sources)?;
+    let analysis_selection = selections.analysis();
+
+    let mut response = None;
+    let mut total = 0;
+    let mut passes = 0;
+
+    // The path, original source, and fixed source of each document previewed by
+    // a dry run
+    let mut previews: IndexMap<Url, (String, String, String)> = IndexMap::new();
+
+    // The conflicts of the latest pass; conflicts of earlier passes are retried by
+    // the following pass against the fixed documents
+    let mut conflicts = Vec::new();
+    while passes < max_passes {
+        passes += 1;
+        conflicts.clear();
+        let results = common
+            .discovery
+            .clone()
+            .apply(Analysis::default())
+            .extend_sources(sources.clone())
+            .extend_exceptions(analysis_selection.exceptions.clone())
+            .enabled_lint_tags(analysis_selection.enabled_tags)
+            .disabled_lint_tags(analysis_selection.disabled_tags)
+            .extend_contents(
+                previews
+                    .iter()
+                    .map(|(uri, (_, _, fixed))| (uri.clone(), fixed.clone())),
+            )
+            .run()
+            .await
+            .map_err(CommandError::from)?;
+
+        let mut applied = 0;
+        for result in results {
+            let document = result.document();
+            let uri = document.uri();
+            if uri.scheme() != "file" {
+                continue;
+            }
+
+            let selection = selections.get(uri)?;
+            let root = document.root();
+            let merge = merge_fixes(document.diagnostics().filter_map(|d| {
+                let d = selection.apply(&rules, d)?;
+                let rule = rules.iter().find(|r| Some(r.id()) == d.rule())?;
+                let span = d.labels().next()?.span();
+                Some(Fix::new(
+                    rule.id(),
+                    span,
+                    rule.replacements(root.inner(), &d),
+                ))
+            }));
+
+            if merge.applied().is_empty() {
+                continue;
+            }
+
+            let path = uri
+                .to_file_path()
+                .map_err(|_| anyhow!("document URI `{uri}` is not a valid file path"))?;
+            let source = root.text().to_string();
+            conflicts.extend(merge.skipped().iter().map(|conflict| {
+                format!(
+                    "skipped fix for rule `{rule}` at `{path}:{line}` as it conflicts with a fix \
+                     for rule `{with}`",
+                    rule = conflict.fix().rule(),
+                    path = path.display(),
+                    line = source[..conflict.fix().span().start()]
+                        .matches('\n')
+                        .count()
+                        + 1,
+                    with = conflict.with(),
+                )
+            }));
+
+            let fixed = merge.apply(source.clone());
+            let count = merge.applied().len();
+            match mode {
+                FixMode::Write => {
+                    std::fs::write(&path, fixed).with_context(|| {
+                        format!("failed to write `{path}`", path = path.display())
+                    })?;
+
+                    info!(
+                        "applied {count} fix{es} to `{path}`",
+                        es = if count == 1 { "" } else { "es" },
+                        path = path.display()
+                    );
+                    applied += count;
+                }
+                FixMode::DryRun => {
+                    previews
+                        .entry(Url::clone(uri))
+                        .or_insert_with(|| (document.path().to_string(), source, String::new()))
+                        .2 = fixed;
+                    applied += count;
+                }
+                FixMode::Interactive => {
+                    let (fixed, hunks) = apply_hunks(&source, &fixed, |hunk| {
+                        let current = match response {
+                            Some(r) => r,
+                            None => tokio::task::block_in_place(|| prompt_hunk(&path, hunk))?,
+                        };
+
+                        if matches!(current, HunkResponse::All | HunkResponse::Quit) {
+                            response = Some(current);
+                        }
+
+                        Ok(matches!(current, HunkResponse::Yes | HunkResponse::All))
+                    })?;
+
+                    if hunks == 0 {
+                        continue;
+                    }
+
+                    std::fs::write(&path, fixed).with_context(|| {
+                        format!("failed to write `{path}`", path = path.display())
+                    })?;
+
+                    info!(
+                        "applied {hunks} hunk{s} of fixes to `{path}`",
+                        s = if hunks == 1 { "" } else { "s" },
+                        path = path.display()
+                    );
+                    applied += hunks;
+                }
+            }
+        }
+
+        total += applied;
+        if applied == 0 {
+            break;
+        }
+
+        if passes == MAX_FIX_PASSES {
+            warn!("stopped after {MAX_FIX_PASSES} passes: some fixes may not have been applied");
+        }
+    }
+
+    // Only the conflicts that were not resolved by a later pass are reported
+    for conflict in &conflicts {
+        warn!("{conflict}");
+    }
+
+    let skipped = conflicts.len();
+
+    for (path, source, fixed) in previews.values() {
+        let diff = TextDiff::from_lines(source, fixed);
+        print!("{}", diff.unified_diff().header(path, path));
+    }
+
+    match mode {
+        FixMode::Write => println!(
+            "applied {total} fix{es} in {passes} pass{passes_es} ({skipped} skipped due to \
+             conflicts)",
+            es = if total == 1 { "" } else { "es" },
+            passes_es = if passes == 1 { "" } else { "es" },
+        ),
+        FixMode::DryRun => {
+            // The diff is written to stdout, so report the summary as a log message
+            info!(
+                "{total} fix{es} would be applied ({skipped} skipped due to conflicts)",
+                es = if total == 1 { "" } else { "es" },
+            );
+        }
+        FixMode::Interactive => println!(
+            "applied {total} hunk{s} of fixes ({skipped} fix{es} skipped due to conflicts)",
+            s = if total == 1 { "" } else { "s" },
+            es = if skipped == 1 { "" } else { "es" },
+        ),
+    }
+
+    Ok(())
+}
+
 /// Reports any unknown rules as diagnostics.
 fn report_unknown_rules(
     excepted: &[String],
@@ -472,3 +1346,37 @@ fn report_unknown_rules(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn interactive_hunks() {
+        let source = (1..=20).map(|i| format!("line {i}\n")).collect::<String>();
+        let fixed = source
+            .replace("line 2\n", "LINE 2\n")
+            .replace("line 18\n", "LINE 18\n");
+
+        // Each change is far enough apart to be its own hunk
+        let mut prompts = 0;
+        let (result, accepted) = apply_hunks(&source, &fixed, |_| {
+            prompts += 1;
+            Ok(prompts == 2)
+        })
+        .unwrap();
+        assert_eq!(prompts, 2);
+        assert_eq!(accepted, 1);
+        assert_eq!(result, source.replace("line 18\n", "LINE 18\n"));
+
+        let (result, accepted) = apply_hunks(&source, &fixed, |_| Ok(true)).unwrap();
+        assert_eq!(accepted, 2);
+        assert_eq!(result, fixed);
+
+        let (result, accepted) = apply_hunks(&source, &fixed, |_| Ok(false)).unwrap();
+        assert_eq!(accepted, 0);
+        assert_eq!(result, source);
+    }
+}
@@ -0,0 +1,766 @@
+//! Implementation of the `convert` subcommand.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::anyhow;
+use clap::Parser;
+use clap::ValueEnum;
+use wdl::ast::AstNode;
+use wdl::ast::AstToken;
+use wdl::ast::v1::CallStatement;
+use wdl::ast::v1::CommandSection;
+use wdl::ast::v1::Decl;
+use wdl::ast::v1::Expr;
+use wdl::ast::v1::LiteralExpr;
+use wdl::ast::v1::Placeholder;
+use wdl::ast::v1::PrimitiveTypeKind;
+use wdl::ast::v1::StringPart;
+use wdl::ast::v1::StrippedCommandPart;
+use wdl::ast::v1::TASK_REQUIREMENT_CONTAINER;
+use wdl::ast::v1::TASK_REQUIREMENT_CONTAINER_ALIAS;
+use wdl::ast::v1::TASK_REQUIREMENT_CPU;
+use wdl::ast::v1::TASK_REQUIREMENT_MEMORY;
+use wdl::ast::v1::TaskDefinition;
+use wdl::ast::v1::Type;
+use wdl::ast::v1::WorkflowDefinition;
+use wdl::ast::v1::WorkflowStatement;
+use wdl::engine::convert_unit_string;
+
+use crate::analysis::Analysis;
+use crate::analysis::Source;
+use crate::commands::CommandError;
+use crate::commands::CommandResult;
+
+/// The indentation used in converted documents.
+const INDENT: &str = "    ";
+
+/// The language to convert a document to.
+#[derive(Clone, Copy, Debug, ValueEnum, PartialEq, Eq)]
+pub enum ConvertTarget {
+    /// A Nextflow DSL2 script.
+    Nextflow,
+}
+
+impl std::fmt::Display for ConvertTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Nextflow => write!(f, "nextflow"),
+        }
+    }
+}
+
+/// Arguments for the `convert` subcommand.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+pub struct Args {
+    /// The WDL document or URL to convert.
+    #[clap(value_name = "SOURCE")]
+    pub source: Source,
+
+    /// The language to convert the document to.
+    #[arg(long, value_name = "TARGET")]
+    pub to: ConvertTarget,
+
+    /// The path of the file to write the converted document to.
+    ///
+    /// Defaults to writing to standard output.
+    #[arg(short, long, value_name = "FILE")]
+    pub output: Option<PathBuf>,
+}
+
+/// Converts a WDL document to a Nextflow DSL2 script.
+///
+/// Tasks are converted to process stubs and the calls at the top level of the
+/// workflow are converted to a workflow that wires the outputs of each process
+/// to the inputs of the next. Constructs that can't be converted are recorded
+/// in a report.
+#[derive(Debug, Default)]
+struct NextflowConverter {
+    /// The converted script.
+    script: String,
+    /// The constructs that could not be converted.
+    report: Vec<String>,
+}
+
+impl NextflowConverter {
+    /// Records a construct that could not be converted.
+    fn unsupported(&mut self, context: &str, message: impl std::fmt::Display) {
+        self.report.push(format!("{context}: {message}"));
+    }
+
+    /// Converts a literal expression to a Groovy literal.
+    ///
+    /// Returns `None` if the expression is not a literal of a primitive value
+    /// or an array of such values.
+    fn literal(expr: &Expr) -> Option<String> {
+        match expr.as_literal()? {
+            LiteralExpr::Boolean(b) => Some(b.value().to_string()),
+            LiteralExpr::Integer(i) => i.value().map(|i| i.to_string()),
+            LiteralExpr::Float(f) => f.value().map(|f| f.to_string()),
+            LiteralExpr::None(_) => Some("null".to_string()),
+            LiteralExpr::String(s) => {
+                let mut text = String::new();
+                s.text()?.unescape_to(&mut text);
+                Some(format!(
+                    "'{text}'",
+                    text = text.replace('\\', "\\\\").replace('\'', "\\'")
+                ))
+            }
+            LiteralExpr::Array(a) => {
+                let elements = a
+                    .elements()
+                    .map(|e| Self::literal(&e))
+                    .collect::<Option<Vec<_>>>()?;
+                Some(format!("[{elements}]", elements = elements.join(", ")))
+            }
+            _ => None,
+        }
+    }
+
+    /// Converts a string expression to a Groovy string, translating
+    /// placeholders that reference a name.
+    ///
+    /// Returns `None` if the expression is not a string or contains a
+    /// placeholder that is not a name reference.
+    fn string(expr: &Expr) -> Option<String> {
+        let Some(LiteralExpr::String(s)) = expr.as_literal() else {
+            return None;
+        };
+
+        let mut string = String::new();
+        for part in s.parts() {
+            match part {
+                StringPart::Text(text) => {
+                    let mut buffer = String::new();
+                    text.unescape_to(&mut buffer);
+                    string.push_str(
+                        &buffer
+                            .replace('\\', "\\\\")
+                            .replace('"', "\\\"")
+                            .replace('$', "\\$"),
+                    );
+                }
+                StringPart::Placeholder(placeholder) => {
+                    let name = placeholder.expr();
+                    let name = name.as_name_ref()?;
+                    if placeholder.option().is_some() {
+                        return None;
+                    }
+
+                    let _ = write!(string, "${{{name}}}", name = name.name().text());
+                }
+            }
+        }
+
+        Some(format!("\"{string}\""))
+    }
+
+    /// Gets the expression of a requirement of a task.
+    ///
+    /// Both the `requirements` and `runtime` sections are searched.
+    fn requirement(task: &TaskDefinition, names: &[&str]) -> Option<Expr> {
+        task.requirements()
+            .and_then(|r| {
+                r.items()
+                    .find(|i| names.contains(&i.name().text()))
+                    .map(|i| i.expr())
+            })
+            .or_else(|| {
+                task.runtime().and_then(|r| {
+                    r.items()
+                        .find(|i| names.contains(&i.name().text()))
+                        .map(|i| i.expr())
+                })
+            })
+    }
+
+    /// Determines if a declared type is staged as a path in Nextflow.
+    fn is_path(ty: &Type) -> bool {
+        match ty {
+            Type::Primitive(ty) => {
+                matches!(
+                    ty.kind(),
+                    PrimitiveTypeKind::File | PrimitiveTypeKind::Directory
+                )
+            }
+            Type::Array(ty) => Self::is_path(&ty.element_type()),
+            _ => false,
+        }
+    }
+
+    /// Converts the directives of a process from the requirements of a task.
+    fn directives(&mut self, context: &str, task: &TaskDefinition) -> String {
+        let mut section = String::new();
+        if let Some(expr) = Self::requirement(
+            task,
+            &[TASK_REQUIREMENT_CONTAINER, TASK_REQUIREMENT_CONTAINER_ALIAS],
+        ) {
+            match (expr.as_literal(), Self::literal(&expr)) {
+                (Some(LiteralExpr::String(_)), Some(image)) => {
+                    let _ = writeln!(section, "{INDENT}container {image}");
+                }
+                _ => self.unsupported(
+                    context,
+                    format!(
+                        "container `{expr}` is not a single literal image",
+                        expr = expr.text()
+                    ),
+                ),
+            }
+        }
+
+        if let Some(expr) = Self::requirement(task, &[TASK_REQUIREMENT_CPU]) {
+            // Nextflow requires a whole number of CPUs, so fractional CPUs are rounded up
+            let cpus = match expr.as_literal() {
+                Some(LiteralExpr::Integer(i)) => i.value(),
+                Some(LiteralExpr::Float(f)) => f.value().map(|f| f.ceil() as i64),
+                _ => None,
+            };
+
+            match cpus {
+                Some(cpus) => {
+                    let _ = writeln!(section, "{INDENT}cpus {cpus}");
+                }
+                None => self.unsupported(
+                    context,
+                    format!("cpu `{expr}` is not a literal number", expr = expr.text()),
+                ),
+            }
+        }
+
+        if let Some(expr) = Self::requirement(task, &[TASK_REQUIREMENT_MEMORY]) {
+            let bytes = match expr.as_literal() {
+                Some(LiteralExpr::Integer(i)) => i.value().and_then(|i| u64::try_from(i).ok()),
+                Some(LiteralExpr::String(s)) => s.text().and_then(|t| {
+                    let mut text = String::new();
+                    t.unescape_to(&mut text);
+                    convert_unit_string(&text)
+                }),
+                _ => None,
+            };
+
+            match bytes {
+                Some(bytes) => {
+                    let _ = writeln!(section, "{INDENT}memory {memory}", memory = memory(bytes));
+                }
+                None => self.unsupported(
+                    context,
+                    format!(
+                        "memory `{expr}` is not a literal amount",
+                        expr = expr.text()
+                    ),
+                ),
+            }
+        }
+
+        section
+    }
+
+    /// Records a placeholder that references a private declaration of a task.
+    ///
+    /// Private declarations are not converted, so a reference to one is not
+    /// defined in the process. `kind` describes where the placeholder appears.
+    fn private_reference(
+        &mut self,
+        context: &str,
+        kind: &str,
+        placeholder: &Placeholder,
+        private: &HashSet<String>,
+    ) {
+        if let Some(name) = placeholder.expr().as_name_ref()
+            && private.contains(name.name().text())
+        {
+            self.unsupported(
+                context,
+                format!(
+                    "{kind} `{placeholder}` references private declaration `{name}`",
+                    placeholder = placeholder.text(),
+                    name = name.name().text()
+                ),
+            );
+        }
+    }
+
+    /// Converts the command section of a task to a process script.
+    ///
+    /// `private` contains the names of the private declarations of the task.
+    fn command(
+        &mut self,
+        context: &str,
+        command: &CommandSection,
+        private: &HashSet<String>,
+    ) -> String {
+        let Some(parts) = command.strip_whitespace() else {
+            self.unsupported(context, "the command has mixed indentation");
+            return format!("{INDENT}\"\"\"\n{INDENT}\"\"\"\n");
+        };
+
+        let mut script = String::new();
+        for part in parts {
+            match part {
+                StrippedCommandPart::Text(text) => {
+                    script.push_str(&text.replace('\\', "\\\\").replace('$', "\\$"));
+                }
+                StrippedCommandPart::Placeholder(placeholder) => {
+                    self.private_reference(context, "command placeholder", &placeholder, private);
+                    let expr = placeholder.expr();
+                    match expr.as_name_ref() {
+                        Some(name) if placeholder.option().is_none() => {
+                            let _ = write!(script, "${{{name}}}", name = name.name().text());
+                        }
+                        _ => {
+                            self.unsupported(
+                                context,
+                                format!(
+                                    "command placeholder `{placeholder}` is not a name reference",
+                                    placeholder = placeholder.text()
+                                ),
+                            );
+                            script.push_str(&placeholder.text().to_string().replace('$', "\\$"));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut section = format!("{INDENT}\"\"\"\n");
+        for line in script.lines() {
+            if line.is_empty() {
+                section.push('\n');
+            } else {
+                let _ = writeln!(section, "{INDENT}{line}");
+            }
+        }
+        let _ = writeln!(section, "{INDENT}\"\"\"");
+        section
+    }
+
+    /// Converts a task to a process stub.
+    fn process(&mut self, task: &TaskDefinition) {
+        let name = task.name();
+        let name = name.text();
+        let context = format!("task `{name}`");
+        let mut sections = vec![self.directives(&context, task)];
+        let private: HashSet<_> = task
+            .declarations()
+            .map(|decl| decl.name().text().to_string())
+            .collect();
+
+        let inputs = task
+            .input()
+            .map(|i| i.declarations().collect::<Vec<_>>())
+            .unwrap_or_default();
+        if !inputs.is_empty() {
+            let mut section = format!("{INDENT}input:\n");
+            for decl in &inputs {
+                let qualifier = if Self::is_path(&decl.ty()) {
+                    "path"
+                } else {
+                    "val"
+                };
+                let _ = writeln!(
+                    section,
+                    "{INDENT}{qualifier} {name}",
+                    name = decl.name().text()
+                );
+            }
+
+            sections.push(section);
+        }
+
+        if let Some(output) = task.output() {
+            let mut section = String::new();
+            for decl in output.declarations() {
+                let name = decl.name();
+                let name = name.text();
+                let expr = decl.expr();
+                let ty = decl.ty();
+                let optional = if ty.is_optional() {
+                    ", optional: true"
+                } else {
+                    ""
+                };
+
+                if let Some(call) = expr.as_call()
+                    && call.target().text() == "stdout"
+                {
+                    let _ = writeln!(section, "{INDENT}stdout emit: {name}");
+                    continue;
+                }
+
+                let string = match expr.as_call() {
+                    Some(call) if call.target().text() == "glob" => call.arguments().next(),
+                    Some(_) => None,
+                    None => Some(expr.clone()),
+                };
+                let pattern = string.as_ref().and_then(Self::string);
+
+                match pattern {
+                    Some(pattern) if Self::is_path(&ty) => {
+                        let _ = writeln!(section, "{INDENT}path {pattern}, emit: {name}{optional}");
+                        if let Some(LiteralExpr::String(s)) =
+                            string.as_ref().and_then(Expr::as_literal)
+                        {
+                            for part in s.parts() {
+                                if let StringPart::Placeholder(placeholder) = part {
+                                    self.private_reference(
+                                        &context,
+                                        &format!("output `{name}` placeholder"),
+                                        &placeholder,
+                                        &private,
+                                    );
+                                }
+                            }
+                        }
+                    }
+                    _ => self.unsupported(
+                        &context,
+                        format!(
+                            "output `{name}` with expression `{expr}` cannot be converted",
+                            expr = expr.text()
+                        ),
+                    ),
+                }
+            }
+
+            if !section.is_empty() {
+                sections.push(format!("{INDENT}output:\n{section}"));
+            }
+        }
+
+        let script = match task.command() {
+            Some(command) => self.command(&context, &command, &private),
+            None => format!("{INDENT}\"\"\"\n{INDENT}\"\"\"\n"),
+        };
+        sections.push(format!("{INDENT}script:\n{script}"));
+
+        let sections: Vec<_> = sections.into_iter().filter(|s| !s.is_empty()).collect();
+        let _ = writeln!(
+            self.script,
+            "process {name} {{\n{sections}}}\n",
+            sections = sections.join("\n")
+        );
+    }
+
+    /// Converts a reference to a workflow input to a parameter.
+    ///
+    /// Returns `None` if the name is not a workflow input.
+    fn param(name: &str, path: bool, params: &HashSet<String>) -> Option<String> {
+        if !params.contains(name) {
+            return None;
+        }
+
+        Some(if path {
+            format!("file(params.{name})")
+        } else {
+            format!("params.{name}")
+        })
+    }
+
+    /// Converts the argument for an input of a called task.
+    ///
+    /// Returns `None` if the argument cannot be converted.
+    fn argument(
+        expr: &Expr,
+        path: bool,
+        params: &HashSet<String>,
+        calls: &HashMap<String, String>,
+    ) -> Option<String> {
+        if let Some(name) = expr.as_name_ref() {
+            return Self::param(name.name().text(), path, params);
+        }
+
+        if let Some(access) = expr.as_access() {
+            let (operand, output) = access.operands();
+            let call = operand.as_name_ref()?;
+            let process = calls.get(call.name().text())?;
+            return Some(format!("{process}.out.{output}", output = output.text()));
+        }
+
+        Self::literal(expr)
+    }
+
+    /// Converts a call statement to a process invocation.
+    fn call(
+        &mut self,
+        context: &str,
+        tasks: &HashMap<String, TaskDefinition>,
+        params: &HashSet<String>,
+        calls: &mut HashMap<String, String>,
+        statement: &CallStatement,
+    ) {
+        let names: Vec<_> = statement.target().names().collect();
+        let [target] = names.as_slice() else {
+            self.unsupported(
+                context,
+                format!(
+                    "call to imported `{target}` is not converted",
+                    target = statement.target().text()
+                ),
+            );
+            return;
+        };
+
+        let target = target.text();
+        let Some(task) = tasks.get(target) else {
+            self.unsupported(
+                context,
+                format!("call to `{target}` does not call a task in the document"),
+            );
+            return;
+        };
+
+        // Nextflow processes may only be invoked once per workflow
+        if calls.values().any(|p| p == target) {
+            self.unsupported(
+                context,
+                format!(
+                    "`{target}` is called more than once; include the process under an alias to \
+                     call it again"
+                ),
+            );
+            return;
+        }
+
+        let name = statement
+            .alias()
+            .map(|a| a.name().text().to_string())
+            .unwrap_or_else(|| target.to_string());
+        // Implicitly bound inputs have no expression
+        let specified: HashMap<_, _> = statement
+            .inputs()
+            .map(|i| (i.name().text().to_string(), i.expr()))
+            .collect();
+
+        let mut arguments = Vec::new();
+        for decl in task
+            .input()
+            .map(|i| i.declarations().collect::<Vec<_>>())
+            .unwrap_or_default()
+        {
+            let input = decl.name();
+            let input = input.text();
+            let path = Self::is_path(&decl.ty());
+            let expr = match (specified.get(input), &decl) {
+                (Some(expr), _) => expr.clone(),
+                (None, Decl::Bound(decl)) => Some(decl.expr()),
+                (None, Decl::Unbound(decl)) if decl.ty().is_optional() => {
+                    // An empty list is the Nextflow idiom for an absent path
+                    arguments.push(if path { "[]" } else { "null" }.to_string());
+                    continue;
+                }
+                (None, Decl::Unbound(_)) => {
+                    self.unsupported(
+                        context,
+                        format!("input `{input}` of call `{name}` is not specified"),
+                    );
+                    arguments.push("null".to_string());
+                    continue;
+                }
+            };
+
+            let (argument, message) = match expr {
+                Some(expr) => (
+                    Self::argument(&expr, path, params, calls),
+                    format!(
+                        "input `{input}` of call `{name}` with expression `{expr}` cannot be \
+                         converted",
+                        expr = expr.text()
+                    ),
+                ),
+                None => (
+                    Self::param(input, path, params),
+                    format!("input `{input}` of call `{name}` is not bound to a workflow input"),
+                ),
+            };
+
+            match argument {
+                Some(argument) => arguments.push(argument),
+                None => {
+                    self.unsupported(context, message);
+                    arguments.push("null".to_string());
+                }
+            }
+        }
+
+        let _ = writeln!(
+            self.script,
+            "{INDENT}{target}({arguments})",
+            arguments = arguments.join(", ")
+        );
+        calls.insert(name, target.to_string());
+    }
+
+    /// Converts a workflow to a Nextflow workflow.
+    fn workflow(&mut self, tasks: &HashMap<String, TaskDefinition>, workflow: &WorkflowDefinition) {
+        let context = format!("workflow `{name}`", name = workflow.name().text());
+        let mut params = HashSet::new();
+        if let Some(input) = workflow.input() {
+            for decl in input.declarations() {
+                let name = decl.name();
+                let value = match &decl {
+                    Decl::Bound(decl) => Self::literal(&decl.expr()).unwrap_or_else(|| {
+                        self.unsupported(
+                            &context,
+                            format!(
+                                "the default of input `{name}` cannot be converted",
+                                name = name.text()
+                            ),
+                        );
+                        "null".to_string()
+                    }),
+                    Decl::Unbound(_) => "null".to_string(),
+                };
+
+                let _ = writeln!(self.script, "params.{name} = {value}", name = name.text());
+                params.insert(name.text().to_string());
+            }
+
+            if !params.is_empty() {
+                self.script.push('\n');
+            }
+        }
+
+        let _ = writeln!(self.script, "workflow {{");
+        let mut calls = HashMap::new();
+        for statement in workflow.statements() {
+            match statement {
+                WorkflowStatement::Call(statement) => {
+                    self.call(&context, tasks, &params, &mut calls, &statement)
+                }
+                WorkflowStatement::Scatter(statement) => self.unsupported(
+                    &context,
+                    format!(
+                        "scatter over `{expr}` is not converted",
+                        expr = statement.expr().text()
+                    ),
+                ),
+                WorkflowStatement::Conditional(statement) => self.unsupported(
+                    &context,
+                    format!(
+                        "conditional on `{expr}` is not converted",
+                        expr = statement
+                            .if_clause()
+                            .expr()
+                            .map(|e| e.text().to_string())
+                            .unwrap_or_default()
+                    ),
+                ),
+                WorkflowStatement::Declaration(decl) => self.unsupported(
+                    &context,
+                    format!(
+                        "private declaration `{name}` is not converted",
+                        name = decl.name().text()
+                    ),
+                ),
+            }
+        }
+        let _ = writeln!(self.script, "}}");
+
+        if workflow.output().is_some() {
+            self.unsupported(&context, "workflow outputs are not converted");
+        }
+    }
+}
+
+/// Formats an amount of memory, in bytes, as a Nextflow memory unit.
+///
+/// The largest unit that represents the amount exactly is used.
+fn memory(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+
+    let mut amount = bytes;
+    let mut unit = 0;
+    while unit < UNITS.len() - 1 && amount > 0 && amount % 1024 == 0 {
+        amount /= 1024;
+        unit += 1;
+    }
+
+    format!("'{amount} {unit}'", unit = UNITS[unit])
+}
+
+/// Performs the `convert` subcommand.
+pub async fn convert(args: Args) -> CommandResult<()> {
+    if let Source::Directory(_) = args.source {
+        return Err(
+            anyhow!("directory sources are not supported for the `convert` command").into(),
+        );
+    }
+
+    let results = Analysis::default()
+        .add_source(args.source.clone())
+        .run()
+        .await
+        .map_err(CommandError::from)?;
+
+    let document = results
+        .filter(&[&args.source])
+        .next()
+        .expect("the root source should always be included in the results")
+        .document();
+
+    let ast = document.root().ast().into_v1().ok_or(anyhow!(
+        "non-v1 WDL document `{}` cannot be processed with this subcommand",
+        document.uri()
+    ))?;
+
+    let mut converter = NextflowConverter::default();
+    let _ = writeln!(
+        converter.script,
+        "// Converted from `{path}` by Sprocket.\n\nnextflow.enable.dsl = 2\n",
+        path = document.path()
+    );
+
+    let tasks: HashMap<_, _> = ast
+        .tasks()
+        .map(|t| (t.name().text().to_string(), t))
+        .collect();
+    for task in ast.tasks() {
+        converter.process(&task);
+    }
+
+    if let Some(workflow) = ast.workflows().next() {
+        converter.workflow(&tasks, &workflow);
+    }
+
+    let script = converter.script.trim_end().to_string() + "\n";
+    match &args.output {
+        Some(path) => std::fs::write(path, &script).with_context(|| {
+            format!(
+                "failed to write converted document `{path}`",
+                path = path.display()
+            )
+        })?,
+        None => print!("{script}"),
+    }
+
+    if !converter.report.is_empty() {
+        eprintln!(
+            "the following constructs could not be converted to {target}:",
+            target = args.to
+        );
+        for entry in &converter.report {
+            eprintln!("  {entry}");
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn memory_units() {
+        assert_eq!(memory(0), "'0 B'");
+        assert_eq!(memory(1000), "'1000 B'");
+        assert_eq!(memory(2048), "'2 KB'");
+        assert_eq!(memory(4 * 1024 * 1024 * 1024), "'4 GB'");
+        assert_eq!(memory(1536 * 1024 * 1024), "'1536 MB'");
+    }
+}
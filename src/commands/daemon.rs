@@ -0,0 +1,516 @@
+//! Implementation of the `daemon` subcommand.
+//!
+//! The daemon periodically re-validates a set of documents so that breakage
+//! that occurs without a change to the documents themselves (e.g. a remote
+//! import that has drifted or a container image that was removed from its
+//! registry) is detected.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use anyhow::anyhow;
+use clap::Parser;
+use crankshaft::docker::Docker;
+use serde::Serialize;
+use strum::VariantArray;
+use tokio::io::AsyncWriteExt as _;
+use tokio::net::TcpListener;
+use tokio::sync::RwLock;
+use tokio::time::MissedTickBehavior;
+use tracing::info;
+use tracing::warn;
+use url::Url;
+use wdl::ast::Severity;
+use wdl::lint::Tag;
+use wdl::lint::TagSet;
+
+use crate::analysis::Analysis;
+use crate::analysis::Source;
+use crate::commands::CommandResult;
+use crate::commands::images::task_images;
+use crate::config::CheckConfig;
+use crate::http;
+
+/// Arguments for the `daemon` subcommand.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+pub struct Args {
+    /// The WDL documents, directories, or URLs to re-validate.
+    #[clap(value_name = "SOURCE", required = true)]
+    pub sources: Vec<Source>,
+
+    /// The interval between re-validations (e.g. `30m`, `24h`, or `7d`).
+    #[clap(long, value_name = "INTERVAL", default_value = "24h", value_parser = parse_interval)]
+    pub interval: Duration,
+
+    /// Checks that the container images of tasks are available from their
+    /// registries.
+    ///
+    /// This requires a connection to the Docker daemon.
+    #[clap(long)]
+    pub check_images: bool,
+
+    /// A URL to notify when the sources break, recover, or break differently.
+    ///
+    /// Notifications are sent as JSON `POST` requests containing the status of
+    /// the re-validation. This option may be specified multiple times.
+    #[clap(long = "webhook", value_name = "URL")]
+    pub webhooks: Vec<Url>,
+
+    /// The address to serve the status of the latest re-validation on (e.g.
+    /// `127.0.0.1:8080`).
+    ///
+    /// The status is served as JSON from `GET /status`.
+    #[clap(long, value_name = "ADDR")]
+    pub listen: Option<SocketAddr>,
+
+    /// Re-validates the sources once and exits.
+    ///
+    /// The status is printed as JSON and the command fails if the sources are
+    /// broken.
+    #[clap(long, conflicts_with = "listen")]
+    pub once: bool,
+
+    /// The `check` configuration that selects the rules of the re-validation.
+    #[clap(skip)]
+    pub check: CheckConfig,
+}
+
+impl Args {
+    /// Applies the configuration to the arguments.
+    pub fn apply(mut self, config: crate::config::Config) -> Self {
+        self.check = config.check;
+        self
+    }
+}
+
+/// Parses an interval with a unit suffix of `s`, `m`, `h`, or `d`.
+fn parse_interval(s: &str) -> Result<Duration, String> {
+    let (number, multiplier) = match s.char_indices().last() {
+        Some((i, 's')) => (&s[..i], 1),
+        Some((i, 'm')) => (&s[..i], 60),
+        Some((i, 'h')) => (&s[..i], 60 * 60),
+        Some((i, 'd')) => (&s[..i], 24 * 60 * 60),
+        _ => {
+            return Err(format!(
+                "interval `{s}` must be a number with a unit suffix of `s`, `m`, `h`, or `d`"
+            ));
+        }
+    };
+
+    number
+        .parse::<u64>()
+        .ok()
+        .filter(|n| *n > 0)
+        .and_then(|n| n.checked_mul(multiplier))
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("invalid interval `{s}`"))
+}
+
+/// Represents the failures found by a re-validation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+struct Failures {
+    /// The error diagnostics of each document with errors, keyed by path.
+    documents: BTreeMap<String, Vec<String>>,
+    /// The images that are not available from their registries, keyed by
+    /// image with the reason.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    images: BTreeMap<String, String>,
+    /// The errors that are not associated with a document (e.g. a source that
+    /// could not be read).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    errors: Vec<String>,
+}
+
+impl Failures {
+    /// Determines if there are no failures.
+    fn is_empty(&self) -> bool {
+        self.documents.is_empty() && self.images.is_empty() && self.errors.is_empty()
+    }
+}
+
+/// Represents the status of a re-validation.
+#[derive(Debug, Clone, Serialize)]
+struct Status {
+    /// The time the re-validation completed, in RFC 3339 format.
+    checked_at: String,
+    /// Whether or not the sources are broken.
+    broken: bool,
+    /// The failures found by the re-validation.
+    #[serde(flatten)]
+    failures: Failures,
+}
+
+impl Status {
+    /// Constructs a new status from the failures of a re-validation.
+    fn new(failures: Failures) -> Self {
+        Self {
+            checked_at: chrono::Utc::now().to_rfc3339(),
+            broken: !failures.is_empty(),
+            failures,
+        }
+    }
+}
+
+/// Represents an event that webhooks are notified of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Event {
+    /// The sources are broken when they previously were not.
+    Broken,
+    /// The sources are broken differently than they previously were.
+    Changed,
+    /// The sources are no longer broken.
+    Recovered,
+}
+
+impl Event {
+    /// Determines the event of a re-validation given the status of the
+    /// previous re-validation.
+    ///
+    /// Returns `None` if webhooks should not be notified.
+    fn new(previous: Option<&Status>, current: &Status) -> Option<Self> {
+        match (
+            previous.map(|p| p.broken).unwrap_or_default(),
+            current.broken,
+        ) {
+            (false, true) => Some(Self::Broken),
+            (true, false) => Some(Self::Recovered),
+            (true, true) if previous.map(|p| &p.failures) != Some(&current.failures) => {
+                Some(Self::Changed)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Represents the payload of a webhook notification.
+#[derive(Debug, Serialize)]
+struct Notification<'a> {
+    /// The event being notified.
+    event: Event,
+    /// The status of the re-validation.
+    status: &'a Status,
+}
+
+/// Checks that the given images are available from their registries.
+///
+/// Returns the images that are not available with the reason.
+async fn check_registries(images: BTreeSet<String>) -> anyhow::Result<BTreeMap<String, String>> {
+    let docker = Docker::with_defaults().context("failed to connect to Docker daemon")?;
+    let mut missing = BTreeMap::new();
+    for image in images {
+        if let Err(e) = docker.inner().inspect_registry_image(&image, None).await {
+            missing.insert(image, e.to_string());
+        }
+    }
+
+    Ok(missing)
+}
+
+/// Re-validates the given sources.
+///
+/// The rules excepted by the configuration are not run. As only errors break
+/// the sources, lint rules are only run in strict mode, where violations of
+/// `Correctness` rules are errors.
+async fn revalidate(sources: &[Source], check_images: bool, config: &CheckConfig) -> Status {
+    let tags = |tags: &[String]| {
+        TagSet::new(
+            tags.iter()
+                .filter_map(|t| Tag::from_str(t).ok())
+                .collect::<Vec<_>>()
+                .as_slice(),
+        )
+    };
+    let (enabled_tags, disabled_tags) = if config.strict {
+        (TagSet::new(Tag::VARIANTS), tags(&config.filter_lint_tags))
+    } else {
+        (TagSet::new(&[]), TagSet::new(&[]))
+    };
+
+    let rules = wdl::lint::rules();
+    let is_error = |diagnostic: &wdl::ast::Diagnostic| {
+        diagnostic.severity() == Severity::Error
+            || diagnostic.rule().is_some_and(|id| {
+                rules
+                    .iter()
+                    .any(|r| r.id() == id && r.tags().contains(Tag::Correctness))
+            })
+    };
+
+    let mut failures = Failures::default();
+    match Analysis::default()
+        .extend_sources(sources.iter().cloned())
+        .extend_exceptions(config.except.iter().cloned())
+        .enabled_lint_tags(enabled_tags)
+        .disabled_lint_tags(disabled_tags)
+        .run()
+        .await
+    {
+        Ok(results) => {
+            let mut images = BTreeSet::new();
+            for result in results.as_slice() {
+                let document = result.document();
+                let errors: Vec<_> = document
+                    .diagnostics()
+                    .filter(|d| is_error(d))
+                    .map(|d| d.message().to_string())
+                    .collect();
+                if !errors.is_empty() {
+                    failures
+                        .documents
+                        .insert(document.path().to_string(), errors);
+                }
+
+                let ast = document.root().ast();
+                if check_images && let Some(ast) = ast.as_v1() {
+                    for task in ast.tasks() {
+                        if let Some(Ok(names)) = task_images(&task) {
+                            images.extend(names);
+                        }
+                    }
+                }
+            }
+
+            if check_images {
+                match check_registries(images).await {
+                    Ok(missing) => failures.images = missing,
+                    Err(e) => failures.errors.push(format!("{e:#}")),
+                }
+            }
+        }
+        Err(errors) => {
+            failures.errors = errors.iter().map(|e| format!("{e:#}")).collect();
+        }
+    }
+
+    Status::new(failures)
+}
+
+/// Notifies the given webhooks of an event.
+async fn notify(client: &reqwest::Client, webhooks: &[Url], event: Event, status: &Status) {
+    let body = match serde_json::to_string(&Notification { event, status }) {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("failed to serialize webhook notification: {e}");
+            return;
+        }
+    };
+
+    for url in webhooks {
+        let response = client
+            .post(url.clone())
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone())
+            .send()
+            .await
+            .and_then(|r| r.error_for_status());
+        if let Err(e) = response {
+            warn!("failed to notify webhook `{url}`: {e}");
+        }
+    }
+}
+
+/// Creates the HTTP response to a request.
+fn respond(request: &http::Request, status: Option<&Status>) -> String {
+    let (code, body) = match (request.method.as_str(), request.path.as_str(), status) {
+        ("GET", "/status", Some(status)) => (
+            "200 OK",
+            serde_json::to_string_pretty(status).unwrap_or_default(),
+        ),
+        ("GET", "/status", None) => (
+            "503 Service Unavailable",
+            r#"{"error":"the sources have not been re-validated yet"}"#.to_string(),
+        ),
+        _ => ("404 Not Found", r#"{"error":"not found"}"#.to_string()),
+    };
+
+    http::response(code, &body)
+}
+
+/// Serves the status of the latest re-validation.
+async fn serve(listener: TcpListener, status: Arc<RwLock<Option<Status>>>) {
+    loop {
+        let mut stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                warn!("failed to accept connection: {e}");
+                continue;
+            }
+        };
+
+        let status = status.clone();
+        tokio::spawn(async move {
+            let Ok(Some(request)) = http::read_request(&mut stream).await else {
+                return;
+            };
+
+            let response = respond(&request, status.read().await.as_ref());
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+/// Performs the `daemon` subcommand.
+pub async fn daemon(args: Args) -> CommandResult<()> {
+    let status = Arc::new(RwLock::new(None));
+    if let Some(addr) = args.listen {
+        let listener = TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("failed to listen on `{addr}`"))?;
+        info!("serving re-validation status at `http://{addr}/status`");
+        tokio::spawn(serve(listener, status.clone()));
+    }
+
+    let client = reqwest::Client::new();
+    let mut interval = tokio::time::interval(args.interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        // The first tick completes immediately
+        interval.tick().await;
+
+        let current = revalidate(&args.sources, args.check_images, &args.check).await;
+        if current.broken {
+            warn!(
+                "re-validation found {documents} document{documents_s} with errors, {images} \
+                 unavailable image{images_s}, and {errors} other error{errors_s}",
+                documents = current.failures.documents.len(),
+                documents_s = if current.failures.documents.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                },
+                images = current.failures.images.len(),
+                images_s = if current.failures.images.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                },
+                errors = current.failures.errors.len(),
+                errors_s = if current.failures.errors.len() == 1 {
+                    ""
+                } else {
+                    "s"
+                },
+            );
+        } else {
+            info!("re-validation found no errors");
+        }
+
+        let previous = status.write().await.replace(current.clone());
+        if let Some(event) = Event::new(previous.as_ref(), &current) {
+            notify(&client, &args.webhooks, event, &current).await;
+        }
+
+        if args.once {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&current).context("failed to serialize status")?
+            );
+
+            if current.broken {
+                return Err(anyhow!("re-validation found the sources to be broken").into());
+            }
+
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn intervals() {
+        assert_eq!(parse_interval("30s").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_interval("30m").unwrap(), Duration::from_secs(1800));
+        assert_eq!(parse_interval("24h").unwrap(), Duration::from_secs(86400));
+        assert_eq!(parse_interval("7d").unwrap(), Duration::from_secs(604800));
+        assert!(parse_interval("24").is_err());
+        assert!(parse_interval("0h").is_err());
+        assert!(parse_interval("h").is_err());
+        assert!(parse_interval("").is_err());
+    }
+
+    #[test]
+    fn events() {
+        let healthy = Status::new(Failures::default());
+        let broken = Status::new(Failures {
+            errors: vec!["failed".to_string()],
+            ..Default::default()
+        });
+        let different = Status::new(Failures {
+            errors: vec!["failed differently".to_string()],
+            ..Default::default()
+        });
+
+        assert_eq!(Event::new(None, &healthy), None);
+        assert_eq!(Event::new(None, &broken), Some(Event::Broken));
+        assert_eq!(Event::new(Some(&healthy), &broken), Some(Event::Broken));
+        assert_eq!(Event::new(Some(&broken), &broken), None);
+        assert_eq!(Event::new(Some(&broken), &different), Some(Event::Changed));
+        assert_eq!(Event::new(Some(&broken), &healthy), Some(Event::Recovered));
+        assert_eq!(Event::new(Some(&healthy), &healthy), None);
+    }
+
+    /// Creates a request with the given method and path.
+    fn request(method: &str, path: &str) -> http::Request {
+        http::Request {
+            method: method.to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn responses() {
+        let status = Status::new(Failures::default());
+        assert!(
+            respond(&request("GET", "/status"), Some(&status)).starts_with("HTTP/1.1 200 OK\r\n")
+        );
+        assert!(
+            respond(&request("GET", "/status"), None)
+                .starts_with("HTTP/1.1 503 Service Unavailable")
+        );
+        assert!(respond(&request("GET", "/"), Some(&status)).starts_with("HTTP/1.1 404 Not Found"));
+        assert!(
+            respond(&request("POST", "/status"), Some(&status))
+                .starts_with("HTTP/1.1 404 Not Found")
+        );
+    }
+
+    #[tokio::test]
+    async fn applies_configuration() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("source.wdl");
+        std::fs::write(
+            &path,
+            "version 1.2\n\ntask test {\n    command {\n        echo hi\n    }\n}\n",
+        )
+        .unwrap();
+        let sources = [Source::from_str(path.to_str().unwrap()).unwrap()];
+        let config = |except: &[&str]| CheckConfig {
+            except: except.iter().map(ToString::to_string).collect(),
+            strict: true,
+            ..Default::default()
+        };
+
+        // Lint rules are only run in strict mode
+        let status = revalidate(&sources, false, &CheckConfig::default()).await;
+        assert!(!status.broken);
+
+        let status = revalidate(&sources, false, &config(&["ShellCheck"])).await;
+        assert!(status.broken);
+
+        let status = revalidate(&sources, false, &config(&["ShellCheck", "HereDocCommands"])).await;
+        assert!(!status.broken);
+    }
+}
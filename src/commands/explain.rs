@@ -117,6 +117,10 @@ pub fn pretty_print_lint_rule(rule: &dyn lint::Rule) {
     println!("{desc}", desc = rule.description());
     println!("\n{explanation}", explanation = rule.explanation());
 
+    if let Some(version) = rule.min_version() {
+        println!("\n{since} WDL {version}", since = "Since:".bold());
+    }
+
     if let Some(url) = rule.url() {
         println!("\n{url}", url = url.underline().blue());
     }
@@ -2,12 +2,14 @@
 
 use std::fs;
 use std::io::IsTerminal;
+use std::path::Path;
 
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
 use clap::Parser;
 use clap::Subcommand;
+use similar::TextDiff;
 use tracing::info;
 use tracing::warn;
 use wdl::analysis::Document;
@@ -23,8 +25,10 @@ use crate::analysis::Analysis;
 use crate::analysis::Source;
 use crate::commands::CommandError;
 use crate::commands::CommandResult;
+use crate::commands::DiscoveryArgs;
 use crate::diagnostics::Mode;
 use crate::diagnostics::emit_diagnostics;
+use crate::editorconfig::Properties;
 
 /// Arguments for the `format` subcommand.
 #[derive(Parser, Debug)]
@@ -56,6 +60,27 @@ pub struct Args {
     #[arg(long, value_name = "LENGTH", global = true)]
     pub max_line_length: Option<usize>,
 
+    /// Ignore `.editorconfig` files.
+    ///
+    /// By default, the `indent_style`, `indent_size`, and `max_line_length`
+    /// properties of `.editorconfig` files are used in place of the
+    /// configuration file's format settings; command line options always take
+    /// precedence.
+    #[arg(long, global = true)]
+    pub no_editorconfig: bool,
+
+    /// The arguments that control how directories are searched.
+    #[command(flatten)]
+    pub discovery: DiscoveryArgs,
+
+    /// Whether the indentation was specified on the command line.
+    #[arg(skip)]
+    explicit_indent: bool,
+
+    /// Whether the maximum line length was specified on the command line.
+    #[arg(skip)]
+    explicit_max_line_length: bool,
+
     /// Subcommand for the `format` command.
     #[command(subcommand)]
     pub command: FormatSubcommand,
@@ -64,6 +89,8 @@ pub struct Args {
 impl Args {
     /// Applies the configuration to the command arguments.
     pub fn apply(mut self, config: crate::config::Config) -> Self {
+        self.explicit_indent = self.with_tabs || self.indentation_size.is_some();
+        self.explicit_max_line_length = self.max_line_length.is_some();
         self.no_color = self.no_color || !config.common.color;
         if self.report_mode.is_none() {
             self.report_mode = Some(config.common.report_mode);
@@ -93,6 +120,18 @@ pub struct RequiredSource {
     source: Source,
 }
 
+/// Arguments for the `format overwrite` subcommand.
+#[derive(Parser, Debug, Clone)]
+pub struct OverwriteArgs {
+    /// Sources to format.
+    sources: Vec<Source>,
+
+    /// Print a unified diff of the changes to STDOUT instead of overwriting
+    /// the documents.
+    #[arg(long)]
+    diff: bool,
+}
+
 /// Subcommands for the `format` command.
 #[derive(Subcommand, Debug, Clone)]
 pub enum FormatSubcommand {
@@ -103,12 +142,48 @@ pub enum FormatSubcommand {
     View(RequiredSource),
 
     /// Reformat all WDL documents via overwriting.
-    Overwrite(OptionalSources),
+    ///
+    /// Formatted documents are written to a temporary file that replaces the
+    /// document; documents that are already formatted are not written to,
+    /// preserving their modification times.
+    Overwrite(OverwriteArgs),
+}
+
+/// Overwrites a document's file with its formatted contents.
+///
+/// The contents are written to a temporary file in the same directory that is
+/// then renamed over the document's file, so that the document is never left
+/// partially written. The file is not written to at all if the contents are
+/// unchanged.
+///
+/// Returns `true` if the file was written to.
+fn overwrite(path: &Path, source: &str, formatted: &str) -> Result<bool> {
+    if source == formatted {
+        return Ok(false);
+    }
+
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("`{path}` is not a file", path = path.display()))?;
+    let mut temp_name = std::ffi::OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(".format.tmp");
+    let temp = path.with_file_name(temp_name);
+
+    let result = fs::write(&temp, formatted)
+        .and_then(|_| fs::set_permissions(&temp, fs::metadata(path)?.permissions()))
+        .and_then(|_| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+
+    result.with_context(|| format!("failed to write `{path}`", path = path.display()))?;
+    Ok(true)
 }
 
 /// Formats a document.
 fn format_document(
-    formatter: &Formatter,
+    args: &Args,
     document: &Document,
     mode: Mode,
     no_color: bool,
@@ -131,15 +206,38 @@ fn format_document(
         .into_v1()
         .expect("only WDL v1.x documents are supported");
     let element = Node::Ast(ast).into_format_element();
-    Ok((source, formatter.format(&element)?))
+    Ok((source, formatter(args, Some(document))?.format(&element)?))
 }
 
-/// Runs the `format` command.
-pub async fn format(args: Args) -> CommandResult<()> {
-    let indent = Indent::try_new(args.with_tabs, args.indentation_size)
+/// Creates a formatter for the given document.
+///
+/// Unless disabled, any `.editorconfig` properties that apply to the document
+/// are used in place of settings that were not specified on the command line.
+fn formatter(args: &Args, document: Option<&Document>) -> Result<Formatter> {
+    let mut with_tabs = args.with_tabs;
+    let mut indentation_size = args.indentation_size;
+    let mut max_line_length = args.max_line_length;
+
+    if let Some(path) = document
+        .filter(|_| !args.no_editorconfig)
+        .and_then(|d| d.uri().to_file_path().ok())
+    {
+        let properties = Properties::for_file(&path)?;
+        if !args.explicit_indent {
+            with_tabs = properties.use_tabs.unwrap_or(with_tabs);
+            indentation_size = properties.indent_size.or(indentation_size);
+        }
+
+        if !args.explicit_max_line_length {
+            max_line_length = properties.max_line_length.or(max_line_length);
+        }
+    }
+
+    // The indentation size is only meaningful when indenting with spaces
+    let indent = Indent::try_new(with_tabs, indentation_size.filter(|_| !with_tabs))
         .context("failed to create indentation configuration")?;
 
-    let max_line_length = match args.max_line_length {
+    let max_line_length = match max_line_length {
         Some(length) => MaxLineLength::try_new(length)
             .context("failed to create max line length configuration")?,
         None => MaxLineLength::default(),
@@ -149,17 +247,26 @@ pub async fn format(args: Args) -> CommandResult<()> {
         .indent(indent)
         .max_line_length(max_line_length)
         .build();
-    let formatter = Formatter::new(config);
+    Ok(Formatter::new(config))
+}
+
+/// Runs the `format` command.
+pub async fn format(args: Args) -> CommandResult<()> {
+    // Validate the formatting options up front
+    formatter(&args, None)?;
 
     let mut errors = 0;
-    match args.command {
+    match args.command.clone() {
         FormatSubcommand::Check(s) => {
             let mut sources = s.sources;
             if sources.is_empty() {
                 sources.push(Source::default());
             }
 
-            let results = Analysis::default()
+            let results = args
+                .discovery
+                .clone()
+                .apply(Analysis::default())
                 .extend_sources(sources.clone())
                 .run()
                 .await
@@ -176,7 +283,7 @@ pub async fn format(args: Args) -> CommandResult<()> {
                 }
 
                 let (source, formatted) = match format_document(
-                    &formatter,
+                    &args,
                     result.document(),
                     args.report_mode.unwrap_or_default(),
                     args.no_color,
@@ -199,7 +306,7 @@ pub async fn format(args: Args) -> CommandResult<()> {
                             pretty_assertions::StrComparison::new(&source, &formatted)
                         );
                     } else {
-                        let diff = similar::TextDiff::from_lines(&source, &formatted);
+                        let diff = TextDiff::from_lines(&source, &formatted);
                         eprint!("{}", diff.unified_diff().header("input", "formatted"));
                     }
                     errors += 1;
@@ -237,7 +344,7 @@ pub async fn format(args: Args) -> CommandResult<()> {
             }
 
             let (_source, formatted) = format_document(
-                &formatter,
+                &args,
                 result.document(),
                 args.report_mode.unwrap_or_default(),
                 args.no_color,
@@ -256,7 +363,10 @@ pub async fn format(args: Args) -> CommandResult<()> {
                 sources.push(Source::default());
             }
 
-            let results = Analysis::default()
+            let results = args
+                .discovery
+                .clone()
+                .apply(Analysis::default())
                 .extend_sources(sources.clone())
                 .run()
                 .await
@@ -275,8 +385,8 @@ pub async fn format(args: Args) -> CommandResult<()> {
                     continue;
                 }
 
-                let (_source, formatted) = match format_document(
-                    &formatter,
+                let (source, formatted) = match format_document(
+                    &args,
                     result.document(),
                     args.report_mode.unwrap_or_default(),
                     args.no_color,
@@ -292,10 +402,23 @@ pub async fn format(args: Args) -> CommandResult<()> {
                     }
                 };
 
-                fs::write(result.document().uri().to_file_path().unwrap(), formatted)
-                    .with_context(|| {
-                        format!("failed to overwrite `{}`", result.document().path())
-                    })?;
+                // Leave documents that are already formatted untouched so that their
+                // modification times are preserved
+                let path = result.document().path();
+                if source == formatted {
+                    info!("`{path}` is already formatted");
+                    continue;
+                }
+
+                if s.diff {
+                    let diff = TextDiff::from_lines(&source, &formatted);
+                    print!("{}", diff.unified_diff().header(&path, &path));
+                    continue;
+                }
+
+                let file = result.document().uri().to_file_path().unwrap();
+                overwrite(&file, &source, &formatted)
+                    .with_context(|| format!("failed to overwrite `{path}`"))?;
             }
         }
     }
@@ -310,3 +433,52 @@ pub async fn format(args: Args) -> CommandResult<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+    use std::time::SystemTime;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn overwrites_files() {
+        let source = "version 1.2\n\ntask foo {\n  command <<<>>>\n}\nworkflow bar {}\n";
+        let formatted = "version 1.2\n\ntask foo {\n    command <<<>>>\n}\n\nworkflow bar {}\n";
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("source.wdl");
+        fs::write(&path, source).unwrap();
+        assert!(overwrite(&path, source, formatted).unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), formatted);
+
+        // The temporary file is renamed over the document
+        let names: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["source.wdl"]);
+    }
+
+    #[test]
+    fn unchanged_files_are_not_written() {
+        let source = "version 1.2\n\nworkflow test {}\n";
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("source.wdl");
+        std::fs::write(&path, source).unwrap();
+
+        let modified = SystemTime::now() - Duration::from_secs(60 * 60);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+
+        assert!(!overwrite(&path, source, source).unwrap());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), source);
+        assert_eq!(path.metadata().unwrap().modified().unwrap(), modified);
+    }
+}
@@ -0,0 +1,392 @@
+//! Implementation of the `images` subcommand.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use anyhow::Context;
+use anyhow::anyhow;
+use clap::Parser;
+use clap::ValueEnum;
+use crankshaft::docker::Docker;
+use serde::Serialize;
+use tracing::warn;
+use wdl::ast::AstNode;
+use wdl::ast::AstToken;
+use wdl::ast::v1::Expr;
+use wdl::ast::v1::LiteralExpr;
+use wdl::ast::v1::TASK_REQUIREMENT_CONTAINER;
+use wdl::ast::v1::TASK_REQUIREMENT_CONTAINER_ALIAS;
+use wdl::ast::v1::TaskDefinition;
+use wdl::engine::config::matches_platform;
+
+use crate::analysis::Analysis;
+use crate::analysis::Source;
+use crate::commands::CommandError;
+use crate::commands::CommandResult;
+
+/// The format of an images report.
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum ImagesFormat {
+    /// A human-readable text report.
+    #[default]
+    Text,
+
+    /// A JSON report.
+    Json,
+}
+
+impl std::fmt::Display for ImagesFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Arguments for the `images` subcommand.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+pub struct Args {
+    /// A source WDL document, directory, or URL.
+    #[clap(value_name = "SOURCE")]
+    pub source: Option<Source>,
+
+    /// Queries the registry of each image for its digest and the platforms it
+    /// is available for.
+    ///
+    /// This requires a connection to the Docker daemon.
+    #[arg(long)]
+    pub inspect: bool,
+
+    /// Fails if an image is not available for the given platform.
+    ///
+    /// The platform may be an architecture (e.g. `arm64`) or an operating
+    /// system and architecture (e.g. `linux/arm64`). Implies `--inspect`.
+    #[arg(long, value_name = "PLATFORM")]
+    pub platform: Option<String>,
+
+    /// The format of the report.
+    #[arg(long, value_name = "FORMAT", default_value_t)]
+    pub format: ImagesFormat,
+}
+
+/// Represents a container image referenced by a task.
+#[derive(Debug, Default, Serialize)]
+struct Image {
+    /// The tasks that reference the image, as `<path>:<task>`.
+    tasks: Vec<String>,
+    /// The digest of the image's manifest, if inspected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    digest: Option<String>,
+    /// The platforms the image is available for, if inspected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    platforms: Option<Vec<String>>,
+    /// Whether or not the image is missing for the target platform.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    missing: bool,
+    /// Whether or not the registry of the image could not be inspected.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    failed: bool,
+}
+
+/// Represents the container images referenced by a set of documents.
+#[derive(Debug, Default, Serialize)]
+struct Report {
+    /// The images that could be statically determined.
+    images: BTreeMap<String, Image>,
+    /// The container expressions that could not be statically evaluated, keyed
+    /// by `<path>:<task>`.
+    unresolved: BTreeMap<String, String>,
+}
+
+impl Report {
+    /// Renders the report as text.
+    fn to_text(&self) -> String {
+        let mut report = String::new();
+        for (name, image) in &self.images {
+            let _ = writeln!(
+                report,
+                "{name}{status}",
+                status = if image.failed {
+                    " (failed to inspect)"
+                } else if image.missing {
+                    " (missing for the target platform)"
+                } else {
+                    ""
+                }
+            );
+            let _ = writeln!(report, "  tasks: {tasks}", tasks = image.tasks.join(", "));
+            if let Some(digest) = &image.digest {
+                let _ = writeln!(report, "  digest: {digest}");
+            }
+
+            if let Some(platforms) = &image.platforms {
+                let _ = writeln!(report, "  platforms: {}", platforms.join(", "));
+            }
+        }
+
+        if !self.unresolved.is_empty() {
+            if !self.images.is_empty() {
+                report.push('\n');
+            }
+
+            let _ = writeln!(report, "unresolved:");
+            for (task, expr) in &self.unresolved {
+                let _ = writeln!(report, "  {task}: `{expr}`");
+            }
+        }
+
+        report
+    }
+
+    /// Creates the error message for the images that failed to be inspected or
+    /// are missing for the target platform.
+    ///
+    /// Returns `None` if every image was inspected and available.
+    fn error(&self, platform: Option<&str>) -> Option<String> {
+        let failed = self.images.values().filter(|i| i.failed).count();
+        let missing = self.images.values().filter(|i| i.missing).count();
+
+        let mut errors = Vec::new();
+        if failed > 0 {
+            errors.push(format!(
+                "{failed} image{s} could not be inspected",
+                s = if failed == 1 { "" } else { "s" },
+            ));
+        }
+
+        if missing > 0 {
+            errors.push(format!(
+                "{missing} image{s} {are} not available for platform `{platform}`",
+                s = if missing == 1 { "" } else { "s" },
+                are = if missing == 1 { "is" } else { "are" },
+                platform = platform.unwrap_or_default()
+            ));
+        }
+
+        if errors.is_empty() {
+            return None;
+        }
+
+        Some(errors.join("; "))
+    }
+}
+
+/// Statically evaluates an expression to a string.
+///
+/// Returns `None` if the expression is not a literal string without
+/// placeholders.
+fn literal_string(expr: &Expr) -> Option<String> {
+    let Expr::Literal(LiteralExpr::String(s)) = expr else {
+        return None;
+    };
+
+    let mut buffer = String::new();
+    s.text()?.unescape_to(&mut buffer);
+    Some(buffer)
+}
+
+/// Statically evaluates the container expression of a task.
+///
+/// Names are resolved to the task's input and private declarations with
+/// literal values.
+///
+/// Returns `None` if the expression can't be statically evaluated.
+fn container_images(task: &TaskDefinition, expr: &Expr) -> Option<Vec<String>> {
+    let resolve = |expr: &Expr| {
+        if let Expr::NameRef(name) = expr {
+            let decl = task
+                .input()
+                .into_iter()
+                .flat_map(|i| i.declarations().collect::<Vec<_>>())
+                .filter_map(|d| d.expr().map(|e| (d.name(), e)))
+                .chain(task.declarations().map(|d| (d.name(), d.expr())))
+                .find(|(n, _)| n.text() == name.name().text())?;
+            return literal_string(&decl.1);
+        }
+
+        literal_string(expr)
+    };
+
+    match expr {
+        Expr::Literal(LiteralExpr::Array(array)) => array.elements().map(|e| resolve(&e)).collect(),
+        _ => resolve(expr).map(|image| vec![image]),
+    }
+}
+
+/// Statically determines the container images of a task.
+///
+/// The `*` image, which allows any container to be used, is not included.
+///
+/// Returns `None` if the task does not specify a container and
+/// `Some(Err(expr))` with the text of the container expression if it can't be
+/// statically evaluated.
+pub(crate) fn task_images(task: &TaskDefinition) -> Option<Result<Vec<String>, String>> {
+    let is_container =
+        |name: &str| name == TASK_REQUIREMENT_CONTAINER || name == TASK_REQUIREMENT_CONTAINER_ALIAS;
+    let expr = task
+        .requirements()
+        .and_then(|r| {
+            r.items()
+                .find(|i| is_container(i.name().text()))
+                .map(|i| i.expr())
+        })
+        .or_else(|| {
+            task.runtime().and_then(|r| {
+                r.items()
+                    .find(|i| is_container(i.name().text()))
+                    .map(|i| i.expr())
+            })
+        })?;
+
+    Some(match container_images(task, &expr) {
+        Some(names) => Ok(names.into_iter().filter(|n| n != "*").collect()),
+        None => Err(expr.text().to_string()),
+    })
+}
+
+/// Performs the `images` subcommand.
+pub async fn images(args: Args) -> CommandResult<()> {
+    let results = Analysis::default()
+        .add_source(args.source.unwrap_or_default())
+        .run()
+        .await
+        .map_err(CommandError::from)?;
+
+    let mut report = Report::default();
+    for result in results {
+        let document = result.document();
+        let path = document.path();
+        let ast = document.root().ast();
+        let Some(ast) = ast.as_v1() else {
+            continue;
+        };
+
+        for task in ast.tasks() {
+            let id = format!("{path}:{name}", name = task.name().text());
+            match task_images(&task) {
+                Some(Ok(names)) => {
+                    for name in names {
+                        report
+                            .images
+                            .entry(name)
+                            .or_default()
+                            .tasks
+                            .push(id.clone());
+                    }
+                }
+                Some(Err(expr)) => {
+                    report.unresolved.insert(id, expr);
+                }
+                None => {}
+            }
+        }
+    }
+
+    if args.inspect || args.platform.is_some() {
+        let docker = Docker::with_defaults().context("failed to connect to Docker daemon")?;
+        for (name, image) in &mut report.images {
+            let inspection = match docker.inner().inspect_registry_image(name, None).await {
+                Ok(inspection) => inspection,
+                Err(e) => {
+                    warn!("failed to inspect image `{name}`: {e}");
+                    image.failed = true;
+                    continue;
+                }
+            };
+
+            image.digest = inspection.descriptor.digest;
+            let platforms = inspection
+                .platforms
+                .iter()
+                .map(|p| {
+                    let mut platform = format!(
+                        "{os}/{arch}",
+                        os = p.os.as_deref().unwrap_or("unknown"),
+                        arch = p.architecture.as_deref().unwrap_or("unknown")
+                    );
+                    if let Some(variant) = &p.variant {
+                        platform.push('/');
+                        platform.push_str(variant);
+                    }
+
+                    platform
+                })
+                .collect::<Vec<_>>();
+
+            if let Some(target) = &args.platform
+                && !platforms.iter().any(|p| matches_platform(p, target))
+            {
+                image.missing = true;
+            }
+
+            image.platforms = Some(platforms);
+        }
+    }
+
+    match args.format {
+        ImagesFormat::Text => print!("{report}", report = report.to_text()),
+        ImagesFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).context("failed to serialize images")?
+        ),
+    }
+
+    if let Some(error) = report.error(args.platform.as_deref()) {
+        return Err(anyhow!(error).into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn reports_inspection_failures() {
+        let mut report = Report::default();
+        assert_eq!(report.error(None), None);
+
+        report.images.insert(
+            "registry.invalid/sprocket/missing:1.0".to_string(),
+            Image {
+                tasks: vec!["source.wdl:test".to_string()],
+                failed: true,
+                ..Default::default()
+            },
+        );
+        report.images.insert(
+            "ubuntu:22.04".to_string(),
+            Image {
+                tasks: vec!["source.wdl:other".to_string()],
+                digest: Some("sha256:abc".to_string()),
+                platforms: Some(vec!["linux/amd64".to_string()]),
+                missing: true,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(
+            report.to_text(),
+            "registry.invalid/sprocket/missing:1.0 (failed to inspect)\n  tasks: \
+             source.wdl:test\nubuntu:22.04 (missing for the target platform)\n  tasks: \
+             source.wdl:other\n  digest: sha256:abc\n  platforms: linux/amd64\n"
+        );
+        assert_eq!(
+            report.error(Some("linux/arm64")).as_deref(),
+            Some(
+                "1 image could not be inspected; 1 image is not available for platform \
+                 `linux/arm64`"
+            )
+        );
+        assert_eq!(
+            serde_json::to_value(&report).unwrap()["images"]
+                ["registry.invalid/sprocket/missing:1.0"],
+            serde_json::json!({ "tasks": ["source.wdl:test"], "failed": true })
+        );
+    }
+}
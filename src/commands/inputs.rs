@@ -1,15 +1,20 @@
 //! Implementation of the `inputs` command.
 
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::PathBuf;
 
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
 use clap::Parser;
+use clap::Subcommand;
+use indexmap::IndexMap;
 use serde_json::Map;
 use serde_json::Value;
 use serde_yaml_ng;
 use wdl::analysis::Document;
+use wdl::analysis::document::Input;
 use wdl::analysis::types::CallKind;
 use wdl::ast::AstNode;
 use wdl::ast::AstToken;
@@ -19,18 +24,27 @@ use wdl::ast::v1::InputSection;
 use wdl::ast::v1::LiteralExpr;
 use wdl::ast::v1::StringPart;
 use wdl::ast::v1::TaskDefinition;
+use wdl::engine::Inputs as EngineInputs;
 
 use crate::analysis::Analysis;
 use crate::analysis::Source;
 use crate::commands::CommandError;
 use crate::commands::CommandResult;
+use crate::inputs::batch::ID_COLUMN;
+use crate::inputs::batch::coerce_value;
+use crate::inputs::batch::parse_table;
 
 /// Arguments for the `inputs` subcommand.
 #[derive(Parser, Debug)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct Args {
+    /// Subcommand for the `inputs` command.
+    #[command(subcommand)]
+    pub command: Option<InputsSubcommand>,
+
     /// A source WDL document or URL.
-    #[arg(value_name = "SOURCE")]
-    pub source: Source,
+    #[arg(value_name = "SOURCE", required = true)]
+    pub source: Option<Source>,
 
     /// The name of the task or workflow for which to generate inputs.
     #[clap(short, long, value_name = "NAME")]
@@ -53,6 +67,49 @@ pub struct Args {
     pub yaml: bool,
 }
 
+/// Subcommands for the `inputs` command.
+#[derive(Subcommand, Debug)]
+pub enum InputsSubcommand {
+    /// Creates inputs from a sample sheet of tab-separated values.
+    FromTsv(FromTsvArgs),
+}
+
+/// Arguments for the `inputs from-tsv` subcommand.
+#[derive(Parser, Debug)]
+pub struct FromTsvArgs {
+    /// The sample sheet of tab-separated values.
+    ///
+    /// The header row names the input of each column; each remaining row is
+    /// a set of inputs.
+    #[arg(value_name = "TSV")]
+    pub tsv: PathBuf,
+
+    /// The WDL document or URL containing the task or workflow.
+    #[arg(long, value_name = "SOURCE")]
+    pub workflow: Source,
+
+    /// The name of the task or workflow for which to create inputs.
+    ///
+    /// Defaults to the document's workflow or, if the document has no
+    /// workflow, its only task.
+    #[arg(short, long, value_name = "NAME")]
+    pub entrypoint: Option<String>,
+
+    /// A JSON file containing an object that maps column names to input
+    /// names.
+    ///
+    /// When a mapping is given, columns not in the mapping are ignored.
+    #[arg(long, value_name = "FILE")]
+    pub mapping: Option<PathBuf>,
+
+    /// Outputs an array of inputs objects, one per row, for use with `sprocket
+    /// run --inputs-batch`.
+    ///
+    /// Without this option, the sample sheet must contain exactly one row.
+    #[arg(long)]
+    pub batch: bool,
+}
+
 /// An input key.
 #[derive(Clone, Debug)]
 pub struct Key(Vec<String>);
@@ -443,17 +500,24 @@ impl InputProcessor {
 
 /// Displays the input schema for a WDL document.
 pub async fn inputs(args: Args) -> CommandResult<()> {
-    if let Source::Directory(_) = args.source {
+    let source = match args.command {
+        Some(InputsSubcommand::FromTsv(args)) => return from_tsv(args).await,
+        None => args
+            .source
+            .expect("source should be required without a subcommand"),
+    };
+
+    if let Source::Directory(_) = source {
         return Err(anyhow!("directory sources are not supported for the `inputs` command").into());
     }
     let results = Analysis::default()
-        .add_source(args.source.clone())
+        .add_source(source.clone())
         .run()
         .await
         .map_err(CommandError::from)?;
 
     let document = results
-        .filter(&[&args.source])
+        .filter(&[&source])
         .next()
         .expect("the root source should always be included in the results")
         .document();
@@ -572,3 +636,190 @@ pub async fn inputs(args: Args) -> CommandResult<()> {
 
     Ok(())
 }
+
+/// Validates a set of inputs against a task or workflow.
+fn validate_inputs(document: &Document, name: &str, object: Map<String, Value>) -> Result<()> {
+    let inputs = match EngineInputs::parse_object(document, object)? {
+        Some((_, inputs)) => inputs,
+        None if document.task_by_name(name).is_some() => EngineInputs::Task(Default::default()),
+        None => EngineInputs::Workflow(Default::default()),
+    };
+
+    match inputs {
+        EngineInputs::Task(inputs) => inputs.validate(
+            document,
+            document.task_by_name(name).expect("task should be present"),
+            None,
+        ),
+        EngineInputs::Workflow(inputs) => inputs.validate(
+            document,
+            document.workflow().expect("workflow should be present"),
+            None,
+        ),
+    }
+}
+
+/// Performs the `inputs from-tsv` subcommand.
+async fn from_tsv(args: FromTsvArgs) -> CommandResult<()> {
+    if let Source::Directory(_) = args.workflow {
+        return Err(anyhow!("directory sources are not supported for the `inputs` command").into());
+    }
+
+    let results = Analysis::default()
+        .add_source(args.workflow.clone())
+        .run()
+        .await
+        .map_err(CommandError::from)?;
+
+    let document = results
+        .filter(&[&args.workflow])
+        .next()
+        .expect("the root source should always be included in the results")
+        .document();
+
+    let (name, inputs): (String, &IndexMap<String, Input>) = match &args.entrypoint {
+        Some(name) => match (document.task_by_name(name), document.workflow()) {
+            (Some(task), _) => (name.clone(), task.inputs()),
+            (None, Some(workflow)) if workflow.name() == name => (name.clone(), workflow.inputs()),
+            _ => {
+                return Err(anyhow!(
+                    "no task or workflow with name `{name}` was found in document `{path}`",
+                    path = document.path()
+                )
+                .into());
+            }
+        },
+        None => match document.workflow() {
+            Some(workflow) => (workflow.name().to_string(), workflow.inputs()),
+            None => {
+                let mut tasks = document.tasks();
+                match (tasks.next(), tasks.next()) {
+                    (Some(task), None) => (task.name().to_string(), task.inputs()),
+                    (Some(_), Some(_)) => {
+                        return Err(anyhow!(
+                            "document `{path}` contains more than one task: use the \
+                             `--entrypoint` option to refer to a specific task by name",
+                            path = document.path()
+                        )
+                        .into());
+                    }
+                    (None, _) => {
+                        return Err(anyhow!(
+                            "document `{path}` contains no workflow or task",
+                            path = document.path()
+                        )
+                        .into());
+                    }
+                }
+            }
+        },
+    };
+
+    let mapping = match &args.mapping {
+        Some(path) => {
+            let contents = std::fs::read_to_string(path).with_context(|| {
+                format!(
+                    "failed to read mapping file `{path}`",
+                    path = path.display()
+                )
+            })?;
+            let mapping: HashMap<String, String> =
+                serde_json::from_str(&contents).with_context(|| {
+                    format!(
+                        "mapping file `{path}` must contain a JSON object mapping column names to \
+                         input names",
+                        path = path.display()
+                    )
+                })?;
+            Some(mapping)
+        }
+        None => None,
+    };
+
+    let contents = std::fs::read_to_string(&args.tsv).with_context(|| {
+        format!(
+            "failed to read sample sheet `{path}`",
+            path = args.tsv.display()
+        )
+    })?;
+    let (columns, rows) = parse_table(&args.tsv, &contents).map_err(anyhow::Error::from)?;
+
+    if let Some(mapping) = &mapping
+        && let Some(column) = mapping.keys().find(|c| !columns.contains(c))
+    {
+        return Err(anyhow!(
+            "mapped column `{column}` does not exist in sample sheet `{path}`",
+            path = args.tsv.display()
+        )
+        .into());
+    }
+
+    // Determine the input of each column; columns without an input are ignored
+    let mut targets = Vec::with_capacity(columns.len());
+    for column in &columns {
+        let target = match &mapping {
+            Some(mapping) => mapping.get(column).cloned(),
+            None if column == ID_COLUMN => None,
+            None => Some(column.clone()),
+        };
+
+        if let Some(target) = &target
+            && !target.contains('.')
+            && !inputs.contains_key(target)
+        {
+            return Err(anyhow!(
+                "column `{column}` does not map to an input of `{name}`: use the `--mapping` \
+                 option to map columns to input names"
+            )
+            .into());
+        }
+
+        targets.push(target);
+    }
+
+    let mut objects = Vec::with_capacity(rows.len());
+    for (i, values) in rows.into_iter().enumerate() {
+        let row = i + 1;
+        let mut object = Map::new();
+        for (target, value) in targets.iter().zip(values) {
+            // Empty values are omitted so that the input's default is used
+            let Some(target) = target else {
+                continue;
+            };
+
+            if value.is_empty() {
+                continue;
+            }
+
+            let value = coerce_value(&value, inputs.get(target).map(Input::ty))
+                .map_err(anyhow::Error::msg)
+                .with_context(|| format!("invalid value for input `{target}` in row {row}"))?;
+            object.insert(format!("{name}.{target}"), value);
+        }
+
+        validate_inputs(document, &name, object.clone())
+            .with_context(|| format!("invalid inputs in row {row}"))?;
+        objects.push(Value::Object(object));
+    }
+
+    let output = if args.batch {
+        Value::Array(objects)
+    } else {
+        match <[Value; 1]>::try_from(objects) {
+            Ok([object]) => object,
+            Err(objects) => {
+                return Err(anyhow!(
+                    "sample sheet `{path}` contains {count} rows: use the `--batch` option to \
+                     create inputs for more than one row",
+                    path = args.tsv.display(),
+                    count = objects.len()
+                )
+                .into());
+            }
+        }
+    };
+
+    let json = serde_json::to_string_pretty(&output).context("failed to serialize inputs")?;
+    println!("{json}");
+    Ok(())
+}
@@ -0,0 +1,319 @@
+//! Implementation of the `new-rule` subcommand.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use clap::Parser;
+use convert_case::Case;
+use convert_case::Casing;
+use wdl::lint::Tag;
+
+use crate::commands::CommandResult;
+
+/// The default path to the `wdl-lint` crate, relative to the repository root.
+const DEFAULT_CRATE_DIR: &str = "crates/wdl-lint";
+
+/// The line that ends the rule table in `rules()`.
+const RULES_TABLE_END: &str = "    ];";
+
+/// Arguments for the `new-rule` subcommand.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// The identifier of the new rule (e.g. `CallInputKeyword`).
+    ///
+    /// The identifier must be pascal case.
+    #[clap(value_name = "RULE_ID")]
+    pub id: String,
+
+    /// The tags of the new rule.
+    #[clap(
+        short,
+        long,
+        value_name = "TAG",
+        value_delimiter = ',',
+        default_value = "Style"
+    )]
+    pub tags: Vec<Tag>,
+
+    /// The path to the `wdl-lint` crate in which to generate the rule.
+    #[clap(long, value_name = "DIR", default_value = DEFAULT_CRATE_DIR)]
+    pub crate_dir: PathBuf,
+}
+
+/// Renders the source of the rule module.
+fn render_rule(id: &str, tags: &[Tag]) -> String {
+    let tags = tags
+        .iter()
+        .map(|t| format!("{t:?}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        r#"//! A lint rule for TODO.
+
+use wdl_analysis::Diagnostics;
+use wdl_analysis::VisitReason;
+use wdl_ast::AstNode;
+use wdl_ast::Diagnostic;
+use wdl_ast::Span;
+use wdl_ast::SyntaxElement;
+use wdl_ast::v1::CallStatement;
+
+use crate::Rule;
+
+/// The identifier for this rule.
+const ID: &str = "{id}";
+
+/// Creates a diagnostic for the rule.
+fn {fn_name}(span: Span) -> Diagnostic {{
+    Diagnostic::note("TODO: describe the problem")
+        .with_rule(ID)
+        .with_highlight(span)
+        .with_fix("TODO: describe the fix")
+}}
+
+crate::rule! {{
+    /// TODO: describe what the rule detects.
+    #[derive(Default, Debug, Clone)]
+    pub struct {id}Rule {{}}
+
+    id: ID,
+    description: "TODO: a one sentence description of the rule.",
+    explanation: "TODO: a longer explanation of why the rule exists.",
+    tags: [{tags}],
+    exceptable_nodes: [VersionStatementNode, CallStatementNode, WorkflowDefinitionNode],
+    related_rules: [],
+
+    visitor {{
+        fn call_statement(
+            &mut self,
+            diagnostics: &mut Diagnostics,
+            reason: VisitReason,
+            call: &CallStatement,
+        ) {{
+            if reason == VisitReason::Exit {{
+                return;
+            }}
+
+            // TODO: replace this with the rule's logic
+            if call.alias().is_none() {{
+                diagnostics.exceptable_add(
+                    {fn_name}(call.target().span()),
+                    SyntaxElement::from(call.inner().clone()),
+                    &self.exceptable_nodes(),
+                );
+            }}
+        }}
+    }}
+}}
+"#,
+        fn_name = id.to_case(Case::Snake),
+    )
+}
+
+/// Renders the source of the rule's test fixture.
+fn render_fixture(id: &str) -> String {
+    format!(
+        r#"#@ except: MatchingOutputMeta, MetaDescription
+#@ except: ParameterMetaMatched, RequirementsSection
+
+## This is a test of the {id} rule.
+
+version 1.2
+
+workflow test {{
+    meta {{}}
+
+    parameter_meta {{}}
+
+    call foo
+}}
+
+task foo {{
+    meta {{}}
+
+    parameter_meta {{}}
+
+    command <<<>>>
+
+    runtime {{}}
+}}
+"#
+    )
+}
+
+/// Reads a file to a string.
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path)
+        .with_context(|| format!("failed to read file `{path}`", path = path.display()))
+}
+
+/// Writes a file, failing if the file already exists.
+fn write_new(path: &Path, contents: &str) -> Result<()> {
+    if path.exists() {
+        bail!("file `{path}` already exists", path = path.display());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| {
+            format!(
+                "failed to create directory `{path}`",
+                path = parent.display()
+            )
+        })?;
+    }
+
+    fs::write(path, contents)
+        .with_context(|| format!("failed to write file `{path}`", path = path.display()))
+}
+
+/// Inserts a line into a sorted block of lines that all start with the given
+/// prefix.
+///
+/// Returns `None` if no lines in the source start with the prefix.
+fn insert_sorted(source: &str, prefix: &str, line: &str) -> Option<String> {
+    let lines = source.lines().collect::<Vec<_>>();
+    let first = lines.iter().position(|l| l.starts_with(prefix))?;
+    let last = first
+        + lines[first..]
+            .iter()
+            .take_while(|l| l.starts_with(prefix))
+            .count();
+    let index = first
+        + lines[first..last]
+            .iter()
+            .take_while(|l| **l < *line)
+            .count();
+
+    let mut result = lines[..index].join("\n");
+    result.push('\n');
+    result.push_str(line);
+    result.push('\n');
+    for l in &lines[index..] {
+        result.push_str(l);
+        result.push('\n');
+    }
+
+    Some(result)
+}
+
+/// Registers the rule module in `src/rules.rs`.
+fn register_module(path: &Path, module: &str) -> Result<()> {
+    let source = read(path)?;
+    let source = insert_sorted(&source, "mod ", &format!("mod {module};"))
+        .and_then(|s| insert_sorted(&s, "pub use ", &format!("pub use {module}::*;")))
+        .with_context(|| {
+            format!(
+                "failed to find rule module declarations in `{path}`",
+                path = path.display()
+            )
+        })?;
+
+    fs::write(path, source)
+        .with_context(|| format!("failed to write file `{path}`", path = path.display()))
+}
+
+/// Registers the rule in the rule table of `src/lib.rs`.
+fn register_rule(path: &Path, id: &str) -> Result<()> {
+    let source = read(path)?;
+    let start = source
+        .find("pub fn rules() -> Vec<Box<dyn Rule>>")
+        .with_context(|| {
+            format!(
+                "failed to find the `rules` function in `{path}`",
+                path = path.display()
+            )
+        })?;
+    let end = source[start..]
+        .find(RULES_TABLE_END)
+        .map(|i| start + i)
+        .with_context(|| {
+            format!(
+                "failed to find the end of the rule table in `{path}`",
+                path = path.display()
+            )
+        })?;
+
+    let mut result = String::with_capacity(source.len());
+    result.push_str(&source[..end]);
+    result.push_str(&format!("        Box::<rules::{id}Rule>::default(),\n"));
+    result.push_str(&source[end..]);
+
+    fs::write(path, result)
+        .with_context(|| format!("failed to write file `{path}`", path = path.display()))
+}
+
+/// Performs the `new-rule` command.
+pub async fn new_rule(args: Args) -> CommandResult<()> {
+    if args.id.to_case(Case::Pascal) != args.id {
+        return Err(anyhow::anyhow!("rule id `{id}` is not pascal case", id = args.id).into());
+    }
+
+    if wdl::lint::rules().iter().any(|r| r.id() == args.id)
+        || wdl::analysis::rules().iter().any(|r| r.id() == args.id)
+    {
+        return Err(anyhow::anyhow!("rule id `{id}` is already in use", id = args.id).into());
+    }
+
+    let dir = &args.crate_dir;
+    let lib = dir.join("src").join("lib.rs");
+    let rules = dir.join("src").join("rules.rs");
+    if !lib.is_file() || !rules.is_file() {
+        return Err(anyhow::anyhow!(
+            "`{dir}` does not appear to be the `wdl-lint` crate",
+            dir = dir.display()
+        )
+        .into());
+    }
+
+    let module = args.id.to_case(Case::Snake);
+    let rule = dir.join("src").join("rules").join(format!("{module}.rs"));
+    let fixture = dir
+        .join("tests")
+        .join("lints")
+        .join(args.id.to_case(Case::Kebab))
+        .join("source.wdl");
+
+    write_new(&rule, &render_rule(&args.id, &args.tags))?;
+    write_new(&fixture, &render_fixture(&args.id))?;
+    register_module(&rules, &module)?;
+    register_rule(&lib, &args.id)?;
+
+    for path in [&rule, &fixture, &rules, &lib] {
+        println!("updated `{path}`", path = path.display());
+    }
+
+    println!(
+        "\nnext steps:\n  1. implement the rule in `{rule}`\n  2. add the rule to \
+         `{dir}/RULES.md`\n  3. generate the expected diagnostics with `BLESS=1 cargo test -p \
+         wdl-lint --test lints`",
+        rule = rule.display(),
+        dir = dir.display(),
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_inserts_sorted() {
+        let source = "//! Rules.\n\nmod a;\nmod c;\n\npub use a::*;\npub use c::*;\n";
+        let source = insert_sorted(source, "mod ", "mod b;").unwrap();
+        let source = insert_sorted(&source, "pub use ", "pub use b::*;").unwrap();
+        assert_eq!(
+            source,
+            "//! Rules.\n\nmod a;\nmod b;\nmod c;\n\npub use a::*;\npub use b::*;\npub use c::*;\n"
+        );
+
+        let source = insert_sorted("mod a;\n", "mod ", "mod z;").unwrap();
+        assert_eq!(source, "mod a;\nmod z;\n");
+        assert!(insert_sorted("", "mod ", "mod a;").is_none());
+    }
+}
@@ -0,0 +1,547 @@
+//! Implementation of the `plan` subcommand.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::anyhow;
+use clap::Parser;
+use wdl::analysis::Document;
+use wdl::ast::AstNode;
+use wdl::ast::AstToken;
+use wdl::ast::v1::CallStatement;
+use wdl::ast::v1::ConditionalStatementClauseKind;
+use wdl::ast::v1::Expr;
+use wdl::ast::v1::LiteralExpr;
+use wdl::ast::v1::NameRefExpr;
+use wdl::ast::v1::WorkflowDefinition;
+use wdl::ast::v1::WorkflowStatement;
+use wdl::engine::Inputs as EngineInputs;
+use wdl::engine::Value;
+use wdl::engine::path::EvaluationPath;
+
+use crate::analysis::Analysis;
+use crate::analysis::Source;
+use crate::commands::CommandError;
+use crate::commands::CommandResult;
+use crate::inputs::Invocation;
+use crate::inputs::OriginPaths;
+
+/// Arguments for the `plan` subcommand.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+pub struct Args {
+    /// The path or URL to a document containing the workflow to plan.
+    #[clap(value_name = "SOURCE")]
+    pub source: Source,
+
+    /// The name of the task or workflow to plan.
+    ///
+    /// This argument is required if trying to plan a task or workflow without
+    /// any inputs.
+    ///
+    /// If `entrypoint` is specified, it will be appended with a `.` delimiter
+    /// and then prepended to all key-value pair inputs on the command line.
+    /// Keys specified within files are unchanged by this argument.
+    #[clap(short, long, value_name = "NAME")]
+    pub entrypoint: Option<String>,
+
+    /// The inputs for the task or workflow.
+    ///
+    /// These inputs can be either paths to files containing inputs or key-value
+    /// pairs passed in on the command line.
+    pub inputs: Vec<String>,
+}
+
+/// Represents a value that was statically evaluated.
+#[derive(Debug, Clone)]
+enum Known {
+    /// The value is a `Boolean`.
+    Boolean(bool),
+    /// The value is an `Int`.
+    Integer(i64),
+    /// The value is a local `File` or `String` path.
+    Path(PathBuf),
+    /// The value is an `Array` of the given length.
+    Array(usize),
+}
+
+/// Represents the statically known values in scope.
+type Env = HashMap<String, Known>;
+
+/// Represents an estimate of the task executions of a workflow.
+#[derive(Debug, Default, Clone, Copy)]
+struct Estimate {
+    /// The total number of task executions.
+    tasks: u64,
+    /// The maximum number of task executions that may run at once.
+    peak: u64,
+}
+
+/// Represents a line of the plan report.
+#[derive(Debug)]
+struct Line {
+    /// The nesting depth of the line.
+    depth: usize,
+    /// The text of the line.
+    text: String,
+}
+
+impl fmt::Display for Line {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:indent$}{text}",
+            "",
+            indent = self.depth * 2,
+            text = self.text
+        )
+    }
+}
+
+/// Statically estimates the task executions of a workflow.
+#[derive(Debug, Default)]
+struct Planner {
+    /// The lines of the report.
+    lines: Vec<Line>,
+    /// The number of scatter collections and conditional expressions that
+    /// could not be statically evaluated.
+    unresolved: usize,
+}
+
+impl Planner {
+    /// Statically evaluates an expression.
+    ///
+    /// Returns `None` if the expression can't be evaluated without running the
+    /// workflow.
+    fn evaluate(&self, expr: &Expr, env: &Env) -> Option<Known> {
+        match expr {
+            Expr::Literal(LiteralExpr::Boolean(b)) => Some(Known::Boolean(b.value())),
+            Expr::Literal(LiteralExpr::Integer(i)) => i.value().map(Known::Integer),
+            Expr::Literal(LiteralExpr::Array(a)) => Some(Known::Array(a.elements().count())),
+            Expr::NameRef(r) => env.get(r.name().text()).cloned(),
+            Expr::Parenthesized(e) => self.evaluate(&e.expr(), env),
+            Expr::LogicalNot(e) => match self.evaluate(&e.operand(), env)? {
+                Known::Boolean(b) => Some(Known::Boolean(!b)),
+                _ => None,
+            },
+            Expr::Call(call) => {
+                let args = call
+                    .arguments()
+                    .map(|a| self.evaluate(&a, env))
+                    .collect::<Vec<_>>();
+                match (call.target().text(), args.as_slice()) {
+                    ("read_lines", [Some(Known::Path(path))]) => {
+                        Some(Known::Array(fs::read_to_string(path).ok()?.lines().count()))
+                    }
+                    ("read_tsv", [Some(Known::Path(path)), rest @ ..]) => {
+                        let lines = fs::read_to_string(path).ok()?.lines().count();
+                        match rest.first() {
+                            Some(Some(Known::Boolean(true))) => {
+                                Some(Known::Array(lines.saturating_sub(1)))
+                            }
+                            Some(Some(Known::Boolean(false))) | None => Some(Known::Array(lines)),
+                            _ => None,
+                        }
+                    }
+                    ("range", [Some(Known::Integer(n))]) => {
+                        Some(Known::Array(usize::try_from(*n).ok()?))
+                    }
+                    ("length", [Some(Known::Array(n))]) => {
+                        Some(Known::Integer(i64::try_from(*n).ok()?))
+                    }
+                    ("zip", [Some(Known::Array(a)), Some(Known::Array(b))]) => {
+                        Some(Known::Array(*a.min(b)))
+                    }
+                    ("cross", [Some(Known::Array(a)), Some(Known::Array(b))]) => {
+                        Some(Known::Array(a.checked_mul(*b)?))
+                    }
+                    ("prefix" | "suffix", [_, Some(Known::Array(n))])
+                    | ("quote" | "squote", [Some(Known::Array(n))]) => Some(Known::Array(*n)),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Plans the statements of a workflow body.
+    ///
+    /// Statements that depend on one another run one after the other, while
+    /// independent statements are assumed to run at the same time.
+    fn statements(
+        &mut self,
+        document: &Document,
+        statements: impl Iterator<Item = WorkflowStatement>,
+        env: &mut Env,
+        depth: usize,
+    ) -> Estimate {
+        // The stage of each name defined by the statements
+        let mut stages: HashMap<String, usize> = HashMap::new();
+        // The peak parallelism of each stage
+        let mut peaks: Vec<u64> = Vec::new();
+        let mut tasks = 0;
+
+        for statement in statements {
+            let stage = references(&statement)
+                .into_iter()
+                .filter_map(|name| stages.get(&name).map(|s| s + 1))
+                .max()
+                .unwrap_or(0);
+
+            let estimate = self.statement(document, &statement, env, depth);
+            tasks += estimate.tasks;
+            if peaks.len() <= stage {
+                peaks.resize(stage + 1, 0);
+            }
+            peaks[stage] += estimate.peak;
+
+            for name in definitions(&statement) {
+                stages.insert(name, stage);
+            }
+        }
+
+        Estimate {
+            tasks,
+            peak: peaks.into_iter().max().unwrap_or(0),
+        }
+    }
+
+    /// Plans a single workflow statement.
+    fn statement(
+        &mut self,
+        document: &Document,
+        statement: &WorkflowStatement,
+        env: &mut Env,
+        depth: usize,
+    ) -> Estimate {
+        match statement {
+            WorkflowStatement::Declaration(decl) => {
+                if let Some(value) = self.evaluate(&decl.expr(), env) {
+                    env.insert(decl.name().text().to_string(), value);
+                }
+
+                Estimate::default()
+            }
+            WorkflowStatement::Call(call) => {
+                let names = call.target().names().collect::<Vec<_>>();
+                let (callee, name) = match names.as_slice() {
+                    [ns, name] => (
+                        document.namespace(ns.text()).map(|ns| ns.document()),
+                        name.text(),
+                    ),
+                    [name] => (Some(document), name.text()),
+                    _ => (None, ""),
+                };
+
+                let Some(workflow) = callee.and_then(|d| workflow_definition(d, name)) else {
+                    // The call is to a task
+                    return Estimate { tasks: 1, peak: 1 };
+                };
+
+                // Bind the statically known inputs of the call to the workflow
+                let mut callee_env = Env::new();
+                for input in call.inputs() {
+                    let value = match input.expr() {
+                        Some(expr) => self.evaluate(&expr, env),
+                        None => env.get(input.name().text()).cloned(),
+                    };
+
+                    if let Some(value) = value {
+                        callee_env.insert(input.name().text().to_string(), value);
+                    }
+                }
+
+                self.lines.push(Line {
+                    depth,
+                    text: format!("call to workflow `{name}`"),
+                });
+                self.workflow(
+                    callee.expect("should have document"),
+                    &workflow,
+                    &mut callee_env,
+                    depth + 1,
+                )
+            }
+            WorkflowStatement::Scatter(scatter) => {
+                let width = match self.evaluate(&scatter.expr(), env) {
+                    Some(Known::Array(n)) => Some(n as u64),
+                    _ => None,
+                };
+
+                self.lines.push(Line {
+                    depth,
+                    text: format!(
+                        "scatter ({variable} in {expr}): {width}",
+                        variable = scatter.variable().text(),
+                        expr = scatter.expr().text(),
+                        width = match width {
+                            Some(1) => "1 iteration".to_string(),
+                            Some(n) => format!("{n} iterations"),
+                            None => "unknown iterations (assuming 1)".to_string(),
+                        }
+                    ),
+                });
+
+                if width.is_none() {
+                    self.unresolved += 1;
+                }
+
+                let mut scope = env.clone();
+                scope.remove(scatter.variable().text());
+                let body = self.statements(document, scatter.statements(), &mut scope, depth + 1);
+                let width = width.unwrap_or(1);
+                Estimate {
+                    tasks: body.tasks.saturating_mul(width),
+                    peak: body.peak.saturating_mul(width),
+                }
+            }
+            WorkflowStatement::Conditional(conditional) => {
+                let mut estimate = Estimate::default();
+                for clause in conditional.clauses() {
+                    let keyword = match clause.kind() {
+                        ConditionalStatementClauseKind::If => "if",
+                        ConditionalStatementClauseKind::ElseIf => "else if",
+                        ConditionalStatementClauseKind::Else => "else",
+                    };
+                    let condition = clause.expr().map(|e| (self.evaluate(&e, env), e));
+                    let text = match &condition {
+                        Some((Some(Known::Boolean(true)), e)) => {
+                            format!("{keyword} ({e}): true", e = e.text())
+                        }
+                        Some((Some(Known::Boolean(false)), e)) => {
+                            format!("{keyword} ({e}): false", e = e.text())
+                        }
+                        Some((_, e)) => {
+                            self.unresolved += 1;
+                            format!("{keyword} ({e}): unknown (assuming true)", e = e.text())
+                        }
+                        None => keyword.to_string(),
+                    };
+
+                    self.lines.push(Line { depth, text });
+                    let mut scope = env.clone();
+                    let body =
+                        self.statements(document, clause.statements(), &mut scope, depth + 1);
+                    let taken = !matches!(condition, Some((Some(Known::Boolean(false)), _)));
+                    if taken {
+                        // Only one clause executes, so report the largest
+                        estimate.tasks = estimate.tasks.max(body.tasks);
+                        estimate.peak = estimate.peak.max(body.peak);
+                    }
+
+                    if matches!(condition, Some((Some(Known::Boolean(true)), _))) {
+                        break;
+                    }
+                }
+
+                estimate
+            }
+        }
+    }
+
+    /// Plans a workflow.
+    ///
+    /// The given environment contains the statically known inputs of the
+    /// workflow; the defaults of any other inputs are evaluated if possible.
+    fn workflow(
+        &mut self,
+        document: &Document,
+        workflow: &WorkflowDefinition,
+        env: &mut Env,
+        depth: usize,
+    ) -> Estimate {
+        if let Some(input) = workflow.input() {
+            for decl in input.declarations() {
+                let name = decl.name().text().to_string();
+                if env.contains_key(&name) {
+                    continue;
+                }
+
+                if let Some(value) = decl.expr().and_then(|e| self.evaluate(&e, env)) {
+                    env.insert(name, value);
+                }
+            }
+        }
+
+        self.statements(document, workflow.statements(), env, depth)
+    }
+}
+
+/// Gets the names referenced by a workflow statement.
+fn references(statement: &WorkflowStatement) -> Vec<String> {
+    let mut names = statement
+        .inner()
+        .descendants()
+        .filter_map(NameRefExpr::cast)
+        .map(|r| r.name().text().to_string())
+        .collect::<Vec<_>>();
+
+    for node in statement.inner().descendants() {
+        if let Some(call) = CallStatement::cast(node) {
+            names.extend(
+                call.inputs()
+                    .filter(|i| i.expr().is_none())
+                    .map(|i| i.name().text().to_string()),
+            );
+            names.extend(call.after().map(|a| a.name().text().to_string()));
+        }
+    }
+
+    names
+}
+
+/// Gets the names defined by a workflow statement that are visible to the
+/// statements that follow it.
+fn definitions(statement: &WorkflowStatement) -> Vec<String> {
+    match statement {
+        WorkflowStatement::Declaration(decl) => vec![decl.name().text().to_string()],
+        WorkflowStatement::Call(call) => call
+            .alias()
+            .map(|a| a.name())
+            .or_else(|| call.target().names().last())
+            .map(|n| vec![n.text().to_string()])
+            .unwrap_or_default(),
+        WorkflowStatement::Scatter(scatter) => {
+            scatter.statements().flat_map(|s| definitions(&s)).collect()
+        }
+        WorkflowStatement::Conditional(conditional) => conditional
+            .clauses()
+            .flat_map(|c| c.statements().collect::<Vec<_>>())
+            .flat_map(|s| definitions(&s))
+            .collect(),
+    }
+}
+
+/// Finds the definition of the workflow with the given name in a document.
+fn workflow_definition(document: &Document, name: &str) -> Option<WorkflowDefinition> {
+    document
+        .root()
+        .ast()
+        .into_v1()?
+        .workflows()
+        .find(|w| w.name().text() == name)
+}
+
+/// Converts an input value to a statically known value.
+///
+/// Relative paths are resolved against the input's origin path.
+fn known_input(name: &str, value: &Value, origins: &OriginPaths) -> Option<Known> {
+    if let Some(b) = value.as_boolean() {
+        return Some(Known::Boolean(b));
+    }
+
+    if let Some(i) = value.as_integer() {
+        return Some(Known::Integer(i));
+    }
+
+    if let Some(array) = value.as_array() {
+        return Some(Known::Array(array.len()));
+    }
+
+    let path = value
+        .as_file()
+        .map(|p| p.as_str())
+        .or_else(|| value.as_string().map(|s| s.as_str()))?;
+    let path = match origins.get(name) {
+        Some(origin) => origin.join(path).ok()?,
+        None => EvaluationPath::Local(PathBuf::from(path)),
+    };
+
+    path.as_local().map(|p| Known::Path(p.to_path_buf()))
+}
+
+/// The main function for the `plan` subcommand.
+pub async fn plan(args: Args) -> CommandResult<()> {
+    if let Source::Directory(_) = args.source {
+        return Err(anyhow!("directory sources are not supported for the `plan` command").into());
+    }
+
+    let results = Analysis::default()
+        .add_source(args.source.clone())
+        .run()
+        .await
+        .map_err(CommandError::from)?;
+
+    // SAFETY: this must exist, as we added it as the only source to be analyzed
+    // above.
+    let document = results.filter(&[&args.source]).next().unwrap().document();
+
+    let inputs = Invocation::coalesce(&args.inputs, args.entrypoint.clone())
+        .await
+        .with_context(|| {
+            format!(
+                "failed to parse inputs from `{sources}`",
+                sources = args.inputs.join("`, `")
+            )
+        })?
+        .into_engine_invocation(document)?;
+
+    let (name, inputs, origins) = match inputs {
+        Some(inputs) => inputs,
+        None => {
+            let name = args
+                .entrypoint
+                .or_else(|| document.workflow().map(|w| w.name().to_string()))
+                .context("the `--entrypoint` option is required if no inputs are provided")?;
+            let inputs = if document.task_by_name(&name).is_some() {
+                EngineInputs::Task(Default::default())
+            } else {
+                EngineInputs::Workflow(Default::default())
+            };
+
+            let origins = OriginPaths::Single(EvaluationPath::Local(
+                std::env::current_dir().context("failed to get current directory")?,
+            ));
+            (name, inputs, origins)
+        }
+    };
+
+    let mut planner = Planner::default();
+    let estimate = match inputs {
+        EngineInputs::Task(_) => {
+            println!("task `{name}`");
+            Estimate { tasks: 1, peak: 1 }
+        }
+        EngineInputs::Workflow(inputs) => {
+            let workflow = workflow_definition(document, &name).with_context(|| {
+                format!(
+                    "no task or workflow with name `{name}` was found in document `{path}`",
+                    path = document.path()
+                )
+            })?;
+
+            let mut env = inputs
+                .iter()
+                .filter_map(|(name, value)| {
+                    known_input(name, value, &origins).map(|v| (name.to_string(), v))
+                })
+                .collect();
+
+            println!("workflow `{name}`");
+            let estimate = planner.workflow(document, &workflow, &mut env, 1);
+            for line in &planner.lines {
+                println!("{line}");
+            }
+
+            estimate
+        }
+    };
+
+    println!();
+    println!("expected task executions: {tasks}", tasks = estimate.tasks);
+    println!("peak parallelism: {peak}", peak = estimate.peak);
+    if planner.unresolved > 0 {
+        println!(
+            "\nnote: {count} scatter collection{s} or condition{s} could not be statically \
+             evaluated; the estimates assume a single iteration for unknown scatters and that \
+             unknown conditions are true",
+            count = planner.unresolved,
+            s = if planner.unresolved == 1 { "" } else { "s" },
+        );
+    }
+
+    Ok(())
+}
@@ -17,6 +17,8 @@ use clap::Parser;
 use colored::Colorize as _;
 use crankshaft::events::Event as CrankshaftEvent;
 use futures::FutureExt as _;
+use futures::StreamExt as _;
+use futures::stream;
 use indexmap::IndexSet;
 use indicatif::ProgressStyle;
 use tokio::select;
@@ -25,16 +27,19 @@ use tokio::sync::broadcast::error::RecvError;
 use tracing::Level;
 use tracing::error;
 use tracing_indicatif::span_ext::IndicatifSpanExt as _;
+use wdl::analysis::Document;
 use wdl::ast::AstNode as _;
 use wdl::ast::Severity;
 use wdl::engine;
 use wdl::engine::CancellationContext;
 use wdl::engine::CancellationContextState;
+use wdl::engine::CoverageRegistry;
 use wdl::engine::EngineEvent;
 use wdl::engine::EvaluationError;
 use wdl::engine::Events;
 use wdl::engine::Inputs as EngineInputs;
 use wdl::engine::config::CallCachingMode;
+use wdl::engine::config::LogSinkConfig;
 use wdl::engine::config::SecretString;
 use wdl::engine::path::EvaluationPath;
 
@@ -42,11 +47,15 @@ use crate::analysis::Analysis;
 use crate::analysis::Source;
 use crate::commands::CommandError;
 use crate::commands::CommandResult;
+use crate::coverage;
+use crate::coverage::CoverageFormat;
+use crate::debugger::StepDebugger;
 use crate::diagnostics::Mode;
 use crate::diagnostics::emit_diagnostics;
 use crate::eval::Evaluator;
 use crate::inputs::Invocation;
 use crate::inputs::OriginPaths;
+use crate::inputs::batch::read_batch;
 
 /// The delay in showing the progress bar.
 ///
@@ -193,6 +202,92 @@ pub struct Args {
     #[clap(long)]
     pub no_call_cache: bool,
 
+    /// Keeps the outputs that exist when a task fails.
+    ///
+    /// The outputs of a failed task are written to `outputs.salvaged.json` in
+    /// the task's directory so that debugging artifacts aren't lost.
+    #[clap(long)]
+    pub salvage_outputs: bool,
+
+    /// Forwards the standard output and standard error of tasks to a sink as
+    /// they execute.
+    ///
+    /// A sink is either `stderr` (lines are prefixed with the task and
+    /// stream), `file:<PATH>` (lines are appended to the file), or an `http`
+    /// or `https` URL (lines are sent as JSON records, e.g. to a Fluentd HTTP
+    /// input). This option may be specified multiple times.
+    #[clap(long = "log-sink", value_name = "SINK")]
+    pub log_sinks: Vec<LogSinkConfig>,
+
+    /// Interactively steps through the calls of a workflow.
+    ///
+    /// Evaluation breaks before each call, allowing the call's evaluated
+    /// inputs to be inspected and the call to be skipped with stubbed
+    /// outputs read from a JSON file.
+    #[clap(long)]
+    pub step: bool,
+
+    /// Writes an evaluation coverage report to the given file.
+    ///
+    /// The report describes which tasks, calls, scatter statements, and
+    /// conditional clauses were evaluated.
+    #[clap(long, value_name = "FILE")]
+    pub coverage: Option<PathBuf>,
+
+    /// The format of the coverage report.
+    #[clap(
+        long,
+        value_name = "FORMAT",
+        default_value_t,
+        value_enum,
+        requires = "coverage"
+    )]
+    pub coverage_format: CoverageFormat,
+
+    /// Runs the task or workflow once for each row of a sample sheet.
+    ///
+    /// The file contains tab-separated values; the header row names the input
+    /// of each column and each following row is a run. An `id` column names
+    /// each run, and values may reference other columns of the same row with
+    /// `${column}`. A file with a `.json` extension instead contains an array
+    /// of inputs objects. Inputs given on the command line are shared by
+    /// every run; unless `--entrypoint` is given, the task or workflow to run
+    /// is derived from their prefix.
+    #[clap(long, value_name = "FILE", conflicts_with_all = ["step", "coverage"])]
+    pub inputs_batch: Option<PathBuf>,
+
+    /// The maximum number of runs of an inputs batch to evaluate at a time.
+    #[clap(
+        long,
+        value_name = "COUNT",
+        default_value_t = 1,
+        requires = "inputs_batch"
+    )]
+    pub batch_concurrency: usize,
+
+    /// Requires every `File` and `Directory` input to be a remote URL or a
+    /// local path within an allowed root.
+    ///
+    /// Inputs that are local paths outside of the allowed roots are reported
+    /// as errors before any task is executed.
+    #[clap(long)]
+    pub hermetic: bool,
+
+    /// A local directory that inputs may be within when running hermetically.
+    ///
+    /// This option may be specified multiple times and adds to the allowed
+    /// roots of the configuration.
+    #[clap(long = "allow-root", value_name = "DIR", requires = "hermetic")]
+    pub allowed_roots: Vec<PathBuf>,
+
+    /// Runs a workflow only up to the call with the given name.
+    ///
+    /// Only the calls that the named call depends on are executed; everything
+    /// downstream of it is skipped. The outputs of the named call are printed
+    /// instead of the workflow's outputs.
+    #[clap(long, value_name = "CALL", conflicts_with = "inputs_batch")]
+    pub until: Option<String>,
+
     /// The engine configuration to use.
     ///
     /// This is not exposed via [`clap`] and is not settable by users.
@@ -262,6 +357,27 @@ impl Args {
             self.engine.task.cache = CallCachingMode::Off;
         }
 
+        // Enable output salvaging if requested
+        if self.salvage_outputs {
+            self.engine.task.salvage_outputs = true;
+        }
+
+        // Enable hermetic evaluation if requested
+        if self.hermetic {
+            self.engine.hermetic.enabled = true;
+        }
+
+        self.engine
+            .hermetic
+            .allowed_roots
+            .extend(self.allowed_roots.iter().cloned());
+
+        // Add the log sinks for this run
+        self.engine
+            .task
+            .log_sinks
+            .extend(self.log_sinks.iter().cloned());
+
         self
     }
 }
@@ -307,6 +423,8 @@ struct State {
     completed: usize,
     /// The number of task results reused from the cache.
     cached: usize,
+    /// The number of tasks that exceeded their maximum execution time.
+    timed_out: usize,
 }
 
 /// Displays evaluation progress.
@@ -339,6 +457,7 @@ async fn progress(
         append(&mut message, state.completed, "completed".green());
         append(&mut message, state.cached, "cached".green());
         append(&mut message, state.failed, "failed".red());
+        append(&mut message, state.timed_out, "timed out".red());
         append(
             &mut message,
             state.tasks.len() - state.executing.len(),
@@ -408,6 +527,9 @@ async fn progress(
                         EngineEvent::ReusedCachedExecutionResult { .. } => {
                             state.cached += 1;
                         }
+                        EngineEvent::TaskTimedOut { .. } => {
+                            state.timed_out += 1;
+                        }
                     };
 
                     pb.pb_set_message(&message(&state));
@@ -472,6 +594,69 @@ pub fn setup_run_dir(root: &Path, entrypoint: &str) -> Result<PathBuf> {
     Ok(output)
 }
 
+/// Prepares the execution directory of a run.
+///
+/// If an output directory was supplied, it is used as the execution directory
+/// after removing it if `overwrite` is `true`; otherwise, a timestamped
+/// execution directory is determined with [`setup_run_dir`].
+fn prepare_output_dir(
+    output: Option<PathBuf>,
+    overwrite: bool,
+    runs_dir: Option<PathBuf>,
+    entrypoint: &str,
+) -> Result<PathBuf> {
+    let Some(supplied_dir) = output else {
+        return setup_run_dir(&runs_dir.unwrap_or(DEFAULT_RUNS_DIR.into()), entrypoint);
+    };
+
+    if supplied_dir.exists() {
+        if !overwrite {
+            bail!(
+                "output directory `{dir}` exists; use the `--overwrite` option to overwrite its \
+                 contents",
+                dir = supplied_dir.display()
+            );
+        }
+
+        std::fs::remove_dir_all(&supplied_dir).with_context(|| {
+            format!(
+                "failed to remove output directory `{dir}`",
+                dir = supplied_dir.display()
+            )
+        })?;
+    }
+
+    Ok(supplied_dir)
+}
+
+/// Handles an interrupt (i.e. Ctrl-C) of evaluation.
+///
+/// Returns an error if evaluation should be immediately terminated.
+fn interrupt(cancellation: &CancellationContext) -> Result<()> {
+    // If we've already been waiting for executing tasks to cancel, immediately bail
+    // out
+    if cancellation.state() == CancellationContextState::Canceling {
+        bail!("evaluation was interrupted");
+    }
+
+    // Log the message indicating whether we're waiting on completion or waiting on
+    // cancellation
+    match cancellation.cancel() {
+        CancellationContextState::NotCanceled => unreachable!("should be canceled"),
+        CancellationContextState::Waiting => {
+            error!("waiting for executing tasks to complete: use Ctrl-C to cancel executing tasks");
+        }
+        CancellationContextState::Canceling => {
+            error!(
+                "waiting for executing tasks to cancel: use Ctrl-C to immediately terminate \
+                 Sprocket"
+            );
+        }
+    }
+
+    Ok(())
+}
+
 /// The main function for the `run` subcommand.
 pub async fn run(args: Args) -> CommandResult<()> {
     if let Source::Directory(_) = args.source {
@@ -552,6 +737,56 @@ pub async fn run(args: Args) -> CommandResult<()> {
 
     let document = results.filter(&[&args.source]).next().unwrap().document();
 
+    if args.inputs_batch.is_some() {
+        return run_batch(args, document, span).await;
+    }
+
+    let coverage = args
+        .coverage
+        .clone()
+        .map(|path| (path, Arc::new(CoverageRegistry::default())));
+    let format = args.coverage_format;
+    let result = run_document(
+        args,
+        document,
+        span,
+        coverage.as_ref().map(|(_, c)| c.clone()),
+    )
+    .await;
+
+    // The coverage report is written on every exit path, even if the run failed
+    // or was terminated; a failure to write it does not replace the run's error
+    if let Some((path, coverage)) = coverage {
+        let written = coverage::render(&coverage, document, format).and_then(|report| {
+            std::fs::write(&path, report).with_context(|| {
+                format!(
+                    "failed to write coverage report `{path}`",
+                    path = path.display()
+                )
+            })
+        });
+        if let Err(e) = written {
+            if result.is_ok() {
+                return Err(e.into());
+            }
+
+            tracing::error!("{e:#}");
+        }
+    }
+
+    result
+}
+
+/// Runs a task or workflow of an analyzed document once.
+///
+/// If a coverage registry is given, the evaluated elements of the document are
+/// recorded in it.
+async fn run_document(
+    args: Args,
+    document: &Document,
+    span: tracing::Span,
+    coverage: Option<Arc<CoverageRegistry>>,
+) -> CommandResult<()> {
     let inputs = Invocation::coalesce(&args.inputs, args.entrypoint.clone())
         .await
         .with_context(|| {
@@ -591,31 +826,7 @@ pub async fn run(args: Args) -> CommandResult<()> {
         }
     };
 
-    let output_dir = if let Some(supplied_dir) = args.output {
-        if supplied_dir.exists() {
-            if !args.overwrite {
-                return Err(anyhow!(
-                    "output directory `{dir}` exists; use the `--overwrite` option to overwrite \
-                     its contents",
-                    dir = supplied_dir.display()
-                )
-                .into());
-            }
-
-            std::fs::remove_dir_all(&supplied_dir).with_context(|| {
-                format!(
-                    "failed to remove output directory `{dir}`",
-                    dir = supplied_dir.display()
-                )
-            })?;
-        }
-        supplied_dir
-    } else {
-        setup_run_dir(
-            &args.runs_dir.unwrap_or(DEFAULT_RUNS_DIR.into()),
-            &entrypoint,
-        )?
-    };
+    let output_dir = prepare_output_dir(args.output, args.overwrite, args.runs_dir, &entrypoint)?;
 
     tracing::info!(
         "`{dir}` will be used as the execution directory",
@@ -655,7 +866,7 @@ pub async fn run(args: Args) -> CommandResult<()> {
         span,
     ));
 
-    let evaluator = Evaluator::new(
+    let mut evaluator = Evaluator::new(
         document,
         &entrypoint,
         inputs,
@@ -663,6 +874,17 @@ pub async fn run(args: Args) -> CommandResult<()> {
         args.engine,
         &output_dir,
     );
+    if args.step {
+        evaluator = evaluator.with_call_interceptor(Arc::new(StepDebugger::default()));
+    }
+
+    if let Some(call) = &args.until {
+        evaluator = evaluator.with_until(call);
+    }
+
+    if let Some(coverage) = coverage {
+        evaluator = evaluator.with_coverage(coverage);
+    }
 
     let mut evaluate = evaluator.run(cancellation.clone(), events).boxed();
 
@@ -671,30 +893,18 @@ pub async fn run(args: Args) -> CommandResult<()> {
             // Always prefer the CTRL-C signal to the evaluation returning.
             biased;
 
-            _ = tokio::signal::ctrl_c() => {
-                // If we've already been waiting for executing tasks to cancel, immediately bail out
-                if cancellation.state() == CancellationContextState::Canceling {
-                    return Err(anyhow!("evaluation was interrupted").into());
-                }
-
-                // Log the message indicating whether we're waiting on completion or waiting on cancellation
-                match cancellation.cancel() {
-                    CancellationContextState::NotCanceled => unreachable!("should be canceled"),
-                    CancellationContextState::Waiting => {
-                        error!("waiting for executing tasks to complete: use Ctrl-C to cancel executing tasks");
-                    },
-                    CancellationContextState::Canceling => {
-                        error!("waiting for executing tasks to cancel: use Ctrl-C to immediately terminate Sprocket");
-                    },
-                }
-            },
+            _ = tokio::signal::ctrl_c() => interrupt(&cancellation)?,
             res = &mut evaluate => {
                 let _ = transfer_progress.await;
                 let _ = crankshaft_progress.await;
 
                 return match res {
                     Ok(outputs) => {
-                        println!("{}", serde_json::to_string_pretty(&outputs.with_name(&entrypoint)).context("failed to serialize outputs")?);
+                        let name = match &args.until {
+                            Some(call) => format!("{entrypoint}.{call}"),
+                            None => entrypoint.clone(),
+                        };
+                        println!("{}", serde_json::to_string_pretty(&outputs.with_name(name)).context("failed to serialize outputs")?);
                         Ok(())
                     }
                     Err(EvaluationError::Canceled) => Err(anyhow!("evaluation was interrupted").into()),
@@ -715,3 +925,225 @@ pub async fn run(args: Args) -> CommandResult<()> {
         }
     }
 }
+
+/// Represents the status of a run of an inputs batch.
+enum BatchStatus {
+    /// The run succeeded.
+    Succeeded,
+    /// The run failed.
+    Failed,
+    /// The run was interrupted.
+    Interrupted,
+}
+
+impl std::fmt::Display for BatchStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Succeeded => write!(f, "succeeded"),
+            Self::Failed => write!(f, "failed"),
+            Self::Interrupted => write!(f, "interrupted"),
+        }
+    }
+}
+
+/// Runs a task or workflow once for each run of an inputs batch.
+///
+/// The runs share the same call cache and their progress is aggregated. A
+/// summary of the status of each run is printed once all runs complete.
+async fn run_batch(args: Args, document: &Document, span: tracing::Span) -> CommandResult<()> {
+    let path = args
+        .inputs_batch
+        .as_deref()
+        .expect("should have inputs batch");
+    let batch = read_batch(path).map_err(anyhow::Error::from)?;
+    if batch.is_empty() {
+        return Err(anyhow!(
+            "inputs batch `{path}` does not contain any runs",
+            path = path.display()
+        )
+        .into());
+    }
+
+    // Resolve the inputs of every run before any run begins
+    let mut runs = Vec::with_capacity(batch.len());
+    for run in &batch {
+        let mut invocation = Invocation::coalesce(&args.inputs, args.entrypoint.clone())
+            .await
+            .with_context(|| {
+                format!(
+                    "failed to parse inputs from `{sources}`",
+                    sources = args.inputs.join("`, `")
+                )
+            })?;
+
+        // As with a single run, the entrypoint may be derived from the other inputs
+        let entrypoint = invocation.entrypoint();
+        let batch_inputs = run
+            .to_inputs(document, entrypoint.as_deref())
+            .with_context(|| {
+                format!("failed to parse inputs for batch run `{id}`", id = run.id())
+            })?;
+        invocation.inputs_mut().extend(batch_inputs);
+
+        let inputs = invocation
+            .into_engine_invocation(document)
+            .with_context(|| format!("invalid inputs for batch run `{id}`", id = run.id()))?;
+
+        let Some((entrypoint, inputs, origins)) = inputs else {
+            return Err(anyhow!(
+                "batch run `{id}` has no inputs: use the `--entrypoint` option to specify the \
+                 task or workflow to run",
+                id = run.id()
+            )
+            .into());
+        };
+
+        runs.push((run.id(), entrypoint, inputs, origins));
+    }
+
+    let entrypoint = runs[0].1.clone();
+    if let Some((id, ..)) = runs.iter().find(|(_, e, ..)| *e != entrypoint) {
+        return Err(anyhow!(
+            "batch run `{id}` runs a different task or workflow than the other runs of the batch"
+        )
+        .into());
+    }
+
+    let root = prepare_output_dir(args.output, args.overwrite, args.runs_dir, &entrypoint)?;
+    tracing::info!(
+        "`{dir}` will be used as the root execution directory of the batch",
+        dir = root.display()
+    );
+
+    let run_kind = match &runs[0].2 {
+        EngineInputs::Task(_) => "task",
+        EngineInputs::Workflow(_) => "workflow",
+    };
+
+    span.pb_set_style(
+        &ProgressStyle::with_template(&format!(
+            "[{{elapsed_precise:.cyan/blue}}] {{spinner:.cyan/blue}} {running} {count} runs of \
+             {run_kind} {name}{{msg}}",
+            running = "running".cyan(),
+            count = runs.len(),
+            name = entrypoint.magenta().bold()
+        ))
+        .unwrap(),
+    );
+
+    let cancellation = CancellationContext::new(args.engine.failure_mode);
+    let events = Events::new(EVENTS_CHANNEL_CAPACITY);
+    let transfer_progress = tokio::spawn(cloud_copy::cli::handle_events(
+        events
+            .subscribe_transfer()
+            .expect("should have transfer events"),
+        cancellation.token(),
+    ));
+    let crankshaft_progress = tokio::spawn(progress(
+        events
+            .subscribe_crankshaft()
+            .expect("should have Crankshaft events"),
+        events
+            .subscribe_engine()
+            .expect("should have engine events"),
+        span,
+    ));
+
+    let evaluations = stream::iter(runs)
+        .map(|(id, entrypoint, inputs, origins)| {
+            let output_dir = root.join(id);
+            let config = args.engine.clone();
+            let cancellation = cancellation.clone();
+            let events = events.clone();
+            let report_mode = args.report_mode.unwrap_or_default();
+            let no_color = args.no_color;
+            async move {
+                let result =
+                    Evaluator::new(document, &entrypoint, inputs, origins, config, &output_dir)
+                        .run(cancellation, events)
+                        .await;
+
+                let status = match result {
+                    Ok(_) => BatchStatus::Succeeded,
+                    Err(EvaluationError::Canceled) => BatchStatus::Interrupted,
+                    Err(EvaluationError::Source(e)) => {
+                        if let Err(e) = emit_diagnostics(
+                            &e.document.path(),
+                            e.document.root().text().to_string(),
+                            &[e.diagnostic],
+                            &e.backtrace,
+                            report_mode,
+                            no_color,
+                        ) {
+                            error!("failed to emit diagnostics for batch run `{id}`: {e:#}");
+                        }
+
+                        BatchStatus::Failed
+                    }
+                    Err(EvaluationError::Other(e)) => {
+                        error!("batch run `{id}` failed: {e:#}");
+                        BatchStatus::Failed
+                    }
+                };
+
+                (id, status, output_dir)
+            }
+        })
+        .buffer_unordered(args.batch_concurrency.max(1))
+        .collect::<Vec<_>>();
+
+    let mut statuses = {
+        tokio::pin!(evaluations);
+        loop {
+            select! {
+                // Always prefer the CTRL-C signal to the evaluation returning.
+                biased;
+
+                _ = tokio::signal::ctrl_c() => interrupt(&cancellation)?,
+                statuses = &mut evaluations => break statuses,
+            }
+        }
+    };
+
+    // Drop the events so that the progress tasks complete
+    drop(events);
+    let _ = transfer_progress.await;
+    let _ = crankshaft_progress.await;
+
+    // Report the runs in the order of the batch
+    statuses.sort_by_key(|(id, ..)| batch.iter().position(|r| r.id() == *id));
+
+    let width = statuses
+        .iter()
+        .map(|(id, ..)| id.len())
+        .max()
+        .unwrap_or_default()
+        .max("run".len());
+    println!(
+        "{run:width$}  {status:11}  directory",
+        run = "run",
+        status = "status"
+    );
+    for (id, status, dir) in &statuses {
+        println!(
+            "{id:width$}  {status:11}  {dir}",
+            status = status.to_string(),
+            dir = dir.display()
+        );
+    }
+
+    let failed = statuses
+        .iter()
+        .filter(|(_, s, _)| !matches!(s, BatchStatus::Succeeded))
+        .count();
+    if failed > 0 {
+        return Err(anyhow!(
+            "{failed} of {total} batch run{s} did not succeed",
+            total = statuses.len(),
+            s = if statuses.len() == 1 { "" } else { "s" }
+        )
+        .into());
+    }
+
+    Ok(())
+}
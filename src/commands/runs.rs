@@ -0,0 +1,343 @@
+//! Implementation of the `runs` subcommand.
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::anyhow;
+use bytesize::ByteSize;
+use clap::Parser;
+use clap::Subcommand;
+use wdl::engine::PROVENANCE_FILE;
+use wdl::engine::Provenance;
+use wdl::engine::ProvenanceManifest;
+use wdl::engine::SIZES_FILE;
+use wdl::engine::SizesReport;
+
+use crate::commands::CommandResult;
+use crate::commands::stats::StatsFormat;
+use crate::cromwell::find_run;
+
+/// Arguments for the `runs` subcommand.
+#[derive(Parser, Debug)]
+pub struct Args {
+    /// Subcommand for the `runs` command.
+    #[command(subcommand)]
+    command: RunsSubcommand,
+}
+
+impl Args {
+    /// Applies the configuration to the arguments.
+    pub fn apply(mut self, config: crate::config::Config) -> Self {
+        match &mut self.command {
+            RunsSubcommand::Stats(args) => {
+                if args.runs_dir.is_none() {
+                    args.runs_dir = Some(config.run.runs_dir);
+                }
+            }
+            RunsSubcommand::Whence(args) => {
+                if args.runs_dir.is_none() {
+                    args.runs_dir = Some(config.run.runs_dir);
+                }
+            }
+        }
+
+        self
+    }
+}
+
+/// Subcommands for the `runs` command.
+#[derive(Subcommand, Debug)]
+pub enum RunsSubcommand {
+    /// Reports the total size of the inputs localized for and the outputs
+    /// delocalized from each call of a run.
+    ///
+    /// Calls are listed from the largest inputs to the smallest, making it
+    /// easy to spot calls that were unintentionally given large inputs.
+    Stats(StatsArgs),
+
+    /// Traces an output file of a run back to the task execution that
+    /// produced it.
+    ///
+    /// This reports the call and attempt that produced the file, the container
+    /// image the task was executed in, and the hash of the task's evaluated
+    /// command.
+    Whence(WhenceArgs),
+}
+
+/// Arguments for the `runs stats` subcommand.
+#[derive(Parser, Debug)]
+pub struct StatsArgs {
+    /// The run to inspect.
+    ///
+    /// This is either the path to a run's execution directory or the name of
+    /// a run beneath the runs directory (e.g. `<timestamp>`,
+    /// `<entrypoint>/<timestamp>`, or `<entrypoint>/_latest`).
+    #[clap(value_name = "RUN")]
+    pub run: String,
+
+    /// The format of the report.
+    #[arg(long, value_name = "FORMAT", default_value_t)]
+    pub format: StatsFormat,
+
+    /// The root "runs" directory; defaults to `./runs/`.
+    #[clap(short, long, value_name = "ROOT_DIR")]
+    pub runs_dir: Option<PathBuf>,
+}
+
+/// Arguments for the `runs whence` subcommand.
+#[derive(Parser, Debug)]
+pub struct WhenceArgs {
+    /// The run to inspect.
+    ///
+    /// This is either the path to a run's execution directory or the name of
+    /// a run beneath the runs directory (e.g. `<timestamp>`,
+    /// `<entrypoint>/<timestamp>`, or `<entrypoint>/_latest`).
+    #[clap(value_name = "RUN")]
+    pub run: String,
+
+    /// The output file (or a file within an output directory) to trace.
+    #[clap(value_name = "OUTPUT")]
+    pub output: PathBuf,
+
+    /// The root "runs" directory; defaults to `./runs/`.
+    #[clap(short, long, value_name = "ROOT_DIR")]
+    pub runs_dir: Option<PathBuf>,
+}
+
+/// Resolves the execution directory of a run.
+fn resolve_run(runs_dir: &Path, run: &str) -> Result<PathBuf> {
+    let path = Path::new(run);
+    if path.is_dir() {
+        return Ok(path.to_path_buf());
+    }
+
+    find_run(runs_dir, run)
+}
+
+/// Finds how an output file of a run was produced.
+///
+/// The output is matched against the manifest both as given and as a
+/// canonical path, so that paths through symbolic links (e.g. `_latest`) and
+/// paths relative to the run's execution directory are traced.
+fn whence<'a>(
+    manifest: &'a ProvenanceManifest,
+    run_dir: &Path,
+    output: &Path,
+) -> Option<(String, &'a Provenance)> {
+    if let Some((path, provenance)) = manifest.get(&output.to_string_lossy()) {
+        return Some((path.to_string(), provenance));
+    }
+
+    let output = if output.is_relative() && !output.exists() {
+        run_dir.join(output)
+    } else {
+        output.to_path_buf()
+    };
+
+    let output = output.canonicalize().ok()?;
+    manifest
+        .iter()
+        .filter_map(|(path, provenance)| {
+            let canonical = Path::new(path).canonicalize().ok()?;
+            output.starts_with(&canonical).then_some((
+                canonical.as_os_str().len(),
+                path,
+                provenance,
+            ))
+        })
+        .max_by_key(|(len, ..)| *len)
+        .map(|(_, path, provenance)| (path.to_string(), provenance))
+}
+
+/// Renders how an output file was produced as text.
+fn to_text(path: &str, provenance: &Provenance) -> String {
+    let mut report = String::new();
+    let _ = writeln!(
+        report,
+        "`{path}` was produced by call `{call}` (task `{task}`)",
+        call = provenance.call(),
+        task = provenance.task()
+    );
+    let _ = writeln!(report, "  attempt: {}", provenance.attempt());
+    let _ = writeln!(
+        report,
+        "  cached: {}",
+        if provenance.cached() { "yes" } else { "no" }
+    );
+    let _ = writeln!(
+        report,
+        "  container: {}",
+        provenance.container().unwrap_or("none")
+    );
+    if provenance.container().is_some() {
+        let _ = writeln!(
+            report,
+            "  digest: {}",
+            provenance
+                .digest()
+                .unwrap_or("unknown (the digest of the image could not be resolved)")
+        );
+    }
+
+    let _ = writeln!(report, "  command hash: {}", provenance.command_hash());
+    let _ = writeln!(report, "  directory: {}", provenance.directory());
+    report
+}
+
+/// Renders the sizes report of a run as text.
+fn sizes_to_text(report: &SizesReport) -> String {
+    let mut text = String::new();
+    let _ = writeln!(
+        text,
+        "localized {localized} and delocalized {delocalized} across {count} call{s}",
+        localized = ByteSize::b(report.localized()),
+        delocalized = ByteSize::b(report.delocalized()),
+        count = report.calls().len(),
+        s = if report.calls().len() == 1 { "" } else { "s" }
+    );
+
+    let mut calls: Vec<_> = report.calls().iter().collect();
+    calls.sort_by(|a, b| b.localized().cmp(&a.localized()));
+    for call in calls {
+        let _ = writeln!(
+            text,
+            "  {call}: localized {localized}, delocalized {delocalized}",
+            call = call.call(),
+            localized = ByteSize::b(call.localized()),
+            delocalized = ByteSize::b(call.delocalized())
+        );
+    }
+
+    text
+}
+
+/// Performs the `runs stats` subcommand.
+fn runs_stats(args: StatsArgs) -> CommandResult<()> {
+    let runs_dir = args
+        .runs_dir
+        .unwrap_or_else(|| crate::commands::run::DEFAULT_RUNS_DIR.into());
+    let run_dir = resolve_run(&runs_dir, &args.run)?;
+
+    let path = run_dir.join(SIZES_FILE);
+    if !path.is_file() {
+        return Err(anyhow!(
+            "run `{run}` has no sizes report: the run may not have completed or was run by an \
+             older version of Sprocket",
+            run = args.run
+        )
+        .into());
+    }
+
+    let report = SizesReport::read(&path)?;
+    match args.format {
+        StatsFormat::Text => print!("{}", sizes_to_text(&report)),
+        StatsFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&report).context("failed to serialize sizes report")?
+        ),
+    }
+
+    Ok(())
+}
+
+/// Performs the `runs whence` subcommand.
+fn runs_whence(args: WhenceArgs) -> CommandResult<()> {
+    let runs_dir = args
+        .runs_dir
+        .unwrap_or_else(|| crate::commands::run::DEFAULT_RUNS_DIR.into());
+    let run_dir = resolve_run(&runs_dir, &args.run)?;
+
+    let path = run_dir.join(PROVENANCE_FILE);
+    if !path.is_file() {
+        return Err(anyhow!(
+            "run `{run}` has no provenance manifest: the run may not have completed or was run by \
+             an older version of Sprocket",
+            run = args.run
+        )
+        .into());
+    }
+
+    let manifest = ProvenanceManifest::read(&path)?;
+    let Some((path, provenance)) = whence(&manifest, &run_dir, &args.output) else {
+        return Err(anyhow!(
+            "`{output}` is not an output file of run `{run}` that was produced by a task",
+            output = args.output.display(),
+            run = args.run
+        )
+        .into());
+    };
+
+    print!("{}", to_text(&path, provenance));
+    Ok(())
+}
+
+/// Performs the `runs` subcommand.
+pub fn runs(args: Args) -> CommandResult<()> {
+    match args.command {
+        RunsSubcommand::Stats(args) => runs_stats(args),
+        RunsSubcommand::Whence(args) => runs_whence(args),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn run_resolution() {
+        let dir = TempDir::new().unwrap();
+        let runs_dir = dir.path();
+        fs::create_dir_all(runs_dir.join("foo").join("first")).unwrap();
+        fs::create_dir_all(runs_dir.join("foo").join("second")).unwrap();
+        fs::create_dir_all(runs_dir.join("bar").join("second")).unwrap();
+
+        assert_eq!(
+            resolve_run(runs_dir, "first").unwrap(),
+            runs_dir.join("foo").join("first")
+        );
+        assert_eq!(
+            resolve_run(runs_dir, "bar/second").unwrap(),
+            runs_dir.join("bar").join("second")
+        );
+        assert!(
+            resolve_run(runs_dir, "second")
+                .unwrap_err()
+                .to_string()
+                .starts_with("run `second` is ambiguous")
+        );
+        assert!(
+            resolve_run(runs_dir, "third")
+                .unwrap_err()
+                .to_string()
+                .starts_with("run `third` was not found")
+        );
+    }
+
+    #[test]
+    fn sizes_report() {
+        let report: SizesReport = serde_json::from_str(
+            r#"{
+                "localized": 3072,
+                "delocalized": 10,
+                "calls": [
+                    { "call": "a", "directory": "/a", "localized": 1024, "delocalized": 10 },
+                    { "call": "b", "directory": "/b", "localized": 2048, "delocalized": 0 }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            sizes_to_text(&report),
+            "localized 3.0 KiB and delocalized 10 B across 2 calls\n  b: localized 2.0 KiB, \
+             delocalized 0 B\n  a: localized 1.0 KiB, delocalized 10 B\n"
+        );
+    }
+}
@@ -0,0 +1,215 @@
+//! Implementation of the `server` subcommand.
+//!
+//! The server exposes a subset of Cromwell's REST API for the runs beneath a
+//! runs directory so that tools built against Cromwell (e.g. Cromshell) can
+//! inspect the runs of `sprocket run` during a migration.
+
+use std::net::SocketAddr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Context;
+use clap::Parser;
+use serde_json::json;
+use tokio::io::AsyncWriteExt as _;
+use tokio::net::TcpListener;
+use tracing::info;
+use tracing::warn;
+
+use crate::commands::CommandResult;
+use crate::cromwell;
+use crate::http;
+
+/// The prefix of the paths of Cromwell's workflow endpoints.
+const WORKFLOWS_PREFIX: &str = "/api/workflows/v1/";
+
+/// Arguments for the `server` subcommand.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+pub struct Args {
+    /// The address to listen on.
+    #[clap(long, value_name = "ADDR", default_value = "127.0.0.1:8000")]
+    pub listen: SocketAddr,
+
+    /// The root "runs" directory to serve the metadata of runs from; defaults
+    /// to the `run.runs_dir` configuration value.
+    #[clap(short, long, value_name = "ROOT_DIR")]
+    pub runs_dir: Option<PathBuf>,
+}
+
+impl Args {
+    /// Applies the configuration to the arguments.
+    pub fn apply(mut self, config: crate::config::Config) -> Self {
+        if self.runs_dir.is_none() {
+            self.runs_dir = Some(config.run.runs_dir);
+        }
+
+        self
+    }
+}
+
+/// Creates the response code and body of a request for the
+/// Cromwell-compatible metadata or status of a run.
+///
+/// Errors are reported in the shape of Cromwell's error responses.
+fn respond_workflow(runs_dir: &Path, id: &str, endpoint: &str) -> (&'static str, String) {
+    let fail = |code, message: String| {
+        (
+            code,
+            json!({ "status": "fail", "message": message }).to_string(),
+        )
+    };
+
+    // The id of a run beneath an entrypoint's directory contains an encoded `/`
+    let id = match urlencoding::decode(id) {
+        Ok(id) if cromwell::is_valid_id(&id) => id,
+        _ => return fail("400 Bad Request", format!("Invalid workflow ID: '{id}'.")),
+    };
+
+    let metadata =
+        match cromwell::find_run(runs_dir, &id).and_then(|dir| cromwell::metadata(&id, &dir)) {
+            Ok(metadata) => metadata,
+            Err(e) => return fail("404 Not Found", format!("{e:#}")),
+        };
+
+    let body = if endpoint == "status" {
+        json!({ "id": id, "status": metadata["status"] })
+    } else {
+        metadata
+    };
+
+    (
+        "200 OK",
+        serde_json::to_string_pretty(&body).unwrap_or_default(),
+    )
+}
+
+/// Creates the HTTP response to a request.
+fn respond(request: &http::Request, runs_dir: &Path) -> String {
+    let workflow = request
+        .path
+        .strip_prefix(WORKFLOWS_PREFIX)
+        .and_then(|p| p.rsplit_once('/'));
+    let (code, body) = match (request.method.as_str(), workflow) {
+        ("GET", Some((id, endpoint @ ("metadata" | "status")))) => {
+            respond_workflow(runs_dir, id, endpoint)
+        }
+        _ => (
+            "404 Not Found",
+            json!({ "status": "fail", "message": "not found" }).to_string(),
+        ),
+    };
+
+    http::response(code, &body)
+}
+
+/// Runs the `server` subcommand.
+pub async fn server(args: Args) -> CommandResult<()> {
+    let runs_dir = Arc::new(args.runs_dir.expect("should have runs directory"));
+    let listener = TcpListener::bind(args.listen)
+        .await
+        .with_context(|| format!("failed to listen on `{addr}`", addr = args.listen))?;
+    info!(
+        "serving the metadata of runs in `{dir}` at `http://{addr}{WORKFLOWS_PREFIX}{{id}}/metadata`",
+        dir = runs_dir.display(),
+        addr = args.listen
+    );
+
+    loop {
+        let mut stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                warn!("failed to accept connection: {e}");
+                continue;
+            }
+        };
+
+        let runs_dir = runs_dir.clone();
+        tokio::spawn(async move {
+            let Ok(Some(request)) = http::read_request(&mut stream).await else {
+                return;
+            };
+
+            // Responding to a request reads from the runs directory
+            let Ok(response) =
+                tokio::task::spawn_blocking(move || respond(&request, &runs_dir)).await
+            else {
+                return;
+            };
+
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Creates a `GET` request for the given path.
+    fn get(path: &str) -> http::Request {
+        http::Request {
+            method: "GET".to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn responses() {
+        let dir = TempDir::new().unwrap();
+        let run_dir = dir.path().join("main").join("run1");
+        fs::create_dir_all(&run_dir).unwrap();
+        fs::write(run_dir.join("inputs.json"), "{}").unwrap();
+        fs::write(run_dir.join("outputs.json"), r#"{ "message": "hello" }"#).unwrap();
+
+        let body = |response: String| -> serde_json::Value {
+            serde_json::from_str(response.split_once("\r\n\r\n").unwrap().1).unwrap()
+        };
+
+        let response = respond(&get("/api/workflows/v1/main%2Frun1/status"), dir.path());
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert_eq!(
+            body(response),
+            json!({ "id": "main/run1", "status": "Succeeded" })
+        );
+
+        let response = respond(&get("/api/workflows/v1/run1/metadata"), dir.path());
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        let metadata = body(response);
+        assert_eq!(metadata["id"], "run1");
+        assert_eq!(metadata["workflowName"], "main");
+        assert_eq!(metadata["outputs"], json!({ "main.message": "hello" }));
+
+        let response = respond(&get("/api/workflows/v1/run2/metadata"), dir.path());
+        assert!(response.starts_with("HTTP/1.1 404 Not Found"));
+        assert_eq!(body(response)["status"], "fail");
+
+        for path in [
+            "/api/workflows/v1/..%2F..%2Fetc/metadata",
+            "/api/workflows/v1/%FF/metadata",
+        ] {
+            assert!(respond(&get(path), dir.path()).starts_with("HTTP/1.1 400 Bad Request"));
+        }
+
+        assert!(
+            respond(&get("/api/workflows/v1/run1/outputs"), dir.path())
+                .starts_with("HTTP/1.1 404 Not Found")
+        );
+        assert!(
+            respond(
+                &http::Request {
+                    method: "POST".to_string(),
+                    path: "/api/workflows/v1/run1/status".to_string(),
+                },
+                dir.path()
+            )
+            .starts_with("HTTP/1.1 404 Not Found")
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Implementation of the `stats` subcommand.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use anyhow::Context;
+use clap::Parser;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use super::check::DEFAULT_TAG_SET;
+use crate::analysis::Analysis;
+use crate::analysis::Source;
+use crate::commands::CommandError;
+use crate::commands::CommandResult;
+use crate::commands::DiscoveryArgs;
+
+/// The maximum number of most-imported documents to report.
+const MAX_IMPORTED: usize = 10;
+
+/// The format of a statistics report.
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum StatsFormat {
+    /// A human-readable text report.
+    #[default]
+    Text,
+
+    /// A JSON report.
+    Json,
+}
+
+impl std::fmt::Display for StatsFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Arguments for the `stats` subcommand.
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+pub struct Args {
+    /// A set of source documents as files, directories, or URLs.
+    #[clap(value_name = "SOURCE")]
+    pub sources: Vec<Source>,
+
+    /// The format of the report.
+    #[arg(long, value_name = "FORMAT", default_value_t)]
+    pub format: StatsFormat,
+
+    /// The arguments that control how directories are searched.
+    #[command(flatten)]
+    pub discovery: DiscoveryArgs,
+}
+
+/// Represents the statistics of a single document.
+#[derive(Debug, Serialize)]
+struct DocumentStats {
+    /// The path to the document.
+    path: String,
+    /// The number of lines in the document.
+    lines: usize,
+    /// The number of rule violations reported for the document.
+    violations: usize,
+    /// The number of rule violations per 100 lines of the document.
+    density: f64,
+}
+
+/// Represents the number of times a document is imported.
+#[derive(Debug, Serialize)]
+struct ImportCount {
+    /// The path to the imported document.
+    path: String,
+    /// The number of import statements that import the document.
+    count: usize,
+}
+
+/// Represents the statistics of a set of documents.
+#[derive(Debug, Default, Serialize)]
+struct Stats {
+    /// The number of documents.
+    documents: usize,
+    /// The total number of lines of WDL.
+    lines: usize,
+    /// The number of workflows.
+    workflows: usize,
+    /// The number of tasks.
+    tasks: usize,
+    /// The number of structs.
+    structs: usize,
+    /// The number of documents for each WDL version.
+    versions: BTreeMap<String, usize>,
+    /// The most-imported documents.
+    imported: Vec<ImportCount>,
+    /// The statistics of each document.
+    files: Vec<DocumentStats>,
+}
+
+impl Stats {
+    /// Renders the statistics as a text report.
+    fn to_text(&self) -> String {
+        let mut report = String::new();
+        let _ = writeln!(report, "documents: {}", self.documents);
+        let _ = writeln!(report, "lines: {}", self.lines);
+        let _ = writeln!(report, "workflows: {}", self.workflows);
+        let _ = writeln!(report, "tasks: {}", self.tasks);
+        let _ = writeln!(report, "structs: {}", self.structs);
+
+        let _ = writeln!(report, "\nversions:");
+        for (version, count) in &self.versions {
+            let _ = writeln!(report, "  {version}: {count}");
+        }
+
+        if !self.imported.is_empty() {
+            let _ = writeln!(report, "\nmost imported:");
+            for import in &self.imported {
+                let _ = writeln!(
+                    report,
+                    "  {path}: {count}",
+                    path = import.path,
+                    count = import.count
+                );
+            }
+        }
+
+        let _ = writeln!(report, "\nrule violations:");
+        for file in &self.files {
+            let _ = writeln!(
+                report,
+                "  {path}: {violations} ({density:.1} per 100 lines)",
+                path = file.path,
+                violations = file.violations,
+                density = file.density
+            );
+        }
+
+        report
+    }
+}
+
+/// Performs the `stats` subcommand.
+pub async fn stats(args: Args) -> CommandResult<()> {
+    let mut sources = args.sources;
+    if sources.is_empty() {
+        sources.push(Source::default());
+    }
+
+    let results = args
+        .discovery
+        .apply(Analysis::default())
+        .extend_sources(sources)
+        .enabled_lint_tags(DEFAULT_TAG_SET)
+        .run()
+        .await
+        .map_err(CommandError::from)?;
+
+    let mut stats = Stats::default();
+    let mut imported: HashMap<String, usize> = HashMap::new();
+    for result in results.as_slice() {
+        let document = result.document();
+
+        // Only local documents are included in the statistics
+        if document.uri().scheme() != "file" {
+            continue;
+        }
+
+        let lines = document.root().text().to_string().lines().count();
+        let violations = document
+            .diagnostics()
+            .filter(|d| d.rule().is_some())
+            .count();
+
+        stats.documents += 1;
+        stats.lines += lines;
+        stats.workflows += usize::from(document.workflow().is_some());
+        stats.tasks += document.tasks().count();
+        stats.structs += document
+            .structs()
+            .filter(|(_, s)| s.namespace().is_none())
+            .count();
+        *stats
+            .versions
+            .entry(
+                document
+                    .version()
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "unknown".to_string()),
+            )
+            .or_default() += 1;
+
+        for (_, ns) in document.namespaces() {
+            *imported
+                .entry(ns.document().path().to_string())
+                .or_default() += 1;
+        }
+
+        stats.files.push(DocumentStats {
+            path: document.path().to_string(),
+            lines,
+            violations,
+            density: if lines == 0 {
+                0.0
+            } else {
+                violations as f64 * 100.0 / lines as f64
+            },
+        });
+    }
+
+    let mut imported = imported
+        .into_iter()
+        .map(|(path, count)| ImportCount { path, count })
+        .collect::<Vec<_>>();
+    imported.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.path.cmp(&b.path)));
+    imported.truncate(MAX_IMPORTED);
+    stats.imported = imported;
+    stats.files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    match args.format {
+        StatsFormat::Text => print!("{report}", report = stats.to_text()),
+        StatsFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&stats).context("failed to serialize statistics")?
+        ),
+    }
+
+    Ok(())
+}
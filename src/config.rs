@@ -19,6 +19,9 @@ use wdl::engine;
 
 use crate::diagnostics::Mode;
 
+/// The name of Sprocket configuration files.
+pub const CONFIG_FILE_NAME: &str = "sprocket.toml";
+
 /// Represents the configuration for the Sprocket CLI tool.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case", deny_unknown_fields)]
@@ -100,6 +103,9 @@ pub struct CheckConfig {
     pub only_lint_tags: Vec<String>,
     /// Set of lint tags to filter out of the enabled lint rules.
     pub filter_lint_tags: Vec<String>,
+    /// Enable all lint rules and report violations of `Correctness` lint rules
+    /// as errors.
+    pub strict: bool,
 }
 
 /// Represents the configuration for the Sprocket `analyzer` command.
@@ -153,7 +159,7 @@ impl Config {
             let dir = dirs::config_dir();
 
             if let Some(dir) = dir {
-                let path = dir.join("sprocket").join("sprocket.toml");
+                let path = dir.join("sprocket").join(CONFIG_FILE_NAME);
                 if path.exists() {
                     trace!("reading configuration from `{path}`", path = path.display());
                     figment = figment.admerge(Toml::file_exact(path));
@@ -161,7 +167,7 @@ impl Config {
             }
 
             // Check PWD for a config file
-            let path = Path::new("sprocket.toml");
+            let path = Path::new(CONFIG_FILE_NAME);
             if path.exists() {
                 trace!("reading configuration from `{path}`", path = path.display());
                 figment = figment.admerge(Toml::file_exact(path));
@@ -208,6 +214,35 @@ impl Config {
         figment.extract().context("failed to merge configuration")
     }
 
+    /// Merges nested configuration files into the configuration.
+    ///
+    /// The files are merged in order, so later files take precedence; lists
+    /// (e.g. `check.except`) are extended rather than replaced.
+    pub fn merge_nested(&self, files: &[PathBuf]) -> Result<Self> {
+        let mut figment = Figment::new().admerge(Serialized::from(self, "default"));
+        for file in files {
+            trace!(
+                "reading nested configuration from `{path}`",
+                path = file.display()
+            );
+            figment = figment.admerge(Toml::file_exact(file));
+        }
+
+        let config: Self = figment
+            .extract()
+            .context("failed to merge nested configuration")?;
+        config.validate().with_context(|| {
+            format!(
+                "validating nested configuration `{path}`",
+                path = files
+                    .last()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default()
+            )
+        })?;
+        Ok(config)
+    }
+
     /// Validate a configuration
     pub fn validate(&self) -> Result<()> {
         if self.check.all_lint_rules && !self.check.only_lint_tags.is_empty() {
@@ -231,3 +266,71 @@ impl Config {
         std::fs::write(path, data).context("failed to write config file")
     }
 }
+
+/// Gets the nested configuration files that apply to the documents of a
+/// directory.
+///
+/// Nested configuration files are the [`CONFIG_FILE_NAME`] files of the
+/// directories below the base directory up to and including the given
+/// directory, ordered from the outermost directory to the innermost. The
+/// configuration file of the base directory itself is not included as it is
+/// read by [`Config::new`].
+///
+/// Returns an empty list if the directory is not below the base directory.
+pub fn nested_config_files(base: &Path, dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<_> = dir
+        .ancestors()
+        .take_while(|d| *d != base && d.starts_with(base))
+        .map(|d| d.join(CONFIG_FILE_NAME))
+        .filter(|p| p.is_file())
+        .collect();
+    files.reverse();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn nested_configs() {
+        let root = tempfile::tempdir().unwrap();
+        let legacy = root.path().join("legacy");
+        let old = legacy.join("old");
+        std::fs::create_dir_all(&old).unwrap();
+        std::fs::write(
+            root.path().join(CONFIG_FILE_NAME),
+            "[check]\nstrict = true\n",
+        )
+        .unwrap();
+        std::fs::write(
+            legacy.join(CONFIG_FILE_NAME),
+            "[check]\nstrict = false\nexcept = [\"SnakeCase\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            old.join(CONFIG_FILE_NAME),
+            "[check]\nexcept = [\"TodoComment\"]\n",
+        )
+        .unwrap();
+
+        assert!(nested_config_files(root.path(), root.path()).is_empty());
+        let files = nested_config_files(root.path(), &old);
+        assert_eq!(
+            files,
+            [legacy.join(CONFIG_FILE_NAME), old.join(CONFIG_FILE_NAME)]
+        );
+
+        let mut config = Config::default();
+        config.check.strict = true;
+        config.check.except = vec!["UnusedInput".into()];
+        let config = config.merge_nested(&files).unwrap();
+        assert!(!config.check.strict);
+        assert_eq!(
+            config.check.except,
+            ["UnusedInput", "SnakeCase", "TodoComment"]
+        );
+    }
+}
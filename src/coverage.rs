@@ -0,0 +1,451 @@
+//! Implementation of evaluation coverage reports.
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+
+use anyhow::Context as _;
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use wdl::analysis::Document;
+use wdl::ast::AstNode as _;
+use wdl::ast::AstToken as _;
+use wdl::ast::Span;
+use wdl::ast::v1::ConditionalStatementClauseKind;
+use wdl::ast::v1::WorkflowStatement;
+use wdl::engine::CoverageRegistry;
+
+/// The format of a coverage report.
+#[derive(Clone, Copy, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum CoverageFormat {
+    /// A human-readable text report.
+    #[default]
+    Text,
+
+    /// A JSON report.
+    Json,
+
+    /// A Cobertura XML report.
+    Cobertura,
+}
+
+impl std::fmt::Display for CoverageFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+            Self::Cobertura => write!(f, "cobertura"),
+        }
+    }
+}
+
+/// Represents the kind of an element in a coverage report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ElementKind {
+    /// The element is a task.
+    Task,
+    /// The element is a call statement.
+    Call,
+    /// The element is a scatter statement.
+    Scatter,
+    /// The element is a clause of a conditional statement.
+    ConditionalClause,
+}
+
+impl ElementKind {
+    /// Gets the display name of the element kind.
+    fn display_name(&self) -> &'static str {
+        match self {
+            Self::Task => "task",
+            Self::Call => "call",
+            Self::Scatter => "scatter",
+            Self::ConditionalClause => "conditional clause",
+        }
+    }
+
+    /// Determines if the element kind is a branch.
+    fn is_branch(&self) -> bool {
+        matches!(self, Self::Scatter | Self::ConditionalClause)
+    }
+}
+
+/// Represents an element in a coverage report.
+#[derive(Debug, Serialize)]
+struct Element {
+    /// The kind of the element.
+    kind: ElementKind,
+    /// The name of the element.
+    name: String,
+    /// The one-based line number of the element.
+    line: usize,
+    /// The number of times the element was evaluated.
+    hits: usize,
+}
+
+/// Represents the coverage of a single document.
+#[derive(Debug, Serialize)]
+struct DocumentCoverage {
+    /// The path of the document.
+    path: String,
+    /// The elements of the document.
+    elements: Vec<Element>,
+}
+
+/// Represents a count of covered elements.
+#[derive(Debug, Default, Serialize)]
+struct Count {
+    /// The number of covered elements.
+    covered: usize,
+    /// The total number of elements.
+    total: usize,
+}
+
+impl Count {
+    /// Gets the rate of coverage, from 0 to 1.
+    fn rate(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.covered as f64 / self.total as f64
+        }
+    }
+}
+
+/// Represents a summary of coverage by element kind.
+#[derive(Debug, Default, Serialize)]
+struct Summary {
+    /// The coverage of tasks.
+    tasks: Count,
+    /// The coverage of call statements.
+    calls: Count,
+    /// The coverage of scatter statements.
+    scatters: Count,
+    /// The coverage of conditional clauses.
+    conditional_clauses: Count,
+}
+
+impl Summary {
+    /// Gets the count for the given element kind.
+    fn count_mut(&mut self, kind: ElementKind) -> &mut Count {
+        match kind {
+            ElementKind::Task => &mut self.tasks,
+            ElementKind::Call => &mut self.calls,
+            ElementKind::Scatter => &mut self.scatters,
+            ElementKind::ConditionalClause => &mut self.conditional_clauses,
+        }
+    }
+}
+
+/// Represents a coverage report.
+#[derive(Debug, Serialize)]
+struct Report {
+    /// The summary of the report.
+    summary: Summary,
+    /// The coverage of each document.
+    documents: Vec<DocumentCoverage>,
+}
+
+/// Gets the one-based line number of the given span.
+fn line(source: &str, span: Span) -> usize {
+    source[..span.start().min(source.len())]
+        .bytes()
+        .filter(|b| *b == b'\n')
+        .count()
+        + 1
+}
+
+/// Gathers the elements of the given workflow statements.
+fn gather_statements(
+    statements: impl Iterator<Item = WorkflowStatement>,
+    source: &str,
+    lookup: &dyn Fn(Span) -> usize,
+    elements: &mut Vec<Element>,
+) {
+    for statement in statements {
+        match statement {
+            WorkflowStatement::Call(stmt) => {
+                let name = stmt
+                    .alias()
+                    .map(|a| a.name().text().to_string())
+                    .unwrap_or_else(|| {
+                        stmt.target()
+                            .names()
+                            .last()
+                            .map(|n| n.text().to_string())
+                            .unwrap_or_default()
+                    });
+                elements.push(Element {
+                    kind: ElementKind::Call,
+                    name,
+                    line: line(source, stmt.span()),
+                    hits: lookup(stmt.span()),
+                });
+            }
+            WorkflowStatement::Scatter(stmt) => {
+                elements.push(Element {
+                    kind: ElementKind::Scatter,
+                    name: stmt.variable().text().to_string(),
+                    line: line(source, stmt.span()),
+                    hits: lookup(stmt.span()),
+                });
+                gather_statements(stmt.statements(), source, lookup, elements);
+            }
+            WorkflowStatement::Conditional(stmt) => {
+                for clause in stmt.clauses() {
+                    let name = match clause.kind() {
+                        ConditionalStatementClauseKind::If => "if",
+                        ConditionalStatementClauseKind::ElseIf => "else if",
+                        ConditionalStatementClauseKind::Else => "else",
+                    };
+                    elements.push(Element {
+                        kind: ElementKind::ConditionalClause,
+                        name: name.to_string(),
+                        line: line(source, clause.span()),
+                        hits: lookup(clause.span()),
+                    });
+                    gather_statements(clause.statements(), source, lookup, elements);
+                }
+            }
+            WorkflowStatement::Declaration(_) => {}
+        }
+    }
+}
+
+/// Builds the coverage report for the given document and its imports.
+fn report(coverage: &CoverageRegistry, document: &Document) -> Report {
+    let mut seen = HashSet::new();
+    let mut stack = vec![document];
+    let mut summary = Summary::default();
+    let mut documents = Vec::new();
+    while let Some(document) = stack.pop() {
+        if !seen.insert(document.uri().to_string()) {
+            continue;
+        }
+
+        stack.extend(document.namespaces().map(|(_, ns)| ns.document()));
+
+        let root = document.root();
+        let source = root.text().to_string();
+        let lookup = |span: Span| coverage.hits(document.uri(), span.start());
+
+        let mut elements = Vec::new();
+        for task in document.tasks() {
+            elements.push(Element {
+                kind: ElementKind::Task,
+                name: task.name().to_string(),
+                line: line(&source, task.name_span()),
+                hits: lookup(task.name_span()),
+            });
+        }
+
+        if let Some(ast) = root.ast().into_v1() {
+            for workflow in ast.workflows() {
+                gather_statements(workflow.statements(), &source, &lookup, &mut elements);
+            }
+        }
+
+        elements.sort_by_key(|e| e.line);
+        for element in &elements {
+            let count = summary.count_mut(element.kind);
+            count.total += 1;
+            if element.hits > 0 {
+                count.covered += 1;
+            }
+        }
+
+        documents.push(DocumentCoverage {
+            path: document.path().to_string(),
+            elements,
+        });
+    }
+
+    documents.sort_by(|a, b| a.path.cmp(&b.path));
+    Report { summary, documents }
+}
+
+/// Renders a coverage report for the given document (and its imports) in the
+/// given format.
+pub fn render(
+    coverage: &CoverageRegistry,
+    document: &Document,
+    format: CoverageFormat,
+) -> Result<String> {
+    let report = report(coverage, document);
+    match format {
+        CoverageFormat::Text => Ok(render_text(&report)),
+        CoverageFormat::Json => {
+            serde_json::to_string_pretty(&report).context("failed to serialize coverage report")
+        }
+        CoverageFormat::Cobertura => Ok(render_cobertura(&report)),
+    }
+}
+
+/// Renders a coverage report as text.
+fn render_text(report: &Report) -> String {
+    let mut text = String::new();
+    for (name, count) in [
+        ("tasks", &report.summary.tasks),
+        ("calls", &report.summary.calls),
+        ("scatters", &report.summary.scatters),
+        ("conditional clauses", &report.summary.conditional_clauses),
+    ] {
+        let _ = writeln!(
+            text,
+            "{name:<20} {covered:>5}/{total:<5} {rate:>6.1}%",
+            covered = count.covered,
+            total = count.total,
+            rate = count.rate() * 100.0
+        );
+    }
+
+    for document in &report.documents {
+        let uncovered = document
+            .elements
+            .iter()
+            .filter(|e| e.hits == 0)
+            .collect::<Vec<_>>();
+        if uncovered.is_empty() {
+            continue;
+        }
+
+        let _ = writeln!(text, "\nnot covered in `{path}`:", path = document.path);
+        for element in uncovered {
+            let _ = writeln!(
+                text,
+                "  {line:>5}: {kind} `{name}`",
+                line = element.line,
+                kind = element.kind.display_name(),
+                name = element.name
+            );
+        }
+    }
+
+    text
+}
+
+/// Escapes a string for use in an XML attribute.
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders a coverage report in the Cobertura XML format.
+fn render_cobertura(report: &Report) -> String {
+    let mut lines = Count::default();
+    let mut branches = Count::default();
+    for element in report.documents.iter().flat_map(|d| &d.elements) {
+        let count = if element.kind.is_branch() {
+            &mut branches
+        } else {
+            &mut lines
+        };
+        count.total += 1;
+        if element.hits > 0 {
+            count.covered += 1;
+        }
+    }
+
+    let mut xml = String::new();
+    let _ = writeln!(xml, r#"<?xml version="1.0" ?>"#);
+    let _ = writeln!(
+        xml,
+        r#"<coverage line-rate="{line_rate:.4}" branch-rate="{branch_rate:.4}" lines-covered="{lines_covered}" lines-valid="{lines_valid}" branches-covered="{branches_covered}" branches-valid="{branches_valid}" complexity="0" version="{version}" timestamp="{timestamp}">"#,
+        line_rate = lines.rate(),
+        branch_rate = branches.rate(),
+        lines_covered = lines.covered,
+        lines_valid = lines.total,
+        branches_covered = branches.covered,
+        branches_valid = branches.total,
+        version = env!("CARGO_PKG_VERSION"),
+        timestamp = chrono::Utc::now().timestamp_millis(),
+    );
+    let _ = writeln!(xml, "  <sources>\n    <source>.</source>\n  </sources>");
+    let _ = writeln!(xml, "  <packages>");
+    let _ = writeln!(
+        xml,
+        r#"    <package name="wdl" line-rate="{line_rate:.4}" branch-rate="{branch_rate:.4}" complexity="0">"#,
+        line_rate = lines.rate(),
+        branch_rate = branches.rate(),
+    );
+    let _ = writeln!(xml, "      <classes>");
+    for document in &report.documents {
+        let mut lines = Count::default();
+        let mut branches = Count::default();
+        for element in &document.elements {
+            let count = if element.kind.is_branch() {
+                &mut branches
+            } else {
+                &mut lines
+            };
+            count.total += 1;
+            if element.hits > 0 {
+                count.covered += 1;
+            }
+        }
+
+        let path = escape_xml(&document.path);
+        let _ = writeln!(
+            xml,
+            r#"        <class name="{path}" filename="{path}" line-rate="{line_rate:.4}" branch-rate="{branch_rate:.4}" complexity="0">"#,
+            line_rate = lines.rate(),
+            branch_rate = branches.rate(),
+        );
+        let _ = writeln!(xml, "          <methods/>\n          <lines>");
+        for element in &document.elements {
+            if element.kind.is_branch() {
+                let covered = usize::from(element.hits > 0);
+                let _ = writeln!(
+                    xml,
+                    r#"            <line number="{line}" hits="{hits}" branch="true" condition-coverage="{percent}% ({covered}/1)"/>"#,
+                    line = element.line,
+                    hits = element.hits,
+                    percent = covered * 100,
+                );
+            } else {
+                let _ = writeln!(
+                    xml,
+                    r#"            <line number="{line}" hits="{hits}" branch="false"/>"#,
+                    line = element.line,
+                    hits = element.hits,
+                );
+            }
+        }
+        let _ = writeln!(xml, "          </lines>\n        </class>");
+    }
+    let _ = writeln!(xml, "      </classes>\n    </package>\n  </packages>");
+    let _ = writeln!(xml, "</coverage>");
+    xml
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn line_numbers() {
+        let source = "version 1.2\n\nworkflow test {\n}\n";
+        assert_eq!(line(source, Span::new(0, 7)), 1);
+        assert_eq!(line(source, Span::new(13, 8)), 3);
+    }
+
+    #[test]
+    fn xml_escaping() {
+        assert_eq!(escape_xml(r#"a<b>&"c""#), "a&lt;b&gt;&amp;&quot;c&quot;");
+    }
+
+    #[test]
+    fn count_rate() {
+        assert_eq!(Count::default().rate(), 1.0);
+        assert_eq!(
+            Count {
+                covered: 1,
+                total: 4
+            }
+            .rate(),
+            0.25
+        );
+    }
+}
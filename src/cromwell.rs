@@ -0,0 +1,587 @@
+//! Cromwell-compatible metadata of runs.
+//!
+//! The metadata is a subset of the workflow metadata returned by Cromwell's
+//! `GET /api/workflows/v1/{id}/metadata` endpoint, so that tools built against
+//! Cromwell's API (e.g. Cromshell) can inspect the runs of `sprocket run`.
+//!
+//! A run or call that failed to evaluate has a failure file in place of its
+//! outputs and is reported as `Failed` (or `Aborted` if it was canceled) with
+//! the error of the evaluation. A run without either file is reported as
+//! `Running`, unless one of its calls failed.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::bail;
+use chrono::DateTime;
+use chrono::SecondsFormat;
+use chrono::Utc;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+
+/// The name of the inputs file of a run or call.
+const INPUTS_FILE: &str = "inputs.json";
+
+/// The name of the outputs file of a run or call.
+const OUTPUTS_FILE: &str = "outputs.json";
+
+/// The name of the outputs file of a call that failed with salvaged outputs.
+const SALVAGED_OUTPUTS_FILE: &str = "outputs.salvaged.json";
+
+/// The name of the file of a run or call that failed to evaluate.
+const FAILURE_FILE: &str = "failure.json";
+
+/// The name of the directory containing the calls of a workflow.
+const CALLS_DIR: &str = "calls";
+
+/// The name of the directory containing the attempts of a task.
+const ATTEMPTS_DIR: &str = "attempts";
+
+/// The name of the stdout file of an attempt.
+const STDOUT_FILE: &str = "stdout";
+
+/// The name of the stderr file of an attempt.
+const STDERR_FILE: &str = "stderr";
+
+/// Determines if the given string is a valid run id.
+///
+/// A run id must be a relative path of one or two components (i.e.
+/// `<timestamp>` or `<entrypoint>/<timestamp>`) so that it cannot refer to a
+/// directory outside of the runs directory.
+pub fn is_valid_id(id: &str) -> bool {
+    let mut count = 0;
+    for component in Path::new(id).components() {
+        match component {
+            Component::Normal(_) => count += 1,
+            _ => return false,
+        }
+    }
+
+    (1..=2).contains(&count)
+}
+
+/// Finds the execution directory of a run beneath the runs directory.
+///
+/// The run is either the name of a run directly beneath the runs directory
+/// (e.g. `<entrypoint>/<timestamp>`) or the name of a run beneath a single
+/// entrypoint's directory (e.g. `<timestamp>`).
+pub fn find_run(runs_dir: &Path, run: &str) -> Result<PathBuf> {
+    let path = runs_dir.join(run);
+    if path.is_dir() {
+        return Ok(path);
+    }
+
+    // Look for the run beneath each entrypoint's directory
+    let mut matches = Vec::new();
+    if let Ok(entries) = runs_dir.read_dir() {
+        for entry in entries.flatten() {
+            let path = entry.path().join(run);
+            if path.is_dir() {
+                matches.push(path);
+            }
+        }
+    }
+
+    match matches.len() {
+        0 => bail!(
+            "run `{run}` was not found in runs directory `{dir}`",
+            dir = runs_dir.display()
+        ),
+        1 => Ok(matches.pop().expect("should have a match")),
+        _ => bail!(
+            "run `{run}` is ambiguous: specify it as `<entrypoint>/{run}` (found {paths})",
+            paths = matches
+                .iter()
+                .map(|p| format!("`{p}`", p = p.display()))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Reads a JSON object from the given file.
+///
+/// The keys of the object are prefixed with the given name, if there is one.
+///
+/// Returns an empty object if the file does not exist.
+fn read_object(path: &Path, prefix: Option<&str>) -> Result<Map<String, Value>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(e) => {
+            return Err(e)
+                .with_context(|| format!("failed to read file `{path}`", path = path.display()));
+        }
+    };
+
+    let object: Map<String, Value> = serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse file `{path}`", path = path.display()))?;
+    Ok(match prefix {
+        Some(prefix) => object
+            .into_iter()
+            .map(|(k, v)| (format!("{prefix}.{k}"), v))
+            .collect(),
+        None => object,
+    })
+}
+
+/// Formats the modification time of a file as a Cromwell timestamp.
+///
+/// Returns `None` if the file does not exist.
+fn modified(path: &Path) -> Option<String> {
+    let time: DateTime<Utc> = fs::metadata(path).and_then(|m| m.modified()).ok()?.into();
+    Some(time.to_rfc3339_opts(SecondsFormat::Millis, true))
+}
+
+/// Reads the failure file of a run or call that failed to evaluate.
+///
+/// Returns whether or not the evaluation was canceled and the Cromwell
+/// failures of the evaluation, or `None` if there is no failure file.
+fn read_failure(dir: &Path) -> Result<Option<(bool, Value)>> {
+    let failure = read_object(&dir.join(FAILURE_FILE), None)?;
+    if failure.is_empty() {
+        return Ok(None);
+    }
+
+    let canceled = failure
+        .get("canceled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let message = failure.get("error").cloned().unwrap_or_default();
+    Ok(Some((
+        canceled,
+        json!([{ "message": message, "causedBy": [] }]),
+    )))
+}
+
+/// Parses the scatter indices from the name of a call's directory.
+///
+/// The directory of a call within a scatter is named `<alias>-<indices>`,
+/// where the indices of nested scatters are joined by `-` from the innermost
+/// scatter to the outermost.
+///
+/// Returns the call's alias and its scatter indices ordered from the
+/// outermost scatter to the innermost; the indices are empty for a call that
+/// is not within a scatter.
+fn parse_call_dir(name: &str) -> (&str, Vec<u64>) {
+    match name.split_once('-') {
+        Some((alias, indices)) => {
+            let mut indices: Vec<_> = indices.split('-').filter_map(|i| i.parse().ok()).collect();
+            indices.reverse();
+            (alias, indices)
+        }
+        None => (name, Vec::new()),
+    }
+}
+
+/// Creates the metadata of the attempts of a call.
+///
+/// Returns the metadata and whether or not the call failed.
+fn call_metadata(alias: &str, shard: i64, dir: &Path) -> Result<(Vec<Value>, bool)> {
+    let done = dir.join(OUTPUTS_FILE).is_file();
+    let failure = if done { None } else { read_failure(dir)? };
+    let failed = !done && (failure.is_some() || dir.join(SALVAGED_OUTPUTS_FILE).is_file());
+    let status = match &failure {
+        _ if done => "Done",
+        Some((true, _)) => "Aborted",
+        _ if failed => "Failed",
+        _ => "Running",
+    };
+    let failures = failure.map(|(_, failures)| failures);
+
+    let inputs = read_object(&dir.join(INPUTS_FILE), None)?;
+    let outputs = read_object(&dir.join(OUTPUTS_FILE), None)?;
+
+    // A call to a workflow has its own calls directory
+    if dir.join(CALLS_DIR).is_dir() {
+        let (metadata, sub_failed) = workflow_metadata(alias, dir)?;
+        let mut call = json!({
+            "shardIndex": shard,
+            "attempt": 1,
+            "executionStatus": if sub_failed && status == "Running" { "Failed" } else { status },
+            "callRoot": dir.display().to_string(),
+            "inputs": inputs,
+            "outputs": outputs,
+            "subWorkflowMetadata": metadata,
+        });
+        if let Some(failures) = failures {
+            call["failures"] = failures;
+        }
+
+        return Ok((vec![call], failed || sub_failed));
+    }
+
+    let mut attempts: Vec<(i64, _)> = match fs::read_dir(dir.join(ATTEMPTS_DIR)) {
+        Ok(entries) => entries
+            .filter_map(|e| {
+                let path = e.ok()?.path();
+                Some((path.file_name()?.to_str()?.parse().ok()?, path))
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    attempts.sort();
+
+    // A call whose result was reused from the call cache has no attempts
+    if attempts.is_empty() {
+        let mut call = json!({
+            "shardIndex": shard,
+            "attempt": 1,
+            "executionStatus": status,
+            "callRoot": dir.display().to_string(),
+            "callCaching": { "hit": done },
+            "inputs": inputs,
+            "outputs": outputs,
+        });
+        if let Some(failures) = failures {
+            call["failures"] = failures;
+        }
+
+        return Ok((vec![call], failed));
+    }
+
+    // Attempts are numbered from zero, whereas Cromwell numbers them from one
+    let last = attempts.len() - 1;
+    let metadata = attempts
+        .into_iter()
+        .enumerate()
+        .map(|(i, (attempt, path))| {
+            let mut call = json!({
+                "shardIndex": shard,
+                "attempt": attempt + 1,
+                "executionStatus": if i < last { "RetryableFailure" } else { status },
+                "callRoot": path.display().to_string(),
+                "stdout": path.join(STDOUT_FILE).display().to_string(),
+                "stderr": path.join(STDERR_FILE).display().to_string(),
+                "inputs": inputs,
+                "outputs": if i == last { outputs.clone() } else { Map::new() },
+            });
+            if i == last
+                && let Some(failures) = &failures
+            {
+                call["failures"] = failures.clone();
+            }
+
+            call
+        })
+        .collect();
+    Ok((metadata, failed))
+}
+
+/// Creates the metadata of a workflow from its execution directory.
+///
+/// Returns the metadata and whether or not the workflow failed.
+fn workflow_metadata(name: &str, dir: &Path) -> Result<(Map<String, Value>, bool)> {
+    // Group the directories of each call by alias; a call within nested
+    // scatters has a directory for every combination of scatter indices
+    let mut dirs: BTreeMap<String, Vec<(Vec<u64>, PathBuf)>> = BTreeMap::new();
+    if let Ok(entries) = fs::read_dir(dir.join(CALLS_DIR)) {
+        for path in entries.filter_map(|e| e.ok().map(|e| e.path())) {
+            if !path.is_dir() {
+                continue;
+            }
+
+            let file_name = path
+                .file_name()
+                .expect("should have a file name")
+                .to_string_lossy()
+                .into_owned();
+            let (alias, indices) = parse_call_dir(&file_name);
+            dirs.entry(alias.to_string())
+                .or_default()
+                .push((indices, path));
+        }
+    }
+
+    let mut calls = Map::new();
+    let mut failed = false;
+    for (alias, mut shards) in dirs {
+        // Flatten the scatter indices into a shard index from the ordered
+        // combinations of indices so that every shard has a unique index
+        shards.sort();
+        let mut attempts = Vec::new();
+        for (i, (indices, path)) in shards.into_iter().enumerate() {
+            let shard = if indices.is_empty() {
+                -1
+            } else {
+                i64::try_from(i).unwrap_or(i64::MAX)
+            };
+
+            let (metadata, call_failed) = call_metadata(&alias, shard, &path)?;
+            failed |= call_failed;
+            attempts.extend(metadata);
+        }
+
+        calls.insert(format!("{name}.{alias}"), attempts.into());
+    }
+
+    let succeeded = dir.join(OUTPUTS_FILE).is_file();
+    let failure = if succeeded { None } else { read_failure(dir)? };
+    let failed = !succeeded && (failure.is_some() || failed);
+    let mut metadata = Map::new();
+    metadata.insert("workflowName".into(), name.into());
+    metadata.insert("workflowRoot".into(), dir.display().to_string().into());
+    metadata.insert(
+        "status".into(),
+        match &failure {
+            _ if succeeded => "Succeeded",
+            Some((true, _)) => "Aborted",
+            _ if failed => "Failed",
+            _ => "Running",
+        }
+        .into(),
+    );
+    if let Some(start) = modified(&dir.join(INPUTS_FILE)) {
+        metadata.insert("start".into(), start.into());
+    }
+    if let Some(end) =
+        modified(&dir.join(OUTPUTS_FILE)).or_else(|| modified(&dir.join(FAILURE_FILE)))
+    {
+        metadata.insert("end".into(), end.into());
+    }
+    if let Some((_, failures)) = failure {
+        metadata.insert("failures".into(), failures);
+    }
+    metadata.insert(
+        "inputs".into(),
+        read_object(&dir.join(INPUTS_FILE), Some(name))?.into(),
+    );
+    metadata.insert(
+        "outputs".into(),
+        read_object(&dir.join(OUTPUTS_FILE), Some(name))?.into(),
+    );
+    metadata.insert("calls".into(), calls.into());
+    Ok((metadata, failed))
+}
+
+/// Creates the Cromwell-compatible metadata of a run.
+///
+/// The name of the workflow is the name of the directory containing the run's
+/// execution directory (i.e. the entrypoint of the run).
+pub fn metadata(id: &str, run_dir: &Path) -> Result<Value> {
+    let name = run_dir
+        .parent()
+        .and_then(Path::file_name)
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (mut metadata, _) = workflow_metadata(&name, run_dir)?;
+    metadata.insert("id".into(), id.into());
+    Ok(metadata.into())
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn run_ids() {
+        assert!(is_valid_id("2025-01-01_000000000000000"));
+        assert!(is_valid_id("main/2025-01-01_000000000000000"));
+        assert!(!is_valid_id(""));
+        assert!(!is_valid_id("a/b/c"));
+        assert!(!is_valid_id("../main"));
+        assert!(!is_valid_id("/main"));
+        assert!(!is_valid_id("main/.."));
+    }
+
+    #[test]
+    fn call_dirs() {
+        assert_eq!(parse_call_dir("hello"), ("hello", vec![]));
+        assert_eq!(parse_call_dir("hello-2"), ("hello", vec![2]));
+        assert_eq!(parse_call_dir("hello-1-0"), ("hello", vec![0, 1]));
+    }
+
+    #[test]
+    fn nested_shards() {
+        let dir = TempDir::new().unwrap();
+        let run_dir = dir.path().join("main").join("run1");
+        for name in ["hello-0-0", "hello-1-0", "hello-0-1", "hello-1-1"] {
+            let call_dir = run_dir.join(CALLS_DIR).join(name);
+            fs::create_dir_all(&call_dir).unwrap();
+            fs::write(
+                call_dir.join(OUTPUTS_FILE),
+                format!(r#"{{ "name": "{name}" }}"#),
+            )
+            .unwrap();
+        }
+
+        let run = metadata("run1", &run_dir).unwrap();
+        let shards: Vec<_> = run["calls"]["main.hello"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| {
+                (
+                    c["shardIndex"].as_i64().unwrap(),
+                    c["outputs"]["name"].as_str().unwrap(),
+                )
+            })
+            .collect();
+
+        // The shards are ordered by the outer scatter's index first
+        assert_eq!(
+            shards,
+            [
+                (0, "hello-0-0"),
+                (1, "hello-1-0"),
+                (2, "hello-0-1"),
+                (3, "hello-1-1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn runs() {
+        let dir = TempDir::new().unwrap();
+        fs::create_dir_all(dir.path().join("main").join("run1")).unwrap();
+        fs::create_dir_all(dir.path().join("main").join("run2")).unwrap();
+        fs::create_dir_all(dir.path().join("other").join("run2")).unwrap();
+
+        let expected = dir.path().join("main").join("run1");
+        assert_eq!(find_run(dir.path(), "main/run1").unwrap(), expected);
+        assert_eq!(find_run(dir.path(), "run1").unwrap(), expected);
+        assert!(
+            find_run(dir.path(), "run2")
+                .unwrap_err()
+                .to_string()
+                .starts_with("run `run2` is ambiguous")
+        );
+        assert!(
+            find_run(dir.path(), "run3")
+                .unwrap_err()
+                .to_string()
+                .starts_with("run `run3` was not found")
+        );
+    }
+
+    #[test]
+    fn run_metadata() {
+        let dir = TempDir::new().unwrap();
+        let run_dir = dir.path().join("main").join("run1");
+        let calls_dir = run_dir.join("calls");
+        fs::create_dir_all(&calls_dir).unwrap();
+        fs::write(run_dir.join(INPUTS_FILE), r#"{ "name": "world" }"#).unwrap();
+        fs::write(
+            run_dir.join(OUTPUTS_FILE),
+            r#"{ "greetings": ["hello", "hi"] }"#,
+        )
+        .unwrap();
+
+        // A scattered call with two shards, the second of which was retried
+        for (shard, attempts) in [(0, 1), (1, 2)] {
+            let call_dir = calls_dir.join(format!("greet-{shard}"));
+            for attempt in 0..attempts {
+                fs::create_dir_all(call_dir.join(ATTEMPTS_DIR).join(attempt.to_string())).unwrap();
+            }
+            fs::write(call_dir.join(INPUTS_FILE), r#"{ "name": "world" }"#).unwrap();
+            fs::write(call_dir.join(OUTPUTS_FILE), r#"{ "greeting": "hello" }"#).unwrap();
+        }
+
+        let run = metadata("main/run1", &run_dir).unwrap();
+        assert_eq!(run["id"], "main/run1");
+        assert_eq!(run["workflowName"], "main");
+        assert_eq!(run["status"], "Succeeded");
+        assert_eq!(run["inputs"], json!({ "main.name": "world" }));
+        assert_eq!(run["outputs"], json!({ "main.greetings": ["hello", "hi"] }));
+        assert!(run["start"].is_string());
+        assert!(run["end"].is_string());
+
+        let calls = run["calls"]["main.greet"].as_array().unwrap();
+        let summary: Vec<_> = calls
+            .iter()
+            .map(|c| {
+                (
+                    c["shardIndex"].as_i64().unwrap(),
+                    c["attempt"].as_i64().unwrap(),
+                    c["executionStatus"].as_str().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [(0, 1, "Done"), (1, 1, "RetryableFailure"), (1, 2, "Done")]
+        );
+        assert_eq!(calls[2]["outputs"], json!({ "greeting": "hello" }));
+        assert_eq!(calls[1]["outputs"], json!({}));
+        assert_eq!(
+            calls[2]["stdout"],
+            calls_dir
+                .join("greet-1")
+                .join(ATTEMPTS_DIR)
+                .join("1")
+                .join(STDOUT_FILE)
+                .display()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn failed_run_metadata() {
+        let dir = TempDir::new().unwrap();
+        let run_dir = dir.path().join("main").join("run1");
+        let call_dir = run_dir.join("calls").join("greet");
+        fs::create_dir_all(call_dir.join(ATTEMPTS_DIR).join("0")).unwrap();
+        fs::write(run_dir.join(INPUTS_FILE), "{}").unwrap();
+
+        // Without a failure file, the run is still running
+        let run = metadata("run1", &run_dir).unwrap();
+        assert_eq!(run["status"], "Running");
+        assert_eq!(run["calls"]["main.greet"][0]["executionStatus"], "Running");
+        assert!(run.get("end").is_none());
+
+        // A failed call is reported as failed even before the run terminates
+        fs::write(
+            call_dir.join(FAILURE_FILE),
+            r#"{ "canceled": false, "error": "process terminated with exit code 1" }"#,
+        )
+        .unwrap();
+        let run = metadata("run1", &run_dir).unwrap();
+        assert_eq!(run["status"], "Failed");
+        assert_eq!(run["calls"]["main.greet"][0]["executionStatus"], "Failed");
+        assert_eq!(
+            run["calls"]["main.greet"][0]["failures"],
+            json!([{ "message": "process terminated with exit code 1", "causedBy": [] }])
+        );
+        assert!(run.get("failures").is_none());
+
+        fs::write(
+            run_dir.join(FAILURE_FILE),
+            r#"{ "canceled": false, "error": "call `greet` failed" }"#,
+        )
+        .unwrap();
+        let run = metadata("run1", &run_dir).unwrap();
+        assert_eq!(run["status"], "Failed");
+        assert_eq!(
+            run["failures"],
+            json!([{ "message": "call `greet` failed", "causedBy": [] }])
+        );
+        assert!(run["end"].is_string());
+
+        // A canceled run is aborted
+        fs::write(
+            run_dir.join(FAILURE_FILE),
+            r#"{ "canceled": true, "error": "evaluation was canceled" }"#,
+        )
+        .unwrap();
+        let run = metadata("run1", &run_dir).unwrap();
+        assert_eq!(run["status"], "Aborted");
+
+        // Salvaged outputs also mark a call as failed
+        fs::remove_file(call_dir.join(FAILURE_FILE)).unwrap();
+        fs::remove_file(run_dir.join(FAILURE_FILE)).unwrap();
+        fs::write(call_dir.join(SALVAGED_OUTPUTS_FILE), "{}").unwrap();
+        let run = metadata("run1", &run_dir).unwrap();
+        assert_eq!(run["status"], "Failed");
+        assert_eq!(run["calls"]["main.greet"][0]["executionStatus"], "Failed");
+    }
+}
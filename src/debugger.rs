@@ -0,0 +1,158 @@
+//! Implementation of the interactive step-through debugger for workflow
+//! evaluation.
+
+use std::io::BufRead as _;
+use std::io::Write as _;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use colored::Colorize as _;
+use futures::FutureExt as _;
+use futures::future::BoxFuture;
+use tokio::sync::Mutex;
+use tracing_indicatif::suspend_tracing_indicatif;
+use wdl::engine::CallAction;
+use wdl::engine::CallInterceptor;
+use wdl::engine::Inputs;
+use wdl::engine::InterceptedCall;
+use wdl::engine::Outputs;
+use wdl::engine::Value;
+
+/// The help text displayed by the debugger.
+const HELP: &str = "\
+commands:
+  c, continue        evaluate the call
+  i, inputs          print the evaluated inputs of the call
+  o, outputs         print the expected outputs of the call
+  s, skip [FILE]     skip the call, using outputs from the given JSON file
+  r, run             evaluate the call and all remaining calls without stopping
+  q, quit            abort the evaluation
+  h, help            print this help text";
+
+/// An interactive debugger that breaks before each workflow call.
+///
+/// Prompts are read from stdin; calls that are evaluated concurrently (e.g.
+/// within a scatter) are prompted for one at a time.
+#[derive(Debug, Default)]
+pub struct StepDebugger {
+    /// Used to serialize prompting for concurrently evaluated calls.
+    lock: Mutex<()>,
+    /// Whether or not stepping has been disabled for the remaining calls.
+    running: AtomicBool,
+}
+
+impl StepDebugger {
+    /// Reads the outputs of a skipped call from the given JSON file.
+    fn read_outputs(path: &Path) -> Result<Outputs> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("failed to open outputs file `{}`", path.display()))?;
+        let outputs: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_reader(std::io::BufReader::new(file)).with_context(|| {
+                format!(
+                    "failed to parse outputs file `{}`: expected a JSON object",
+                    path.display()
+                )
+            })?;
+
+        outputs
+            .into_iter()
+            .map(|(name, value)| {
+                let value: Value = serde_json::from_value(value)
+                    .with_context(|| format!("invalid value for output `{name}`"))?;
+                Ok((name, value))
+            })
+            .collect()
+    }
+
+    /// Prompts the user for the action to take for the given call.
+    fn prompt(&self, call: InterceptedCall<'_>) -> Result<CallAction> {
+        let mut stderr = std::io::stderr();
+        writeln!(
+            stderr,
+            "{break_} before call `{name}` to `{target}` ({id})",
+            break_ = "break".yellow().bold(),
+            name = call.name.magenta().bold(),
+            target = call.target,
+            id = call.id,
+        )?;
+
+        let stdin = std::io::stdin();
+        let mut line = String::new();
+        loop {
+            write!(stderr, "{prompt} ", prompt = "(debug)".cyan())?;
+            stderr.flush()?;
+
+            line.clear();
+            if stdin
+                .lock()
+                .read_line(&mut line)
+                .context("failed to read from stdin")?
+                == 0
+            {
+                bail!("evaluation was aborted by the debugger");
+            }
+
+            let mut parts = line.split_whitespace();
+            match parts.next().unwrap_or_default() {
+                "" | "c" | "continue" => return Ok(CallAction::Continue),
+                "i" | "inputs" => {
+                    let json = match call.inputs {
+                        Inputs::Task(inputs) => serde_json::to_string_pretty(inputs),
+                        Inputs::Workflow(inputs) => serde_json::to_string_pretty(inputs),
+                    }
+                    .context("failed to serialize call inputs")?;
+                    writeln!(stderr, "{json}")?;
+                }
+                "o" | "outputs" => {
+                    for (name, output) in call.ty.outputs() {
+                        writeln!(stderr, "{ty} {name}", ty = output.ty())?;
+                    }
+                }
+                "s" | "skip" => {
+                    let outputs = match parts.next() {
+                        Some(path) => match Self::read_outputs(Path::new(path)) {
+                            Ok(outputs) => outputs,
+                            Err(e) => {
+                                writeln!(stderr, "{error}: {e:#}", error = "error".red().bold())?;
+                                continue;
+                            }
+                        },
+                        None => Outputs::default(),
+                    };
+
+                    return Ok(CallAction::Skip(outputs));
+                }
+                "r" | "run" => {
+                    self.running.store(true, Ordering::SeqCst);
+                    return Ok(CallAction::Continue);
+                }
+                "q" | "quit" => bail!("evaluation was aborted by the debugger"),
+                "h" | "help" | "?" => writeln!(stderr, "{HELP}")?,
+                command => writeln!(
+                    stderr,
+                    "{error}: unknown command `{command}`; use `help` for a list of commands",
+                    error = "error".red().bold()
+                )?,
+            }
+        }
+    }
+}
+
+impl CallInterceptor for StepDebugger {
+    fn intercept<'a>(&'a self, call: InterceptedCall<'a>) -> BoxFuture<'a, Result<CallAction>> {
+        async move {
+            let _guard = self.lock.lock().await;
+            if self.running.load(Ordering::SeqCst) {
+                return Ok(CallAction::Continue);
+            }
+
+            // Reading from stdin blocks, so let the runtime know before prompting
+            tokio::task::block_in_place(|| suspend_tracing_indicatif(|| self.prompt(call)))
+        }
+        .boxed()
+    }
+}
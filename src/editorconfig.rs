@@ -0,0 +1,298 @@
+//! Support for reading formatting options from `.editorconfig` files.
+//!
+//! Only the properties relevant to formatting WDL documents are read:
+//! `indent_style`, `indent_size`, and `max_line_length`.
+//!
+//! See <https://editorconfig.org/> for the file format.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use anyhow::Result;
+use globset::GlobBuilder;
+use tracing::warn;
+use wdl::format::config::Indent;
+use wdl::format::config::MaxLineLength;
+
+/// The name of an editorconfig file.
+const EDITORCONFIG_FILENAME: &str = ".editorconfig";
+
+/// Represents a section of an editorconfig file.
+#[derive(Debug)]
+struct Section {
+    /// The glob of the section.
+    glob: String,
+    /// The properties of the section as lowercase key-value pairs.
+    properties: Vec<(String, String)>,
+}
+
+/// Represents a parsed editorconfig file.
+#[derive(Debug, Default)]
+struct EditorConfig {
+    /// Whether or not the file is the root editorconfig file.
+    root: bool,
+    /// The sections of the file.
+    sections: Vec<Section>,
+}
+
+impl EditorConfig {
+    /// Parses an editorconfig file from a string.
+    fn parse(contents: &str) -> Self {
+        let mut config = Self::default();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(glob) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                config.sections.push(Section {
+                    glob: glob.to_string(),
+                    properties: Vec::new(),
+                });
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+
+            let key = key.trim().to_lowercase();
+            let value = value.trim().to_lowercase();
+            match config.sections.last_mut() {
+                Some(section) => section.properties.push((key, value)),
+                None if key == "root" => config.root = value == "true",
+                None => {}
+            }
+        }
+
+        config
+    }
+}
+
+/// Determines if an editorconfig section glob matches the given path.
+///
+/// The path is relative to the directory containing the editorconfig file.
+fn matches(glob: &str, path: &Path) -> bool {
+    // Globs without a separator match files in any directory
+    let glob = match glob.strip_prefix('/') {
+        Some(glob) => glob.to_string(),
+        None if glob.contains('/') => glob.to_string(),
+        None => format!("**/{glob}"),
+    };
+
+    GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .build()
+        .map(|g| g.compile_matcher().is_match(path))
+        .unwrap_or(false)
+}
+
+/// Represents the formatting properties read from editorconfig files.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Properties {
+    /// Whether or not to indent with tabs.
+    pub use_tabs: Option<bool>,
+    /// The number of spaces to use for indentation levels.
+    pub indent_size: Option<usize>,
+    /// The maximum line length, where `0` means no maximum.
+    pub max_line_length: Option<usize>,
+}
+
+impl Properties {
+    /// Applies a property to the set of properties.
+    ///
+    /// Unknown properties and invalid values are ignored.
+    ///
+    /// Values outside of the range supported by the formatter are warned about
+    /// and reset the property to its default.
+    fn apply(&mut self, config_path: &Path, key: &str, value: &str) {
+        let out_of_range = || {
+            warn!(
+                "ignoring `{key} = {value}` in editorconfig file `{path}` as it is outside of the \
+                 supported range",
+                path = config_path.display()
+            );
+        };
+
+        match (key, value) {
+            ("indent_style", "tab") => self.use_tabs = Some(true),
+            ("indent_style", "space") => self.use_tabs = Some(false),
+            ("indent_size", "tab") => self.indent_size = None,
+            ("indent_size", size) => {
+                if let Ok(size) = size.parse() {
+                    if size > 0 && Indent::try_new(false, Some(size)).is_ok() {
+                        self.indent_size = Some(size);
+                    } else {
+                        out_of_range();
+                        self.indent_size = None;
+                    }
+                }
+            }
+            ("max_line_length", "off") => self.max_line_length = Some(0),
+            ("max_line_length", length) => {
+                if let Ok(length) = length.parse() {
+                    if MaxLineLength::try_new(length).is_ok() {
+                        self.max_line_length = Some(length);
+                    } else {
+                        out_of_range();
+                        self.max_line_length = None;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Reads the formatting properties that apply to the given file.
+    ///
+    /// Editorconfig files are read from the file's directory and each of its
+    /// parent directories until a file with `root = true` is found; properties
+    /// from closer files take precedence.
+    pub fn for_file(path: &Path) -> Result<Self> {
+        let mut configs = Vec::new();
+        for dir in path.ancestors().skip(1) {
+            let config_path = dir.join(EDITORCONFIG_FILENAME);
+            if !config_path.is_file() {
+                continue;
+            }
+
+            let contents = fs::read_to_string(&config_path).with_context(|| {
+                format!(
+                    "failed to read editorconfig file `{path}`",
+                    path = config_path.display()
+                )
+            })?;
+
+            let config = EditorConfig::parse(&contents);
+            let root = config.root;
+            configs.push((dir, config_path, config));
+            if root {
+                break;
+            }
+        }
+
+        let mut properties = Self::default();
+        for (dir, config_path, config) in configs.iter().rev() {
+            let Ok(relative) = path.strip_prefix(dir) else {
+                continue;
+            };
+
+            for section in &config.sections {
+                if matches(&section.glob, relative) {
+                    for (key, value) in &section.properties {
+                        properties.apply(config_path, key, value);
+                    }
+                }
+            }
+        }
+
+        Ok(properties)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn it_reads_properties() {
+        let root = tempfile::tempdir().expect("should create temporary directory");
+        let nested = root.path().join("nested");
+        fs::create_dir_all(&nested).unwrap();
+
+        fs::write(
+            root.path().join(EDITORCONFIG_FILENAME),
+            "root = true\n\n[*]\nindent_style = space\nindent_size = \
+             2\n\n[*.wdl]\nmax_line_length = 100\n",
+        )
+        .unwrap();
+        fs::write(
+            nested.join(EDITORCONFIG_FILENAME),
+            "# Nested settings\n[{foo,bar}.wdl]\nindent_style = tab\n\n[other/*.wdl]\nindent_size \
+             = 8\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            Properties::for_file(&root.path().join("a.wdl")).unwrap(),
+            Properties {
+                use_tabs: Some(false),
+                indent_size: Some(2),
+                max_line_length: Some(100),
+            }
+        );
+
+        assert_eq!(
+            Properties::for_file(&nested.join("foo.wdl")).unwrap(),
+            Properties {
+                use_tabs: Some(true),
+                indent_size: Some(2),
+                max_line_length: Some(100),
+            }
+        );
+
+        assert_eq!(
+            Properties::for_file(&nested.join("other").join("baz.wdl")).unwrap(),
+            Properties {
+                use_tabs: Some(false),
+                indent_size: Some(8),
+                max_line_length: Some(100),
+            }
+        );
+
+        assert_eq!(
+            Properties::for_file(&root.path().join("a.txt")).unwrap(),
+            Properties {
+                use_tabs: Some(false),
+                indent_size: Some(2),
+                max_line_length: None,
+            }
+        );
+    }
+
+    #[test]
+    fn it_ignores_out_of_range_values() {
+        let root = tempfile::tempdir().expect("should create temporary directory");
+        let nested = root.path().join("nested");
+        fs::create_dir_all(&nested).unwrap();
+
+        fs::write(
+            root.path().join(EDITORCONFIG_FILENAME),
+            "root = true\n\n[*]\nindent_size = 2\nmax_line_length = 100\n",
+        )
+        .unwrap();
+        fs::write(
+            nested.join(EDITORCONFIG_FILENAME),
+            "[*]\nindent_size = 0\nmax_line_length = 100000\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            Properties::for_file(&nested.join("a.wdl")).unwrap(),
+            Properties {
+                use_tabs: None,
+                indent_size: None,
+                max_line_length: None,
+            }
+        );
+
+        fs::write(
+            nested.join(EDITORCONFIG_FILENAME),
+            "[*]\nindent_size = 17\nmax_line_length = 59\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            Properties::for_file(&nested.join("a.wdl")).unwrap(),
+            Properties {
+                use_tabs: None,
+                indent_size: None,
+                max_line_length: None,
+            }
+        );
+    }
+}
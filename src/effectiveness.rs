@@ -0,0 +1,306 @@
+//! Implementation of lint rule effectiveness reports.
+//!
+//! An effectiveness report records the findings and `#@ except` comments of
+//! each lint rule in each document. When a previous report is available, the
+//! findings that have since gone away are attributed to being fixed or to
+//! being suppressed by new `#@ except` comments, and the totals are carried
+//! forward so that a report aggregates every run that contributed to it.
+//!
+//! The rules enabled for each document are recorded so that findings that go
+//! away because their rule was disabled are not attributed to either.
+//!
+//! Reports are only ever written to the local file system.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use anyhow::Context as _;
+use anyhow::Result;
+use anyhow::bail;
+use serde::Deserialize;
+use serde::Serialize;
+use wdl::analysis::EXCEPT_COMMENT_PREFIX;
+use wdl::ast::SyntaxKind;
+use wdl::ast::SyntaxNode;
+
+/// The version of the effectiveness report format.
+const REPORT_VERSION: u32 = 1;
+
+/// Represents the counts of a rule in a single document.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct DocumentCounts {
+    /// The number of findings of the rule.
+    findings: usize,
+    /// The number of `#@ except` comments for the rule.
+    exceptions: usize,
+}
+
+/// Represents the summary of a rule across all documents.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct RuleSummary {
+    /// The current number of findings of the rule.
+    findings: usize,
+    /// The current number of `#@ except` comments for the rule.
+    exceptions: usize,
+    /// The number of findings fixed since the previous report.
+    fixed: usize,
+    /// The number of findings suppressed since the previous report.
+    suppressed: usize,
+    /// The number of findings fixed across all reports.
+    total_fixed: usize,
+    /// The number of findings suppressed across all reports.
+    total_suppressed: usize,
+    /// The fraction of resolved findings that were fixed rather than
+    /// suppressed across all reports.
+    ///
+    /// This is `None` if no findings have been resolved.
+    fix_rate: Option<f64>,
+}
+
+/// Represents a lint rule effectiveness report.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EffectivenessReport {
+    /// The version of the report format.
+    version: u32,
+    /// The number of runs aggregated by the report.
+    runs: usize,
+    /// The summary of each rule, keyed by rule identifier.
+    rules: BTreeMap<String, RuleSummary>,
+    /// The counts of each rule in each document, keyed by document path and
+    /// then rule identifier.
+    documents: BTreeMap<String, BTreeMap<String, DocumentCounts>>,
+    /// The identifiers of the rules enabled for each document, keyed by
+    /// document path.
+    enabled: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl EffectivenessReport {
+    /// Reads a report from the given path.
+    ///
+    /// Returns `Ok(None)` if the file does not exist.
+    pub fn read(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read `{path}`", path = path.display()))?;
+        let report: Self = serde_json::from_str(&contents).with_context(|| {
+            format!(
+                "failed to parse effectiveness report `{path}`",
+                path = path.display()
+            )
+        })?;
+
+        if report.version != REPORT_VERSION {
+            bail!(
+                "effectiveness report `{path}` has unsupported version {version}",
+                path = path.display(),
+                version = report.version
+            );
+        }
+
+        Ok(Some(report))
+    }
+
+    /// Adds a linted document to the report.
+    ///
+    /// The given rule identifiers are the rules of the document's findings;
+    /// `#@ except` comments are counted for the rules in `rules`. The rules in
+    /// `enabled` are the rules that were run on the document.
+    pub fn add_document<'a>(
+        &mut self,
+        path: &str,
+        root: &SyntaxNode,
+        findings: impl IntoIterator<Item = &'a str>,
+        rules: &[&str],
+        enabled: impl IntoIterator<Item = &'a str>,
+    ) {
+        self.enabled.insert(
+            path.to_string(),
+            enabled.into_iter().map(str::to_string).collect(),
+        );
+
+        let counts = self.documents.entry(path.to_string()).or_default();
+        for rule in findings {
+            counts.entry(rule.to_string()).or_default().findings += 1;
+        }
+
+        for token in root
+            .descendants_with_tokens()
+            .filter_map(|e| e.into_token())
+            .filter(|t| t.kind() == SyntaxKind::Comment)
+        {
+            let Some(ids) = token.text().strip_prefix(EXCEPT_COMMENT_PREFIX) else {
+                continue;
+            };
+
+            for id in ids.split(',').map(str::trim) {
+                if rules.contains(&id) {
+                    counts.entry(id.to_string()).or_default().exceptions += 1;
+                }
+            }
+        }
+    }
+
+    /// Summarizes the report by comparing it with a previous report.
+    ///
+    /// Only documents in both reports are compared for the rules enabled for
+    /// the document in both: a finding that has gone away is suppressed if a
+    /// new `#@ except` comment was added for its rule in the same document and
+    /// is fixed otherwise.
+    pub fn summarize(&mut self, previous: Option<&Self>) {
+        self.version = REPORT_VERSION;
+        self.runs = previous.map(|p| p.runs).unwrap_or_default() + 1;
+        self.rules.clear();
+
+        for (rule, counts) in self.documents.values().flat_map(|d| d.iter()) {
+            let summary = self.rules.entry(rule.clone()).or_default();
+            summary.findings += counts.findings;
+            summary.exceptions += counts.exceptions;
+        }
+
+        if let Some(previous) = previous {
+            for (path, current) in &self.documents {
+                let Some(before) = previous.documents.get(path) else {
+                    continue;
+                };
+
+                let enabled = |report: &Self| report.enabled.get(path).cloned().unwrap_or_default();
+                let (enabled_before, enabled_now) = (enabled(previous), enabled(self));
+                for (rule, before) in before {
+                    if !enabled_before.contains(rule) || !enabled_now.contains(rule) {
+                        continue;
+                    }
+
+                    let now = current.get(rule).copied().unwrap_or_default();
+                    let resolved = before.findings.saturating_sub(now.findings);
+                    let suppressed = resolved.min(now.exceptions.saturating_sub(before.exceptions));
+
+                    let summary = self.rules.entry(rule.clone()).or_default();
+                    summary.fixed += resolved - suppressed;
+                    summary.suppressed += suppressed;
+                }
+            }
+
+            // Carry forward the totals of rules that no longer have findings
+            for rule in previous.rules.keys() {
+                self.rules.entry(rule.clone()).or_default();
+            }
+        }
+
+        for (rule, summary) in &mut self.rules {
+            let before = previous.and_then(|p| p.rules.get(rule));
+            summary.total_fixed = before.map(|s| s.total_fixed).unwrap_or_default() + summary.fixed;
+            summary.total_suppressed =
+                before.map(|s| s.total_suppressed).unwrap_or_default() + summary.suppressed;
+
+            let resolved = summary.total_fixed + summary.total_suppressed;
+            summary.fix_rate =
+                (resolved > 0).then_some(summary.total_fixed as f64 / resolved as f64);
+        }
+    }
+
+    /// Writes the report to the given path.
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let contents = serde_json::to_string_pretty(self)
+            .context("failed to serialize effectiveness report")?;
+        std::fs::write(path, contents)
+            .with_context(|| format!("failed to write `{path}`", path = path.display()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Creates a report from the given document counts.
+    fn report(documents: &[(&str, &str, usize, usize)]) -> EffectivenessReport {
+        let mut report = EffectivenessReport::default();
+        for (path, rule, findings, exceptions) in documents {
+            report
+                .enabled
+                .entry(path.to_string())
+                .or_default()
+                .extend(["Foo", "Bar", "Baz"].map(str::to_string));
+            report
+                .documents
+                .entry(path.to_string())
+                .or_default()
+                .insert(
+                    rule.to_string(),
+                    DocumentCounts {
+                        findings: *findings,
+                        exceptions: *exceptions,
+                    },
+                );
+        }
+
+        report
+    }
+
+    #[test]
+    fn fixed_and_suppressed() {
+        let mut previous = report(&[("a.wdl", "Foo", 3, 0), ("b.wdl", "Bar", 2, 0)]);
+        previous.summarize(None);
+        assert_eq!(previous.runs, 1);
+        assert_eq!(previous.rules["Foo"].findings, 3);
+        assert_eq!(previous.rules["Foo"].fix_rate, None);
+
+        // One `Foo` finding is suppressed and another is fixed; the `Bar`
+        // findings are fixed
+        let mut current = report(&[("a.wdl", "Foo", 1, 1), ("b.wdl", "Baz", 1, 0)]);
+        current.summarize(Some(&previous));
+        assert_eq!(current.runs, 2);
+
+        let foo = &current.rules["Foo"];
+        assert_eq!((foo.findings, foo.exceptions), (1, 1));
+        assert_eq!((foo.fixed, foo.suppressed), (1, 1));
+        assert_eq!(foo.fix_rate, Some(0.5));
+
+        let bar = &current.rules["Bar"];
+        assert_eq!((bar.findings, bar.fixed, bar.suppressed), (0, 2, 0));
+        assert_eq!(bar.fix_rate, Some(1.0));
+
+        let baz = &current.rules["Baz"];
+        assert_eq!((baz.findings, baz.fixed, baz.suppressed), (1, 0, 0));
+
+        // Totals are carried forward even if nothing changes
+        let mut next = report(&[("a.wdl", "Foo", 1, 1), ("b.wdl", "Baz", 1, 0)]);
+        next.summarize(Some(&current));
+        assert_eq!(next.runs, 3);
+        assert_eq!(next.rules["Bar"].total_fixed, 2);
+        assert_eq!(next.rules["Foo"].total_suppressed, 1);
+        assert_eq!(next.rules["Foo"].fixed, 0);
+    }
+
+    #[test]
+    fn missing_documents() {
+        // Documents that are no longer linted are not counted as fixed
+        let mut previous = report(&[("a.wdl", "Foo", 3, 0)]);
+        previous.summarize(None);
+
+        let mut current = report(&[("b.wdl", "Foo", 1, 0)]);
+        current.summarize(Some(&previous));
+        assert_eq!(current.rules["Foo"].fixed, 0);
+        assert_eq!(current.rules["Foo"].findings, 1);
+    }
+
+    #[test]
+    fn disabled_rules() {
+        // Findings of a rule that is no longer enabled are not counted as fixed
+        let mut previous = report(&[("a.wdl", "Foo", 3, 0), ("a.wdl", "Bar", 2, 0)]);
+        previous.summarize(None);
+
+        let mut current = report(&[("a.wdl", "Bar", 1, 0)]);
+        current
+            .enabled
+            .insert("a.wdl".to_string(), BTreeSet::from(["Bar".to_string()]));
+        current.summarize(Some(&previous));
+        assert_eq!(current.rules["Foo"].fixed, 0);
+        assert_eq!(current.rules["Bar"].fixed, 1);
+    }
+}
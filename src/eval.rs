@@ -1,10 +1,13 @@
 //! Facilities for performing a typical WDL evaluation using the `wdl-*` crates.
 
 use std::path::Path;
+use std::sync::Arc;
 
 use anyhow::anyhow;
 use wdl::analysis::Document;
+use wdl::engine::CallInterceptor;
 use wdl::engine::CancellationContext;
+use wdl::engine::CoverageRegistry;
 use wdl::engine::EvaluatedTask;
 use wdl::engine::EvaluationError;
 use wdl::engine::EvaluationResult;
@@ -35,6 +38,15 @@ pub struct Evaluator<'a> {
 
     /// The output directory.
     output_dir: &'a Path,
+
+    /// The interceptor to invoke prior to evaluating workflow calls.
+    interceptor: Option<Arc<dyn CallInterceptor>>,
+
+    /// The name of the workflow call to evaluate up to.
+    until: Option<String>,
+
+    /// The registry to record evaluation coverage into.
+    coverage: Option<Arc<CoverageRegistry>>,
 }
 
 impl<'a> Evaluator<'a> {
@@ -54,9 +66,33 @@ impl<'a> Evaluator<'a> {
             origins,
             config,
             output_dir,
+            interceptor: None,
+            until: None,
+            coverage: None,
         }
     }
 
+    /// Sets the interceptor to invoke prior to evaluating each workflow call.
+    pub fn with_call_interceptor(mut self, interceptor: Arc<dyn CallInterceptor>) -> Self {
+        self.interceptor = Some(interceptor);
+        self
+    }
+
+    /// Sets the name of the workflow call to evaluate up to.
+    ///
+    /// Only the calls that the named call depends on are evaluated and the
+    /// outputs of the named call are returned.
+    pub fn with_until(mut self, call: impl Into<String>) -> Self {
+        self.until = Some(call.into());
+        self
+    }
+
+    /// Sets the registry to record evaluation coverage into.
+    pub fn with_coverage(mut self, coverage: Arc<CoverageRegistry>) -> Self {
+        self.coverage = Some(coverage);
+        self
+    }
+
     /// Runs a WDL task or workflow evaluation.
     pub async fn run(
         mut self,
@@ -72,6 +108,14 @@ impl<'a> Evaluator<'a> {
                     )
                 })?;
 
+                if self.until.is_some() {
+                    return Err(anyhow!(
+                        "task `{name}` cannot be evaluated up to a call as it is not a workflow",
+                        name = self.name
+                    )
+                    .into());
+                }
+
                 // Ensure all the paths specified in the inputs are relative to
                 // their respective origin paths.
                 inputs
@@ -82,9 +126,13 @@ impl<'a> Evaluator<'a> {
                     })
                     .await?;
 
-                let evaluator =
+                let mut evaluator =
                     TopLevelEvaluator::new(self.output_dir, self.config, cancellation, events)
                         .await?;
+                if let Some(coverage) = self.coverage {
+                    evaluator = evaluator.with_coverage(coverage);
+                }
+
                 evaluator
                     .evaluate_task(self.document, task, inputs, self.output_dir)
                     .await
@@ -113,12 +161,29 @@ impl<'a> Evaluator<'a> {
                     })
                     .await?;
 
-                let evaluator =
+                let mut evaluator =
                     TopLevelEvaluator::new(self.output_dir, self.config, cancellation, events)
                         .await?;
-                evaluator
-                    .evaluate_workflow(self.document, inputs, self.output_dir)
-                    .await
+                if let Some(interceptor) = self.interceptor {
+                    evaluator = evaluator.with_call_interceptor(interceptor);
+                }
+
+                if let Some(coverage) = self.coverage {
+                    evaluator = evaluator.with_coverage(coverage);
+                }
+
+                match &self.until {
+                    Some(call) => {
+                        evaluator
+                            .evaluate_workflow_until(self.document, inputs, self.output_dir, call)
+                            .await
+                    }
+                    None => {
+                        evaluator
+                            .evaluate_workflow(self.document, inputs, self.output_dir)
+                            .await
+                    }
+                }
             }
         }
     }
@@ -0,0 +1,144 @@
+//! A minimal HTTP/1.1 responder for the JSON endpoints served by sprocket.
+//!
+//! Each connection serves a single request: the head of the request is read,
+//! a response is written with `Connection: close`, and the connection is
+//! closed. Request bodies are not read.
+
+use std::io;
+
+use tokio::io::AsyncRead;
+use tokio::io::AsyncReadExt as _;
+
+/// The maximum size of the head of a request that is read.
+const MAX_HEAD_SIZE: usize = 8 * 1024;
+
+/// Represents the request line of an HTTP request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// The method of the request (e.g. `GET`).
+    pub method: String,
+    /// The path of the request target, without its query.
+    pub path: String,
+}
+
+impl Request {
+    /// Parses a request from the head of an HTTP request.
+    ///
+    /// Returns `None` if the request line is malformed.
+    fn parse(head: &str) -> Option<Self> {
+        let mut parts = head.lines().next()?.split_whitespace();
+        let method = parts.next()?;
+        let target = parts.next()?;
+        if !parts.next()?.starts_with("HTTP/") {
+            return None;
+        }
+
+        Some(Self {
+            method: method.to_string(),
+            path: target
+                .split_once('?')
+                .map_or(target, |(path, _)| path)
+                .to_string(),
+        })
+    }
+}
+
+/// Reads the head of an HTTP request from a stream.
+///
+/// The stream is read until the blank line that ends the head, so a request
+/// that arrives across multiple reads is parsed whole.
+///
+/// Returns `Ok(None)` if the stream ends, the head exceeds the maximum size,
+/// or the request line is malformed.
+pub async fn read_request(stream: &mut (impl AsyncRead + Unpin)) -> io::Result<Option<Request>> {
+    let mut head = Vec::new();
+    let mut buffer = [0; 1024];
+    loop {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+
+        // Only the bytes that could complete the terminator need to be searched
+        let start = head.len().saturating_sub(3);
+        head.extend_from_slice(&buffer[..read]);
+        if let Some(end) = head[start..].windows(4).position(|w| w == b"\r\n\r\n") {
+            head.truncate(start + end);
+            return Ok(Request::parse(&String::from_utf8_lossy(&head)));
+        }
+
+        if head.len() > MAX_HEAD_SIZE {
+            return Ok(None);
+        }
+    }
+}
+
+/// Formats an HTTP response with a JSON body.
+pub fn response(code: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {code}\r\nContent-Type: application/json\r\nContent-Length: \
+         {len}\r\nConnection: close\r\n\r\n{body}",
+        len = body.len()
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn reads_requests_split_across_reads() {
+        let (mut client, mut server) = tokio::io::duplex(64);
+        let writer = tokio::spawn(async move {
+            use tokio::io::AsyncWriteExt as _;
+
+            for part in [
+                "GET /api/wor",
+                "kflows/v1/run1/status?x=1 HTTP/1.1\r\nHost: loc",
+                "al\r\n\r\n",
+            ] {
+                client.write_all(part.as_bytes()).await.unwrap();
+                client.flush().await.unwrap();
+                tokio::task::yield_now().await;
+            }
+
+            client
+        });
+
+        let request = read_request(&mut server).await.unwrap().unwrap();
+        assert_eq!(
+            request,
+            Request {
+                method: "GET".to_string(),
+                path: "/api/workflows/v1/run1/status".to_string(),
+            }
+        );
+        drop(writer.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn rejects_incomplete_and_malformed_requests() {
+        let mut incomplete: &[u8] = b"GET /status HTTP/1.1\r\n";
+        assert_eq!(read_request(&mut incomplete).await.unwrap(), None);
+
+        let mut malformed: &[u8] = b"GET\r\n\r\n";
+        assert_eq!(read_request(&mut malformed).await.unwrap(), None);
+
+        let oversized = format!(
+            "GET /{path} HTTP/1.1\r\n\r\n",
+            path = "a".repeat(MAX_HEAD_SIZE)
+        );
+        assert_eq!(read_request(&mut oversized.as_bytes()).await.unwrap(), None);
+    }
+
+    #[test]
+    fn responses() {
+        assert_eq!(
+            response("200 OK", "{}"),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: \
+             2\r\nConnection: close\r\n\r\n{}"
+        );
+    }
+}
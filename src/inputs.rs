@@ -13,6 +13,7 @@ use wdl::engine::Inputs as EngineInputs;
 use wdl::engine::LocatedJsonValue;
 use wdl::engine::path::EvaluationPath;
 
+pub mod batch;
 pub mod file;
 pub mod origin_paths;
 
@@ -223,6 +224,28 @@ impl Invocation {
         &mut self.inputs
     }
 
+    /// Gets the name of the task or workflow the inputs are for.
+    ///
+    /// If an entrypoint was not given when coalescing, it is derived from the
+    /// prefix shared by every input key, as it is when converting to an
+    /// [`EngineInvocation`](Self::into_engine_invocation).
+    ///
+    /// Returns `None` if there are no inputs or the keys do not share a prefix.
+    pub fn entrypoint(&self) -> Option<String> {
+        if let Some(entrypoint) = &self.entrypoint {
+            return Some(entrypoint.clone());
+        }
+
+        let mut prefixes = self
+            .inputs
+            .keys()
+            .map(|key| key.split_once('.').map(|(prefix, _)| prefix));
+        let first = prefixes.next()??;
+        prefixes
+            .all(|prefix| prefix == Some(first))
+            .then(|| first.to_string())
+    }
+
     /// Converts an [`EngineInvocation`] for the given [`Invocation`]
     ///
     /// Returns `Ok(Some(_))` if the inputs are not empty.
@@ -534,6 +557,29 @@ mod tests {
         check_cannot_coalesce_string("cannot coalesce string with }").await;
     }
 
+    #[tokio::test]
+    async fn entrypoint() {
+        let invocation = Invocation::coalesce(["foo.bar=1", "foo.baz.qux=2"], None)
+            .await
+            .unwrap();
+        assert_eq!(invocation.entrypoint().as_deref(), Some("foo"));
+
+        let invocation = Invocation::coalesce(["bar=1"], Some("foo".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(invocation.entrypoint().as_deref(), Some("foo"));
+
+        let invocation = Invocation::coalesce(["foo.bar=1", "baz.qux=2"], None)
+            .await
+            .unwrap();
+        assert_eq!(invocation.entrypoint(), None);
+
+        let invocation = Invocation::coalesce(Vec::<&str>::new(), None)
+            .await
+            .unwrap();
+        assert_eq!(invocation.entrypoint(), None);
+    }
+
     #[test]
     fn multiple_equal_signs() {
         let (key, value) = r#"foo="bar=baz""#.parse::<Input>().unwrap().unwrap_pair();
@@ -0,0 +1,560 @@
+//! Batches of input sets read from a sample sheet.
+
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use regex::Captures;
+use regex::Regex;
+use serde_json::Map as JsonMap;
+use serde_json::Value as JsonValue;
+use thiserror::Error;
+use wdl::analysis::Document;
+use wdl::analysis::types::PrimitiveType;
+use wdl::analysis::types::Type;
+use wdl::engine::LocatedJsonValue;
+use wdl::engine::path::EvaluationPath;
+
+use super::JsonInputMap;
+
+/// The name of the column that names each run of a batch.
+///
+/// This column is not passed to the runs as an input.
+pub const ID_COLUMN: &str = "id";
+
+/// A regex that matches a reference to a column in a templated value.
+static TEMPLATE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    // SAFETY: this is checked statically with tests to always unwrap.
+    Regex::new(r"\$\{([^}]*)\}").unwrap()
+});
+
+/// An error related to a batch of inputs.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Failed to read the batch file.
+    #[error("failed to read inputs batch `{path}`: {error}", path = .path.display())]
+    Io {
+        /// The path to the batch file.
+        path: PathBuf,
+        /// The I/O error that occurred.
+        error: std::io::Error,
+    },
+
+    /// The batch file has no header row.
+    #[error("inputs batch `{0}` is missing a header row", .0.display())]
+    MissingHeader(PathBuf),
+
+    /// A row has a different number of columns than the header.
+    #[error("row {row} of the inputs batch has {actual} column(s) but the header has {expected}")]
+    ColumnCount {
+        /// The one-based row number, excluding the header.
+        row: usize,
+        /// The number of columns in the header.
+        expected: usize,
+        /// The number of columns in the row.
+        actual: usize,
+    },
+
+    /// A header column is empty or duplicated.
+    #[error("inputs batch column `{0}` is empty or duplicated")]
+    InvalidColumn(String),
+
+    /// A templated value references an unknown column.
+    #[error("row {row} of the inputs batch references unknown column `{column}`")]
+    UnknownColumn {
+        /// The one-based row number, excluding the header.
+        row: usize,
+        /// The name of the referenced column.
+        column: String,
+    },
+
+    /// A column is not prefixed with the name of a task or workflow and the
+    /// entrypoint could not be determined.
+    #[error(
+        "inputs batch column `{0}` is not prefixed with the name of a task or workflow: use the \
+         `--entrypoint` option to specify the task or workflow to run"
+    )]
+    UnqualifiedColumn(String),
+
+    /// A run identifier is invalid or duplicated.
+    #[error(
+        "run identifier `{0}` of the inputs batch is invalid or duplicated: identifiers must be \
+         unique and cannot be empty or contain path separators"
+    )]
+    InvalidId(String),
+
+    /// A value could not be coerced to the declared type of its input.
+    #[error("invalid value for input `{key}`: {reason}")]
+    InvalidValue {
+        /// The key of the input.
+        key: String,
+        /// The reason the value is invalid.
+        reason: String,
+    },
+
+    /// The JSON batch file is not an array of objects.
+    #[error("a JSON inputs batch must be an array of objects")]
+    NonArrayRoot,
+
+    /// An error occurring in [`serde_json`].
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+/// A [`Result`](std::result::Result) with an [`Error`](enum@self::Error).
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The inputs of a run of a batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum RunInputs {
+    /// The values of a row of tab-separated values.
+    ///
+    /// The keys are not prefixed with the name of the task or workflow and the
+    /// values are coerced to the declared types of their inputs.
+    Row(Vec<(String, String)>),
+    /// An inputs object.
+    ///
+    /// The keys are prefixed with the name of the task or workflow as in an
+    /// inputs file.
+    Object(JsonMap<String, JsonValue>),
+}
+
+/// A run of a batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchRun {
+    /// The identifier of the run.
+    id: String,
+    /// The inputs of the run.
+    inputs: RunInputs,
+    /// The directory that relative paths in the inputs are resolved against.
+    origin: PathBuf,
+}
+
+impl BatchRun {
+    /// Gets the identifier of the run.
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Gets the inputs of the run as located JSON values for the given
+    /// document.
+    ///
+    /// If `entrypoint` is `Some(_)`, it is prefixed to the keys of inputs read
+    /// from tab-separated values; otherwise, each column must be prefixed with
+    /// the name of the task or workflow. Values read from tab-separated values
+    /// are coerced to the declared type of their input (see [`coerce_value`]).
+    pub fn to_inputs(&self, document: &Document, entrypoint: Option<&str>) -> Result<JsonInputMap> {
+        let located = |value| LocatedJsonValue {
+            origin: EvaluationPath::Local(self.origin.clone()),
+            value,
+        };
+
+        match &self.inputs {
+            RunInputs::Row(values) => values
+                .iter()
+                .map(|(key, value)| {
+                    let key = match entrypoint {
+                        Some(entrypoint) => format!("{entrypoint}.{key}"),
+                        None if key.contains('.') => key.clone(),
+                        None => return Err(Error::UnqualifiedColumn(key.clone())),
+                    };
+
+                    let value = coerce_value(value, input_type(document, &key).as_ref()).map_err(
+                        |reason| Error::InvalidValue {
+                            key: key.clone(),
+                            reason,
+                        },
+                    )?;
+                    Ok((key, located(value)))
+                })
+                .collect(),
+            RunInputs::Object(object) => Ok(object
+                .iter()
+                .map(|(key, value)| (key.clone(), located(value.clone())))
+                .collect()),
+        }
+    }
+}
+
+/// Gets the declared type of the input with the given qualified key.
+///
+/// Returns `None` if the key does not name an input of a task or workflow in
+/// the document (e.g. for a nested input of a call).
+fn input_type(document: &Document, key: &str) -> Option<Type> {
+    let (callee, name) = key.split_once('.')?;
+    let inputs = match (document.task_by_name(callee), document.workflow()) {
+        (Some(task), _) => task.inputs(),
+        (None, Some(workflow)) if workflow.name() == callee => workflow.inputs(),
+        _ => return None,
+    };
+
+    inputs.get(name).map(|input| input.ty().clone())
+}
+
+/// Coerces a value of a sample sheet to the JSON representation of an input of
+/// the given type.
+///
+/// Values of compound types are parsed as JSON. If the type of the input is
+/// unknown (e.g. for a nested input), the value is parsed as JSON if possible
+/// and is otherwise treated as a string.
+pub fn coerce_value(value: &str, ty: Option<&Type>) -> std::result::Result<JsonValue, String> {
+    match ty {
+        Some(Type::Primitive(PrimitiveType::Boolean, _)) => value
+            .parse::<bool>()
+            .map(JsonValue::Bool)
+            .map_err(|_| format!("expected a `Boolean` value but found `{value}`")),
+        Some(Type::Primitive(PrimitiveType::Integer, _)) => value
+            .parse::<i64>()
+            .map(JsonValue::from)
+            .map_err(|_| format!("expected an `Int` value but found `{value}`")),
+        Some(Type::Primitive(PrimitiveType::Float, _)) => value
+            .parse::<f64>()
+            .map(JsonValue::from)
+            .map_err(|_| format!("expected a `Float` value but found `{value}`")),
+        Some(ty @ (Type::Compound(..) | Type::Object | Type::OptionalObject)) => {
+            serde_json::from_str(value).map_err(|e| {
+                format!("expected a JSON value of type `{ty}` but found `{value}`: {e}")
+            })
+        }
+        Some(_) => Ok(JsonValue::String(value.to_string())),
+        None => Ok(serde_json::from_str(value).unwrap_or_else(|_| JsonValue::from(value))),
+    }
+}
+
+/// Gets the directory that relative paths in a batch file are resolved
+/// against.
+fn origin(path: &Path) -> Result<PathBuf> {
+    let absolute = std::path::absolute(path).map_err(|error| Error::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+
+    Ok(absolute.parent().map(Path::to_path_buf).unwrap_or(absolute))
+}
+
+/// Expands the column references (e.g. `${sample}`) in a value.
+fn expand(value: &str, row: usize, columns: &[&str], values: &[&str]) -> Result<String> {
+    let mut error = None;
+    let expanded = TEMPLATE_REGEX.replace_all(value, |captures: &Captures<'_>| {
+        let column = &captures[1];
+        match columns.iter().position(|c| *c == column) {
+            Some(index) => values[index].to_string(),
+            None => {
+                error.get_or_insert_with(|| Error::UnknownColumn {
+                    row,
+                    column: column.to_string(),
+                });
+                String::new()
+            }
+        }
+    });
+
+    match error {
+        Some(e) => Err(e),
+        None => Ok(expanded.into_owned()),
+    }
+}
+
+/// Parses a table of tab-separated values.
+///
+/// Returns the names of the columns from the header row and the values of the
+/// remaining rows.
+///
+/// Values may reference the value of another column in the same row with
+/// `${column}`; the references are expanded in the returned rows.
+pub fn parse_table(path: &Path, contents: &str) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+    let mut lines = contents
+        .lines()
+        .map(|l| l.strip_suffix('\r').unwrap_or(l))
+        .filter(|l| !l.trim().is_empty());
+
+    let header = lines
+        .next()
+        .ok_or_else(|| Error::MissingHeader(path.to_path_buf()))?;
+    let columns: Vec<_> = header.split('\t').map(str::trim).collect();
+
+    let mut seen = HashSet::new();
+    for column in &columns {
+        if column.is_empty() || !seen.insert(*column) {
+            return Err(Error::InvalidColumn(column.to_string()));
+        }
+    }
+
+    let mut rows = Vec::new();
+    for (i, line) in lines.enumerate() {
+        let row = i + 1;
+        let values: Vec<_> = line.split('\t').map(str::trim).collect();
+        if values.len() != columns.len() {
+            return Err(Error::ColumnCount {
+                row,
+                expected: columns.len(),
+                actual: values.len(),
+            });
+        }
+
+        rows.push(
+            values
+                .iter()
+                .map(|v| expand(v, row, &columns, &values))
+                .collect::<Result<_>>()?,
+        );
+    }
+
+    Ok((columns.into_iter().map(str::to_string).collect(), rows))
+}
+
+/// Validates the identifier of a run.
+fn validate_id(id: String, ids: &mut HashSet<String>) -> Result<String> {
+    if id.is_empty()
+        || id.contains(['/', '\\'])
+        || id == "."
+        || id == ".."
+        || !ids.insert(id.clone())
+    {
+        return Err(Error::InvalidId(id));
+    }
+
+    Ok(id)
+}
+
+/// Parses a batch from tab-separated values.
+///
+/// The header row names the input of each column; the remaining rows are the
+/// runs of the batch. If a column is named [`ID_COLUMN`], it identifies each
+/// run; otherwise, runs are identified by their one-based row number.
+///
+/// See [`parse_table`] for how values may reference other columns. Empty
+/// values are not passed to the run so that the input's default is used.
+///
+/// Relative paths in the values are resolved against the directory containing
+/// the batch file.
+pub fn parse_batch(path: &Path, contents: &str) -> Result<Vec<BatchRun>> {
+    let (columns, rows) = parse_table(path, contents)?;
+    let origin = origin(path)?;
+    let id_column = columns.iter().position(|c| c == ID_COLUMN);
+    let mut ids = HashSet::new();
+    let mut runs = Vec::with_capacity(rows.len());
+    for (i, values) in rows.into_iter().enumerate() {
+        let id = match id_column {
+            Some(index) => values[index].clone(),
+            None => (i + 1).to_string(),
+        };
+
+        let inputs = columns
+            .iter()
+            .zip(&values)
+            .enumerate()
+            .filter(|(index, (_, value))| Some(*index) != id_column && !value.is_empty())
+            .map(|(_, (column, value))| (column.clone(), value.clone()))
+            .collect();
+
+        runs.push(BatchRun {
+            id: validate_id(id, &mut ids)?,
+            inputs: RunInputs::Row(inputs),
+            origin: origin.clone(),
+        });
+    }
+
+    Ok(runs)
+}
+
+/// Parses a batch from a JSON array of inputs objects.
+///
+/// Runs are identified by their one-based index in the array.
+///
+/// Relative paths in the inputs are resolved against the directory containing
+/// the batch file.
+pub fn parse_json_batch(path: &Path, contents: &str) -> Result<Vec<BatchRun>> {
+    let origin = origin(path)?;
+    let JsonValue::Array(elements) = serde_json::from_str(contents)? else {
+        return Err(Error::NonArrayRoot);
+    };
+
+    elements
+        .into_iter()
+        .enumerate()
+        .map(|(i, element)| {
+            let JsonValue::Object(object) = element else {
+                return Err(Error::NonArrayRoot);
+            };
+
+            Ok(BatchRun {
+                id: (i + 1).to_string(),
+                inputs: RunInputs::Object(object),
+                origin: origin.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Reads a batch from a file.
+///
+/// A file with a `.json` extension contains a JSON array of inputs objects
+/// (see [`parse_json_batch`]); any other file contains tab-separated values
+/// (see [`parse_batch`]).
+pub fn read_batch(path: &Path) -> Result<Vec<BatchRun>> {
+    let contents = std::fs::read_to_string(path).map_err(|error| Error::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+
+    if path.extension().and_then(OsStr::to_str) == Some("json") {
+        return parse_json_batch(path, &contents);
+    }
+
+    parse_batch(path, &contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn template_regex() {
+        // Ensures the regex unwraps.
+        LazyLock::force(&TEMPLATE_REGEX);
+    }
+
+    #[test]
+    fn batch_runs() {
+        let origin = std::env::current_dir().unwrap().join("sheets");
+        let runs = parse_batch(
+            Path::new("sheets/samples.tsv"),
+            "id\tsample\treads\tthreads\nA\tfoo\tdata/${sample}.fq\t4\nB\tbar\tdata/${id}.fq\t\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            runs,
+            [
+                BatchRun {
+                    id: "A".into(),
+                    inputs: RunInputs::Row(vec![
+                        ("sample".into(), "foo".into()),
+                        ("reads".into(), "data/foo.fq".into()),
+                        ("threads".into(), "4".into())
+                    ]),
+                    origin: origin.clone(),
+                },
+                BatchRun {
+                    id: "B".into(),
+                    inputs: RunInputs::Row(vec![
+                        ("sample".into(), "bar".into()),
+                        ("reads".into(), "data/B.fq".into())
+                    ]),
+                    origin,
+                },
+            ]
+        );
+
+        let runs = parse_batch(Path::new("samples.tsv"), "sample\nfoo\nbar\n").unwrap();
+        assert_eq!(runs[0].id(), "1");
+        assert_eq!(runs[1].id(), "2");
+    }
+
+    #[test]
+    fn coerced_values() {
+        let ty = |ty| Type::Primitive(ty, false);
+        assert_eq!(
+            coerce_value("123", Some(&ty(PrimitiveType::String))).unwrap(),
+            JsonValue::from("123")
+        );
+        assert_eq!(
+            coerce_value("true", Some(&ty(PrimitiveType::File))).unwrap(),
+            JsonValue::from("true")
+        );
+        assert_eq!(
+            coerce_value("null", Some(&ty(PrimitiveType::String))).unwrap(),
+            JsonValue::from("null")
+        );
+        assert_eq!(
+            coerce_value("123", Some(&ty(PrimitiveType::Integer))).unwrap(),
+            JsonValue::from(123)
+        );
+        assert_eq!(
+            coerce_value("1.5", Some(&ty(PrimitiveType::Float))).unwrap(),
+            JsonValue::from(1.5)
+        );
+        assert_eq!(
+            coerce_value("false", Some(&ty(PrimitiveType::Boolean))).unwrap(),
+            JsonValue::Bool(false)
+        );
+        assert_eq!(coerce_value("123", None).unwrap(), JsonValue::from(123));
+        assert_eq!(coerce_value("foo", None).unwrap(), JsonValue::from("foo"));
+        assert!(coerce_value("foo", Some(&ty(PrimitiveType::Integer))).is_err());
+        assert!(coerce_value("yes", Some(&ty(PrimitiveType::Boolean))).is_err());
+    }
+
+    #[test]
+    fn json_batch_runs() {
+        let origin = std::env::current_dir().unwrap().join("batches");
+        let runs = parse_json_batch(
+            Path::new("batches/batch.json"),
+            r#"[{"wf.sample": "a=b", "wf.threads": 4, "wf.name": "123"}, {}]"#,
+        )
+        .unwrap();
+
+        let object = |value: JsonValue| value.as_object().unwrap().clone();
+        assert_eq!(
+            runs,
+            [
+                BatchRun {
+                    id: "1".into(),
+                    inputs: RunInputs::Object(object(serde_json::json!({
+                        "wf.sample": "a=b",
+                        "wf.threads": 4,
+                        "wf.name": "123",
+                    }))),
+                    origin: origin.clone(),
+                },
+                BatchRun {
+                    id: "2".into(),
+                    inputs: RunInputs::Object(Default::default()),
+                    origin,
+                },
+            ]
+        );
+
+        assert!(matches!(
+            parse_json_batch(Path::new("batch.json"), r#"{"wf.sample": "foo"}"#),
+            Err(Error::NonArrayRoot)
+        ));
+    }
+
+    #[test]
+    fn invalid_batches() {
+        let path = Path::new("samples.tsv");
+        assert!(matches!(
+            parse_batch(path, ""),
+            Err(Error::MissingHeader(_))
+        ));
+        assert!(matches!(
+            parse_batch(path, "a\ta\n1\t2\n"),
+            Err(Error::InvalidColumn(_))
+        ));
+        assert!(matches!(
+            parse_batch(path, "a\tb\n1\n"),
+            Err(Error::ColumnCount {
+                row: 1,
+                expected: 2,
+                actual: 1
+            })
+        ));
+        assert!(matches!(
+            parse_batch(path, "a\n${b}\n"),
+            Err(Error::UnknownColumn { row: 1, .. })
+        ));
+        assert!(matches!(
+            parse_batch(path, "id\ta\nx\t1\nx\t2\n"),
+            Err(Error::InvalidId(_))
+        ));
+        assert!(matches!(
+            parse_batch(path, "id\ta\n../x\t1\n"),
+            Err(Error::InvalidId(_))
+        ));
+    }
+}
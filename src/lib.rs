@@ -24,8 +24,10 @@ use commands::Commands;
 pub use config::Config;
 use git_testament::git_testament;
 use git_testament::render_testament;
+use tracing::level_filters::LevelFilter;
 use tracing::trace;
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::Layer as _;
 use tracing_subscriber::layer::SubscriberExt as _;
 
 use crate::commands::CommandResult;
@@ -33,13 +35,24 @@ use crate::commands::CommandResult;
 mod analysis;
 mod commands;
 mod config;
+mod coverage;
+mod cromwell;
+mod debugger;
 mod diagnostics;
+mod editorconfig;
+mod effectiveness;
 mod eval;
+mod http;
 mod inputs;
+mod report;
 
 /// ignorefile basename to respect.
 const IGNORE_FILENAME: &str = ".sprocketignore";
 
+/// The environment variable for filtering log messages by module (e.g.
+/// `SPROCKET_LOG=wdl_engine=debug`).
+const LOG_ENV_VAR: &str = "SPROCKET_LOG";
+
 git_testament!(TESTAMENT);
 
 #[derive(clap::Parser, Debug)]
@@ -53,6 +66,16 @@ struct Cli {
     #[command(flatten)]
     verbosity: Verbosity<WarnLevel>,
 
+    /// The format of log messages.
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        global = true,
+        default_value_t,
+        value_enum
+    )]
+    log_format: LogFormat,
+
     /// Path to the configuration file.
     #[arg(long, short, global = true)]
     config: Vec<PathBuf>,
@@ -65,37 +88,68 @@ struct Cli {
     skip_config_search: bool,
 }
 
+/// The format of log messages.
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum, PartialEq, Eq)]
+enum LogFormat {
+    /// Human-readable log messages.
+    #[default]
+    Text,
+
+    /// Log messages as JSON objects, one per line.
+    Json,
+}
+
+impl std::fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Text => write!(f, "text"),
+            Self::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// Creates the filter for log messages.
+///
+/// The directives of the `SPROCKET_LOG` environment variable are applied on
+/// top of the given verbosity. For compatibility, the `RUST_LOG` environment
+/// variable is respected when `SPROCKET_LOG` is not set; it replaces the
+/// verbosity entirely.
+fn log_filter(verbosity: LevelFilter) -> anyhow::Result<EnvFilter> {
+    if let Ok(directives) = std::env::var(LOG_ENV_VAR) {
+        return EnvFilter::try_new(format!("{verbosity},{directives}"))
+            .with_context(|| format!("invalid `{LOG_ENV_VAR}` filter `{directives}`"));
+    }
+
+    if std::env::var(EnvFilter::DEFAULT_ENV).is_ok() {
+        return Ok(EnvFilter::from_default_env());
+    }
+
+    Ok(EnvFilter::default().add_directive(verbosity.into()))
+}
+
 async fn inner() -> CommandResult<()> {
     let cli = Cli::parse();
 
-    match std::env::var("RUST_LOG") {
-        Ok(_) => {
-            let indicatif_layer = tracing_indicatif::IndicatifLayer::new();
+    let indicatif_layer = tracing_indicatif::IndicatifLayer::new();
+    let writer = indicatif_layer.get_stderr_writer();
+    let fmt_layer = match cli.log_format {
+        LogFormat::Text => tracing_subscriber::fmt::layer()
+            .with_writer(writer)
+            .with_ansi(stderr().is_terminal())
+            .boxed(),
+        LogFormat::Json => tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(writer)
+            .boxed(),
+    };
 
-            let subscriber = tracing_subscriber::fmt::Subscriber::builder()
-                .with_env_filter(EnvFilter::from_default_env())
-                .with_writer(indicatif_layer.get_stderr_writer())
-                .with_ansi(stderr().is_terminal())
-                .finish()
-                .with(indicatif_layer);
+    let subscriber = tracing_subscriber::registry()
+        .with(log_filter(cli.verbosity.tracing_level_filter())?)
+        .with(fmt_layer)
+        .with(indicatif_layer);
 
-            tracing::subscriber::set_global_default(subscriber)
-                .context("failed to set tracing subscriber")?;
-        }
-        Err(_) => {
-            let indicatif_layer = tracing_indicatif::IndicatifLayer::new();
-
-            let subscriber = tracing_subscriber::fmt()
-                .with_max_level(cli.verbosity)
-                .with_writer(indicatif_layer.get_stderr_writer())
-                .with_ansi(stderr().is_terminal())
-                .finish()
-                .with(indicatif_layer);
-
-            tracing::subscriber::set_global_default(subscriber)
-                .context("failed to set tracing subscriber")?;
-        }
-    };
+    tracing::subscriber::set_global_default(subscriber)
+        .context("failed to set tracing subscriber")?;
 
     let config = match &cli.command {
         Commands::Config(config_args) if config_args.is_init() => {
@@ -128,14 +182,24 @@ async fn inner() -> CommandResult<()> {
             commands::completions::completions(args, &mut cmd).await
         }
         Commands::Config(args) => commands::config::config(args, config),
+        Commands::Convert(args) => commands::convert::convert(args).await,
+        Commands::Daemon(args) => commands::daemon::daemon(args.apply(config)).await,
         Commands::Explain(args) => commands::explain::explain(args),
         Commands::Format(args) => commands::format::format(args.apply(config)).await,
+        Commands::Images(args) => commands::images::images(args).await,
         Commands::Inputs(args) => commands::inputs::inputs(args).await,
         Commands::Lint(args) => commands::check::lint(args.apply(config)).await,
+        Commands::Plan(args) => commands::plan::plan(args).await,
         Commands::Run(args) => commands::run::run(args.apply(config)).await,
+        Commands::Runs(args) => commands::runs::runs(args.apply(config)),
+        Commands::Server(args) => commands::server::server(args.apply(config)).await,
+        Commands::Stats(args) => commands::stats::stats(args).await,
         Commands::Validate(args) => commands::validate::validate(args.apply(config)).await,
         Commands::Dev(commands::DevCommands::Doc(args)) => commands::doc::doc(args).await,
         Commands::Dev(commands::DevCommands::Lock(args)) => commands::lock::lock(args).await,
+        Commands::Dev(commands::DevCommands::NewRule(args)) => {
+            commands::new_rule::new_rule(args).await
+        }
     }
 }
 
@@ -0,0 +1,384 @@
+//! Implementation of HTML reports of check and lint results.
+//!
+//! A report is a single, self-contained HTML file for sharing results with
+//! those who do not use the command line. The diagnostics of every document
+//! are listed in a table that can be searched and filtered by rule, severity,
+//! and file, each with an excerpt of the source it applies to. The
+//! explanations of the reported rules are included at the end of the report.
+
+use std::collections::BTreeSet;
+
+use maud::DOCTYPE;
+use maud::Markup;
+use maud::PreEscaped;
+use maud::html;
+use wdl::analysis;
+use wdl::ast::Diagnostic;
+use wdl::ast::Severity;
+use wdl::ast::Span;
+use wdl::lint;
+
+/// The number of lines of context shown before and after the source of a
+/// diagnostic.
+const CONTEXT_LINES: usize = 1;
+
+/// The styles of the report.
+const STYLES: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2rem; color: #1f2328; }
+h1 { margin-bottom: 0.25rem; }
+.summary { color: #59636e; margin-top: 0; }
+.filters { display: flex; flex-wrap: wrap; gap: 0.75rem; margin: 1rem 0; }
+.filters input, .filters select { padding: 0.25rem; font-size: 0.9rem; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border-bottom: 1px solid #d1d9e0; padding: 0.5rem; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; }
+.severity-error { color: #cf222e; font-weight: bold; }
+.severity-warning { color: #9a6700; font-weight: bold; }
+.severity-note { color: #0969da; font-weight: bold; }
+.fix { color: #59636e; margin: 0.25rem 0 0; }
+pre.excerpt { background: #f6f8fa; padding: 0.5rem; margin: 0.5rem 0 0; overflow-x: auto; }
+.line-number { display: inline-block; min-width: 3ch; margin-right: 1ch; color: #8c959f; text-align: right; user-select: none; }
+mark { background: #fff8c5; outline: 1px solid #d4a72c; }
+.explanation { white-space: pre-wrap; }
+"#;
+
+/// The script for searching and filtering the diagnostics of the report.
+const SCRIPT: &str = r##"
+const search = document.getElementById("search");
+const filters = document.querySelectorAll("select[data-filter]");
+const rows = document.querySelectorAll("#diagnostics tbody tr");
+const shown = document.getElementById("shown");
+
+function apply() {
+  const query = search.value.toLowerCase();
+  let count = 0;
+  for (const row of rows) {
+    let visible = row.textContent.toLowerCase().includes(query);
+    for (const filter of filters) {
+      if (filter.value && row.dataset[filter.dataset.filter] !== filter.value) {
+        visible = false;
+      }
+    }
+
+    row.hidden = !visible;
+    count += visible ? 1 : 0;
+  }
+
+  shown.textContent = count;
+}
+
+search.addEventListener("input", apply);
+for (const filter of filters) {
+  filter.addEventListener("change", apply);
+}
+"##;
+
+/// Gets the display name of a severity.
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+    }
+}
+
+/// Formats a count of the given noun, pluralizing the noun as needed.
+fn counted(count: usize, noun: &str) -> String {
+    format!("{count} {noun}{s}", s = if count == 1 { "" } else { "s" })
+}
+
+/// Renders an excerpt of the given source with the given span highlighted.
+///
+/// The excerpt includes the lines of the span and the lines of context around
+/// it.
+fn excerpt(source: &str, span: Span) -> Markup {
+    let start = span.start().min(source.len());
+    let end = span.end().clamp(start, source.len());
+    let first_line = source[..start].matches('\n').count();
+    let last_line = first_line + source[start..end].matches('\n').count();
+    let first_line = first_line.saturating_sub(CONTEXT_LINES);
+    let last_line = last_line + CONTEXT_LINES;
+
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for (index, line) in source.split_inclusive('\n').enumerate() {
+        let line_start = offset;
+        offset += line.len();
+        if index < first_line {
+            continue;
+        }
+
+        if index > last_line {
+            break;
+        }
+
+        let text = line.trim_end_matches(['\r', '\n']);
+        let line_end = line_start + text.len();
+        let highlight_start = start.clamp(line_start, line_end) - line_start;
+        let highlight_end = end.clamp(line_start, line_end) - line_start;
+        lines.push((
+            index + 1,
+            &text[..highlight_start],
+            &text[highlight_start..highlight_end],
+            &text[highlight_end..],
+        ));
+    }
+
+    html! {
+        pre.excerpt {
+            @for (number, before, highlighted, after) in lines {
+                span.line-number { (number) }
+                (before)
+                @if !highlighted.is_empty() {
+                    mark { (highlighted) }
+                }
+                (after)
+                "\n"
+            }
+        }
+    }
+}
+
+/// Represents a diagnostic in a report.
+#[derive(Debug)]
+struct Entry {
+    /// The path of the document of the diagnostic.
+    path: String,
+    /// The one-based line and column of the diagnostic.
+    ///
+    /// This is `None` if the diagnostic has no location.
+    location: Option<(usize, usize)>,
+    /// The severity of the diagnostic.
+    severity: Severity,
+    /// The rule of the diagnostic.
+    rule: Option<String>,
+    /// The message of the diagnostic.
+    message: String,
+    /// The fix of the diagnostic.
+    fix: Option<String>,
+    /// The excerpt of the source of the diagnostic.
+    excerpt: Option<Markup>,
+}
+
+/// Represents an HTML report of check and lint results.
+#[derive(Debug, Default)]
+pub struct HtmlReport {
+    /// The diagnostics of the report.
+    entries: Vec<Entry>,
+}
+
+impl HtmlReport {
+    /// Adds the diagnostics of a document to the report.
+    pub fn add_document<'a>(
+        &mut self,
+        path: &str,
+        source: &str,
+        diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+    ) {
+        for diagnostic in diagnostics {
+            let span = diagnostic.labels().next().map(|l| l.span());
+            let location = span.map(|span| {
+                let start = span.start().min(source.len());
+                let line_start = source[..start].rfind('\n').map(|i| i + 1).unwrap_or(0);
+                (
+                    source[..start].matches('\n').count() + 1,
+                    source[line_start..start].chars().count() + 1,
+                )
+            });
+
+            self.entries.push(Entry {
+                path: path.to_string(),
+                location,
+                severity: diagnostic.severity(),
+                rule: diagnostic.rule().map(str::to_string),
+                message: diagnostic.message().to_string(),
+                fix: diagnostic.fix().map(str::to_string),
+                excerpt: span.map(|span| excerpt(source, span)),
+            });
+        }
+    }
+
+    /// Renders the report as an HTML document.
+    pub fn render(&self) -> String {
+        let rules = self
+            .entries
+            .iter()
+            .filter_map(|e| e.rule.as_deref())
+            .collect::<BTreeSet<_>>();
+        let severities = self
+            .entries
+            .iter()
+            .map(|e| e.severity)
+            .collect::<BTreeSet<_>>();
+        let paths = self
+            .entries
+            .iter()
+            .map(|e| e.path.as_str())
+            .collect::<BTreeSet<_>>();
+
+        let count = |severity: Severity| {
+            self.entries
+                .iter()
+                .filter(|e| e.severity == severity)
+                .count()
+        };
+        let analysis_rules = analysis::rules();
+        let lint_rules = lint::rules();
+
+        html! {
+            (DOCTYPE)
+            html lang="en" {
+                head {
+                    meta charset="utf-8";
+                    meta name="viewport" content="width=device-width, initial-scale=1";
+                    title { "Sprocket report" }
+                    style { (PreEscaped(STYLES)) }
+                }
+                body {
+                    h1 { "Sprocket report" }
+                    p.summary {
+                        (counted(count(Severity::Error), "error")) ", "
+                        (counted(count(Severity::Warning), "warning")) ", and "
+                        (counted(count(Severity::Note), "note")) " across "
+                        (counted(paths.len(), "document"))
+                    }
+                    div.filters {
+                        input #search type="search" placeholder="Search diagnostics";
+                        select data-filter="rule" aria-label="Rule" {
+                            option value="" { "All rules" }
+                            @for rule in &rules {
+                                option value=(rule) { (rule) }
+                            }
+                        }
+                        select data-filter="severity" aria-label="Severity" {
+                            option value="" { "All severities" }
+                            @for severity in &severities {
+                                option value=(severity_name(*severity)) {
+                                    (severity_name(*severity))
+                                }
+                            }
+                        }
+                        select data-filter="file" aria-label="File" {
+                            option value="" { "All files" }
+                            @for path in &paths {
+                                option value=(path) { (path) }
+                            }
+                        }
+                        span { "Showing " span #shown { (self.entries.len()) } " of " (self.entries.len()) }
+                    }
+                    table #diagnostics {
+                        thead {
+                            tr {
+                                th { "Severity" }
+                                th { "Rule" }
+                                th { "Location" }
+                                th { "Diagnostic" }
+                            }
+                        }
+                        tbody {
+                            @for entry in &self.entries {
+                                tr
+                                    data-rule=(entry.rule.as_deref().unwrap_or_default())
+                                    data-severity=(severity_name(entry.severity))
+                                    data-file=(entry.path)
+                                {
+                                    td class={ "severity-" (severity_name(entry.severity)) } {
+                                        (severity_name(entry.severity))
+                                    }
+                                    td {
+                                        @if let Some(rule) = &entry.rule {
+                                            a href={ "#rule-" (rule) } { (rule) }
+                                        }
+                                    }
+                                    td {
+                                        (entry.path)
+                                        @if let Some((line, column)) = entry.location {
+                                            ":" (line) ":" (column)
+                                        }
+                                    }
+                                    td {
+                                        (entry.message)
+                                        @if let Some(fix) = &entry.fix {
+                                            p.fix { "fix: " (fix) }
+                                        }
+                                        @if let Some(excerpt) = &entry.excerpt {
+                                            (excerpt)
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    @if !rules.is_empty() {
+                        h2 { "Rules" }
+                        @for id in &rules {
+                            @if let Some(rule) = lint_rules.iter().find(|r| r.id() == *id) {
+                                section id={ "rule-" (id) } {
+                                    h3 { (id) }
+                                    p { (rule.description()) }
+                                    p.explanation { (rule.explanation()) }
+                                    @if let Some(url) = rule.url() {
+                                        p { a href=(url) { (url) } }
+                                    }
+                                }
+                            } @else if let Some(rule) = analysis_rules.iter().find(|r| r.id() == *id) {
+                                section id={ "rule-" (id) } {
+                                    h3 { (id) }
+                                    p { (rule.description()) }
+                                    p.explanation { (rule.explanation()) }
+                                }
+                            }
+                        }
+                    }
+                    script { (PreEscaped(SCRIPT)) }
+                }
+            }
+        }
+        .into_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn source_excerpt() {
+        let source = "version 1.2\n\nworkflow foo {\n}\n";
+        let start = source.find("foo").unwrap();
+        let markup = excerpt(source, Span::new(start, 3)).into_string();
+        assert_eq!(
+            markup,
+            "<pre class=\"excerpt\"><span class=\"line-number\">2</span>\n<span \
+             class=\"line-number\">3</span>workflow <mark>foo</mark> {\n<span \
+             class=\"line-number\">4</span>}\n</pre>"
+        );
+    }
+
+    #[test]
+    fn report_entries() {
+        let source = "version 1.2\n\nworkflow foo {}\n";
+        let start = source.find("foo").unwrap();
+        let diagnostics = [
+            Diagnostic::warning("a <warning>")
+                .with_rule("SnakeCase")
+                .with_highlight(Span::new(start, 3)),
+            Diagnostic::error("an error"),
+        ];
+
+        let mut report = HtmlReport::default();
+        report.add_document("foo.wdl", source, &diagnostics);
+        assert_eq!(report.entries[0].location, Some((3, 10)));
+        assert_eq!(report.entries[1].location, None);
+
+        let html = report.render();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("a &lt;warning&gt;"));
+        assert!(html.contains("1 error, 1 warning, and 0 notes across 1 document"));
+        assert!(html.contains("foo.wdl:3:10"));
+        assert!(html.contains("<a href=\"#rule-SnakeCase\">SnakeCase</a>"));
+        assert!(html.contains("<section id=\"rule-SnakeCase\">"));
+    }
+}
@@ -11,6 +11,9 @@
 //!   * `inputs` - a directory containing the starting files that the test will
 //!     run with. The contents of this directory are copied to a temp directory
 //!     and the temporary directory used as the command's working directory.
+//!   * `symlinks` - symbolic links to create in the temp directory after the
+//!     inputs are copied; each line contains the path of a link and the path it
+//!     links to, separated by a space.
 //!   * `outputs` - a directory containing the expected ending files that the
 //!     temp directory will contain. If a test does not need to verify the
 //!     resulting directory contents, it may omit an `outputs` directory.
@@ -99,9 +102,38 @@ async fn setup_working_test_directory(test_path: &Path) -> Result<TempDir> {
             .await
             .context("failed to copy input files to temp directory")?;
     }
+    let symlinks_path = test_path.join("symlinks");
+    if symlinks_path.exists() {
+        create_symlinks(&symlinks_path, working_test_directory.path())
+            .await
+            .context("failed to create symbolic links in temp directory")?;
+    }
     Ok(working_test_directory)
 }
 
+/// Creates the symbolic links listed in the given file in the target path.
+async fn create_symlinks(symlinks_path: &Path, target: &Path) -> Result<()> {
+    let symlinks = fs::read_to_string(symlinks_path)
+        .await
+        .with_context(|| format!("failed to read symbolic links at path {symlinks_path:?}"))?;
+    for line in symlinks.lines().filter(|l| !l.trim().is_empty()) {
+        let (link, original) = line
+            .split_once(' ')
+            .ok_or_else(|| anyhow!("invalid symbolic link `{line}`"))?;
+        let link = target.join(link);
+        #[cfg(unix)]
+        let result = fs::symlink(original, &link).await;
+        #[cfg(windows)]
+        let result = if link.parent().unwrap_or(target).join(original).is_dir() {
+            fs::symlink_dir(original, &link).await
+        } else {
+            fs::symlink_file(original, &link).await
+        };
+        result.with_context(|| format!("failed to create symbolic link at {link:?}"))?;
+    }
+    Ok(())
+}
+
 /// Recursively copies the source path to the target path.
 async fn recursive_copy(source: &Path, target: &Path) -> Result<()> {
     if !target.exists() {